@@ -152,8 +152,13 @@ fn main() -> Result<()> {
         println!("    - {}", parent.short());
     }
     println!("  Files changed: {}", commit_details.files_changed.len());
-    for file in &commit_details.files_changed {
-        println!("    - {}", file.display());
+    for stat in &commit_details.file_stats {
+        println!(
+            "    - {} (+{} -{})",
+            stat.path.display(),
+            stat.insertions,
+            stat.deletions
+        );
     }
     println!(
         "  Changes: +{} -{}",
@@ -56,6 +56,9 @@ fn demonstrate_repository_errors(repo_path: &std::path::Path) -> Result<()> {
             println!("   CommandFailed caught: {}", msg);
             println!("   Git command failed - path exists but isn't a repo");
         }
+        Err(e) => {
+            println!("   Other error caught: {}", e);
+        }
     }
 
     // 2. Opening a file as a repository
@@ -72,6 +75,9 @@ fn demonstrate_repository_errors(repo_path: &std::path::Path) -> Result<()> {
         Err(GitError::IoError(msg)) => {
             println!("   IoError caught: {}", msg);
         }
+        Err(e) => {
+            println!("   Other error caught: {}", e);
+        }
     }
 
     fs::remove_file(&fake_repo_path)?;
@@ -90,6 +96,9 @@ fn demonstrate_repository_errors(repo_path: &std::path::Path) -> Result<()> {
             println!("   CommandFailed caught: {}", msg);
             println!("   Git init command failed");
         }
+        Err(e) => {
+            println!("   Other error caught: {}", e);
+        }
     }
 
     println!();
@@ -119,6 +128,9 @@ fn demonstrate_file_operation_errors(repo_path: &std::path::Path) -> Result<()>
         Err(GitError::IoError(msg)) => {
             println!("   IoError caught: {}", msg);
         }
+        Err(e) => {
+            println!("   Other error caught: {}", e);
+        }
     }
 
     // 2. Mixed valid and invalid files
@@ -146,6 +158,9 @@ fn demonstrate_file_operation_errors(repo_path: &std::path::Path) -> Result<()>
         Err(GitError::IoError(msg)) => {
             println!("   IoError caught: {}", msg);
         }
+        Err(e) => {
+            println!("   Other error caught: {}", e);
+        }
     }
 
     println!();
@@ -172,6 +187,9 @@ fn demonstrate_git_command_errors(repo_path: &std::path::Path) -> Result<()> {
         Err(GitError::IoError(msg)) => {
             println!("   IoError caught: {}", msg);
         }
+        Err(e) => {
+            println!("   Other error caught: {}", e);
+        }
     }
 
     // 2. Commit with problematic message
@@ -197,6 +215,9 @@ fn demonstrate_git_command_errors(repo_path: &std::path::Path) -> Result<()> {
         Err(GitError::IoError(msg)) => {
             println!("   IoError with long message: {}", msg);
         }
+        Err(e) => {
+            println!("   Other error caught: {}", e);
+        }
     }
 
     println!();
@@ -278,6 +299,9 @@ fn demonstrate_error_recovery_patterns(repo_path: &std::path::Path) -> Result<()
         Err(GitError::IoError(msg)) => {
             println!("      IoError during batch add: {}", msg);
         }
+        Err(e) => {
+            println!("      Other error caught: {}", e);
+        }
     }
 
     // Pattern 3: Status checking before operations
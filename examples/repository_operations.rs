@@ -115,6 +115,9 @@ fn main() -> Result<()> {
             println!("Expected error caught: IoError");
             println!("   Error message: {}", msg);
         }
+        Err(e) => {
+            println!("Unexpected error: {}", e);
+        }
     }
     println!();
 
@@ -135,6 +138,9 @@ fn main() -> Result<()> {
             println!("Expected error caught: IoError");
             println!("   Error message: {}", msg);
         }
+        Err(e) => {
+            println!("Unexpected error: {}", e);
+        }
     }
     println!();
 
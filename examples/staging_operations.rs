@@ -273,6 +273,7 @@ fn display_status_breakdown(status: &rustic_git::GitStatus) {
             IndexStatus::Deleted => "[D]",
             IndexStatus::Renamed => "[R]",
             IndexStatus::Copied => "[C]",
+            IndexStatus::Unmerged => "[U]",
             IndexStatus::Clean => "[ ]",
         };
         println!("      {} {:?}: {} files", marker, index_status, count);
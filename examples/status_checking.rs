@@ -268,6 +268,7 @@ fn display_detailed_status(status: &rustic_git::GitStatus) {
                 IndexStatus::Deleted => "[D]",
                 IndexStatus::Renamed => "[R]",
                 IndexStatus::Copied => "[C]",
+                IndexStatus::Unmerged => "[U]",
                 IndexStatus::Clean => "[ ]",
             };
             let worktree_marker = match entry.worktree_status {
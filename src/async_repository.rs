@@ -0,0 +1,397 @@
+//! Async API on the tokio runtime (requires the `tokio` feature)
+//!
+//! [`AsyncRepository`] wraps a [`Repository`] and runs the common operations through
+//! `tokio::process::Command`, so awaiting them never blocks a runtime worker thread.
+//! It builds the same git arguments and parses output with the same code as the
+//! blocking API, so results are identical.
+//!
+//! Anything not covered here is available through [`AsyncRepository::repo`]; run
+//! those calls with `tokio::task::spawn_blocking` when they may take a while.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::AsyncRepository;
+//!
+//! # async fn example() -> rustic_git::Result<()> {
+//! let repo = AsyncRepository::open(".").await?;
+//! repo.add(&["README.md"]).await?;
+//! let hash = repo.commit("Update README").await?;
+//! println!("{} on {:?}", hash.short(), repo.current_branch().await?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::commands::add::add_command;
+use crate::commands::branch::{BRANCH_FORMAT, parse_branch_output};
+use crate::commands::log::{GIT_LOG_FORMAT, build_log_args, parse_log_output};
+use crate::commands::remote::{check_push_output, fetch_args, push_args};
+use crate::error::{GitError, Result};
+use crate::utils::{git_async, git_raw_async, validate_arg, validate_ref_name};
+use crate::{
+    Branch, BranchList, CommitLog, DiffOptions, DiffOutput, FetchOptions, GitStatus, Hash,
+    LogOptions, PushOptions, Repository, StatusOptions,
+};
+
+/// A [`Repository`] whose operations run asynchronously on tokio
+///
+/// Cloning is cheap and shares the repository's settings (timeout, backend, warning
+/// handler and so on), which apply to async calls as they do to blocking ones. The
+/// exception is [`Repository::with_serialization`]: its lock is tied to a thread and
+/// cannot be held across an await point, so async calls do not take it.
+#[derive(Debug, Clone)]
+pub struct AsyncRepository {
+    repo: Repository,
+}
+
+impl AsyncRepository {
+    /// Wrap an already opened repository
+    pub fn new(repo: Repository) -> Self {
+        Self { repo }
+    }
+
+    /// Open an existing Git repository at the specified path
+    ///
+    /// Opening runs git to locate the repository, which happens on tokio's blocking
+    /// thread pool.
+    pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path: PathBuf = path.as_ref().to_path_buf();
+        let repo = tokio::task::spawn_blocking(move || Repository::open(path))
+            .await
+            .map_err(|e| GitError::CommandFailed(format!("Failed to open repository: {}", e)))??;
+        Ok(Self::new(repo))
+    }
+
+    /// The wrapped blocking repository, for operations without an async counterpart
+    pub fn repo(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// Consume the wrapper and return the blocking repository
+    pub fn into_inner(self) -> Repository {
+        self.repo
+    }
+
+    /// Run an arbitrary git command in the repository and return its stdout
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments to pass to git, without the leading `git`
+    pub async fn run(&self, args: &[&str]) -> Result<String> {
+        self.repo.check_git()?;
+        git_async(args, Some(&self.repo), &[]).await
+    }
+
+    /// Get the status of the repository, see [`Repository::status`]
+    pub async fn status(&self) -> Result<GitStatus> {
+        self.status_with_options(&StatusOptions::new()).await
+    }
+
+    /// Get the status of the repository, see [`Repository::status_with_options`]
+    ///
+    /// With [`StatusOptions::recurse_submodules`], dirty submodules are still read with
+    /// blocking calls.
+    pub async fn status_with_options(&self, options: &StatusOptions) -> Result<GitStatus> {
+        self.repo.check_git()?;
+
+        let args = self.repo.status_args(options);
+        let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let stdout = git_async(&args_str, Some(&self.repo), &[]).await?;
+        self.repo.status_from_output(&stdout, options)
+    }
+
+    /// Add specific files or paths to the staging area, see [`Repository::add`]
+    pub async fn add<P: AsRef<Path>>(&self, paths: &[P]) -> Result<()> {
+        self.repo.check_git()?;
+
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let mut args = add_command(&self.repo)?;
+        args.push("--");
+        let path_strings: Vec<String> = paths
+            .iter()
+            .map(|p| p.as_ref().to_string_lossy().to_string())
+            .collect();
+        args.extend(path_strings.iter().map(|s| s.as_str()));
+
+        git_async(&args, Some(&self.repo), &[]).await?;
+        Ok(())
+    }
+
+    /// Add all changes to the staging area, see [`Repository::add_all`]
+    pub async fn add_all(&self) -> Result<()> {
+        self.repo.check_git()?;
+
+        let mut args = add_command(&self.repo)?;
+        args.push(".");
+        git_async(&args, Some(&self.repo), &[]).await?;
+        Ok(())
+    }
+
+    /// Create a commit of the staged changes, see [`Repository::commit`]
+    pub async fn commit(&self, message: &str) -> Result<Hash> {
+        self.repo.check_git()?;
+
+        if message.trim().is_empty() {
+            return Err(GitError::CommandFailed(
+                "Commit message cannot be empty".to_string(),
+            ));
+        }
+
+        let status = self.status().await?;
+        if status.staged_files().count() == 0 {
+            return Err(GitError::CommandFailed(
+                "No changes staged for commit".to_string(),
+            ));
+        }
+
+        git_async(&["commit", "-m", message], Some(&self.repo), &[])
+            .await
+            .map_err(|e| match e {
+                GitError::CommandFailed(msg) => GitError::CommandFailed(format!(
+                    "Commit failed: {}. Ensure git user.name and user.email are configured.",
+                    msg
+                )),
+                other => other,
+            })?;
+
+        let hash_output = git_async(&["rev-parse", "HEAD"], Some(&self.repo), &[]).await?;
+        Ok(Hash(hash_output.trim().to_string()))
+    }
+
+    /// List all branches, see [`Repository::branches`]
+    pub async fn branches(&self) -> Result<BranchList> {
+        self.repo.check_git()?;
+
+        let stdout = git_async(
+            &["for-each-ref", BRANCH_FORMAT, "refs/heads", "refs/remotes"],
+            Some(&self.repo),
+            &[],
+        )
+        .await?;
+        Ok(BranchList::new(parse_branch_output(&stdout)?))
+    }
+
+    /// Get the current branch, see [`Repository::current_branch`]
+    pub async fn current_branch(&self) -> Result<Option<Branch>> {
+        self.repo.check_git()?;
+
+        let stdout = git_async(&["branch", "--show-current"], Some(&self.repo), &[]).await?;
+        if stdout.trim().is_empty() {
+            // Might be in detached HEAD state
+            return Ok(None);
+        }
+
+        Ok(self.branches().await?.current().cloned())
+    }
+
+    /// Create a new branch, see [`Repository::create_branch`]
+    pub async fn create_branch(&self, name: &str, start_point: Option<&str>) -> Result<Branch> {
+        self.repo.check_git()?;
+        validate_ref_name("branch", name)?;
+        if let Some(start) = start_point {
+            validate_arg("revision", start)?;
+        }
+
+        let mut args = vec!["branch", name];
+        args.extend(start_point);
+        git_async(&args, Some(&self.repo), &[]).await?;
+
+        self.branches()
+            .await?
+            .find(name)
+            .cloned()
+            .ok_or_else(|| GitError::CommandFailed(format!("Failed to create branch: {}", name)))
+    }
+
+    /// Switch to an existing branch, see [`Repository::checkout`]
+    pub async fn checkout(&self, branch: &Branch) -> Result<()> {
+        self.repo.check_git()?;
+
+        let branch_name = if branch.is_remote() {
+            branch.short_name()
+        } else {
+            &branch.name
+        };
+        validate_arg("revision", branch_name)?;
+
+        git_async(&["checkout", branch_name, "--"], Some(&self.repo), &[]).await?;
+        Ok(())
+    }
+
+    /// Get commit history with default options, see [`Repository::log`]
+    pub async fn log(&self) -> Result<CommitLog> {
+        self.log_with_options(&LogOptions::new().max_count(100))
+            .await
+    }
+
+    /// Get commit history with custom options, see [`Repository::log_with_options`]
+    pub async fn log_with_options(&self, options: &LogOptions) -> Result<CommitLog> {
+        self.repo.check_git()?;
+
+        let args = build_log_args(GIT_LOG_FORMAT, &[], options)?;
+        let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let stdout = git_async(&args_str, Some(&self.repo), &[]).await?;
+        Ok(CommitLog::new(parse_log_output(&stdout)?))
+    }
+
+    /// Diff the working directory against the index, see [`Repository::diff`]
+    pub async fn diff(&self) -> Result<DiffOutput> {
+        self.diff_with_options(&DiffOptions::new()).await
+    }
+
+    /// Diff the index against HEAD, see [`Repository::diff_staged`]
+    pub async fn diff_staged(&self) -> Result<DiffOutput> {
+        self.diff_with_options(&DiffOptions::new().cached()).await
+    }
+
+    /// Diff with custom options, see [`Repository::diff_with_options`]
+    pub async fn diff_with_options(&self, options: &DiffOptions) -> Result<DiffOutput> {
+        self.repo.check_git()?;
+
+        let args = self.repo.diff_args(None, options)?;
+        let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let output = git_async(&args_str, Some(&self.repo), &[]).await?;
+        self.repo.diff_from_output(&output, options)
+    }
+
+    /// Fetch from a remote, see [`Repository::fetch`]
+    pub async fn fetch(&self, remote: &str) -> Result<()> {
+        self.fetch_with_options(remote, FetchOptions::default())
+            .await
+    }
+
+    /// Fetch from a remote with options, see [`Repository::fetch_with_options`]
+    ///
+    /// A [`FetchOptions::with_progress`] callback receives its events after git exits.
+    pub async fn fetch_with_options(&self, remote: &str, options: FetchOptions) -> Result<()> {
+        self.repo.check_git()?;
+
+        let args = fetch_args(remote, &options)?;
+        let Some(progress) = &options.progress else {
+            git_async(&args, Some(&self.repo), &[]).await?;
+            return Ok(());
+        };
+
+        // Progress is reported once git exits rather than streamed
+        let output = git_raw_async(&args, Some(&self.repo), &[]).await?;
+        progress.replay(&output.stderr);
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git fetch failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Push a branch to a remote, see [`Repository::push`]
+    pub async fn push(&self, remote: &str, branch: &str) -> Result<()> {
+        self.push_with_options(remote, branch, PushOptions::default())
+            .await
+    }
+
+    /// Push a branch to a remote with options, see [`Repository::push_with_options`]
+    ///
+    /// A [`PushOptions::with_progress`] callback receives its events after git exits.
+    pub async fn push_with_options(
+        &self,
+        remote: &str,
+        branch: &str,
+        options: PushOptions,
+    ) -> Result<()> {
+        self.repo.check_git()?;
+
+        let args = push_args(remote, branch, &options)?;
+        let output = git_raw_async(&args, Some(&self.repo), &[]).await?;
+        if let Some(progress) = &options.progress {
+            progress.replay(&output.stderr);
+            progress.push_porcelain(&output.stdout);
+        }
+        check_push_output(&args, &output)
+    }
+}
+
+impl From<Repository> for AsyncRepository {
+    fn from(repo: Repository) -> Self {
+        Self::new(repo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_async_matches_blocking() {
+        let temp = TempRepo::new("async_matches_blocking").unwrap();
+        temp.commit_file("a.txt", "one\n", "Initial commit")
+            .unwrap();
+        let repo = AsyncRepository::open(temp.path()).await.unwrap();
+
+        temp.write_file("a.txt", "two\n").unwrap();
+        temp.write_file("b.txt", "new\n").unwrap();
+        assert_eq!(
+            repo.status().await.unwrap().to_porcelain(),
+            temp.status().unwrap().to_porcelain()
+        );
+        assert_eq!(
+            repo.diff().await.unwrap().to_string(),
+            temp.diff().unwrap().to_string()
+        );
+
+        repo.add(&["a.txt", "b.txt"]).await.unwrap();
+        assert_eq!(repo.diff_staged().await.unwrap().len(), 2);
+        let hash = repo.commit("Second commit").await.unwrap();
+        assert!(repo.status().await.unwrap().is_clean());
+
+        let log = repo.log().await.unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.all()[0].hash, hash);
+        assert_eq!(log.all()[0].message.subject, "Second commit");
+    }
+
+    #[tokio::test]
+    async fn test_async_branches() {
+        let temp = TempRepo::new("async_branches").unwrap();
+        temp.commit_file("a.txt", "one\n", "Initial commit")
+            .unwrap();
+        let repo = AsyncRepository::from(temp.repo().clone());
+
+        let branch = repo.create_branch("feature", None).await.unwrap();
+        assert!(!branch.is_current);
+        repo.checkout(&branch).await.unwrap();
+        let current = repo.current_branch().await.unwrap().unwrap();
+        assert_eq!(current.name, "feature");
+        assert_eq!(repo.branches().await.unwrap().len(), 2);
+
+        assert!(repo.create_branch("-f", None).await.is_err());
+        assert!(repo.commit("Nothing staged").await.is_err());
+        assert!(repo.run(&["rev-parse", "--verify", "nope"]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_async_timeout() {
+        let temp = TempRepo::new("async_timeout").unwrap();
+        temp.config().set("alias.hang", "!sleep 5").unwrap();
+        let repo =
+            AsyncRepository::new(temp.repo().clone().with_timeout(Duration::from_millis(200)));
+
+        assert!(repo.status().await.unwrap().is_clean());
+        let started = std::time::Instant::now();
+        match repo.run(&["hang"]).await {
+            Err(GitError::TimedOut { command, timeout }) => {
+                assert_eq!(command, "hang");
+                assert_eq!(timeout, Duration::from_millis(200));
+            }
+            other => panic!("Expected TimedOut, got: {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(3));
+    }
+}
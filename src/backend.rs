@@ -0,0 +1,228 @@
+//! Pluggable execution of git commands
+//!
+//! By default every command spawns the `git` executable. A [`GitBackend`] installed with
+//! [`Repository::from_backend`] or [`Repository::with_backend`] receives each command as
+//! a [`GitInvocation`] instead and decides what output it produces. This is how
+//! [`MockBackend`](crate::testing::MockBackend) (feature `testing`) lets applications
+//! unit test their git logic with scripted outputs.
+//!
+//! Backends speak git's command-line protocol: they get git's arguments and answer with
+//! the output git would print, so the parsing behind the high-level [`Repository`] API is
+//! shared by every backend. A backend built on `gix` or `git2` can serve the commands it
+//! implements in-process and hand the rest to [`CliBackend`], the executable-based
+//! default, or fail them where no git binary is installed.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{CliBackend, GitBackend, GitInvocation, Repository, Result};
+//! use std::process::Output;
+//!
+//! /// Answers `rev-parse HEAD` itself and runs everything else with git
+//! struct CachedHead {
+//!     head: Output,
+//!     cli: CliBackend,
+//! }
+//!
+//! impl GitBackend for CachedHead {
+//!     fn run(&self, invocation: &GitInvocation) -> Result<Output> {
+//!         if invocation.matches(&["rev-parse", "HEAD"]) {
+//!             return Ok(self.head.clone());
+//!         }
+//!         self.cli.run(invocation)
+//!     }
+//! }
+//!
+//! let cli = CliBackend::new("/srv/app");
+//! let head = cli.run(&GitInvocation::from_args(&["rev-parse", "HEAD"]))?;
+//! let repo = Repository::from_backend("/srv/app", CachedHead { head, cli });
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::Result;
+use crate::repository::Repository;
+use crate::types::GitVersion;
+use crate::utils::{git_raw_with_env, git_raw_with_env_and_stdin};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+/// A single git command, as handed to a [`GitBackend`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitInvocation {
+    /// Arguments after `git`, e.g. `["rev-parse", "HEAD"]`
+    pub args: Vec<String>,
+    /// Environment variables set for this command only (such as `GIT_INDEX_FILE`)
+    pub env: Vec<(String, String)>,
+    /// Bytes written to the command's stdin, if any
+    pub stdin: Option<Vec<u8>>,
+}
+
+impl GitInvocation {
+    pub(crate) fn new(args: &[&str], env: &[(&str, &str)], stdin: Option<&[u8]>) -> Self {
+        Self {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: env
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            stdin: stdin.map(<[u8]>::to_vec),
+        }
+    }
+
+    /// An invocation with only arguments, e.g. for calling a backend directly
+    pub fn from_args(args: &[&str]) -> Self {
+        Self::new(args, &[], None)
+    }
+
+    /// The subcommand, e.g. `"commit"`
+    pub fn subcommand(&self) -> Option<&str> {
+        self.args.first().map(String::as_str)
+    }
+
+    /// Check whether the arguments equal `args`
+    pub fn matches(&self, args: &[&str]) -> bool {
+        self.args.len() == args.len() && self.args.iter().zip(args).all(|(a, b)| a == b)
+    }
+}
+
+impl fmt::Display for GitInvocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "git")?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Executes git commands on behalf of a [`Repository`]
+///
+/// Implementations return the process output git would have produced; a non-zero exit
+/// status is reported through [`Output::status`], not as an error. Returning `Err` is for
+/// failures to run the command at all.
+///
+/// Streaming APIs ([`blame_incremental`](Repository::blame_incremental) and
+/// [`log_stream`](Repository::log_stream)) need a real process and fail with a backend.
+pub trait GitBackend: Send + Sync {
+    /// Run one git command
+    fn run(&self, invocation: &GitInvocation) -> Result<Output>;
+
+    /// The git version to assume when choosing command-line flags
+    fn version(&self) -> Result<GitVersion> {
+        Repository::git_version()
+    }
+}
+
+/// The default backend: runs the `git` executable in a directory
+///
+/// Repositories without a backend behave like this already; it exists so custom backends
+/// can delegate the commands they do not implement. Unlike a handle without a backend,
+/// it ignores the handle's hermetic settings, explicit git directory and timeout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliBackend {
+    dir: PathBuf,
+}
+
+impl CliBackend {
+    /// Create a backend that runs git in `dir`
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The directory git runs in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl GitBackend for CliBackend {
+    fn run(&self, invocation: &GitInvocation) -> Result<Output> {
+        Repository::ensure_git()?;
+
+        let args: Vec<&str> = invocation.args.iter().map(String::as_str).collect();
+        let env: Vec<(&str, &str)> = invocation
+            .env
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        match &invocation.stdin {
+            Some(input) => git_raw_with_env_and_stdin(&args, Some(self.dir()), &env, input),
+            None => git_raw_with_env(&args, Some(self.dir()), &env),
+        }
+    }
+}
+
+/// Backend registered on a [`Repository`]
+#[derive(Clone)]
+pub(crate) struct BackendHandle(pub(crate) std::sync::Arc<dyn GitBackend>);
+
+impl fmt::Debug for BackendHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("GitBackend")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use crate::{CommitOptions, Identity};
+    use std::sync::{Arc, Mutex};
+
+    /// Records every invocation and hands it to git
+    struct Recording {
+        cli: CliBackend,
+        calls: Arc<Mutex<Vec<GitInvocation>>>,
+    }
+
+    impl GitBackend for Recording {
+        fn run(&self, invocation: &GitInvocation) -> Result<Output> {
+            self.calls.lock().unwrap().push(invocation.clone());
+            self.cli.run(invocation)
+        }
+    }
+
+    #[test]
+    fn test_cli_backend_delegation() {
+        let temp = TempRepo::new("cli_backend").unwrap();
+        temp.write_file("a.txt", "one\n").unwrap();
+        temp.write_file(".gitignore", "*.log\n").unwrap();
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let backend = Recording {
+            cli: CliBackend::new(temp.path()),
+            calls: calls.clone(),
+        };
+        let repo = Repository::from_backend(temp.path(), backend);
+
+        repo.add(&["a.txt", ".gitignore"]).unwrap();
+        let options = CommitOptions::new().with_identity(Identity::new("Ada", "ada@example.com"));
+        let hash = repo.commit_with_options("Initial", options).unwrap();
+        let checked = repo.ignore_check_batch(&["x.log", "a.txt"]).unwrap();
+
+        // Results come from the real repository
+        assert_eq!(temp.head_hash().unwrap(), hash);
+        assert_eq!(temp.log().unwrap().all()[0].author.name, "Ada");
+        let ignored: Vec<bool> = checked.iter().map(|(_, ignored)| *ignored).collect();
+        assert_eq!(ignored, [true, false]);
+
+        let calls = calls.lock().unwrap();
+        let commit = calls
+            .iter()
+            .find(|c| c.subcommand() == Some("commit"))
+            .unwrap();
+        assert!(
+            commit
+                .env
+                .contains(&("GIT_AUTHOR_NAME".to_string(), "Ada".to_string()))
+        );
+        let check = calls
+            .iter()
+            .find(|c| c.subcommand() == Some("check-ignore"))
+            .unwrap();
+        assert_eq!(check.stdin.as_deref(), Some(&b"x.log\0a.txt\0"[..]));
+    }
+}
@@ -3,6 +3,19 @@ use std::path::Path;
 use crate::utils::git;
 use crate::{Repository, Result};
 
+/// Base `git add` arguments.
+///
+/// `--sparse` (git 2.34+) lets changes outside a sparse-checkout definition be staged;
+/// without it git rejects such paths, or silently skips them for `.` and `-u`. It has
+/// no effect when sparse checkout is off.
+pub(crate) fn add_command(repo: &Repository) -> Result<Vec<&'static str>> {
+    if repo.command_git_version()?.at_least(2, 34, 0) {
+        Ok(vec!["add", "--sparse"])
+    } else {
+        Ok(vec!["add"])
+    }
+}
+
 impl Repository {
     /// Add specific files or paths to the staging area.
     ///
@@ -14,13 +27,14 @@ impl Repository {
     ///
     /// A `Result` indicating success or a `GitError` if the operation fails.
     pub fn add<P: AsRef<Path>>(&self, paths: &[P]) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         if paths.is_empty() {
             return Ok(());
         }
 
-        let mut args = vec!["add"];
+        let mut args = add_command(self)?;
+        args.push("--");
         let path_strings: Vec<String> = paths
             .iter()
             .map(|p| p.as_ref().to_string_lossy().to_string())
@@ -30,7 +44,7 @@ impl Repository {
             args.push(path_str);
         }
 
-        let _stdout = git(&args, Some(self.repo_path()))?;
+        let _stdout = git(&args, Some(self))?;
         Ok(())
     }
 
@@ -40,8 +54,10 @@ impl Repository {
     ///
     /// A `Result` indicating success or a `GitError` if the operation fails.
     pub fn add_all(&self) -> Result<()> {
-        Self::ensure_git()?;
-        let _stdout = git(&["add", "."], Some(self.repo_path()))?;
+        self.check_git()?;
+        let mut args = add_command(self)?;
+        args.push(".");
+        let _stdout = git(&args, Some(self))?;
         Ok(())
     }
 
@@ -51,8 +67,10 @@ impl Repository {
     ///
     /// A `Result` indicating success or a `GitError` if the operation fails.
     pub fn add_update(&self) -> Result<()> {
-        Self::ensure_git()?;
-        let _stdout = git(&["add", "-u"], Some(self.repo_path()))?;
+        self.check_git()?;
+        let mut args = add_command(self)?;
+        args.push("-u");
+        let _stdout = git(&args, Some(self))?;
         Ok(())
     }
 }
@@ -0,0 +1,382 @@
+//! Commit ancestry queries
+//!
+//! This module provides cheap ancestry checks and counts built on `git merge-base` and
+//! `git rev-list`, so callers can answer questions like "can this branch be
+//! fast-forwarded?" or "how far behind is it?" without loading full logs.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::Repository;
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! // Check whether main can be fast-forwarded to feature
+//! if repo.is_ancestor("main", "feature")? {
+//!     println!("main is an ancestor of feature");
+//! }
+//!
+//! // Find which of several refs are not reachable from the others
+//! let heads = repo.independent_heads(&["main", "feature", "hotfix"])?;
+//! println!("{} independent heads", heads.len());
+//!
+//! // Branch dashboard numbers
+//! let (ahead, behind) = repo.ahead_behind("feature", "main")?;
+//! println!("feature is {} ahead, {} behind main", ahead, behind);
+//! println!("{} commits since v1.0", repo.count_commits("v1.0..HEAD")?);
+//!
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, git_raw, validate_arg};
+
+impl Repository {
+    /// Check whether commit `ancestor` is an ancestor of commit `descendant`.
+    ///
+    /// Equivalent to `git merge-base --is-ancestor <ancestor> <descendant>`.
+    /// A commit is considered an ancestor of itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `ancestor` - The potential ancestor (hash, branch, tag, or other revision)
+    /// * `descendant` - The potential descendant revision
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if `ancestor` is reachable from `descendant`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let can_fast_forward = repo.is_ancestor("HEAD", "origin/main")?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn is_ancestor(&self, ancestor: &str, descendant: &str) -> Result<bool> {
+        self.check_git()?;
+        validate_arg("revision", ancestor)?;
+        validate_arg("revision", descendant)?;
+
+        let args = ["merge-base", "--is-ancestor", ancestor, descendant];
+        let output = git_raw(&args, Some(self))?;
+
+        // Exit code 0 means ancestor, 1 means not an ancestor, anything else is an error
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => Err(GitError::CommandFailed(format!(
+                "git merge-base failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+        }
+    }
+
+    /// Find the refs that cannot be reached from any other ref in the list.
+    ///
+    /// Equivalent to `git merge-base --independent <refs>`. Refs that are ancestors of
+    /// another ref in the list are dropped; the remaining commits are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `refs` - The revisions to compare
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the hashes of the independent heads.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let heads = repo.independent_heads(&["main", "feature"])?;
+    /// if heads.len() == 1 {
+    ///     println!("One branch contains the other");
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn independent_heads(&self, refs: &[&str]) -> Result<Vec<Hash>> {
+        self.check_git()?;
+
+        if refs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        for rev in refs {
+            validate_arg("revision", rev)?;
+        }
+
+        let mut args = vec!["merge-base", "--independent"];
+        args.extend_from_slice(refs);
+
+        let output = git(&args, Some(self))?;
+        Ok(parse_hash_lines(&output))
+    }
+
+    /// Find the best common ancestor of two commits.
+    ///
+    /// Equivalent to `git merge-base <a> <b>`. When there are several equally good
+    /// common ancestors (criss-cross merges), git picks one of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first revision
+    /// * `b` - The second revision
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the merge base, or `None` if the histories are unrelated.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// if let Some(base) = repo.merge_base("main", "feature")? {
+    ///     println!("feature forked from main at {}", base.short());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn merge_base(&self, a: &str, b: &str) -> Result<Option<Hash>> {
+        self.check_git()?;
+        validate_arg("revision", a)?;
+        validate_arg("revision", b)?;
+
+        let output = git_raw(&["merge-base", a, b], Some(self))?;
+
+        // Exit code 1 means the histories share no commits
+        match output.status.code() {
+            Some(0) => Ok(Some(Hash::from(
+                String::from_utf8_lossy(&output.stdout).trim(),
+            ))),
+            Some(1) => Ok(None),
+            _ => Err(GitError::CommandFailed(format!(
+                "git merge-base failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+        }
+    }
+
+    /// Count the commits in a revision range.
+    ///
+    /// Equivalent to `git rev-list --count <range>`, without loading the commits.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - A revision or range, such as `"HEAD"`, `"main..feature"`, or
+    ///   `"v1.0..HEAD"`
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of commits.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// println!("{} commits on feature", repo.count_commits("main..feature")?);
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn count_commits(&self, range: &str) -> Result<usize> {
+        self.check_git()?;
+        validate_arg("revision range", range)?;
+
+        let output = git(&["rev-list", "--count", range, "--"], Some(self))?;
+        output.trim().parse().map_err(|_| {
+            GitError::CommandFailed(format!(
+                "unexpected git rev-list --count output: {}",
+                output.trim()
+            ))
+        })
+    }
+
+    /// Count the commits only on `a` and only on `b`.
+    ///
+    /// Equivalent to `git rev-list --left-right --count <a>...<b>`. For a branch and
+    /// its upstream this is how far the branch is ahead of and behind the upstream.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The revision whose unique commits are counted first (e.g. a branch)
+    /// * `b` - The revision to compare against (e.g. its upstream)
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `(ahead, behind)`: commits reachable only from `a`, and
+    /// commits reachable only from `b`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let (ahead, behind) = repo.ahead_behind("HEAD", "origin/main")?;
+    /// println!("{} ahead, {} behind", ahead, behind);
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn ahead_behind(&self, a: &str, b: &str) -> Result<(usize, usize)> {
+        self.check_git()?;
+        validate_arg("revision", a)?;
+        validate_arg("revision", b)?;
+
+        let range = format!("{}...{}", a, b);
+        let output = git(
+            &["rev-list", "--left-right", "--count", &range, "--"],
+            Some(self),
+        )?;
+        let mut counts = output.split_whitespace().map(str::parse::<usize>);
+        match (counts.next(), counts.next()) {
+            (Some(Ok(ahead)), Some(Ok(behind))) => Ok((ahead, behind)),
+            _ => Err(GitError::CommandFailed(format!(
+                "unexpected git rev-list --count output: {}",
+                output.trim()
+            ))),
+        }
+    }
+}
+
+/// Parse one hash per line, skipping blank lines
+fn parse_hash_lines(output: &str) -> Vec<Hash> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(Hash::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+    use std::{env, fs};
+
+    fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
+        let temp_dir = env::temp_dir().join(format!("rustic_git_ancestry_test_{}", test_name));
+
+        // Clean up if exists
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+
+        // Configure git user for testing
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        (temp_dir, repo)
+    }
+
+    fn create_file_and_commit(
+        repo: &Repository,
+        temp_dir: &Path,
+        filename: &str,
+        content: &str,
+        message: &str,
+    ) -> Hash {
+        fs::write(temp_dir.join(filename), content).unwrap();
+        repo.add(&[filename]).unwrap();
+        repo.commit(message).unwrap()
+    }
+
+    #[test]
+    fn test_parse_hash_lines() {
+        let hashes = parse_hash_lines("abc123\n\ndef456\n");
+        assert_eq!(hashes, vec![Hash::from("abc123"), Hash::from("def456")]);
+        assert!(parse_hash_lines("").is_empty());
+    }
+
+    #[test]
+    fn test_is_ancestor() {
+        let (temp_dir, repo) = create_test_repo("is_ancestor");
+
+        let first = create_file_and_commit(&repo, &temp_dir, "a.txt", "a", "First");
+        let second = create_file_and_commit(&repo, &temp_dir, "b.txt", "b", "Second");
+
+        assert!(repo.is_ancestor(first.as_str(), second.as_str()).unwrap());
+        assert!(!repo.is_ancestor(second.as_str(), first.as_str()).unwrap());
+        assert!(repo.is_ancestor(second.as_str(), "HEAD").unwrap());
+
+        // Unknown revisions are an error, not "false"
+        assert!(repo.is_ancestor("does-not-exist", "HEAD").is_err());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_independent_heads() {
+        let (temp_dir, repo) = create_test_repo("independent_heads");
+
+        let base = create_file_and_commit(&repo, &temp_dir, "base.txt", "base", "Base");
+        repo.create_branch("feature", None).unwrap();
+        let main_tip = create_file_and_commit(&repo, &temp_dir, "main.txt", "main", "Main");
+
+        // feature still points at base, which is an ancestor of HEAD
+        let heads = repo.independent_heads(&["HEAD", "feature"]).unwrap();
+        assert_eq!(heads, vec![main_tip.clone()]);
+
+        // Diverge feature so both heads are independent
+        repo.checkout_new("topic", Some(base.as_str())).unwrap();
+        let topic_tip = create_file_and_commit(&repo, &temp_dir, "topic.txt", "topic", "Topic");
+
+        let heads = repo
+            .independent_heads(&[main_tip.as_str(), topic_tip.as_str()])
+            .unwrap();
+        assert_eq!(heads.len(), 2);
+        assert!(heads.contains(&main_tip));
+        assert!(heads.contains(&topic_tip));
+
+        assert!(repo.independent_heads(&[]).unwrap().is_empty());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_base_and_counts() {
+        let (temp_dir, repo) = create_test_repo("merge_base_counts");
+
+        let base = create_file_and_commit(&repo, &temp_dir, "base.txt", "base", "Base");
+        let main = repo.current_branch().unwrap().unwrap();
+        repo.checkout_new("feature", None).unwrap();
+        create_file_and_commit(&repo, &temp_dir, "f1.txt", "f1", "Feature 1");
+        create_file_and_commit(&repo, &temp_dir, "f2.txt", "f2", "Feature 2");
+        repo.checkout(&main).unwrap();
+        create_file_and_commit(&repo, &temp_dir, "main.txt", "main", "Main");
+
+        assert_eq!(
+            repo.merge_base("HEAD", "feature").unwrap(),
+            Some(base.clone())
+        );
+        assert_eq!(repo.ahead_behind("feature", "HEAD").unwrap(), (2, 1));
+        assert_eq!(repo.ahead_behind("HEAD", "HEAD").unwrap(), (0, 0));
+        assert_eq!(repo.count_commits("HEAD").unwrap(), 2);
+        assert_eq!(repo.count_commits("HEAD..feature").unwrap(), 2);
+        assert_eq!(repo.count_commits(base.as_str()).unwrap(), 1);
+
+        // Unrelated histories have no merge base
+        git(&["checkout", "--orphan", "orphan"], Some(&repo)).unwrap();
+        create_file_and_commit(&repo, &temp_dir, "orphan.txt", "orphan", "Orphan");
+        assert_eq!(repo.merge_base("orphan", "feature").unwrap(), None);
+        assert_eq!(repo.ahead_behind("orphan", "feature").unwrap(), (1, 3));
+
+        assert!(repo.count_commits("does-not-exist").is_err());
+        assert!(repo.merge_base("--all", "HEAD").is_err());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
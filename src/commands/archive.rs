@@ -0,0 +1,274 @@
+//! Git archive operations
+//!
+//! This module exports the files of a commit, tag or tree as a tar or zip archive with
+//! `git archive`, so release tooling can produce source archives straight from a tag.
+//! Only tracked content is included; `export-ignore` attributes are honoured.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{ArchiveFormat, ArchiveOptions, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! // Source release with every file under a `project-1.0/` directory
+//! let options = ArchiveOptions::new().with_prefix("project-1.0/");
+//! repo.archive_with_options("v1.0", ArchiveFormat::TarGz, "project-1.0.tar.gz", options)?;
+//!
+//! // Only the docs, in memory
+//! let options = ArchiveOptions::new().with_path("docs");
+//! let bytes = repo.archive_to_bytes("v1.0", ArchiveFormat::Zip, options)?;
+//! println!("{} bytes", bytes.len());
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::utils::{git_raw, validate_arg};
+use std::path::{Path, PathBuf};
+
+/// Archive formats supported by `git archive`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Uncompressed tar
+    Tar,
+    /// Gzip-compressed tar
+    TarGz,
+    /// Zip
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// The `--format` value git uses for this format
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Tar => "tar",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::Zip => "zip",
+        }
+    }
+}
+
+/// Options for archive operations
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveOptions {
+    prefix: Option<String>,
+    paths: Vec<PathBuf>,
+}
+
+impl ArchiveOptions {
+    /// Create new ArchiveOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prepend `prefix` to every path in the archive (`--prefix`)
+    ///
+    /// Use a trailing slash, e.g. `project-1.0/`, to put the files in a directory.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Only include files matching `path`; can be called repeatedly
+    pub fn with_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+}
+
+impl Repository {
+    /// Write an archive of `treeish` to `output_path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `treeish` - The commit, tag or tree to export
+    /// * `format` - The archive format
+    /// * `output_path` - Where to write the archive; relative paths are resolved
+    ///   against the repository's working directory
+    pub fn archive<P: AsRef<Path>>(
+        &self,
+        treeish: &str,
+        format: ArchiveFormat,
+        output_path: P,
+    ) -> Result<()> {
+        self.archive_with_options(treeish, format, output_path, ArchiveOptions::new())
+    }
+
+    /// Write an archive of `treeish` to `output_path` with custom options.
+    pub fn archive_with_options<P: AsRef<Path>>(
+        &self,
+        treeish: &str,
+        format: ArchiveFormat,
+        output_path: P,
+        options: ArchiveOptions,
+    ) -> Result<()> {
+        self.check_git()?;
+
+        let output = format!("--output={}", output_path.as_ref().display());
+        self.run_archive(treeish, format, Some(&output), &options)?;
+        Ok(())
+    }
+
+    /// Build an archive of `treeish` in memory.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the archive bytes.
+    pub fn archive_to_bytes(
+        &self,
+        treeish: &str,
+        format: ArchiveFormat,
+        options: ArchiveOptions,
+    ) -> Result<Vec<u8>> {
+        self.check_git()?;
+        self.run_archive(treeish, format, None, &options)
+    }
+
+    /// Run `git archive`, returning its stdout
+    fn run_archive(
+        &self,
+        treeish: &str,
+        format: ArchiveFormat,
+        output: Option<&str>,
+        options: &ArchiveOptions,
+    ) -> Result<Vec<u8>> {
+        validate_arg("tree-ish", treeish)?;
+
+        let format_arg = format!("--format={}", format.as_str());
+        let prefix_arg = options
+            .prefix
+            .as_ref()
+            .map(|prefix| format!("--prefix={}", prefix));
+        let paths: Vec<String> = options
+            .paths
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+
+        let mut args = vec!["archive", &format_arg];
+        if let Some(ref prefix_arg) = prefix_arg {
+            args.push(prefix_arg);
+        }
+        if let Some(output) = output {
+            args.push(output);
+        }
+        args.push(treeish);
+        if !paths.is_empty() {
+            args.push("--");
+            args.extend(paths.iter().map(String::as_str));
+        }
+
+        let output = git_raw(&args, Some(self))?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git archive failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(output.stdout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use std::fs;
+
+    /// List the entries of a tar archive, skipping git's pax global header
+    fn tar_entries(bytes: &[u8]) -> Vec<String> {
+        let mut entries = Vec::new();
+        // Walk the 512-byte tar headers; names are NUL-terminated in the first 100 bytes
+        let mut offset = 0;
+        while offset + 512 <= bytes.len() {
+            let header = &bytes[offset..offset + 512];
+            if header.iter().all(|&b| b == 0) {
+                break;
+            }
+            let name_end = header[..100].iter().position(|&b| b == 0).unwrap_or(100);
+            let name = String::from_utf8_lossy(&header[..name_end]).to_string();
+            let size_field = String::from_utf8_lossy(&header[124..136]);
+            let size = usize::from_str_radix(size_field.trim_matches(['\0', ' ']), 8).unwrap_or(0);
+            if header[156] != b'g' {
+                entries.push(name);
+            }
+            offset += 512 + size.div_ceil(512) * 512;
+        }
+        entries
+    }
+
+    fn setup(name: &str) -> TempRepo {
+        let repo = TempRepo::new(name).unwrap();
+        repo.commit_files(
+            &[("README.md", "readme\n"), ("docs/guide.md", "guide\n")],
+            "Initial",
+        )
+        .unwrap();
+        repo.create_tag("v1.0", None).unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_archive_to_bytes() {
+        let repo = setup("archive_bytes");
+        repo.commit_file("later.txt", "later\n", "Later").unwrap();
+
+        let bytes = repo
+            .archive_to_bytes("v1.0", ArchiveFormat::Tar, ArchiveOptions::new())
+            .unwrap();
+        let entries = tar_entries(&bytes);
+        assert!(entries.contains(&"README.md".to_string()));
+        assert!(entries.contains(&"docs/guide.md".to_string()));
+        assert!(!entries.contains(&"later.txt".to_string()));
+
+        let options = ArchiveOptions::new()
+            .with_prefix("project-1.0/")
+            .with_path("docs");
+        let bytes = repo
+            .archive_to_bytes("v1.0", ArchiveFormat::Tar, options)
+            .unwrap();
+        assert_eq!(
+            tar_entries(&bytes),
+            vec![
+                "project-1.0/",
+                "project-1.0/docs/",
+                "project-1.0/docs/guide.md"
+            ]
+        );
+
+        let zip = repo
+            .archive_to_bytes("HEAD", ArchiveFormat::Zip, ArchiveOptions::new())
+            .unwrap();
+        assert!(zip.starts_with(b"PK\x03\x04"));
+        let gz = repo
+            .archive_to_bytes("HEAD", ArchiveFormat::TarGz, ArchiveOptions::new())
+            .unwrap();
+        assert!(gz.starts_with(&[0x1f, 0x8b]));
+
+        assert!(
+            repo.archive_to_bytes("--list", ArchiveFormat::Tar, ArchiveOptions::new())
+                .is_err()
+        );
+        assert!(
+            repo.archive_to_bytes("missing", ArchiveFormat::Tar, ArchiveOptions::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_archive_to_file() {
+        let repo = setup("archive_file");
+        let output = repo.path().with_extension("zip");
+
+        repo.archive("v1.0", ArchiveFormat::Zip, &output).unwrap();
+        let bytes = fs::read(&output).unwrap();
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        assert_eq!(
+            bytes,
+            repo.archive_to_bytes("v1.0", ArchiveFormat::Zip, ArchiveOptions::new())
+                .unwrap()
+        );
+        fs::remove_file(&output).unwrap();
+    }
+}
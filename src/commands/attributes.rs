@@ -0,0 +1,441 @@
+//! Custom merge drivers and clean/smudge filters
+//!
+//! Git only applies a merge driver or filter when two things line up: the driver is
+//! defined in config (`merge.<name>.*` / `filter.<name>.*`) and a `.gitattributes`
+//! entry assigns it to matching paths. The helpers here write both halves, so
+//! domain-specific formats (lockfiles, generated code, binary assets) get the right
+//! merge and checkout behavior.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{FilterDriver, MergeDriver, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! // Regenerate lockfiles instead of merging them line by line
+//! let driver = MergeDriver::new("lockfile", "npm install --package-lock-only && cp package-lock.json %A")
+//!     .with_description("regenerate lockfile");
+//! repo.register_merge_driver(&driver, &["package-lock.json"])?;
+//!
+//! // Strip notebook outputs before they are staged
+//! let filter = FilterDriver::new("nbstrip")
+//!     .with_clean("jupyter nbconvert --clear-output --stdin --stdout")
+//!     .with_smudge("cat");
+//! repo.register_filter(&filter, &["*.ipynb"])?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::GitError;
+use crate::utils::git;
+use crate::{Repository, Result};
+use std::fs;
+use std::path::Path;
+
+/// A custom merge driver (`merge.<name>.*`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeDriver {
+    /// The driver name referenced by `merge=<name>` attributes
+    pub name: String,
+    /// The command to run; git substitutes `%O`, `%A`, `%B`, `%L` and `%P`
+    pub driver: String,
+    /// Human-readable description (`merge.<name>.name`)
+    pub description: Option<String>,
+    /// Driver used for internal merges of common ancestors (`merge.<name>.recursive`)
+    pub recursive: Option<String>,
+}
+
+impl MergeDriver {
+    /// Create a merge driver running `driver` for paths assigned to `name`
+    pub fn new(name: impl Into<String>, driver: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            driver: driver.into(),
+            description: None,
+            recursive: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_recursive(mut self, recursive: impl Into<String>) -> Self {
+        self.recursive = Some(recursive.into());
+        self
+    }
+
+    /// The `.gitattributes` assignment for this driver
+    pub fn attribute(&self) -> String {
+        format!("merge={}", self.name)
+    }
+}
+
+/// A clean/smudge filter (`filter.<name>.*`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterDriver {
+    /// The filter name referenced by `filter=<name>` attributes
+    pub name: String,
+    /// Command converting worktree content to the stored blob
+    pub clean: Option<String>,
+    /// Command converting the stored blob to worktree content
+    pub smudge: Option<String>,
+    /// Long-running filter process command (`filter.<name>.process`)
+    pub process: Option<String>,
+    /// Treat filter failures as errors instead of passing content through
+    pub required: bool,
+}
+
+impl FilterDriver {
+    /// Create a filter with no commands configured
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            clean: None,
+            smudge: None,
+            process: None,
+            required: false,
+        }
+    }
+
+    pub fn with_clean(mut self, clean: impl Into<String>) -> Self {
+        self.clean = Some(clean.into());
+        self
+    }
+
+    pub fn with_smudge(mut self, smudge: impl Into<String>) -> Self {
+        self.smudge = Some(smudge.into());
+        self
+    }
+
+    pub fn with_process(mut self, process: impl Into<String>) -> Self {
+        self.process = Some(process.into());
+        self
+    }
+
+    pub fn with_required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// The `.gitattributes` assignment for this filter
+    pub fn attribute(&self) -> String {
+        format!("filter={}", self.name)
+    }
+}
+
+impl Repository {
+    /// Define a merge driver in config and assign it to paths in `.gitattributes`
+    ///
+    /// # Arguments
+    /// * `driver` - The merge driver definition
+    /// * `patterns` - `.gitattributes` patterns the driver applies to
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use rustic_git::{MergeDriver, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// // Always keep our version of generated files
+    /// repo.register_merge_driver(&MergeDriver::new("ours", "true"), &["generated/**"])?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn register_merge_driver(&self, driver: &MergeDriver, patterns: &[&str]) -> Result<()> {
+        self.check_git()?;
+        validate_driver_name(&driver.name)?;
+
+        let section = format!("merge.{}", driver.name);
+        let config = self.config();
+        config.set(&format!("{}.driver", section), &driver.driver)?;
+        if let Some(description) = &driver.description {
+            config.set(&format!("{}.name", section), description)?;
+        }
+        if let Some(recursive) = &driver.recursive {
+            config.set(&format!("{}.recursive", section), recursive)?;
+        }
+
+        self.attributes_add(patterns, &driver.attribute())
+    }
+
+    /// Define a clean/smudge filter in config and assign it to paths in `.gitattributes`
+    ///
+    /// # Arguments
+    /// * `filter` - The filter definition
+    /// * `patterns` - `.gitattributes` patterns the filter applies to
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use rustic_git::{FilterDriver, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let filter = FilterDriver::new("lfs")
+    ///     .with_process("git-lfs filter-process")
+    ///     .with_required();
+    /// repo.register_filter(&filter, &["*.psd", "*.zip"])?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn register_filter(&self, filter: &FilterDriver, patterns: &[&str]) -> Result<()> {
+        self.check_git()?;
+        validate_driver_name(&filter.name)?;
+
+        let section = format!("filter.{}", filter.name);
+        let config = self.config();
+        if let Some(clean) = &filter.clean {
+            config.set(&format!("{}.clean", section), clean)?;
+        }
+        if let Some(smudge) = &filter.smudge {
+            config.set(&format!("{}.smudge", section), smudge)?;
+        }
+        if let Some(process) = &filter.process {
+            config.set(&format!("{}.process", section), process)?;
+        }
+        if filter.required {
+            config.set(&format!("{}.required", section), "true")?;
+        }
+
+        self.attributes_add(patterns, &filter.attribute())
+    }
+
+    /// Append `<pattern> <attribute>` lines to the root `.gitattributes`
+    ///
+    /// Lines that are already present are skipped, so repeated calls are safe.
+    /// Patterns containing whitespace are quoted.
+    ///
+    /// # Arguments
+    /// * `patterns` - Path patterns to assign the attribute to
+    /// * `attribute` - The attribute assignment, e.g. `merge=union` or `-diff`
+    pub fn attributes_add(&self, patterns: &[&str], attribute: &str) -> Result<()> {
+        let attributes_path = self.repo_path().join(".gitattributes");
+        let existing_content = if attributes_path.exists() {
+            fs::read_to_string(&attributes_path)?
+        } else {
+            String::new()
+        };
+
+        let mut content = existing_content.clone();
+        for pattern in patterns {
+            let line = format!("{} {}", quote_pattern(pattern), attribute);
+            if content.lines().any(|existing| existing.trim() == line) {
+                continue;
+            }
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&line);
+            content.push('\n');
+        }
+
+        if content != existing_content {
+            fs::write(attributes_path, content)?;
+        }
+        Ok(())
+    }
+
+    /// Get the value of a gitattribute for a path
+    ///
+    /// # Arguments
+    /// * `path` - Path to check, relative to the working directory
+    /// * `attribute` - The attribute name, e.g. `merge` or `filter`
+    ///
+    /// # Returns
+    /// * `Ok(Some(value))` with the value, or `set`/`unset` for boolean attributes
+    /// * `Ok(None)` if the attribute is unspecified for the path
+    pub fn check_attr<P: AsRef<Path>>(&self, path: P, attribute: &str) -> Result<Option<String>> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let output = git(
+            &["check-attr", "-z", attribute, "--", &path_str],
+            Some(self),
+        )?;
+
+        // Output is `<path>\0<attribute>\0<value>\0`
+        let value = output.split('\0').nth(2).ok_or_else(|| {
+            GitError::CommandFailed(format!("unexpected git check-attr output: {:?}", output))
+        })?;
+
+        Ok(match value {
+            "unspecified" => None,
+            value => Some(value.to_string()),
+        })
+    }
+}
+
+/// Driver names become config subsections and attribute values, so they cannot
+/// contain whitespace, `=` or quotes
+fn validate_driver_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || c == '=' || c == '"' || c == '\\')
+    {
+        return Err(GitError::CommandFailed(format!(
+            "Invalid driver name: {:?}",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Quote a `.gitattributes` pattern in C style if it contains whitespace
+fn quote_pattern(pattern: &str) -> String {
+    if !pattern.chars().any(char::is_whitespace) {
+        return pattern.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in pattern.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
+        let temp_dir = env::temp_dir().join(format!("rustic_git_attributes_test_{}", test_name));
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_attributes_add_is_idempotent() {
+        let (temp_dir, repo) = create_test_repo("attributes_add");
+
+        repo.attributes_add(&["*.lock", "my file.txt"], "merge=union")
+            .unwrap();
+        repo.attributes_add(&["*.lock"], "merge=union").unwrap();
+        repo.attributes_add(&["*.lock"], "-diff").unwrap();
+
+        let content = fs::read_to_string(temp_dir.join(".gitattributes")).unwrap();
+        assert_eq!(
+            content,
+            "*.lock merge=union\n\"my file.txt\" merge=union\n*.lock -diff\n"
+        );
+
+        assert_eq!(
+            repo.check_attr("Cargo.lock", "merge").unwrap(),
+            Some("union".to_string())
+        );
+        assert_eq!(
+            repo.check_attr("my file.txt", "merge").unwrap(),
+            Some("union".to_string())
+        );
+        assert_eq!(
+            repo.check_attr("Cargo.lock", "diff").unwrap(),
+            Some("unset".to_string())
+        );
+        assert_eq!(repo.check_attr("main.rs", "merge").unwrap(), None);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_register_merge_driver_resolves_conflict() {
+        let (temp_dir, repo) = create_test_repo("merge_driver");
+
+        let driver = MergeDriver::new("keep-ours", "true").with_description("keep our version");
+        repo.register_merge_driver(&driver, &["*.gen"]).unwrap();
+        assert_eq!(repo.config().get("merge.keep-ours.driver").unwrap(), "true");
+        assert_eq!(
+            repo.config().get("merge.keep-ours.name").unwrap(),
+            "keep our version"
+        );
+
+        fs::write(temp_dir.join("data.gen"), "base\n").unwrap();
+        repo.add(&[".gitattributes", "data.gen"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+        let base_branch = repo.current_branch().unwrap().unwrap();
+
+        repo.checkout_new("feature", None).unwrap();
+        fs::write(temp_dir.join("data.gen"), "feature\n").unwrap();
+        repo.add(&["data.gen"]).unwrap();
+        repo.commit("Feature change").unwrap();
+
+        repo.checkout(&base_branch).unwrap();
+        fs::write(temp_dir.join("data.gen"), "main\n").unwrap();
+        repo.add(&["data.gen"]).unwrap();
+        repo.commit("Main change").unwrap();
+
+        let status = repo.merge("feature").unwrap();
+        assert!(
+            matches!(status, crate::MergeStatus::Success(_)),
+            "expected driver to resolve the merge, got {:?}",
+            status
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("data.gen")).unwrap(),
+            "main\n"
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_register_filter_applies_clean() {
+        let (temp_dir, repo) = create_test_repo("filter");
+
+        let filter = FilterDriver::new("upper")
+            .with_clean("tr a-z A-Z")
+            .with_smudge("cat")
+            .with_required();
+        repo.register_filter(&filter, &["*.txt"]).unwrap();
+        assert_eq!(
+            repo.config().get("filter.upper.clean").unwrap(),
+            "tr a-z A-Z"
+        );
+        assert_eq!(repo.config().get("filter.upper.required").unwrap(), "true");
+        assert_eq!(
+            repo.check_attr("notes.txt", "filter").unwrap(),
+            Some("upper".to_string())
+        );
+
+        fs::write(temp_dir.join("notes.txt"), "hello\n").unwrap();
+        repo.add(&["notes.txt"]).unwrap();
+        let blob = git(&["show", ":notes.txt"], Some(&repo)).unwrap();
+        assert_eq!(blob, "HELLO\n");
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_register_rejects_invalid_names() {
+        let (temp_dir, repo) = create_test_repo("invalid_name");
+
+        let result = repo.register_filter(&FilterDriver::new("bad name"), &["*.txt"]);
+        assert!(matches!(result, Err(GitError::CommandFailed(_))));
+        let result = repo.register_merge_driver(&MergeDriver::new("", "true"), &["*.txt"]);
+        assert!(matches!(result, Err(GitError::CommandFailed(_))));
+        assert!(!temp_dir.join(".gitattributes").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_quote_pattern() {
+        assert_eq!(quote_pattern("*.txt"), "*.txt");
+        assert_eq!(quote_pattern("a b.txt"), "\"a b.txt\"");
+        assert_eq!(quote_pattern("a \"b\".txt"), "\"a \\\"b\\\".txt\"");
+    }
+}
@@ -0,0 +1,717 @@
+//! Line-by-line authorship annotation
+//!
+//! This module wraps `git blame`. [`Repository::blame`] attributes every line of a file
+//! at once, parsed from `--porcelain` output. [`Repository::blame_incremental`] streams
+//! results while git is still walking history, so editors can annotate large files
+//! progressively instead of waiting for the whole file to be attributed.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{BlameOptions, Repository};
+//! use std::ops::ControlFlow;
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! let options = BlameOptions::new().with_lines(10, 20).with_ignore_whitespace();
+//! for line in repo.blame_with_options("src/lib.rs", options)?.iter() {
+//!     println!("{:>4} {} {:<16} {}", line.line_no, line.hash.short(), line.author, line.content);
+//! }
+//!
+//! repo.blame_incremental("src/lib.rs", |hunk| {
+//!     println!(
+//!         "lines {}..{} from {} by {}",
+//!         hunk.final_start,
+//!         hunk.final_end(),
+//!         hunk.hash.short(),
+//!         hunk.author.name
+//!     );
+//!     ControlFlow::Continue(())
+//! })?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::log::Author;
+use crate::error::GitError;
+use crate::types::Hash;
+use crate::utils::{git, git_spawn_stdout, parse_unix_timestamp};
+use crate::{Repository, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+
+/// A run of consecutive lines attributed to the same commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameHunk {
+    /// The commit that last changed these lines
+    pub hash: Hash,
+    /// First line number (1-based) of the hunk in the commit that introduced it
+    pub orig_start: usize,
+    /// First line number (1-based) of the hunk in the blamed file
+    pub final_start: usize,
+    /// Number of lines in the hunk
+    pub line_count: usize,
+    pub author: Author,
+    pub committer: Author,
+    /// First line of the commit message
+    pub summary: String,
+    /// Path of the file in the commit that introduced these lines
+    pub filename: PathBuf,
+    /// Whether the commit is a boundary of the blamed range (e.g. a root commit)
+    pub boundary: bool,
+}
+
+impl BlameHunk {
+    /// Line number (1-based, exclusive) just past the end of the hunk in the blamed file
+    pub fn final_end(&self) -> usize {
+        self.final_start + self.line_count
+    }
+
+    /// Whether these lines are not committed yet
+    pub fn is_uncommitted(&self) -> bool {
+        self.hash.as_str().bytes().all(|b| b == b'0')
+    }
+}
+
+/// A line of a file with the commit that last changed it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    /// The commit that last changed this line
+    pub hash: Hash,
+    /// Name of the commit's author
+    pub author: String,
+    /// Email of the commit's author
+    pub author_email: String,
+    /// When the commit was authored
+    pub timestamp: DateTime<Utc>,
+    /// First line of the commit message
+    pub summary: String,
+    /// Line number (1-based) in the blamed file
+    pub line_no: usize,
+    /// Line number (1-based) in the commit that introduced the line
+    pub orig_line_no: usize,
+    /// Path of the file in the commit that introduced the line
+    pub filename: PathBuf,
+    /// The line's text, without the trailing newline
+    pub content: String,
+    /// Whether the commit is a boundary of the blamed range (e.g. a root commit)
+    pub boundary: bool,
+}
+
+impl BlameLine {
+    /// Whether this line is not committed yet
+    pub fn is_uncommitted(&self) -> bool {
+        self.hash.as_str().bytes().all(|b| b == b'0')
+    }
+}
+
+/// The attributed lines of a file, in line order
+#[derive(Debug, Clone)]
+pub struct BlameOutput {
+    lines: Box<[BlameLine]>,
+}
+
+impl BlameOutput {
+    /// Create a new BlameOutput from attributed lines
+    pub fn new(mut lines: Vec<BlameLine>) -> Self {
+        lines.sort_by_key(|line| line.line_no);
+        Self {
+            lines: lines.into_boxed_slice(),
+        }
+    }
+
+    /// Get an iterator over all lines
+    pub fn iter(&self) -> impl Iterator<Item = &BlameLine> + '_ {
+        self.lines.iter()
+    }
+
+    /// Get the line with the given 1-based line number
+    pub fn line(&self, line_no: usize) -> Option<&BlameLine> {
+        self.lines
+            .binary_search_by_key(&line_no, |line| line.line_no)
+            .ok()
+            .map(|index| &self.lines[index])
+    }
+
+    /// Get the lines last changed by a commit
+    pub fn by_commit<'a>(&'a self, hash: &'a Hash) -> impl Iterator<Item = &'a BlameLine> + 'a {
+        self.lines.iter().filter(move |line| &line.hash == hash)
+    }
+
+    /// Get the distinct commits in line order of first appearance
+    pub fn commits(&self) -> Vec<&Hash> {
+        let mut commits: Vec<&Hash> = Vec::new();
+        for line in self.lines.iter() {
+            if !commits.contains(&&line.hash) {
+                commits.push(&line.hash);
+            }
+        }
+        commits
+    }
+
+    /// Get the number of lines
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Check if no lines were attributed
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+/// Options for blaming a file
+#[derive(Debug, Clone, Default)]
+pub struct BlameOptions {
+    /// Only blame lines `start..=end` (1-based, `-L <start>,<end>`)
+    pub lines: Option<(usize, usize)>,
+    /// Ignore whitespace when finding where lines came from (`-w`)
+    pub ignore_whitespace: bool,
+    /// Follow lines moved or copied within the file (`-M`)
+    pub detect_moves: bool,
+    /// Follow lines moved or copied from other files in the same commit (`-C`)
+    pub detect_copies: bool,
+}
+
+impl BlameOptions {
+    /// Create new default blame options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only blame lines `start` through `end` (inclusive, 1-based)
+    pub fn with_lines(mut self, start: usize, end: usize) -> Self {
+        self.lines = Some((start, end));
+        self
+    }
+
+    /// Ignore whitespace changes
+    pub fn with_ignore_whitespace(mut self) -> Self {
+        self.ignore_whitespace = true;
+        self
+    }
+
+    /// Attribute lines moved within the file to their original commit
+    pub fn with_detect_moves(mut self) -> Self {
+        self.detect_moves = true;
+        self
+    }
+
+    /// Attribute lines copied from other files to their original commit
+    pub fn with_detect_copies(mut self) -> Self {
+        self.detect_copies = true;
+        self
+    }
+}
+
+/// Per-commit details, which `--incremental` and `--porcelain` only print the first
+/// time a commit appears
+#[derive(Debug, Clone, Default)]
+struct CommitInfo {
+    author_name: String,
+    author_mail: String,
+    author_time: String,
+    committer_name: String,
+    committer_mail: String,
+    committer_time: String,
+    summary: String,
+    boundary: bool,
+    filename: String,
+}
+
+/// Incremental state machine over `git blame --incremental` output
+#[derive(Default)]
+struct IncrementalParser {
+    commits: HashMap<String, CommitInfo>,
+    current: Option<(String, usize, usize, usize)>,
+}
+
+impl IncrementalParser {
+    /// Feed one output line, returning a hunk once its `filename` line is reached
+    fn push_line(&mut self, line: &str) -> Result<Option<BlameHunk>> {
+        let Some((hash, orig_start, final_start, line_count)) = self.current.clone() else {
+            self.current = Some(parse_hunk_header(line)?);
+            return Ok(None);
+        };
+
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        let info = self.commits.entry(hash.clone()).or_default();
+        match key {
+            "author" => info.author_name = value.to_string(),
+            "author-mail" => info.author_mail = strip_angles(value),
+            "author-time" => info.author_time = value.to_string(),
+            "committer" => info.committer_name = value.to_string(),
+            "committer-mail" => info.committer_mail = strip_angles(value),
+            "committer-time" => info.committer_time = value.to_string(),
+            "summary" => info.summary = value.to_string(),
+            "boundary" => info.boundary = true,
+            "filename" => {
+                let info = info.clone();
+                self.current = None;
+                return Ok(Some(BlameHunk {
+                    hash: Hash::from(hash),
+                    orig_start,
+                    final_start,
+                    line_count,
+                    author: Author {
+                        name: info.author_name,
+                        email: info.author_mail,
+                        timestamp: parse_unix_timestamp(&info.author_time)?,
+                    },
+                    committer: Author {
+                        name: info.committer_name,
+                        email: info.committer_mail,
+                        timestamp: parse_unix_timestamp(&info.committer_time)?,
+                    },
+                    summary: info.summary,
+                    filename: PathBuf::from(value),
+                    boundary: info.boundary,
+                }));
+            }
+            // author-tz, committer-tz, previous, and any future headers
+            _ => {}
+        }
+        Ok(None)
+    }
+}
+
+/// Parse `git blame --porcelain` output: a `<hash> <orig> <final>[ <count>]` header,
+/// commit details the first time a commit appears, and the line itself after a tab
+fn parse_porcelain(output: &str) -> Result<Vec<BlameLine>> {
+    let mut commits: HashMap<&str, CommitInfo> = HashMap::new();
+    let mut current: Option<(&str, usize, usize)> = None;
+    let mut lines = Vec::new();
+
+    for line in output.lines() {
+        let Some((hash, orig_line_no, line_no)) = current else {
+            if line.is_empty() {
+                continue;
+            }
+            current = Some(parse_porcelain_header(line)?);
+            continue;
+        };
+
+        let info = commits.entry(hash).or_default();
+        if let Some(content) = line.strip_prefix('\t') {
+            current = None;
+            lines.push(BlameLine {
+                hash: Hash::from(hash),
+                author: info.author_name.clone(),
+                author_email: info.author_mail.clone(),
+                timestamp: parse_unix_timestamp(&info.author_time)?,
+                summary: info.summary.clone(),
+                line_no,
+                orig_line_no,
+                filename: PathBuf::from(&info.filename),
+                content: content.to_string(),
+                boundary: info.boundary,
+            });
+            continue;
+        }
+
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => info.author_name = value.to_string(),
+            "author-mail" => info.author_mail = strip_angles(value),
+            "author-time" => info.author_time = value.to_string(),
+            "summary" => info.summary = value.to_string(),
+            "boundary" => info.boundary = true,
+            "filename" => info.filename = value.to_string(),
+            // committer-*, author-tz, previous, and any future headers
+            _ => {}
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Parse `<hash> <orig> <final>[ <count>]`; the count only starts a group of lines
+fn parse_porcelain_header(line: &str) -> Result<(&str, usize, usize)> {
+    let invalid = || GitError::CommandFailed(format!("Invalid blame line header: {}", line));
+    let mut parts = line.split(' ');
+    let hash = parts.next().filter(|h| !h.is_empty()).ok_or_else(invalid)?;
+    let mut number = || {
+        parts
+            .next()
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or_else(invalid)
+    };
+    let orig_line_no = number()?;
+    let line_no = number()?;
+    Ok((hash, orig_line_no, line_no))
+}
+
+fn parse_hunk_header(line: &str) -> Result<(String, usize, usize, usize)> {
+    let invalid = || GitError::CommandFailed(format!("Invalid blame hunk header: {}", line));
+    let mut parts = line.split(' ');
+    let hash = parts.next().filter(|h| !h.is_empty()).ok_or_else(invalid)?;
+    let mut number = || {
+        parts
+            .next()
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or_else(invalid)
+    };
+    let orig_start = number()?;
+    let final_start = number()?;
+    let line_count = number()?;
+    Ok((hash.to_string(), orig_start, final_start, line_count))
+}
+
+fn strip_angles(value: &str) -> String {
+    value
+        .strip_prefix('<')
+        .and_then(|v| v.strip_suffix('>'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+impl Repository {
+    /// Attribute every line of a file to the commit that last changed it
+    ///
+    /// Lines changed in the working tree are attributed to the all-zero hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to annotate, relative to the repository root
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the [`BlameOutput`] or a `GitError` if blame fails.
+    pub fn blame<P: AsRef<Path>>(&self, path: P) -> Result<BlameOutput> {
+        self.blame_with_options(path, BlameOptions::new())
+    }
+
+    /// Attribute lines of a file with custom options
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to annotate, relative to the repository root
+    /// * `options` - Line range and move/copy detection settings
+    pub fn blame_with_options<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: BlameOptions,
+    ) -> Result<BlameOutput> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let range;
+        let mut args = vec!["blame", "--porcelain"];
+        if let Some((start, end)) = options.lines {
+            range = format!("{},{}", start, end);
+            args.push("-L");
+            args.push(&range);
+        }
+        if options.ignore_whitespace {
+            args.push("-w");
+        }
+        if options.detect_moves {
+            args.push("-M");
+        }
+        if options.detect_copies {
+            args.push("-C");
+        }
+        args.push("--");
+        args.push(&path_str);
+
+        let output = git(&args, Some(self))?;
+        Ok(BlameOutput::new(parse_porcelain(&output)?))
+    }
+
+    /// Annotate a file progressively, invoking `callback` for each hunk as soon as
+    /// git has attributed it.
+    ///
+    /// Hunks arrive in the order git resolves them, not in line order; each is
+    /// delivered exactly once and together they cover every line of the working-tree
+    /// version of the file. Returning `ControlFlow::Break` from the callback stops git
+    /// early without an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to annotate, relative to the repository root
+    /// * `callback` - Called with each [`BlameHunk`] as it is produced
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `GitError` if blame fails.
+    pub fn blame_incremental<P, F>(&self, path: P, mut callback: F) -> Result<()>
+    where
+        P: AsRef<Path>,
+        F: FnMut(BlameHunk) -> ControlFlow<()>,
+    {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let (process, stdout) =
+            git_spawn_stdout(&["blame", "--incremental", "--", &path_str], Some(self))?;
+
+        // Returning early, on an error, a break or a panic in the callback, drops
+        // `process`, which kills and reaps git
+        let mut reader = BufReader::new(stdout);
+        let mut parser = IncrementalParser::default();
+        let mut buffer = Vec::new();
+
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                break;
+            }
+
+            let line = String::from_utf8_lossy(&buffer);
+            let line = line.trim_end_matches('\n');
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(hunk) = parser.push_line(line)?
+                && callback(hunk).is_break()
+            {
+                return Ok(());
+            }
+        }
+
+        process.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_parse_incremental_reuses_commit_info() {
+        let output = "\
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa 1 1 2
+author Alice
+author-mail <alice@example.com>
+author-time 1700000000
+author-tz +0000
+committer Bob
+committer-mail <bob@example.com>
+committer-time 1700000100
+committer-tz +0000
+summary Initial commit
+boundary
+filename a.txt
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa 4 5 1
+filename old.txt
+";
+        let mut parser = IncrementalParser::default();
+        let hunks: Vec<BlameHunk> = output
+            .lines()
+            .filter_map(|line| parser.push_line(line).unwrap())
+            .collect();
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].final_start, 1);
+        assert_eq!(hunks[0].final_end(), 3);
+        assert_eq!(hunks[0].author.email, "alice@example.com");
+        assert_eq!(hunks[0].committer.name, "Bob");
+        assert!(hunks[0].boundary);
+
+        // The second hunk only carries a header and filename
+        assert_eq!(hunks[1].orig_start, 4);
+        assert_eq!(hunks[1].final_start, 5);
+        assert_eq!(hunks[1].summary, "Initial commit");
+        assert_eq!(hunks[1].filename, PathBuf::from("old.txt"));
+        assert!(!hunks[1].is_uncommitted());
+    }
+
+    #[test]
+    fn test_parse_porcelain() {
+        let output = "\
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa 1 1 2
+author Alice
+author-mail <alice@example.com>
+author-time 1700000000
+author-tz +0000
+committer Bob
+committer-mail <bob@example.com>
+committer-time 1700000100
+committer-tz +0000
+summary Initial commit
+boundary
+filename a.txt
+\tfirst
+aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa 2 2
+\t\tindented
+bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb 1 3 1
+author Carol
+author-mail <carol@example.com>
+author-time 1700000200
+author-tz +0000
+summary Add line
+previous aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa a.txt
+filename a.txt
+\t
+";
+        let blame = BlameOutput::new(parse_porcelain(output).unwrap());
+        assert_eq!(blame.len(), 3);
+
+        let first = blame.line(1).unwrap();
+        assert_eq!(first.author, "Alice");
+        assert_eq!(first.author_email, "alice@example.com");
+        assert_eq!(first.timestamp.timestamp(), 1_700_000_000);
+        assert_eq!(first.content, "first");
+        assert!(first.boundary);
+
+        // Repeated commits carry only a header and the line
+        let second = blame.line(2).unwrap();
+        assert_eq!(second.summary, "Initial commit");
+        assert_eq!(second.filename, PathBuf::from("a.txt"));
+        assert_eq!(second.content, "\tindented");
+
+        let third = blame.line(3).unwrap();
+        assert_eq!(third.orig_line_no, 1);
+        assert_eq!(third.content, "");
+        assert_eq!(blame.commits().len(), 2);
+        assert_eq!(blame.by_commit(&third.hash).count(), 1);
+        assert!(blame.line(4).is_none());
+
+        assert!(parse_porcelain("deadbeef one two\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_incremental_invalid_header() {
+        let mut parser = IncrementalParser::default();
+        assert!(parser.push_line("deadbeef one two").is_err());
+    }
+
+    #[test]
+    fn test_blame_with_options() {
+        let test_path = env::temp_dir().join("test_blame_with_options");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        fs::write(test_path.join("file.txt"), "one\ntwo\nthree\n").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let first = repo.commit("First").unwrap();
+
+        fs::write(test_path.join("file.txt"), "one\n  two\nthree\nfour\n").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let second = repo.commit("Second").unwrap();
+
+        let blame = repo.blame("file.txt").unwrap();
+        assert_eq!(blame.len(), 4);
+        assert_eq!(blame.line(1).unwrap().hash, first);
+        assert_eq!(blame.line(2).unwrap().hash, second);
+        assert_eq!(blame.line(2).unwrap().content, "  two");
+        assert_eq!(blame.line(4).unwrap().summary, "Second");
+        assert_eq!(blame.line(1).unwrap().author, "Test User");
+
+        // Whitespace-only changes are attributed to the earlier commit with -w
+        let blame = repo
+            .blame_with_options(
+                "file.txt",
+                BlameOptions::new()
+                    .with_lines(2, 3)
+                    .with_ignore_whitespace()
+                    .with_detect_moves()
+                    .with_detect_copies(),
+            )
+            .unwrap();
+        assert_eq!(blame.len(), 2);
+        assert_eq!(blame.line(2).unwrap().hash, first);
+        assert!(blame.line(1).is_none());
+
+        assert!(repo.blame("missing.txt").is_err());
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_blame_incremental() {
+        let test_path = env::temp_dir().join("test_blame_incremental");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        fs::write(test_path.join("file.txt"), "one\ntwo\nthree\n").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let first = repo.commit("First").unwrap();
+
+        fs::write(test_path.join("file.txt"), "one\nTWO\nthree\nfour\n").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let second = repo.commit("Second").unwrap();
+
+        // Uncommitted lines are attributed to the all-zero hash
+        fs::write(test_path.join("file.txt"), "one\nTWO\nthree\nfour\nfive\n").unwrap();
+
+        let mut hunks = Vec::new();
+        repo.blame_incremental("file.txt", |hunk| {
+            hunks.push(hunk);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+        hunks.sort_by_key(|h| h.final_start);
+        let lines: usize = hunks.iter().map(|h| h.line_count).sum();
+        assert_eq!(lines, 5);
+
+        let owner = |line: usize| {
+            hunks
+                .iter()
+                .find(|h| h.final_start <= line && line < h.final_end())
+                .unwrap()
+        };
+        assert_eq!(owner(1).hash, first);
+        assert_eq!(owner(1).summary, "First");
+        assert_eq!(owner(2).hash, second);
+        assert_eq!(owner(3).hash, first);
+        assert_eq!(owner(4).hash, second);
+        assert!(owner(5).is_uncommitted());
+        assert_eq!(owner(1).author.name, "Test User");
+
+        // Breaking out stops after the first hunk
+        let mut seen = 0;
+        repo.blame_incremental("file.txt", |_| {
+            seen += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+        assert_eq!(seen, 1);
+
+        // A panicking callback unwinds out of the call, and git is killed on the way
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            repo.blame_incremental("file.txt", |_| -> ControlFlow<()> {
+                panic!("callback failed")
+            })
+        }));
+        assert!(result.is_err());
+        let mut after_panic = 0;
+        repo.blame_incremental("file.txt", |_| {
+            after_panic += 1;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(after_panic, hunks.len());
+
+        // git's stderr is reported once it exits
+        match repo.blame_incremental("missing.txt", |_| ControlFlow::Continue(())) {
+            Err(GitError::CommandFailed(msg)) => {
+                assert!(msg.starts_with("git blame failed"), "{}", msg);
+                assert!(msg.contains("missing.txt"), "{}", msg);
+            }
+            other => panic!("Expected CommandFailed, got {:?}", other),
+        }
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+}
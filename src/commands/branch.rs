@@ -1,5 +1,5 @@
 use crate::types::Hash;
-use crate::utils::git;
+use crate::utils::{git, validate_arg, validate_ref_name};
 use crate::{Repository, Result};
 use std::fmt;
 
@@ -133,13 +133,70 @@ impl fmt::Display for BranchList {
     }
 }
 
+/// Options for [`Repository::prune_merged_branches`]
+#[derive(Debug, Clone, Default)]
+pub struct BranchPruneOptions {
+    include_remote: bool,
+    dry_run: bool,
+    protected: Vec<String>,
+}
+
+impl BranchPruneOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also delete merged branches on their remotes (`git push <remote> --delete`)
+    pub fn with_remote(mut self) -> Self {
+        self.include_remote = true;
+        self
+    }
+
+    /// Report what would be deleted without deleting anything
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Never delete branches matching this pattern (`*` and `?` wildcards)
+    ///
+    /// Local branches are matched by name (`release/*`), remote-tracking branches
+    /// by `<remote>/<name>` (`origin/release/*`) and by name alone.
+    pub fn with_protected(mut self, pattern: &str) -> Self {
+        self.protected.push(pattern.to_string());
+        self
+    }
+}
+
+/// The result of [`Repository::prune_merged_branches`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchPruneReport {
+    /// Whether this was a dry run
+    pub dry_run: bool,
+    /// Deleted (or, in a dry run, deletable) local branches
+    pub local: Vec<String>,
+    /// Deleted (or deletable) remote branches, as `<remote>/<name>`
+    pub remote: Vec<String>,
+    /// Merged branches kept because they are protected, current, or the target
+    pub protected: Vec<String>,
+}
+
+impl BranchPruneReport {
+    /// Check if no branches were (or would be) deleted
+    pub fn is_empty(&self) -> bool {
+        self.local.is_empty() && self.remote.is_empty()
+    }
+}
+
 impl Repository {
     /// List all branches in the repository
     pub fn branches(&self) -> Result<BranchList> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
-        // Use git branch -vv --all for comprehensive branch information
-        let stdout = git(&["branch", "-vv", "--all"], Some(self.repo_path()))?;
+        let stdout = git(
+            &["for-each-ref", BRANCH_FORMAT, "refs/heads", "refs/remotes"],
+            Some(self),
+        )?;
 
         let branches = parse_branch_output(&stdout)?;
         Ok(BranchList::new(branches))
@@ -147,9 +204,9 @@ impl Repository {
 
     /// Get the current branch
     pub fn current_branch(&self) -> Result<Option<Branch>> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
-        let stdout = git(&["branch", "--show-current"], Some(self.repo_path()))?;
+        let stdout = git(&["branch", "--show-current"], Some(self))?;
         let current_name = stdout.trim();
 
         if current_name.is_empty() {
@@ -164,14 +221,18 @@ impl Repository {
 
     /// Create a new branch
     pub fn create_branch(&self, name: &str, start_point: Option<&str>) -> Result<Branch> {
-        Self::ensure_git()?;
+        self.check_git()?;
+        validate_ref_name("branch", name)?;
+        if let Some(start) = start_point {
+            validate_arg("revision", start)?;
+        }
 
         let mut args = vec!["branch", name];
         if let Some(start) = start_point {
             args.push(start);
         }
 
-        let _stdout = git(&args, Some(self.repo_path()))?;
+        let _stdout = git(&args, Some(self))?;
 
         // Get information about the newly created branch
         let branches = self.branches()?;
@@ -182,7 +243,7 @@ impl Repository {
 
     /// Delete a branch
     pub fn delete_branch(&self, branch: &Branch, force: bool) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         if branch.is_current {
             return Err(crate::error::GitError::CommandFailed(
@@ -190,16 +251,17 @@ impl Repository {
             ));
         }
 
+        validate_ref_name("branch", &branch.name)?;
         let flag = if force { "-D" } else { "-d" };
         let args = vec!["branch", flag, &branch.name];
 
-        let _stdout = git(&args, Some(self.repo_path()))?;
+        let _stdout = git(&args, Some(self))?;
         Ok(())
     }
 
     /// Switch to an existing branch
     pub fn checkout(&self, branch: &Branch) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         let branch_name = if branch.is_remote() {
             branch.short_name()
@@ -207,20 +269,27 @@ impl Repository {
             &branch.name
         };
 
-        let _stdout = git(&["checkout", branch_name], Some(self.repo_path()))?;
+        validate_arg("revision", branch_name)?;
+
+        // The trailing `--` keeps a branch named like a file from being read as a path
+        let _stdout = git(&["checkout", branch_name, "--"], Some(self))?;
         Ok(())
     }
 
     /// Create a new branch and switch to it
     pub fn checkout_new(&self, name: &str, start_point: Option<&str>) -> Result<Branch> {
-        Self::ensure_git()?;
+        self.check_git()?;
+        validate_ref_name("branch", name)?;
+        if let Some(start) = start_point {
+            validate_arg("revision", start)?;
+        }
 
         let mut args = vec!["checkout", "-b", name];
         if let Some(start) = start_point {
             args.push(start);
         }
 
-        let _stdout = git(&args, Some(self.repo_path()))?;
+        let _stdout = git(&args, Some(self))?;
 
         // Get information about the newly created and checked out branch
         self.current_branch()?.ok_or_else(|| {
@@ -230,95 +299,303 @@ impl Repository {
             ))
         })
     }
-}
 
-/// Parse the output of `git branch -vv --all`
-fn parse_branch_output(output: &str) -> Result<Vec<Branch>> {
-    let mut branches = Vec::new();
+    /// Create a local branch tracking a remote-tracking branch and switch to it
+    ///
+    /// The local branch gets the remote branch's name without the remote prefix, so
+    /// `origin/feature/login` becomes `feature/login`. If that branch already exists and
+    /// tracks `remote_branch` (or tracks nothing, in which case the upstream is set), it
+    /// is switched to instead; a branch tracking something else is an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `remote_branch` - Remote-tracking branch as `<remote>/<branch>`, e.g. the
+    ///   [`name`](Branch::name) of a [`BranchType::RemoteTracking`] entry
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the checked-out local branch or a `GitError`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// repo.fetch("origin")?;
+    /// let branch = repo.checkout_tracking("origin/feature")?;
+    /// assert_eq!(branch.upstream.as_deref(), Some("origin/feature"));
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn checkout_tracking(&self, remote_branch: &str) -> Result<Branch> {
+        self.check_git()?;
+        validate_ref_name("branch", remote_branch)?;
+
+        let remotes: Vec<String> = git(&["remote"], Some(self))?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let (_, name) = split_remote_branch(&remotes, remote_branch).ok_or_else(|| {
+            crate::error::GitError::CommandFailed(format!(
+                "Not a remote-tracking branch: {}",
+                remote_branch
+            ))
+        })?;
 
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+        let branches = self.branches()?;
+        if branches
+            .find(remote_branch)
+            .is_none_or(|branch| !branch.is_remote())
+        {
+            return Err(crate::error::GitError::CommandFailed(format!(
+                "Remote-tracking branch not found: {}",
+                remote_branch
+            )));
         }
 
-        // Skip the line that shows HEAD -> branch mapping for remotes
-        if line.contains("->") {
-            continue;
+        match branches.find(name).filter(|branch| branch.is_local()) {
+            Some(existing) => match existing.upstream.as_deref() {
+                Some(upstream) if upstream != remote_branch => {
+                    return Err(crate::error::GitError::CommandFailed(format!(
+                        "Branch {} already exists and tracks {}",
+                        name, upstream
+                    )));
+                }
+                Some(_) => {
+                    git(&["switch", name], Some(self))?;
+                }
+                None => {
+                    git(&["switch", name], Some(self))?;
+                    let upstream = format!("--set-upstream-to={}", remote_branch);
+                    git(&["branch", &upstream], Some(self))?;
+                }
+            },
+            None => {
+                git(
+                    &["switch", "--track", "-c", name, remote_branch],
+                    Some(self),
+                )?;
+            }
         }
 
-        let is_current = line.starts_with('*');
-        let line = if is_current {
-            line[1..].trim() // Skip the '*' and trim
-        } else {
-            line.trim() // Just trim whitespace for non-current branches
-        };
+        self.current_branch()?.ok_or_else(|| {
+            crate::error::GitError::CommandFailed(format!(
+                "Failed to check out tracking branch: {}",
+                name
+            ))
+        })
+    }
 
-        // Parse branch name (first word)
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.is_empty() {
-            continue;
+    /// Delete branches that are fully merged into `into`
+    ///
+    /// The current branch, the target itself and any branch matching a protected
+    /// pattern are kept. Remote branches are only considered with
+    /// [`BranchPruneOptions::with_remote`]; they are deleted on the remote, which
+    /// also removes the remote-tracking ref.
+    ///
+    /// # Arguments
+    ///
+    /// * `into` - The branch or revision merged branches must be reachable from
+    /// * `options` - Remote, dry-run and protection settings
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{BranchPruneOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let options = BranchPruneOptions::new()
+    ///     .with_protected("release/*")
+    ///     .with_dry_run();
+    /// let report = repo.prune_merged_branches("main", options)?;
+    /// for branch in &report.local {
+    ///     println!("would delete {}", branch);
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn prune_merged_branches(
+        &self,
+        into: &str,
+        options: BranchPruneOptions,
+    ) -> Result<BranchPruneReport> {
+        self.check_git()?;
+        validate_arg("revision", into)?;
+
+        let merged = format!("--merged={}", into);
+        let mut args = vec![
+            "for-each-ref",
+            "--format=%(refname)%00%(symref)%00%(HEAD)",
+            &merged,
+            "refs/heads/",
+        ];
+        if options.include_remote {
+            args.push("refs/remotes/");
         }
-
-        let name = parts[0].to_string();
-
-        // Determine branch type
-        let branch_type = if name.starts_with("remotes/") {
-            BranchType::RemoteTracking
-        } else {
-            BranchType::Local
-        };
-
-        // Extract commit hash (second part if available)
-        let commit_hash = if parts.len() > 1 {
-            Hash::from(parts[1].to_string())
-        } else {
-            Hash::from("0000000000000000000000000000000000000000".to_string())
+        let output = git(&args, Some(self))?;
+
+        // Resolve the target to a full ref so it is never deleted, whatever its spelling
+        let target_ref = git(&["rev-parse", "--symbolic-full-name", into], Some(self))
+            .map(|output| output.trim().to_string())
+            .unwrap_or_default();
+        let remotes: Vec<String> = git(&["remote"], Some(self))?
+            .lines()
+            .map(str::to_string)
+            .collect();
+
+        let mut report = BranchPruneReport {
+            dry_run: options.dry_run,
+            ..BranchPruneReport::default()
         };
+        let mut remote_deletions: Vec<(String, String)> = Vec::new();
+
+        let records: Vec<(&str, &str, &str)> = output
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\0');
+                Some((fields.next()?, fields.next()?, fields.next()?))
+            })
+            .collect();
+
+        // Remote default branches (refs/remotes/<remote>/HEAD targets) are kept, as is
+        // the target's counterpart on every remote
+        let remote_defaults: Vec<&str> = records
+            .iter()
+            .map(|(_, symref, _)| *symref)
+            .filter(|symref| !symref.is_empty())
+            .collect();
+        let target_name = target_ref
+            .strip_prefix("refs/heads/")
+            .or_else(|| {
+                let tracking = target_ref.strip_prefix("refs/remotes/")?;
+                split_remote_branch(&remotes, tracking).map(|(_, name)| name)
+            })
+            .unwrap_or(into);
+
+        for (refname, symref, head) in records {
+            // Skip symbolic refs such as refs/remotes/origin/HEAD
+            if !symref.is_empty() {
+                continue;
+            }
 
-        // Extract upstream information (look for [upstream] pattern)
-        let upstream = if let Some(bracket_start) = line.find('[') {
-            if let Some(bracket_end) = line.find(']') {
-                let upstream_info = &line[bracket_start + 1..bracket_end];
-                // Extract just the upstream branch name, ignore ahead/behind info
-                let upstream_branch = upstream_info
-                    .split(':')
-                    .next()
-                    .unwrap_or(upstream_info)
-                    .trim();
-                if upstream_branch.is_empty() {
-                    None
+            if let Some(name) = refname.strip_prefix("refs/heads/") {
+                if head == "*" || refname == target_ref || is_protected(&options.protected, &[name])
+                {
+                    report.protected.push(name.to_string());
+                } else {
+                    report.local.push(name.to_string());
+                }
+            } else if let Some(tracking) = refname.strip_prefix("refs/remotes/") {
+                let Some((remote, name)) = split_remote_branch(&remotes, tracking) else {
+                    continue;
+                };
+                if name == target_name
+                    || remote_defaults.contains(&refname)
+                    || is_protected(&options.protected, &[tracking, name])
+                {
+                    report.protected.push(tracking.to_string());
                 } else {
-                    Some(upstream_branch.to_string())
+                    report.remote.push(tracking.to_string());
+                    remote_deletions.push((remote.to_string(), name.to_string()));
                 }
-            } else {
-                None
             }
-        } else {
-            None
+        }
+
+        if !options.dry_run {
+            for name in &report.local {
+                git(&["branch", "-D", name], Some(self))?;
+            }
+            for (remote, name) in &remote_deletions {
+                git(&["push", remote, "--delete", name], Some(self))?;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// `for-each-ref` fields read by [`parse_branch_output`], separated by NUL
+pub(crate) const BRANCH_FORMAT: &str =
+    "--format=%(HEAD)%00%(refname)%00%(objectname)%00%(upstream:short)%00%(symref)";
+
+/// Parse `git for-each-ref` output produced with [`BRANCH_FORMAT`]
+pub(crate) fn parse_branch_output(output: &str) -> Result<Vec<Branch>> {
+    let mut branches = Vec::new();
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split('\0').collect();
+        let [head, refname, object, upstream, symref] = fields[..] else {
+            continue;
         };
 
-        // Clean up remote branch names
-        let clean_name = if branch_type == BranchType::RemoteTracking {
-            name.strip_prefix("remotes/").unwrap_or(&name).to_string()
+        // Skip symbolic refs such as refs/remotes/origin/HEAD
+        if !symref.is_empty() {
+            continue;
+        }
+
+        let (name, branch_type) = if let Some(name) = refname.strip_prefix("refs/heads/") {
+            (name, BranchType::Local)
+        } else if let Some(name) = refname.strip_prefix("refs/remotes/") {
+            (name, BranchType::RemoteTracking)
         } else {
-            name
+            continue;
         };
 
-        let branch = Branch {
-            name: clean_name,
+        branches.push(Branch {
+            name: name.to_string(),
             branch_type,
-            is_current,
-            commit_hash,
-            upstream,
-        };
-
-        branches.push(branch);
+            is_current: head == "*",
+            commit_hash: Hash::from(object),
+            upstream: Some(upstream.to_string()).filter(|upstream| !upstream.is_empty()),
+        });
     }
 
     Ok(branches)
 }
 
+/// Split `<remote>/<branch>` using the longest configured remote name that matches
+fn split_remote_branch<'a>(remotes: &[String], tracking: &'a str) -> Option<(&'a str, &'a str)> {
+    remotes
+        .iter()
+        .filter_map(|remote| {
+            let name = tracking.strip_prefix(remote.as_str())?.strip_prefix('/')?;
+            Some((&tracking[..remote.len()], name))
+        })
+        .max_by_key(|(remote, _)| remote.len())
+}
+
+/// Check if any of the names matches any protected pattern
+fn is_protected(patterns: &[String], names: &[&str]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| names.iter().any(|name| wildcard_match(pattern, name)))
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters and `?` one
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -487,11 +764,12 @@ mod tests {
 
     #[test]
     fn test_parse_branch_output() {
-        let output = r#"
-* main                abc1234 [origin/main] Initial commit
-  feature             def5678 Feature branch
-  remotes/origin/main abc1234 Initial commit
-"#;
+        let output = "\
+*\0refs/heads/main\0abc1234\0origin/main\0
+ \0refs/heads/feature\0def5678\0\0
+ \0refs/remotes/origin/HEAD\0abc1234\0\0refs/remotes/origin/main
+ \0refs/remotes/origin/main\0abc1234\0\0
+";
 
         let branches = parse_branch_output(output).unwrap();
 
@@ -572,4 +850,211 @@ mod tests {
         // Clean up
         fs::remove_dir_all(test_path).unwrap();
     }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("main", "main"));
+        assert!(!wildcard_match("main", "main2"));
+        assert!(wildcard_match("release/*", "release/1.0"));
+        assert!(wildcard_match("*", "anything/at/all"));
+        assert!(wildcard_match("v?.x", "v1.x"));
+        assert!(wildcard_match("*fix*", "feature/hotfix-1"));
+        assert!(!wildcard_match("release/*", "feature/release"));
+    }
+
+    #[test]
+    fn test_split_remote_branch() {
+        let remotes = vec!["origin".to_string(), "origin/mirror".to_string()];
+        assert_eq!(
+            split_remote_branch(&remotes, "origin/feature/x"),
+            Some(("origin", "feature/x"))
+        );
+        assert_eq!(
+            split_remote_branch(&remotes, "origin/mirror/topic"),
+            Some(("origin/mirror", "topic"))
+        );
+        assert_eq!(split_remote_branch(&remotes, "upstream/main"), None);
+    }
+
+    #[test]
+    fn test_prune_merged_branches() {
+        let test_path = std::env::temp_dir().join("test_prune_merged_branches");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(test_path.join("file.txt"), "base").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+        let main = repo.current_branch().unwrap().unwrap().name;
+
+        repo.create_branch("merged-feature", None).unwrap();
+        repo.create_branch("release/1.0", None).unwrap();
+        repo.checkout_new("unmerged", None).unwrap();
+        fs::write(test_path.join("file.txt"), "unmerged").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Unmerged work").unwrap();
+        repo.checkout(repo.branches().unwrap().find(&main).unwrap())
+            .unwrap();
+
+        let options = BranchPruneOptions::new()
+            .with_protected("release/*")
+            .with_dry_run();
+        let report = repo.prune_merged_branches(&main, options).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.local, vec!["merged-feature".to_string()]);
+        assert_eq!(
+            report.protected,
+            vec!["main".to_string(), "release/1.0".to_string()]
+                .into_iter()
+                .map(|name| if name == "main" { main.clone() } else { name })
+                .collect::<Vec<_>>()
+        );
+        assert!(repo.branches().unwrap().find("merged-feature").is_some());
+
+        let report = repo
+            .prune_merged_branches(&main, BranchPruneOptions::new().with_protected("release/*"))
+            .unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(report.local, vec!["merged-feature".to_string()]);
+        let branches = repo.branches().unwrap();
+        assert!(branches.find("merged-feature").is_none());
+        assert!(branches.find("release/1.0").is_some());
+        assert!(branches.find("unmerged").is_some());
+
+        // Nothing left to prune
+        let report = repo
+            .prune_merged_branches(&main, BranchPruneOptions::new().with_protected("release/*"))
+            .unwrap();
+        assert!(report.is_empty());
+
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_option_like_names_are_rejected() {
+        let repo = crate::testing::TempRepo::new("branch_option_names").unwrap();
+        repo.commit_file("file.txt", "base", "Initial commit")
+            .unwrap();
+
+        assert!(repo.create_branch("--force", None).is_err());
+        assert!(repo.create_branch("topic", Some("--orphan")).is_err());
+        assert!(repo.checkout_new("-d", None).is_err());
+        assert!(repo.create_tag("--delete", None).is_err());
+        assert!(
+            repo.add_remote("--mirror", "https://example.com/r.git")
+                .is_err()
+        );
+        assert!(repo.reset_hard("--merge").is_err());
+        assert!(repo.merge("--abort").is_err());
+        assert!(repo.branches().unwrap().find("--force").is_none());
+
+        // A branch named like a file is still checked out as a branch
+        let main = repo.current_branch().unwrap().unwrap();
+        let branch = repo.create_branch("file.txt", None).unwrap();
+        repo.checkout(&branch).unwrap();
+        assert_eq!(repo.current_branch().unwrap().unwrap().name, "file.txt");
+        repo.checkout(&main).unwrap();
+        assert_eq!(repo.log().unwrap().len(), 1);
+        let options = crate::LogOptions::new().range(&main.name, "file.txt");
+        assert!(repo.log_with_options(&options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_checkout_tracking() {
+        let base = std::env::temp_dir().join("test_checkout_tracking");
+        if base.exists() {
+            fs::remove_dir_all(&base).unwrap();
+        }
+        fs::create_dir_all(&base).unwrap();
+
+        let origin = base.join("origin.git");
+        Repository::init(&origin, true).unwrap();
+        let repo = Repository::clone(&origin.to_string_lossy(), base.join("work")).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(base.join("work").join("file.txt"), "base").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+        let main = repo.current_branch().unwrap().unwrap().name;
+        repo.create_branch("feature/login", None).unwrap();
+        repo.create_branch("other", None).unwrap();
+        repo.push("origin", &main).unwrap();
+        repo.push("origin", "feature/login").unwrap();
+        repo.push("origin", "other").unwrap();
+        repo.fetch("origin").unwrap();
+        for name in ["feature/login", "other"] {
+            let branch = repo.branches().unwrap().find(name).cloned().unwrap();
+            repo.delete_branch(&branch, true).unwrap();
+        }
+
+        // Created from the remote-tracking branch
+        let branch = repo.checkout_tracking("origin/feature/login").unwrap();
+        assert_eq!(branch.name, "feature/login");
+        assert!(branch.is_current);
+        assert_eq!(branch.upstream.as_deref(), Some("origin/feature/login"));
+
+        // Already exists and tracks it: just switched to
+        repo.checkout_new("scratch", None).unwrap();
+        let branch = repo.checkout_tracking("origin/feature/login").unwrap();
+        assert_eq!(branch.name, "feature/login");
+
+        // Exists without an upstream: upstream is set
+        repo.create_branch("other", None).unwrap();
+        let branch = repo.checkout_tracking("origin/other").unwrap();
+        assert_eq!(branch.upstream.as_deref(), Some("origin/other"));
+
+        // Exists but tracks something else
+        repo.checkout_tracking("origin/feature/login").unwrap();
+        let upstream = format!("--set-upstream-to=origin/{}", main);
+        git(&["branch", &upstream, "other"], Some(&repo)).unwrap();
+        assert!(repo.checkout_tracking("origin/other").is_err());
+
+        assert!(repo.checkout_tracking("origin/missing").is_err());
+        assert!(repo.checkout_tracking("feature/login").is_err());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_prune_merged_branches_remote() {
+        let base = std::env::temp_dir().join("test_prune_merged_branches_remote");
+        if base.exists() {
+            fs::remove_dir_all(&base).unwrap();
+        }
+        fs::create_dir_all(&base).unwrap();
+
+        let origin = base.join("origin.git");
+        Repository::init(&origin, true).unwrap();
+        let repo = Repository::clone(&origin.to_string_lossy(), base.join("work")).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(base.join("work").join("file.txt"), "base").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+        let main = repo.current_branch().unwrap().unwrap().name;
+        repo.create_branch("done", None).unwrap();
+        repo.push("origin", &main).unwrap();
+        repo.push("origin", "done").unwrap();
+        repo.fetch("origin").unwrap();
+
+        let report = repo
+            .prune_merged_branches(&main, BranchPruneOptions::new().with_remote())
+            .unwrap();
+        assert_eq!(report.local, vec!["done".to_string()]);
+        assert_eq!(report.remote, vec!["origin/done".to_string()]);
+        assert!(report.protected.contains(&format!("origin/{}", main)));
+
+        let remote_refs = git(&["for-each-ref", "--format=%(refname)"], Some(&origin)).unwrap();
+        assert!(!remote_refs.contains("refs/heads/done"));
+        assert!(remote_refs.contains(&format!("refs/heads/{}", main)));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
 }
@@ -0,0 +1,273 @@
+//! Git bundle operations
+//!
+//! A bundle is a single file holding commits and refs, created with `git bundle`.
+//! Bundles move history where no network connection exists: create one on one side,
+//! carry the file over, then verify it and clone or unbundle it on the other side.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::Repository;
+//!
+//! // On the connected machine: everything on main since the last transfer
+//! let repo = Repository::open(".")?;
+//! repo.bundle_create("/media/usb/update.bundle", &["last-sync..main"])?;
+//!
+//! // On the air-gapped machine
+//! let mirror = Repository::open("/srv/mirror")?;
+//! let info = mirror.bundle_verify("/media/usb/update.bundle")?;
+//! for bundle_ref in &info.refs {
+//!     println!("{} {}", bundle_ref.hash.short(), bundle_ref.name);
+//! }
+//! mirror.bundle_unbundle("/media/usb/update.bundle")?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, git_raw, validate_arg};
+use std::path::Path;
+
+/// A ref stored in a bundle
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleRef {
+    /// The commit the ref points at
+    pub hash: Hash,
+    /// The full ref name, e.g. `refs/heads/main`, or `HEAD`
+    pub name: String,
+}
+
+/// The contents of a verified bundle
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleInfo {
+    /// The refs the bundle provides
+    pub refs: Vec<BundleRef>,
+    /// Commits the receiving repository must already have
+    pub prerequisites: Vec<Hash>,
+}
+
+impl BundleInfo {
+    /// Check whether the bundle holds complete history, so it can be cloned from
+    pub fn is_complete(&self) -> bool {
+        self.prerequisites.is_empty()
+    }
+}
+
+impl Repository {
+    /// Write the commits reachable from `refs` to a bundle file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The bundle file to create; relative paths are resolved against the
+    ///   repository's working directory
+    /// * `refs` - Branches, tags or ranges such as `v1.0..main` to include; an empty
+    ///   slice bundles every ref (`--all`)
+    pub fn bundle_create<P: AsRef<Path>>(&self, path: P, refs: &[&str]) -> Result<()> {
+        self.check_git()?;
+        for rev in refs {
+            validate_arg("revision", rev)?;
+        }
+
+        let path_str = path.as_ref().to_string_lossy();
+        let mut args = vec!["bundle", "create", "--quiet", &path_str];
+        if refs.is_empty() {
+            args.push("--all");
+        } else {
+            args.extend_from_slice(refs);
+        }
+
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// Check that a bundle is valid and that this repository has its prerequisites.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the bundle's refs and prerequisites, or a `GitError` if
+    /// the file is not a bundle or prerequisite commits are missing.
+    pub fn bundle_verify<P: AsRef<Path>>(&self, path: P) -> Result<BundleInfo> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let output = git_raw(&["bundle", "verify", &path_str], Some(self))?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git bundle verify failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        Ok(parse_bundle_verify(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// Store the objects of a bundle in this repository.
+    ///
+    /// Refs are not updated; create branches or tags from the returned refs as needed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the refs the bundle provides.
+    pub fn bundle_unbundle<P: AsRef<Path>>(&self, path: P) -> Result<Vec<BundleRef>> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let output = git(&["bundle", "unbundle", &path_str], Some(self))?;
+        Ok(output.lines().filter_map(parse_bundle_ref).collect())
+    }
+
+    /// Clone a new repository from a bundle file.
+    ///
+    /// The bundle must hold complete history (see [`BundleInfo::is_complete`]). The
+    /// bundle becomes the `origin` remote, so later bundles written to the same path
+    /// can be fetched.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The bundle file
+    /// * `dest` - Where to create the repository
+    pub fn clone_from_bundle<P: AsRef<Path>, Q: AsRef<Path>>(
+        path: P,
+        dest: Q,
+    ) -> Result<Repository> {
+        Self::clone(&path.as_ref().to_string_lossy(), dest)
+    }
+}
+
+/// Parse a `<hash> <ref>` line; prerequisite lines may have no name
+fn parse_bundle_ref(line: &str) -> Option<BundleRef> {
+    let (hash, name) = line.split_once(' ').unwrap_or((line, ""));
+    if hash.is_empty() || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(BundleRef {
+        hash: Hash::from(hash),
+        name: name.trim().to_string(),
+    })
+}
+
+/// Parse the stdout of `git bundle verify`
+///
+/// Refs follow a "The bundle contains ..." line and prerequisites a "The bundle
+/// requires ..." line; prerequisite lines may carry a commit subject after the hash.
+fn parse_bundle_verify(output: &str) -> BundleInfo {
+    let mut info = BundleInfo {
+        refs: Vec::new(),
+        prerequisites: Vec::new(),
+    };
+    let mut in_prerequisites = false;
+
+    for line in output.lines() {
+        if line.starts_with("The bundle contains") {
+            in_prerequisites = false;
+        } else if line.starts_with("The bundle requires") {
+            in_prerequisites = true;
+        } else if let Some(bundle_ref) = parse_bundle_ref(line) {
+            if in_prerequisites {
+                info.prerequisites.push(bundle_ref.hash);
+            } else {
+                info.refs.push(bundle_ref);
+            }
+        }
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use std::fs;
+
+    #[test]
+    fn test_parse_bundle_verify() {
+        let output = "\
+The bundle contains this ref:
+c3afc904f934ded3e077544814bf1ee5db899be4 refs/heads/main
+The bundle requires this ref:
+6c57018724b5e2804c60064d38aaa71cdc9e94cf
+The bundle uses this hash algorithm: sha1
+";
+        let info = parse_bundle_verify(output);
+        assert_eq!(
+            info.refs,
+            vec![BundleRef {
+                hash: Hash::from("c3afc904f934ded3e077544814bf1ee5db899be4"),
+                name: "refs/heads/main".to_string(),
+            }]
+        );
+        assert_eq!(
+            info.prerequisites,
+            vec![Hash::from("6c57018724b5e2804c60064d38aaa71cdc9e94cf")]
+        );
+        assert!(!info.is_complete());
+    }
+
+    #[test]
+    fn test_bundle_create_verify_clone() {
+        let source = TempRepo::new("bundle_source").unwrap();
+        source.commit_file("a.txt", "a\n", "First").unwrap();
+        let head = source.commit_file("b.txt", "b\n", "Second").unwrap();
+        let branch = source.current_branch().unwrap().unwrap().name;
+        let bundle = source.path().with_extension("bundle");
+
+        source.bundle_create(&bundle, &[]).unwrap();
+        let info = source.bundle_verify(&bundle).unwrap();
+        assert!(info.is_complete());
+        assert!(info.refs.iter().any(|r| r.name == "HEAD" && r.hash == head));
+        assert!(
+            info.refs
+                .iter()
+                .any(|r| r.name == format!("refs/heads/{}", branch))
+        );
+
+        let dest = source.path().with_extension("clone");
+        let _ = fs::remove_dir_all(&dest);
+        let clone = Repository::clone_from_bundle(&bundle, &dest).unwrap();
+        let log = clone.recent_commits(1).unwrap();
+        assert_eq!(log.iter().next().unwrap().hash, head);
+        assert!(dest.join("b.txt").exists());
+
+        fs::remove_dir_all(&dest).unwrap();
+        fs::remove_file(&bundle).unwrap();
+        assert!(source.bundle_verify(&bundle).is_err());
+        assert!(source.bundle_create(&bundle, &["--all"]).is_err());
+    }
+
+    #[test]
+    fn test_bundle_incremental_unbundle() {
+        let source = TempRepo::new("bundle_incremental").unwrap();
+        let first = source.commit_file("a.txt", "a\n", "First").unwrap();
+        let dest = source.path().with_extension("clone");
+        let _ = fs::remove_dir_all(&dest);
+        let target = Repository::clone(&source.path().to_string_lossy(), &dest).unwrap();
+
+        let second = source.commit_file("b.txt", "b\n", "Second").unwrap();
+        let branch = source.current_branch().unwrap().unwrap().name;
+        let bundle = source.path().with_extension("bundle");
+        let range = format!("{}..{}", first, branch);
+        source.bundle_create(&bundle, &[&range]).unwrap();
+
+        // A repository without the prerequisite cannot use the bundle
+        let unrelated = TempRepo::new("bundle_unrelated").unwrap();
+        unrelated
+            .commit_file("other.txt", "other\n", "Other")
+            .unwrap();
+        assert!(unrelated.bundle_verify(&bundle).is_err());
+
+        let info = target.bundle_verify(&bundle).unwrap();
+        assert_eq!(info.prerequisites, vec![first]);
+        assert!(!target.has_commit(&second).unwrap());
+        let refs = target.bundle_unbundle(&bundle).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].hash, second);
+        assert!(target.has_commit(&second).unwrap());
+
+        fs::remove_dir_all(&dest).unwrap();
+        fs::remove_file(&bundle).unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+//! Git cherry-pick operations
+//!
+//! This module applies the changes of existing commits on top of the current branch
+//! with `git cherry-pick`, and drives a cherry-pick that stopped on conflicts through
+//! `--continue` and `--abort`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{CherryPickOptions, CherryPickStatus, Hash, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//! let fix = Hash::from("a1b2c3d");
+//!
+//! let options = CherryPickOptions::new().with_record_origin();
+//! match repo.cherry_pick_with_options(&fix, options)? {
+//!     CherryPickStatus::Success(hash) => println!("Picked as {}", hash.short()),
+//!     CherryPickStatus::Conflicts(files) => {
+//!         println!("Conflicts in {:?}", files);
+//!         repo.cherry_pick_abort()?;
+//!     }
+//! }
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::merge::extract_conflicted_files;
+use crate::error::{GitError, Result};
+use crate::repository::{Repository, resolve_git_dir};
+use crate::types::Hash;
+use crate::utils::{git, git_raw_with_env, validate_arg};
+use std::path::PathBuf;
+
+/// The result of a cherry-pick operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum CherryPickStatus {
+    /// All commits were applied; HEAD points at the last new commit (or is unchanged
+    /// with [`CherryPickOptions::with_no_commit`], leaving the changes staged)
+    Success(Hash),
+    /// A commit has conflicts that need manual resolution; the cherry-pick is left in
+    /// progress
+    Conflicts(Vec<PathBuf>),
+}
+
+/// Options for cherry-pick operations
+#[derive(Debug, Clone, Default)]
+pub struct CherryPickOptions {
+    no_commit: bool,
+    record_origin: bool,
+    mainline: Option<u32>,
+}
+
+impl CherryPickOptions {
+    /// Create new CherryPickOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the changes to the index and working tree without committing (`--no-commit`)
+    pub fn with_no_commit(mut self) -> Self {
+        self.no_commit = true;
+        self
+    }
+
+    /// Append "(cherry picked from commit ...)" to the message (`-x`)
+    pub fn with_record_origin(mut self) -> Self {
+        self.record_origin = true;
+        self
+    }
+
+    /// Pick a merge commit relative to its `parent`-th parent (1-based, `-m <parent>`)
+    pub fn with_mainline(mut self, parent: u32) -> Self {
+        self.mainline = Some(parent);
+        self
+    }
+}
+
+impl Repository {
+    /// Apply the changes introduced by a commit on top of the current branch.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The commit to pick
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `CherryPickStatus`.
+    pub fn cherry_pick(&self, hash: &Hash) -> Result<CherryPickStatus> {
+        self.cherry_pick_with_options(hash, CherryPickOptions::new())
+    }
+
+    /// Apply a commit with custom options.
+    pub fn cherry_pick_with_options(
+        &self,
+        hash: &Hash,
+        options: CherryPickOptions,
+    ) -> Result<CherryPickStatus> {
+        self.check_git()?;
+        validate_arg("revision", hash.as_str())?;
+
+        self.run_cherry_pick(&options, hash.as_str())
+    }
+
+    /// Apply the commits after `from` up to and including `to`, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The commit before the first one to pick (excluded, like `from..to`)
+    /// * `to` - The last commit to pick
+    pub fn cherry_pick_range(&self, from: &Hash, to: &Hash) -> Result<CherryPickStatus> {
+        self.cherry_pick_range_with_options(from, to, CherryPickOptions::new())
+    }
+
+    /// Apply a range of commits with custom options.
+    pub fn cherry_pick_range_with_options(
+        &self,
+        from: &Hash,
+        to: &Hash,
+        options: CherryPickOptions,
+    ) -> Result<CherryPickStatus> {
+        self.check_git()?;
+        validate_arg("revision", from.as_str())?;
+        validate_arg("revision", to.as_str())?;
+
+        let range = format!("{}..{}", from, to);
+        self.run_cherry_pick(&options, &range)
+    }
+
+    /// Check if a cherry-pick is waiting to be continued or aborted.
+    pub fn cherry_pick_in_progress(&self) -> Result<bool> {
+        self.check_git()?;
+
+        let git_dir = resolve_git_dir(self)?;
+        Ok(git_dir.join("CHERRY_PICK_HEAD").exists())
+    }
+
+    /// Continue a cherry-pick after its conflicts have been resolved and staged.
+    ///
+    /// The commit keeps its original message. Remaining commits of a range are picked
+    /// next and may conflict again.
+    pub fn cherry_pick_continue(&self) -> Result<CherryPickStatus> {
+        self.check_git()?;
+        self.cherry_pick_step(&["cherry-pick", "--continue"])
+    }
+
+    /// Abort an in-progress cherry-pick and restore the branch to where it was.
+    pub fn cherry_pick_abort(&self) -> Result<()> {
+        self.check_git()?;
+
+        git(&["cherry-pick", "--abort"], Some(self))?;
+        Ok(())
+    }
+
+    /// Build and run `git cherry-pick` for `revisions`
+    fn run_cherry_pick(
+        &self,
+        options: &CherryPickOptions,
+        revisions: &str,
+    ) -> Result<CherryPickStatus> {
+        let mainline;
+        let mut args = vec!["cherry-pick"];
+        if options.no_commit {
+            args.push("--no-commit");
+        }
+        if options.record_origin {
+            args.push("-x");
+        }
+        if let Some(parent) = options.mainline {
+            mainline = parent.to_string();
+            args.push("-m");
+            args.push(&mainline);
+        }
+        args.push(revisions);
+
+        self.cherry_pick_step(&args)
+    }
+
+    /// Run a cherry-pick step and map its outcome to a [`CherryPickStatus`]
+    fn cherry_pick_step(&self, args: &[&str]) -> Result<CherryPickStatus> {
+        // `--continue` opens an editor for the commit message; keep the original one
+        let output = git_raw_with_env(args, Some(self), &[("GIT_EDITOR", "true")])?;
+        if output.status.success() {
+            let head = git(&["rev-parse", "HEAD"], Some(self))?;
+            return Ok(CherryPickStatus::Success(Hash::from(head.trim())));
+        }
+
+        let conflicts = extract_conflicted_files(self)?;
+        if !conflicts.is_empty() {
+            return Ok(CherryPickStatus::Conflicts(conflicts));
+        }
+
+        Err(GitError::CommandFailed(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use std::fs;
+
+    /// A repo on the default branch with three commits on `feature` to pick from
+    fn setup(name: &str) -> (TempRepo, Vec<Hash>) {
+        let repo = TempRepo::new(name).unwrap();
+        repo.commit_file("file.txt", "base\n", "Base").unwrap();
+        let main = repo.current_branch().unwrap().unwrap();
+
+        repo.checkout_new("feature", None).unwrap();
+        let picks = vec![
+            repo.commit_file("a.txt", "a\n", "Add a").unwrap(),
+            repo.commit_file("b.txt", "b\n", "Add b").unwrap(),
+            repo.commit_file("file.txt", "feature\n", "Change file")
+                .unwrap(),
+        ];
+        repo.checkout(&main).unwrap();
+        (repo, picks)
+    }
+
+    #[test]
+    fn test_cherry_pick_single_and_options() {
+        let (repo, picks) = setup("cherry_pick_single");
+
+        let status = repo.cherry_pick(&picks[1]).unwrap();
+        let CherryPickStatus::Success(head) = status else {
+            panic!("expected success, got {:?}", status);
+        };
+        assert_eq!(head, repo.head_hash().unwrap());
+        assert!(repo.path().join("b.txt").exists());
+        assert!(!repo.path().join("a.txt").exists());
+
+        let status = repo
+            .cherry_pick_with_options(&picks[0], CherryPickOptions::new().with_record_origin())
+            .unwrap();
+        assert!(matches!(status, CherryPickStatus::Success(_)));
+        let details = repo.show_commit(&repo.head_hash().unwrap()).unwrap();
+        let body = details.commit.message.body.unwrap_or_default();
+        assert!(body.contains(&format!("(cherry picked from commit {})", picks[0])));
+
+        // --no-commit stages the change without moving HEAD
+        let before = repo.head_hash().unwrap();
+        let status = repo
+            .cherry_pick_with_options(&picks[2], CherryPickOptions::new().with_no_commit())
+            .unwrap();
+        assert_eq!(status, CherryPickStatus::Success(before));
+        assert!(repo.status().unwrap().has_changes());
+
+        assert!(repo.cherry_pick(&Hash::from("--abort")).is_err());
+    }
+
+    #[test]
+    fn test_cherry_pick_range() {
+        let (repo, picks) = setup("cherry_pick_range");
+
+        let status = repo.cherry_pick_range(&picks[0], &picks[2]).unwrap();
+        assert!(matches!(status, CherryPickStatus::Success(_)));
+        assert!(!repo.path().join("a.txt").exists());
+        assert!(repo.path().join("b.txt").exists());
+        assert_eq!(
+            fs::read_to_string(repo.path().join("file.txt")).unwrap(),
+            "feature\n"
+        );
+    }
+
+    #[test]
+    fn test_cherry_pick_conflicts() {
+        let (repo, picks) = setup("cherry_pick_conflicts");
+        repo.commit_file("file.txt", "main\n", "Main change")
+            .unwrap();
+        let before = repo.head_hash().unwrap();
+
+        let status = repo.cherry_pick(&picks[2]).unwrap();
+        assert_eq!(
+            status,
+            CherryPickStatus::Conflicts(vec![PathBuf::from("file.txt")])
+        );
+        assert!(repo.cherry_pick_in_progress().unwrap());
+        assert_eq!(
+            repo.conflict_operation().unwrap(),
+            Some(crate::ConflictOperation::CherryPick)
+        );
+
+        repo.cherry_pick_abort().unwrap();
+        assert!(!repo.cherry_pick_in_progress().unwrap());
+        assert_eq!(repo.head_hash().unwrap(), before);
+
+        assert!(matches!(
+            repo.cherry_pick(&picks[2]).unwrap(),
+            CherryPickStatus::Conflicts(_)
+        ));
+        repo.write_file("file.txt", "resolved\n").unwrap();
+        repo.mark_resolved(&["file.txt"]).unwrap();
+        assert!(matches!(
+            repo.cherry_pick_continue().unwrap(),
+            CherryPickStatus::Success(_)
+        ));
+        assert!(!repo.cherry_pick_in_progress().unwrap());
+        let log = repo.recent_commits(1).unwrap();
+        assert_eq!(log.iter().next().unwrap().message.subject, "Change file");
+    }
+}
@@ -1,5 +1,138 @@
-use crate::utils::git;
-use crate::{Hash, Repository, Result};
+use crate::utils::{git, git_raw, git_raw_with_stdin, git_with_env};
+use crate::{GitError, Hash, Identity, Repository, Result};
+use chrono::{DateTime, Utc};
+use std::fs;
+
+/// Options for creating a commit
+#[derive(Debug, Clone, Default)]
+pub struct CommitOptions {
+    /// Seed the message from the configured `commit.template`
+    pub use_template: bool,
+    /// Author and committer for this commit, instead of the configured user
+    pub identity: Option<Identity>,
+    /// Replace the last commit instead of creating a new one (`--amend`)
+    pub amend: bool,
+    /// When amending, take over authorship and set the author date to now
+    /// (`--reset-author`)
+    pub reset_author: bool,
+    /// Author for this commit only; takes precedence over `identity` for the author
+    pub author: Option<Identity>,
+    /// Author and committer date, for reproducible commit hashes
+    pub date: Option<DateTime<Utc>>,
+    /// Add a `Signed-off-by` trailer for the committer (`--signoff`)
+    pub signoff: bool,
+    /// Allow a commit that records no changes (`--allow-empty`)
+    pub allow_empty: bool,
+    /// Skip the pre-commit and commit-msg hooks (`--no-verify`)
+    pub no_verify: bool,
+    /// GPG-sign the commit with this key id, or the default key when empty
+    /// (`--gpg-sign`)
+    pub gpg_sign: Option<String>,
+}
+
+impl CommitOptions {
+    /// Create new default commit options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the configured `commit.template` (with comment lines stripped) to the
+    /// message, so scaffolding such as ticket references or trailers is filled in the
+    /// same way `git commit` would pre-fill the editor.
+    ///
+    /// Has no effect when no template is configured.
+    pub fn with_template(mut self) -> Self {
+        self.use_template = true;
+        self
+    }
+
+    /// Commit as `identity` (both author and committer) without changing the config
+    ///
+    /// When amending, only the committer changes unless combined with
+    /// [`with_reset_author`](Self::with_reset_author).
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Replace the last commit instead of creating a new one
+    pub fn with_amend(mut self) -> Self {
+        self.amend = true;
+        self
+    }
+
+    /// Make the committer (the configured user, or the identity set with
+    /// [`with_identity`](Self::with_identity)) the author of an amended commit, and
+    /// reset its author date to now
+    pub fn with_reset_author(mut self) -> Self {
+        self.reset_author = true;
+        self
+    }
+
+    /// Record `name <email>` as the author, leaving the committer unchanged
+    pub fn with_author(mut self, name: &str, email: &str) -> Self {
+        self.author = Some(Identity::new(name, email));
+        self
+    }
+
+    /// Override the author and committer dates
+    pub fn with_date(mut self, date: DateTime<Utc>) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// Add a `Signed-off-by` trailer for the committer
+    pub fn with_signoff(mut self) -> Self {
+        self.signoff = true;
+        self
+    }
+
+    /// Allow committing without staged changes
+    pub fn with_allow_empty(mut self) -> Self {
+        self.allow_empty = true;
+        self
+    }
+
+    /// Bypass the pre-commit and commit-msg hooks
+    pub fn with_no_verify(mut self) -> Self {
+        self.no_verify = true;
+        self
+    }
+
+    /// GPG-sign the commit with `key_id`; pass `""` to use the configured signing key
+    pub fn with_gpg_sign(mut self, key_id: &str) -> Self {
+        self.gpg_sign = Some(key_id.to_string());
+        self
+    }
+
+    /// Build the environment variables git reads the author, committer and dates from
+    fn commit_env(&self) -> Vec<(&'static str, String)> {
+        let mut env: Vec<(&'static str, String)> = self
+            .identity
+            .as_ref()
+            .map(|identity| {
+                identity
+                    .env()
+                    .iter()
+                    .map(|(key, value)| (*key, value.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Some(ref author) = self.author {
+            env.push(("GIT_AUTHOR_NAME", author.name.clone()));
+            env.push(("GIT_AUTHOR_EMAIL", author.email.clone()));
+        }
+
+        if let Some(date) = self.date {
+            let raw_date = format!("@{} +0000", date.timestamp());
+            env.push(("GIT_AUTHOR_DATE", raw_date.clone()));
+            env.push(("GIT_COMMITTER_DATE", raw_date));
+        }
+
+        env
+    }
+}
 
 impl Repository {
     /// Create a commit with the given message.
@@ -12,7 +145,42 @@ impl Repository {
     ///
     /// A `Result` containing the `Hash` of the new commit or a `GitError`.
     pub fn commit(&self, message: &str) -> Result<Hash> {
-        Self::ensure_git()?;
+        self.commit_with_options(message, CommitOptions::default())
+    }
+
+    /// Create a commit with the given message and options.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The commit message
+    /// * `options` - Commit options to customize the operation
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Hash` of the new commit or a `GitError`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{CommitOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// repo.add(&["src/lib.rs"])?;
+    /// repo.commit_with_options("Fix parser", CommitOptions::new().with_template())?;
+    ///
+    /// // A CI bot commit with fixed authorship and date, so the hash is reproducible
+    /// let date = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+    /// let options = CommitOptions::new()
+    ///     .with_author("Release Bot", "release-bot@example.com")
+    ///     .with_date(date)
+    ///     .with_signoff()
+    ///     .with_allow_empty()
+    ///     .with_no_verify();
+    /// let hash = repo.commit_with_options("Trigger release", options)?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn commit_with_options(&self, message: &str, options: CommitOptions) -> Result<Hash> {
+        self.check_git()?;
 
         if message.trim().is_empty() {
             return Err(crate::error::GitError::CommandFailed(
@@ -20,29 +188,140 @@ impl Repository {
             ));
         }
 
-        // Check if there are staged changes
-        let status = self.status()?;
-        let has_staged = status.staged_files().count() > 0;
+        self.run_commit(Some(message), options)
+    }
 
-        if !has_staged {
+    /// Replace the last commit with one that includes the currently staged changes.
+    ///
+    /// Nothing needs to be staged, so this can also just reword the last commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The new commit message, or `None` to keep the current one
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Hash` of the amended commit or a `GitError`.
+    pub fn commit_amend(&self, message: Option<&str>) -> Result<Hash> {
+        self.commit_amend_with_options(message, CommitOptions::new())
+    }
+
+    /// Amend the last commit with custom options.
+    ///
+    /// The original author and author date are kept unless
+    /// [`CommitOptions::with_reset_author`] is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The new commit message, or `None` to keep the current one
+    /// * `options` - Commit options; amending is implied
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{CommitOptions, Identity, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// repo.add(&["generated.rs"])?;
+    /// let bot = Identity::new("Codegen Bot", "bot@example.com");
+    /// repo.commit_amend_with_options(
+    ///     None,
+    ///     CommitOptions::new().with_identity(bot).with_reset_author(),
+    /// )?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn commit_amend_with_options(
+        &self,
+        message: Option<&str>,
+        options: CommitOptions,
+    ) -> Result<Hash> {
+        self.check_git()?;
+
+        if message.is_some_and(|message| message.trim().is_empty()) {
             return Err(crate::error::GitError::CommandFailed(
-                "No changes staged for commit".to_string(),
+                "Commit message cannot be empty".to_string(),
             ));
         }
 
-        let _stdout =
-            git(&["commit", "-m", message], Some(self.repo_path())).map_err(|e| match e {
-                crate::error::GitError::CommandFailed(msg) => {
-                    crate::error::GitError::CommandFailed(format!(
-                        "Commit failed: {}. Ensure git user.name and user.email are configured.",
-                        msg
-                    ))
-                }
-                other => other,
-            })?;
+        self.run_commit(message, options.with_amend())
+    }
+
+    /// Run `git commit`; `None` keeps the message of the amended commit (`--no-edit`)
+    fn run_commit(&self, message: Option<&str>, options: CommitOptions) -> Result<Hash> {
+        let mut message = message.map(str::to_string);
+        if let Some(ref mut message) = message
+            && options.use_template
+            && let Some(template) = self.commit_template()?
+        {
+            let template = self.strip_message_comments(&template)?;
+            if !template.is_empty() {
+                *message = format!("{}\n\n{}", message.trim_end(), template);
+            }
+        }
+
+        // Check if there are staged changes; amending may only reword
+        if !options.amend && !options.allow_empty {
+            let status = self.status()?;
+            let has_staged = status.staged_files().count() > 0;
+
+            if !has_staged {
+                return Err(crate::error::GitError::CommandFailed(
+                    "No changes staged for commit".to_string(),
+                ));
+            }
+        }
+
+        let mut args = vec!["commit"];
+        if options.amend {
+            args.push("--amend");
+        }
+        if options.reset_author {
+            args.push("--reset-author");
+        }
+        if options.signoff {
+            args.push("--signoff");
+        }
+        if options.allow_empty {
+            args.push("--allow-empty");
+        }
+        if options.no_verify {
+            args.push("--no-verify");
+        }
+        let gpg_sign = options
+            .gpg_sign
+            .as_ref()
+            .map(|key_id| match key_id.as_str() {
+                "" => "--gpg-sign".to_string(),
+                key_id => format!("--gpg-sign={}", key_id),
+            });
+        if let Some(ref gpg_sign) = gpg_sign {
+            args.push(gpg_sign);
+        }
+        match message {
+            Some(ref message) => {
+                args.push("-m");
+                args.push(message);
+            }
+            None => args.push("--no-edit"),
+        }
+
+        let commit_env = options.commit_env();
+        let env: Vec<(&str, &str)> = commit_env
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+        let _stdout = git_with_env(&args, Some(self), &env).map_err(|e| match e {
+            crate::error::GitError::CommandFailed(msg) => {
+                crate::error::GitError::CommandFailed(format!(
+                    "Commit failed: {}. Ensure git user.name and user.email are configured.",
+                    msg
+                ))
+            }
+            other => other,
+        })?;
 
         // Get the commit hash of the just-created commit
-        let hash_output = git(&["rev-parse", "HEAD"], Some(self.repo_path()))?;
+        let hash_output = git(&["rev-parse", "HEAD"], Some(self))?;
         let commit_hash = hash_output.trim().to_string();
 
         Ok(Hash(commit_hash))
@@ -59,7 +338,7 @@ impl Repository {
     ///
     /// A `Result` containing the `Hash` of the new commit or a `GitError`.
     pub fn commit_with_author(&self, message: &str, author: &str) -> Result<Hash> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         if message.trim().is_empty() {
             return Err(crate::error::GitError::CommandFailed(
@@ -83,7 +362,7 @@ impl Repository {
             ));
         }
 
-        let _stdout = git(&["commit", "-m", message, "--author", author], Some(self.repo_path()))
+        let _stdout = git(&["commit", "-m", message, "--author", author], Some(self))
             .map_err(|e| match e {
                 crate::error::GitError::CommandFailed(msg) => {
                     crate::error::GitError::CommandFailed(format!(
@@ -95,11 +374,69 @@ impl Repository {
             })?;
 
         // Get the commit hash of the just-created commit
-        let hash_output = git(&["rev-parse", "HEAD"], Some(self.repo_path()))?;
+        let hash_output = git(&["rev-parse", "HEAD"], Some(self))?;
         let commit_hash = hash_output.trim().to_string();
 
         Ok(Hash(commit_hash))
     }
+
+    /// Read the commit message template configured by `commit.template`.
+    ///
+    /// Relative template paths are resolved against the repository root and `~` is
+    /// expanded, matching how `git commit` locates the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the raw template contents, `None` if no template is
+    /// configured, or a `GitError` if the configured file cannot be read.
+    pub fn commit_template(&self) -> Result<Option<String>> {
+        self.check_git()?;
+
+        let output = git_raw(
+            &["config", "--path", "--get", "commit.template"],
+            Some(self),
+        )?;
+
+        // Exit code 1 means the key is not set
+        let path = match output.status.code() {
+            Some(0) => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            Some(1) => return Ok(None),
+            _ => {
+                return Err(GitError::CommandFailed(format!(
+                    "git config commit.template failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+        };
+        if path.is_empty() {
+            return Ok(None);
+        }
+
+        let template_path = self.repo_path().join(&path);
+        fs::read_to_string(&template_path).map(Some).map_err(|e| {
+            GitError::IoError(format!(
+                "Failed to read commit template {}: {}",
+                template_path.display(),
+                e
+            ))
+        })
+    }
+
+    /// Strip comment lines and surplus whitespace the way `git commit` cleans messages
+    fn strip_message_comments(&self, text: &str) -> Result<String> {
+        let output = git_raw_with_stdin(
+            &["stripspace", "--strip-comments"],
+            Some(self),
+            text.as_bytes(),
+        )?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git stripspace failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
 }
 
 #[cfg(test)]
@@ -244,6 +581,258 @@ mod tests {
         fs::remove_dir_all(test_path).unwrap();
     }
 
+    #[test]
+    fn test_commit_with_template() {
+        let test_path = "/tmp/test_commit_template_repo";
+        let repo = create_test_repo(test_path);
+
+        assert_eq!(repo.commit_template().unwrap(), None);
+
+        fs::write(
+            format!("{}/.gitmessage", test_path),
+            "# Explain why\n\nTicket: OPS-1\n",
+        )
+        .unwrap();
+        repo.config().set("commit.template", ".gitmessage").unwrap();
+        assert_eq!(
+            repo.commit_template().unwrap().as_deref(),
+            Some("# Explain why\n\nTicket: OPS-1\n")
+        );
+
+        create_and_stage_file(&repo, test_path, "test.txt", "test content");
+        repo.commit_with_options("Add test file", CommitOptions::new().with_template())
+            .unwrap();
+        let message = git(&["log", "-1", "--format=%B"], Some(repo.repo_path())).unwrap();
+        assert_eq!(message.trim_end(), "Add test file\n\nTicket: OPS-1");
+
+        // Without the option the template is ignored
+        create_and_stage_file(&repo, test_path, "other.txt", "other content");
+        repo.commit("Plain commit").unwrap();
+        let message = git(&["log", "-1", "--format=%B"], Some(repo.repo_path())).unwrap();
+        assert_eq!(message.trim_end(), "Plain commit");
+
+        // A missing template file is reported instead of silently ignored
+        repo.config().set("commit.template", "missing.txt").unwrap();
+        assert!(matches!(repo.commit_template(), Err(GitError::IoError(_))));
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_commit_merge_and_tag_with_identity() {
+        let test_path = "/tmp/test_commit_identity_repo";
+        let repo = create_test_repo(test_path);
+        let bot = Identity::new("Release Bot", "bot@example.com");
+        let last_ident = |format: &str| {
+            git(&["log", "-1", format], Some(repo.repo_path()))
+                .unwrap()
+                .trim()
+                .to_string()
+        };
+
+        create_and_stage_file(&repo, test_path, "test.txt", "test content");
+        repo.commit_with_options(
+            "Bot commit",
+            CommitOptions::new().with_identity(bot.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            last_ident("--format=%an <%ae> %cn <%ce>"),
+            "Release Bot <bot@example.com> Release Bot <bot@example.com>"
+        );
+
+        repo.checkout_new("feature", None).unwrap();
+        create_and_stage_file(&repo, test_path, "feature.txt", "feature");
+        repo.commit("Feature commit").unwrap();
+        assert_eq!(last_ident("--format=%an"), "Test User");
+        repo.checkout_new("main-line", Some("HEAD~1")).unwrap();
+        repo.merge_with_options(
+            "feature",
+            crate::MergeOptions::new()
+                .with_fast_forward(crate::FastForwardMode::Never)
+                .with_identity(bot.clone()),
+        )
+        .unwrap();
+        assert_eq!(last_ident("--format=%cn <%ce>"), bot.to_string());
+
+        repo.create_tag_with_options(
+            "v1.0.0",
+            None,
+            crate::TagOptions::new()
+                .with_message("Release".to_string())
+                .with_identity(bot),
+        )
+        .unwrap();
+        let tagger = git(&["cat-file", "-p", "v1.0.0"], Some(repo.repo_path())).unwrap();
+        assert!(tagger.contains("tagger Release Bot <bot@example.com>"));
+
+        // The configured user is untouched
+        assert_eq!(repo.config().get("user.name").unwrap(), "Test User");
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_commit_amend() {
+        let test_path = "/tmp/test_commit_amend_repo";
+        let repo = create_test_repo(test_path);
+        let last = |format: &str| {
+            git(&["log", "-1", format], Some(repo.repo_path()))
+                .unwrap()
+                .trim()
+                .to_string()
+        };
+
+        create_and_stage_file(&repo, test_path, "a.txt", "a");
+        repo.commit("Initial commit").unwrap();
+        create_and_stage_file(&repo, test_path, "b.txt", "b");
+        let original = repo
+            .commit_with_options(
+                "Add b",
+                CommitOptions::new().with_identity(Identity::new("Alice", "alice@example.com")),
+            )
+            .unwrap();
+
+        // Fold in a late change, keeping the message and the author
+        create_and_stage_file(&repo, test_path, "c.txt", "c");
+        let amended = repo.commit_amend(None).unwrap();
+        assert_ne!(amended, original);
+        assert_eq!(last("--format=%s|%an|%cn"), "Add b|Alice|Test User");
+        assert_eq!(
+            last("--format=%P"),
+            git(&["rev-parse", "HEAD~1"], Some(repo.repo_path()))
+                .unwrap()
+                .trim()
+        );
+        assert!(repo.status().unwrap().is_clean());
+        let count = git(&["rev-list", "--count", "HEAD"], Some(repo.repo_path())).unwrap();
+        assert_eq!(count.trim(), "2");
+
+        // Reword without staged changes, and take over authorship
+        let bot = Identity::new("Fixup Bot", "bot@example.com");
+        repo.commit_amend_with_options(
+            Some("Add b and c"),
+            CommitOptions::new().with_identity(bot).with_reset_author(),
+        )
+        .unwrap();
+        assert_eq!(
+            last("--format=%s|%an|%cn"),
+            "Add b and c|Fixup Bot|Fixup Bot"
+        );
+
+        // commit_with_options can amend too
+        repo.commit_with_options("Add b, c", CommitOptions::new().with_amend())
+            .unwrap();
+        assert_eq!(last("--format=%s"), "Add b, c");
+
+        assert!(repo.commit_amend(Some("  ")).is_err());
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_commit_options_author_date_signoff() {
+        let test_path = "/tmp/test_commit_options_author_date_repo";
+        let repo = create_test_repo(test_path);
+        let last = |format: &str| {
+            git(&["log", "-1", format], Some(repo.repo_path()))
+                .unwrap()
+                .trim()
+                .to_string()
+        };
+        let date = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let options = || {
+            CommitOptions::new()
+                .with_author("Release Bot", "bot@example.com")
+                .with_date(date)
+        };
+
+        create_and_stage_file(&repo, test_path, "a.txt", "a");
+        let hash = repo
+            .commit_with_options("Bot commit", options().with_signoff())
+            .unwrap();
+        assert_eq!(
+            last("--format=%an <%ae>|%cn|%at|%ct"),
+            "Release Bot <bot@example.com>|Test User|1700000000|1700000000"
+        );
+        assert!(last("--format=%b").contains("Signed-off-by: Test User <test@example.com>"));
+        assert_eq!(hash.as_str(), last("--format=%H"));
+
+        // The author override wins over the identity for the author only
+        create_and_stage_file(&repo, test_path, "b.txt", "b");
+        repo.commit_with_options(
+            "Bot commit 2",
+            options().with_identity(Identity::new("CI", "ci@example.com")),
+        )
+        .unwrap();
+        assert_eq!(last("--format=%an|%cn"), "Release Bot|CI");
+
+        // Fixed authorship and dates make the commit reproducible
+        git(&["reset", "--hard", "HEAD~1"], Some(repo.repo_path())).unwrap();
+        create_and_stage_file(&repo, test_path, "b.txt", "b");
+        let again = repo
+            .commit_with_options(
+                "Bot commit 2",
+                options().with_identity(Identity::new("CI", "ci@example.com")),
+            )
+            .unwrap();
+        assert_eq!(again.as_str(), last("--format=%H"));
+        let reflog = git(&["rev-parse", "HEAD@{2}"], Some(repo.repo_path())).unwrap();
+        assert_eq!(again.as_str(), reflog.trim());
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_commit_options_allow_empty_no_verify_gpg_sign() {
+        let test_path = "/tmp/test_commit_options_flags_repo";
+        let repo = create_test_repo(test_path);
+
+        create_and_stage_file(&repo, test_path, "a.txt", "a");
+        let first = repo.commit("Initial commit").unwrap();
+
+        assert!(
+            repo.commit_with_options("Empty", CommitOptions::new())
+                .is_err()
+        );
+        let empty = repo
+            .commit_with_options("Empty", CommitOptions::new().with_allow_empty())
+            .unwrap();
+        assert_ne!(empty, first);
+
+        // A rejecting pre-commit hook is skipped with no_verify
+        let hook = Path::new(test_path).join(".git/hooks/pre-commit");
+        fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        create_and_stage_file(&repo, test_path, "b.txt", "b");
+        assert!(repo.commit("Blocked").is_err());
+        repo.commit_with_options("Unblocked", CommitOptions::new().with_no_verify())
+            .unwrap();
+
+        // Signing goes through gpg.program, which fails here
+        repo.config().set("gpg.program", "false").unwrap();
+        create_and_stage_file(&repo, test_path, "c.txt", "c");
+        assert!(
+            repo.commit_with_options("Signed", CommitOptions::new().with_gpg_sign("ABCDEF"))
+                .is_err()
+        );
+        assert!(
+            repo.commit_with_options("Signed", CommitOptions::new().with_gpg_sign(""))
+                .is_err()
+        );
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
     #[test]
     fn test_git_config_is_set_in_test_repo() {
         let test_path = "/tmp/test_git_config_repo";
@@ -0,0 +1,321 @@
+//! In-memory commit index for repeated history queries
+//!
+//! Interactive tools often ask many questions about the same history: who wrote what,
+//! which commits touched a file, which messages mention a ticket. A [`CommitIndex`]
+//! answers them from memory after a single `git log` pass, and
+//! [`refresh`](CommitIndex::refresh) only reads commits added since.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::Repository;
+//!
+//! let repo = Repository::open(".")?;
+//! let mut index = repo.commit_index("HEAD")?;
+//!
+//! for commit in index.by_author("jane@example.com") {
+//!     println!("{}", commit);
+//! }
+//! println!("{} commits touched Cargo.toml", index.touching("Cargo.toml").count());
+//!
+//! // Later, after new commits were made
+//! let added = index.refresh()?;
+//! println!("indexed {} new commits", added);
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::log::{Commit, DECORATE_FULL, FILE_HISTORY_FORMAT, parse_log_record};
+use crate::error::Result;
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, git_raw, validate_arg};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Commits reachable from a revision, indexed by hash, author, and path
+///
+/// Author lookups match the exact name or email; paths are repository-relative and
+/// match files exactly. Renames are indexed under both the old and the new path. Merge
+/// commits are not indexed by path, as in `git log -- <path>` without `-m`.
+#[derive(Debug, Clone)]
+pub struct CommitIndex {
+    repo: Repository,
+    rev: String,
+    tip: Option<Hash>,
+    /// Oldest first, so positions stay valid when newer commits are appended
+    commits: Vec<Commit>,
+    by_hash: HashMap<String, usize>,
+    by_author: HashMap<String, Vec<usize>>,
+    by_path: HashMap<PathBuf, Vec<usize>>,
+}
+
+impl CommitIndex {
+    /// The revision this index follows, as passed to [`Repository::commit_index`]
+    pub fn rev(&self) -> &str {
+        &self.rev
+    }
+
+    /// The commit the revision pointed to at the last build or refresh
+    pub fn tip(&self) -> Option<&Hash> {
+        self.tip.as_ref()
+    }
+
+    /// Number of indexed commits
+    pub fn len(&self) -> usize {
+        self.commits.len()
+    }
+
+    /// Check if the index holds no commits
+    pub fn is_empty(&self) -> bool {
+        self.commits.is_empty()
+    }
+
+    /// Iterate over all commits, newest first
+    pub fn iter(&self) -> impl Iterator<Item = &Commit> {
+        self.commits.iter().rev()
+    }
+
+    /// Find a commit by full hash
+    pub fn get(&self, hash: &str) -> Option<&Commit> {
+        self.by_hash
+            .get(hash)
+            .map(|&position| &self.commits[position])
+    }
+
+    /// Commits whose author name or email is exactly `author`, newest first
+    pub fn by_author(&self, author: &str) -> impl Iterator<Item = &Commit> {
+        self.lookup(self.by_author.get(author))
+    }
+
+    /// Commits that changed the file at `path`, newest first
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Repository-relative path, or an absolute path inside the work tree
+    pub fn touching<P: AsRef<Path>>(&self, path: P) -> impl Iterator<Item = &Commit> {
+        let path = self
+            .repo
+            .to_repo_relative(path.as_ref())
+            .unwrap_or_else(|_| path.as_ref().to_path_buf());
+        self.lookup(self.by_path.get(&path))
+    }
+
+    /// Commits whose message contains `text` (case-insensitive), newest first
+    pub fn message_contains(&self, text: &str) -> impl Iterator<Item = &Commit> {
+        let text = text.to_lowercase();
+        self.iter()
+            .filter(move |commit| commit.message_contains(&text))
+    }
+
+    /// Distinct author names and emails in the index
+    pub fn authors(&self) -> impl Iterator<Item = &str> {
+        self.by_author.keys().map(String::as_str)
+    }
+
+    /// Index the commits added to the revision since the last build or refresh
+    ///
+    /// If the previous tip is no longer reachable (after a reset, rebase, or amend),
+    /// the index is rebuilt from scratch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of commits that were indexed.
+    pub fn refresh(&mut self) -> Result<usize> {
+        let tip = resolve(&self.repo, &self.rev)?;
+        if tip == self.tip {
+            return Ok(0);
+        }
+
+        match (&self.tip, &tip) {
+            (Some(old), Some(new)) if self.repo.is_ancestor(old.as_str(), new.as_str())? => {
+                let exclude = format!("^{}", old);
+                let added = self.load(&[new.as_str(), &exclude])?;
+                self.tip = tip;
+                Ok(added)
+            }
+            _ => {
+                *self = Self::build(&self.repo, &self.rev)?;
+                Ok(self.commits.len())
+            }
+        }
+    }
+
+    /// Walk the history of `rev` and index every commit
+    fn build(repo: &Repository, rev: &str) -> Result<Self> {
+        let mut index = Self {
+            repo: repo.clone(),
+            rev: rev.to_string(),
+            tip: resolve(repo, rev)?,
+            commits: Vec::new(),
+            by_hash: HashMap::new(),
+            by_author: HashMap::new(),
+            by_path: HashMap::new(),
+        };
+        if let Some(tip) = index.tip.clone() {
+            index.load(&[tip.as_str()])?;
+        }
+        Ok(index)
+    }
+
+    /// Read the commits selected by `revisions` and append them, oldest first
+    fn load(&mut self, revisions: &[&str]) -> Result<usize> {
+        let mut args = vec![
+            "log",
+            FILE_HISTORY_FORMAT,
+            "--no-show-signature",
+            DECORATE_FULL,
+            "--name-only",
+            "--no-renames",
+            "-z",
+        ];
+        args.extend_from_slice(revisions);
+        args.push("--");
+        let output = git(&args, Some(&self.repo))?;
+
+        let records = parse_index_output(&output)?;
+        let added = records.len();
+        for (commit, paths) in records.into_iter().rev() {
+            self.insert(commit, paths);
+        }
+        Ok(added)
+    }
+
+    fn insert(&mut self, commit: Commit, paths: Vec<PathBuf>) {
+        let position = self.commits.len();
+        self.by_hash
+            .insert(commit.hash.as_str().to_string(), position);
+
+        let mut authors = vec![commit.author.name.clone(), commit.author.email.clone()];
+        authors.dedup();
+        for author in authors {
+            self.by_author.entry(author).or_default().push(position);
+        }
+        for path in paths {
+            self.by_path.entry(path).or_default().push(position);
+        }
+
+        self.commits.push(commit);
+    }
+
+    fn lookup(&self, positions: Option<&Vec<usize>>) -> impl Iterator<Item = &Commit> {
+        positions
+            .into_iter()
+            .flatten()
+            .rev()
+            .map(|&position| &self.commits[position])
+    }
+}
+
+impl Repository {
+    /// Build a [`CommitIndex`] of the commits reachable from `rev`
+    ///
+    /// Reads the history once with `git log --name-only`; queries on the index then
+    /// run without git. An unborn branch yields an empty index that
+    /// [`refresh`](CommitIndex::refresh) fills once commits exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `rev` - The revision to follow, usually `"HEAD"` or a branch name
+    pub fn commit_index(&self, rev: &str) -> Result<CommitIndex> {
+        self.check_git()?;
+        validate_arg("revision", rev)?;
+        CommitIndex::build(self, rev)
+    }
+}
+
+/// Resolve `rev` to a commit, or `None` if it does not exist yet (e.g. an unborn HEAD)
+fn resolve(repo: &Repository, rev: &str) -> Result<Option<Hash>> {
+    let spec = format!("{}^{{commit}}", rev);
+    let output = git_raw(&["rev-parse", "--verify", "--quiet", &spec], Some(repo))?;
+    let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((output.status.success() && !hash.is_empty()).then(|| Hash::from(hash)))
+}
+
+/// Parse `FILE_HISTORY_FORMAT` records followed by `--name-only -z` path lists
+fn parse_index_output(output: &str) -> Result<Vec<(Commit, Vec<PathBuf>)>> {
+    let mut records = Vec::new();
+
+    for record in output.split('\x1e') {
+        let Some((header, paths)) = record.split_once('\x1d') else {
+            continue;
+        };
+        let Some(commit) = parse_log_record(header)? else {
+            continue;
+        };
+
+        let paths = paths
+            .trim_start_matches(['\0', '\n'])
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        records.push((commit, paths));
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn test_parse_index_output() {
+        let output = "\x1eaaa\x1fJane\x1fjane@example.com\x1f1700000000\x1fJane\x1fjane@example.com\x1f1700000000\x1fbbb\x1f\x1fSecond\x1f\x1d\0\nsrc/lib.rs\0dir/with space.txt\0\x1ebbb\x1fBob\x1fbob@example.com\x1f1600000000\x1fBob\x1fbob@example.com\x1f1600000000\x1f\x1f\x1fFirst\x1fBody\n\x1d\0\nREADME.md\0";
+        let records = parse_index_output(output).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].0.hash.as_str(), "aaa");
+        assert_eq!(
+            records[0].1,
+            vec![
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("dir/with space.txt")
+            ]
+        );
+        assert_eq!(records[1].0.message.body.as_deref(), Some("Body"));
+        assert_eq!(records[1].1, vec![PathBuf::from("README.md")]);
+        assert!(parse_index_output("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_commit_index_queries_and_refresh() {
+        let repo = TempRepo::new("commit_index").unwrap();
+        let mut index = repo.commit_index("HEAD").unwrap();
+        assert!(index.is_empty());
+        assert!(index.tip().is_none());
+
+        let first = repo
+            .commit_files(&[("a.txt", "a\n"), ("b.txt", "b\n")], "Add a and b")
+            .unwrap();
+        let second = repo
+            .commit_file("a.txt", "a2\n", "Fix PROJ-7 in a")
+            .unwrap();
+        assert_eq!(index.refresh().unwrap(), 2);
+        assert_eq!(index.tip(), Some(&second));
+        assert_eq!(
+            index.get(first.as_str()).unwrap().message.subject,
+            "Add a and b"
+        );
+
+        let touching_a: Vec<&Hash> = index.touching("a.txt").map(|c| &c.hash).collect();
+        assert_eq!(touching_a, vec![&second, &first]);
+        assert_eq!(index.touching(repo.path().join("b.txt")).count(), 1);
+        assert_eq!(index.by_author(crate::testing::TEST_USER_EMAIL).count(), 2);
+        assert_eq!(index.by_author("nobody").count(), 0);
+        assert_eq!(index.message_contains("proj-7").count(), 1);
+
+        // Only new commits are read
+        assert_eq!(index.refresh().unwrap(), 0);
+        let third = repo.commit_file("c.txt", "c\n", "Add c").unwrap();
+        assert_eq!(index.refresh().unwrap(), 1);
+        assert_eq!(index.iter().next().unwrap().hash, third);
+        assert_eq!(index.len(), 3);
+
+        // Rewritten history triggers a rebuild
+        git(&["reset", "--hard", first.as_str()], Some(repo.repo())).unwrap();
+        assert_eq!(index.refresh().unwrap(), 1);
+        assert!(index.get(second.as_str()).is_none());
+        assert_eq!(index.touching("a.txt").count(), 1);
+    }
+}
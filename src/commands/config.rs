@@ -1,5 +1,124 @@
-use crate::utils::git;
-use crate::{Repository, Result};
+use crate::utils::{git, git_raw, validate_arg};
+use crate::{GitError, Repository, Result};
+
+/// Signature format used for signing commits and tags (`gpg.format`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SigningFormat {
+    /// OpenPGP signatures via GnuPG
+    Gpg,
+    /// SSH signatures via ssh-keygen
+    Ssh,
+}
+
+impl SigningFormat {
+    /// The value git expects for `gpg.format`
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gpg => "openpgp",
+            Self::Ssh => "ssh",
+        }
+    }
+
+    /// The config key holding the signing program for this format
+    pub const fn program_key(&self) -> &'static str {
+        match self {
+            Self::Gpg => "gpg.program",
+            Self::Ssh => "gpg.ssh.program",
+        }
+    }
+
+    /// Parse a `gpg.format` value
+    pub fn from_config_value(value: &str) -> Option<Self> {
+        match value {
+            "openpgp" => Some(Self::Gpg),
+            "ssh" => Some(Self::Ssh),
+            _ => None,
+        }
+    }
+}
+
+/// Commit and tag signing configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningConfig {
+    /// The signature format
+    pub format: SigningFormat,
+    /// The signing key: a GPG key id, or an SSH public key path or literal `key::` value
+    pub key: String,
+    /// Custom signing program (e.g. `gpg2` or `/usr/bin/ssh-keygen`)
+    pub program: Option<String>,
+    /// Sign every commit by default (`commit.gpgsign`)
+    pub sign_commits: bool,
+    /// Sign every annotated tag by default (`tag.gpgsign`)
+    pub sign_tags: bool,
+}
+
+impl SigningConfig {
+    /// Create a signing configuration that signs both commits and tags
+    pub fn new(format: SigningFormat, key: impl Into<String>) -> Self {
+        Self {
+            format,
+            key: key.into(),
+            program: None,
+            sign_commits: true,
+            sign_tags: true,
+        }
+    }
+
+    pub fn with_program(mut self, program: impl Into<String>) -> Self {
+        self.program = Some(program.into());
+        self
+    }
+
+    pub fn with_sign_commits(mut self, sign_commits: bool) -> Self {
+        self.sign_commits = sign_commits;
+        self
+    }
+
+    pub fn with_sign_tags(mut self, sign_tags: bool) -> Self {
+        self.sign_tags = sign_tags;
+        self
+    }
+}
+
+/// A URL rewrite rule (`url.<base>.insteadOf` or `url.<base>.pushInsteadOf`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UrlRewrite {
+    /// The URL that replaces the matched prefix
+    pub base: String,
+    /// The URL prefix being rewritten
+    pub instead_of: String,
+    /// Whether the rule only applies to push URLs (`pushInsteadOf`)
+    pub push_only: bool,
+}
+
+impl UrlRewrite {
+    /// Create a rule rewriting `instead_of` to `base` for fetch and push
+    pub fn new(base: impl Into<String>, instead_of: impl Into<String>) -> Self {
+        Self {
+            base: base.into(),
+            instead_of: instead_of.into(),
+            push_only: false,
+        }
+    }
+
+    /// Create a rule rewriting `instead_of` to `base` for push only
+    pub fn push(base: impl Into<String>, instead_of: impl Into<String>) -> Self {
+        Self {
+            push_only: true,
+            ..Self::new(base, instead_of)
+        }
+    }
+
+    /// The config key holding this rule
+    pub fn config_key(&self) -> String {
+        let variable = if self.push_only {
+            "pushInsteadOf"
+        } else {
+            "insteadOf"
+        };
+        format!("url.{}.{}", self.base, variable)
+    }
+}
 
 /// Repository configuration manager
 ///
@@ -17,6 +136,10 @@ impl<'a> RepoConfig<'a> {
 
     pub const USER_NAME_KEY: &'static str = "user.name";
     pub const USER_EMAIL_KEY: &'static str = "user.email";
+    pub const GPG_FORMAT_KEY: &'static str = "gpg.format";
+    pub const SIGNING_KEY_KEY: &'static str = "user.signingkey";
+    pub const COMMIT_GPGSIGN_KEY: &'static str = "commit.gpgsign";
+    pub const TAG_GPGSIGN_KEY: &'static str = "tag.gpgsign";
 
     /// Configure git user name and email for this repository
     ///
@@ -66,6 +189,161 @@ impl<'a> RepoConfig<'a> {
         Ok((name, email))
     }
 
+    /// Configure commit and tag signing for this repository
+    ///
+    /// Writes `gpg.format`, `user.signingkey`, the format's program key
+    /// (`gpg.program` or `gpg.ssh.program`), `commit.gpgsign`, and `tag.gpgsign`
+    /// in one call. When no program is given, a previously configured program for
+    /// the same format is removed so git falls back to its default.
+    ///
+    /// # Arguments
+    ///
+    /// * `signing` - The signing configuration to apply
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rustic_git::{Repository, SigningConfig, SigningFormat};
+    /// use std::{env, fs};
+    ///
+    /// let test_path = env::temp_dir().join("config_set_signing_test");
+    /// if test_path.exists() {
+    ///     fs::remove_dir_all(&test_path).unwrap();
+    /// }
+    ///
+    /// let repo = Repository::init(&test_path, false)?;
+    /// repo.config().set_signing(SigningConfig::new(
+    ///     SigningFormat::Ssh,
+    ///     "~/.ssh/id_ed25519.pub",
+    /// ))?;
+    ///
+    /// // Clean up
+    /// fs::remove_dir_all(&test_path).unwrap();
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn set_signing(&self, signing: SigningConfig) -> Result<()> {
+        self.set(Self::GPG_FORMAT_KEY, signing.format.as_str())?;
+        self.set(Self::SIGNING_KEY_KEY, &signing.key)?;
+
+        let program_key = signing.format.program_key();
+        match &signing.program {
+            Some(program) => self.set(program_key, program)?,
+            None if self.get(program_key).is_ok() => self.unset(program_key)?,
+            None => {}
+        }
+
+        self.set(Self::COMMIT_GPGSIGN_KEY, bool_value(signing.sign_commits))?;
+        self.set(Self::TAG_GPGSIGN_KEY, bool_value(signing.sign_tags))?;
+        Ok(())
+    }
+
+    /// Get the current signing configuration
+    ///
+    /// # Returns
+    ///
+    /// `Ok(None)` if no signing key is configured, otherwise the signing settings.
+    /// An unset `gpg.format` means GPG, and unset sign flags mean `false`, as in git.
+    pub fn get_signing(&self) -> Result<Option<SigningConfig>> {
+        let Ok(key) = self.get(Self::SIGNING_KEY_KEY) else {
+            return Ok(None);
+        };
+
+        let format = self
+            .get(Self::GPG_FORMAT_KEY)
+            .ok()
+            .and_then(|value| SigningFormat::from_config_value(&value))
+            .unwrap_or(SigningFormat::Gpg);
+
+        Ok(Some(SigningConfig {
+            format,
+            key,
+            program: self.get(format.program_key()).ok(),
+            sign_commits: self.get_bool(Self::COMMIT_GPGSIGN_KEY)?,
+            sign_tags: self.get_bool(Self::TAG_GPGSIGN_KEY)?,
+        }))
+    }
+
+    /// Read a boolean value with git's canonicalization, defaulting to `false` if unset
+    fn get_bool(&self, key: &str) -> Result<bool> {
+        match git(&["config", "--type=bool", key], Some(self.repo)) {
+            Ok(value) => Ok(value.trim() == "true"),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Add a URL rewrite rule
+    ///
+    /// Multiple prefixes may map to the same base; adding an existing rule is a no-op.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{Repository, UrlRewrite};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// repo.config().add_url_rewrite(&UrlRewrite::new(
+    ///     "https://x-access-token@github.com/",
+    ///     "git@github.com:",
+    /// ))?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn add_url_rewrite(&self, rewrite: &UrlRewrite) -> Result<()> {
+        if self.url_rewrites()?.contains(rewrite) {
+            return Ok(());
+        }
+        git(
+            &[
+                "config",
+                "--add",
+                &rewrite.config_key(),
+                &rewrite.instead_of,
+            ],
+            Some(self.repo),
+        )?;
+        Ok(())
+    }
+
+    /// Remove a URL rewrite rule, leaving other prefixes for the same base intact
+    pub fn remove_url_rewrite(&self, rewrite: &UrlRewrite) -> Result<()> {
+        git(
+            &[
+                "config",
+                "--fixed-value",
+                "--unset-all",
+                &rewrite.config_key(),
+                &rewrite.instead_of,
+            ],
+            Some(self.repo),
+        )?;
+        Ok(())
+    }
+
+    /// List all URL rewrite rules visible to this repository
+    ///
+    /// Includes rules from global and system config, in git's precedence order.
+    pub fn url_rewrites(&self) -> Result<Vec<UrlRewrite>> {
+        let output = git_raw(
+            &[
+                "config",
+                "-z",
+                "--get-regexp",
+                r"^url\..*\.(push)?insteadof$",
+            ],
+            Some(self.repo),
+        )?;
+
+        // Exit code 1 means no matching keys
+        match output.status.code() {
+            Some(0) => Ok(parse_url_rewrites(&String::from_utf8_lossy(&output.stdout))),
+            Some(1) => Ok(Vec::new()),
+            _ => Err(GitError::CommandFailed(format!(
+                "git config --get-regexp failed: stdout='{}' stderr='{}'",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+        }
+    }
+
     /// Set a git configuration value for this repository
     ///
     /// # Arguments
@@ -93,7 +371,8 @@ impl<'a> RepoConfig<'a> {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn set(&self, key: &str, value: &str) -> Result<()> {
-        git(&["config", key, value], Some(self.repo.repo_path()))?;
+        validate_arg("config key", key)?;
+        git(&["config", key, value], Some(self.repo))?;
         Ok(())
     }
 
@@ -128,7 +407,8 @@ impl<'a> RepoConfig<'a> {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn get(&self, key: &str) -> Result<String> {
-        git(&["config", key], Some(self.repo.repo_path())).map(|s| s.trim().to_string())
+        validate_arg("config key", key)?;
+        git(&["config", key], Some(self.repo)).map(|s| s.trim().to_string())
     }
 
     /// Remove a git configuration value from this repository
@@ -157,11 +437,40 @@ impl<'a> RepoConfig<'a> {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn unset(&self, key: &str) -> Result<()> {
-        git(&["config", "--unset", key], Some(self.repo.repo_path()))?;
+        validate_arg("config key", key)?;
+        git(&["config", "--unset", key], Some(self.repo))?;
         Ok(())
     }
 }
 
+fn bool_value(value: bool) -> &'static str {
+    if value { "true" } else { "false" }
+}
+
+/// Parse `git config -z --get-regexp` output for `url.*.insteadOf` keys
+///
+/// Each record is `key\nvalue\0`. Section and variable names come back
+/// lowercased, but the base URL (the subsection) keeps its case.
+fn parse_url_rewrites(output: &str) -> Vec<UrlRewrite> {
+    output
+        .split('\0')
+        .filter_map(|record| {
+            let (key, value) = record.split_once('\n')?;
+            let (base, variable) = key.strip_prefix("url.")?.rsplit_once('.')?;
+            let push_only = match variable {
+                "insteadof" => false,
+                "pushinsteadof" => true,
+                _ => return None,
+            };
+            Some(UrlRewrite {
+                base: base.to_string(),
+                instead_of: value.to_string(),
+                push_only,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -292,4 +601,90 @@ mod tests {
         // Clean up
         fs::remove_dir_all(&test_path).unwrap();
     }
+
+    #[test]
+    fn test_config_set_and_get_signing() {
+        let test_path = env::temp_dir().join("test_config_signing");
+
+        // Clean up if exists
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        assert_eq!(repo.config().get_signing().unwrap(), None);
+
+        let ssh = SigningConfig::new(SigningFormat::Ssh, "/home/me/.ssh/id_ed25519.pub")
+            .with_program("/usr/bin/ssh-keygen")
+            .with_sign_tags(false);
+        repo.config().set_signing(ssh.clone()).unwrap();
+
+        assert_eq!(repo.config().get("gpg.format").unwrap(), "ssh");
+        assert_eq!(
+            repo.config().get("gpg.ssh.program").unwrap(),
+            "/usr/bin/ssh-keygen"
+        );
+        assert_eq!(repo.config().get("commit.gpgsign").unwrap(), "true");
+        assert_eq!(repo.config().get("tag.gpgsign").unwrap(), "false");
+        assert_eq!(repo.config().get_signing().unwrap(), Some(ssh));
+
+        // Switching back to GPG without a program clears only the GPG program
+        repo.config().set("gpg.program", "gpg2").unwrap();
+        let gpg =
+            SigningConfig::new(SigningFormat::Gpg, "ABCDEF0123456789").with_sign_commits(false);
+        repo.config().set_signing(gpg.clone()).unwrap();
+
+        assert_eq!(repo.config().get("gpg.format").unwrap(), "openpgp");
+        assert!(repo.config().get("gpg.program").is_err());
+        assert_eq!(repo.config().get_signing().unwrap(), Some(gpg));
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_config_url_rewrites() {
+        let test_path = env::temp_dir().join("test_config_url_rewrites");
+
+        // Clean up if exists
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        assert!(repo.config().url_rewrites().unwrap().is_empty());
+
+        let fetch = UrlRewrite::new("https://Mirror.example.com/", "git@github.com:");
+        let fetch_alt = UrlRewrite::new("https://Mirror.example.com/", "gh:");
+        let push = UrlRewrite::push("ssh://git@push.example.com/", "https://example.com/");
+        repo.config().add_url_rewrite(&fetch).unwrap();
+        repo.config().add_url_rewrite(&fetch_alt).unwrap();
+        repo.config().add_url_rewrite(&push).unwrap();
+        repo.config().add_url_rewrite(&fetch).unwrap();
+
+        let rewrites = repo.config().url_rewrites().unwrap();
+        assert_eq!(
+            rewrites,
+            vec![fetch.clone(), fetch_alt.clone(), push.clone()]
+        );
+
+        repo.config().remove_url_rewrite(&fetch).unwrap();
+        let rewrites = repo.config().url_rewrites().unwrap();
+        assert_eq!(rewrites, vec![fetch_alt, push]);
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_url_rewrites() {
+        let output = "url.https://a/.insteadof\nx:\0url.b.c/.pushinsteadof\ny:\0";
+        assert_eq!(
+            parse_url_rewrites(output),
+            vec![
+                UrlRewrite::new("https://a/", "x:"),
+                UrlRewrite::push("b.c/", "y:"),
+            ]
+        );
+    }
 }
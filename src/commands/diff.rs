@@ -1,5 +1,7 @@
+use crate::commands::objects::FileMode;
+use crate::commands::status::IgnoreSubmodules;
 use crate::types::Hash;
-use crate::utils::git;
+use crate::utils::{git, unquote_path, validate_arg};
 use crate::{Repository, Result};
 use std::fmt;
 use std::path::PathBuf;
@@ -49,19 +51,35 @@ impl fmt::Display for DiffStatus {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DiffChunk {
     pub old_start: usize,
     pub old_count: usize,
     pub new_start: usize,
     pub new_count: usize,
+    /// Text after the hunk range, usually the enclosing function
+    /// (`@@ -1,3 +1,4 @@ fn main() {`)
+    pub heading: Option<String>,
     pub lines: Box<[DiffLine]>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DiffLine {
     pub line_type: DiffLineType,
     pub content: String,
+    /// The line is the last one in its file and has no trailing newline
+    /// (`\ No newline at end of file`)
+    pub no_newline: bool,
+}
+
+impl DiffLine {
+    pub fn new(line_type: DiffLineType, content: &str) -> Self {
+        Self {
+            line_type,
+            content: content.to_string(),
+            no_newline: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -98,6 +116,14 @@ pub struct FileDiff {
     pub chunks: Box<[DiffChunk]>,
     pub additions: usize,
     pub deletions: usize,
+    /// Mode before the change (`None` for added files or when unchanged)
+    pub old_mode: Option<FileMode>,
+    /// Mode after the change (`None` for deleted files or when unchanged)
+    pub new_mode: Option<FileMode>,
+    /// Similarity percentage for renames and copies (`similarity index 90%`)
+    pub similarity: Option<u8>,
+    /// Git treated the file as binary, so there are no chunks or line counts
+    pub binary: bool,
 }
 
 impl FileDiff {
@@ -109,6 +135,10 @@ impl FileDiff {
             chunks: Box::new([]),
             additions: 0,
             deletions: 0,
+            old_mode: None,
+            new_mode: None,
+            similarity: None,
+            binary: false,
         }
     }
 
@@ -128,9 +158,46 @@ impl FileDiff {
         self
     }
 
+    pub fn with_modes(mut self, old_mode: Option<FileMode>, new_mode: Option<FileMode>) -> Self {
+        self.old_mode = old_mode;
+        self.new_mode = new_mode;
+        self
+    }
+
+    /// Record the similarity percentage of a rename or copy
+    pub fn with_similarity(mut self, similarity: u8) -> Self {
+        self.similarity = Some(similarity);
+        self
+    }
+
+    /// Mark the file as binary
+    pub fn with_binary(mut self) -> Self {
+        self.binary = true;
+        self
+    }
+
     pub fn is_binary(&self) -> bool {
-        self.chunks.is_empty() && (self.additions > 0 || self.deletions > 0)
+        self.binary
+    }
+
+    /// Check if the file mode changed (e.g. executable bit toggled or type changed)
+    pub fn mode_changed(&self) -> bool {
+        matches!((self.old_mode, self.new_mode), (Some(old), Some(new)) if old != new)
     }
+
+    /// Check if the file changed type (e.g. symlink to regular file), as opposed to
+    /// only toggling the executable bit
+    pub fn type_changed(&self) -> bool {
+        match (self.old_mode, self.new_mode) {
+            (Some(old), Some(new)) => old != new && !(is_file_mode(old) && is_file_mode(new)),
+            _ => false,
+        }
+    }
+}
+
+/// Regular and executable files are the same type; everything else is distinct
+fn is_file_mode(mode: FileMode) -> bool {
+    matches!(mode, FileMode::Regular | FileMode::Executable)
 }
 
 impl fmt::Display for FileDiff {
@@ -148,6 +215,26 @@ impl fmt::Display for FileDiff {
     }
 }
 
+/// Exact line counts for one changed file, from `--numstat`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStat {
+    /// The path after the change
+    pub path: PathBuf,
+    /// The path before the change, if the file was renamed or copied
+    pub old_path: Option<PathBuf>,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Whether git treated the file as binary (line counts are then zero)
+    pub binary: bool,
+}
+
+impl FileStat {
+    /// Check if the file was renamed or copied
+    pub fn is_rename(&self) -> bool {
+        self.old_path.is_some()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DiffStats {
     pub files_changed: usize,
@@ -236,6 +323,27 @@ impl fmt::Display for DiffOutput {
     }
 }
 
+/// How submodule changes are shown in diffs (`--submodule=<format>`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubmoduleFormat {
+    /// Only the old and new commit hashes (git's default)
+    Short,
+    /// The list of commits in the changed range
+    Log,
+    /// The full content diff of the submodule, recursively
+    Diff,
+}
+
+impl SubmoduleFormat {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Short => "short",
+            Self::Log => "log",
+            Self::Diff => "diff",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DiffOptions {
     pub context_lines: Option<usize>,
@@ -244,10 +352,14 @@ pub struct DiffOptions {
     pub ignore_blank_lines: bool,
     pub paths: Option<Vec<PathBuf>>,
     pub name_only: bool,
+    pub name_status: bool,
+    pub diff_filter: Option<String>,
     pub stat_only: bool,
     pub numstat: bool,
     pub cached: bool,
     pub no_index: bool,
+    pub submodule: Option<SubmoduleFormat>,
+    pub ignore_submodules: Option<IgnoreSubmodules>,
 }
 
 impl DiffOptions {
@@ -259,10 +371,14 @@ impl DiffOptions {
             ignore_blank_lines: false,
             paths: None,
             name_only: false,
+            name_status: false,
+            diff_filter: None,
             stat_only: false,
             numstat: false,
             cached: false,
             no_index: false,
+            submodule: None,
+            ignore_submodules: None,
         }
     }
 
@@ -296,16 +412,45 @@ impl DiffOptions {
         self
     }
 
+    /// List changed files with their status (`--name-status`), including both paths
+    /// of renames and copies, without loading file contents
+    pub fn name_status(mut self) -> Self {
+        self.name_status = true;
+        self
+    }
+
+    /// Only report changes of the given kinds, e.g. `"ACDMR"`; lowercase letters
+    /// exclude a kind instead (`--diff-filter`)
+    pub fn diff_filter(mut self, filter: &str) -> Self {
+        self.diff_filter = Some(filter.to_string());
+        self
+    }
+
+    /// Report only per-file line counts and statuses, without patch text
     pub fn stat_only(mut self) -> Self {
         self.stat_only = true;
         self
     }
 
+    /// Same as [`stat_only`](Self::stat_only): exact counts from `--numstat`
     pub fn numstat(mut self) -> Self {
         self.numstat = true;
         self
     }
 
+    /// Choose how submodule changes are shown; `SubmoduleFormat::Diff` includes
+    /// the changed files inside submodules as regular file diffs
+    pub fn submodule(mut self, format: SubmoduleFormat) -> Self {
+        self.submodule = Some(format);
+        self
+    }
+
+    /// Override which submodule changes are reported
+    pub fn ignore_submodules(mut self, mode: IgnoreSubmodules) -> Self {
+        self.ignore_submodules = Some(mode);
+        self
+    }
+
     pub fn cached(mut self) -> Self {
         self.cached = true;
         self
@@ -323,6 +468,79 @@ impl Default for DiffOptions {
     }
 }
 
+/// The revisions a diff compares, given as branches, tags, hashes or expressions
+/// such as `HEAD~3`
+///
+/// Strings convert with git's range syntax: `"a..b"` is a [`Range`](Self::Range),
+/// `"a...b"` a [`MergeBase`](Self::MergeBase), and anything else a
+/// [`Single`](Self::Single) revision. An empty side means `HEAD`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Revspec {
+    /// One revision compared with the working tree, or with the index when
+    /// [`DiffOptions::cached`] is set
+    Single(String),
+    /// Two revisions compared directly (`<from>..<to>`)
+    Range(String, String),
+    /// The changes on `to` since it diverged from `from` (`<from>...<to>`)
+    MergeBase(String, String),
+}
+
+impl Revspec {
+    /// Parse a revision or `..`/`...` range expression
+    pub fn parse(spec: &str) -> Self {
+        let side = |rev: &str| {
+            if rev.is_empty() {
+                "HEAD".to_string()
+            } else {
+                rev.to_string()
+            }
+        };
+        if let Some((from, to)) = spec.split_once("...") {
+            Revspec::MergeBase(side(from), side(to))
+        } else if let Some((from, to)) = spec.split_once("..") {
+            Revspec::Range(side(from), side(to))
+        } else {
+            Revspec::Single(spec.to_string())
+        }
+    }
+
+    /// The revisions named, for validation
+    fn revisions(&self) -> Vec<&str> {
+        match self {
+            Revspec::Single(rev) => vec![rev],
+            Revspec::Range(from, to) | Revspec::MergeBase(from, to) => vec![from, to],
+        }
+    }
+}
+
+impl From<&str> for Revspec {
+    fn from(spec: &str) -> Self {
+        Revspec::parse(spec)
+    }
+}
+
+impl From<String> for Revspec {
+    fn from(spec: String) -> Self {
+        Revspec::parse(&spec)
+    }
+}
+
+impl From<&Hash> for Revspec {
+    fn from(hash: &Hash) -> Self {
+        Revspec::Single(hash.as_str().to_string())
+    }
+}
+
+impl fmt::Display for Revspec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Revspec::Single(rev) => write!(f, "{}", rev),
+            Revspec::Range(from, to) => write!(f, "{}..{}", from, to),
+            Revspec::MergeBase(from, to) => write!(f, "{}...{}", from, to),
+        }
+    }
+}
+
 impl Repository {
     /// Get diff between working directory and index (staged changes)
     ///
@@ -380,7 +598,7 @@ impl Repository {
     ///
     /// A `Result` containing the `DiffOutput` or a `GitError`.
     pub fn diff_head(&self) -> Result<DiffOutput> {
-        self.diff_commits_with_options(None, Some(&Hash::from("HEAD")), &DiffOptions::new())
+        self.diff_revspec("HEAD")
     }
 
     /// Get diff between two commits
@@ -409,7 +627,119 @@ impl Repository {
     /// # }
     /// ```
     pub fn diff_commits(&self, from: &Hash, to: &Hash) -> Result<DiffOutput> {
-        self.diff_commits_with_options(Some(from), Some(to), &DiffOptions::new())
+        let spec = Revspec::Range(from.as_str().to_string(), to.as_str().to_string());
+        self.diff_revspec(spec)
+    }
+
+    /// Get diff for a revision or range given by name
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - A [`Revspec`], or a string such as `"main..feature"`, `"HEAD~3"` or
+    ///   `"v1.0"`
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `DiffOutput` or a `GitError`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// # fn main() -> rustic_git::Result<()> {
+    /// let repo = Repository::open(".")?;
+    /// let since_release = repo.diff_revspec("v1.0..main")?;
+    /// let uncommitted = repo.diff_revspec("HEAD~3")?;
+    /// println!("{}\n{}", since_release, uncommitted);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff_revspec<R: Into<Revspec>>(&self, spec: R) -> Result<DiffOutput> {
+        self.diff_revspec_with_options(spec, &DiffOptions::new())
+    }
+
+    /// Get diff for a revision or range with custom options
+    pub fn diff_revspec_with_options<R: Into<Revspec>>(
+        &self,
+        spec: R,
+        options: &DiffOptions,
+    ) -> Result<DiffOutput> {
+        self.diff_commits_with_options(Some(&spec.into()), options)
+    }
+
+    /// Get the changes on `b` since it diverged from `a` (`git diff a...b`)
+    ///
+    /// This is what a pull request from `b` into `a` would change, ignoring commits
+    /// made on `a` in the meantime.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// # fn main() -> rustic_git::Result<()> {
+    /// let repo = Repository::open(".")?;
+    /// let review = repo.diff_merge_base("main", "feature")?;
+    /// println!("{}", review);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff_merge_base(&self, a: &str, b: &str) -> Result<DiffOutput> {
+        self.diff_revspec(Revspec::MergeBase(a.to_string(), b.to_string()))
+    }
+
+    /// Get the changes a commit introduced, with full patch content
+    ///
+    /// Root commits show every file as added, and merge commits are compared with
+    /// their first parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The commit to show
+    /// * `options` - Formatting and path options; `cached` and `no_index` do not apply
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `DiffOutput` or a `GitError`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{DiffOptions, Repository};
+    ///
+    /// # fn main() -> rustic_git::Result<()> {
+    /// let repo = Repository::open(".")?;
+    /// for commit in repo.recent_commits(5)?.iter() {
+    ///     let diff = repo.show_commit_diff(&commit.hash, &DiffOptions::new())?;
+    ///     println!("{} {}", commit.hash.short(), diff.stats);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn show_commit_diff(&self, hash: &Hash, options: &DiffOptions) -> Result<DiffOutput> {
+        self.check_git()?;
+        validate_arg("commit", hash.as_str())?;
+
+        let mut args = self.diff_args(None, options)?;
+        args[0] = "show".to_string();
+        let separator = args
+            .iter()
+            .position(|arg| arg == "--")
+            .unwrap_or(args.len());
+        args.splice(
+            separator..separator,
+            [
+                "--format=".to_string(),
+                "--no-show-signature".to_string(),
+                "--diff-merges=first-parent".to_string(),
+                hash.as_str().to_string(),
+            ],
+        );
+
+        let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let output = git(&args_str, Some(self))?;
+        self.diff_from_output(&output, options)
     }
 
     /// Get diff with custom options
@@ -438,18 +768,60 @@ impl Repository {
     /// # }
     /// ```
     pub fn diff_with_options(&self, options: &DiffOptions) -> Result<DiffOutput> {
-        self.diff_commits_with_options(None, None, options)
+        self.diff_commits_with_options(None, options)
     }
 
     /// Internal method to handle all diff operations
     fn diff_commits_with_options(
         &self,
-        from: Option<&Hash>,
-        to: Option<&Hash>,
+        spec: Option<&Revspec>,
+        options: &DiffOptions,
+    ) -> Result<DiffOutput> {
+        self.check_git()?;
+
+        let args = self.diff_args(spec, options)?;
+        let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let output = git(&args_str, Some(self))?;
+        self.diff_from_output(&output, options)
+    }
+
+    /// Parse `git diff` output in the format `options` asked for and style its paths
+    pub(crate) fn diff_from_output(
+        &self,
+        output: &str,
         options: &DiffOptions,
     ) -> Result<DiffOutput> {
-        Self::ensure_git()?;
+        let mut diff = if options.name_status {
+            parse_name_status_output(output)
+        } else if options.name_only {
+            parse_name_only_output(output)
+        } else if options.stat_only || options.numstat {
+            parse_raw_numstat_output(output)
+        } else {
+            parse_diff_output(output)
+        }?;
+
+        // --no-index compares arbitrary filesystem paths, which are left as given
+        if !options.no_index {
+            let command_dir = self.command_dir();
+            for file in diff.files.iter_mut() {
+                file.path = self.styled_path(&command_dir, std::mem::take(&mut file.path));
+                file.old_path = file
+                    .old_path
+                    .take()
+                    .map(|old_path| self.styled_path(&command_dir, old_path));
+            }
+        }
 
+        Ok(diff)
+    }
+
+    /// Build the `git diff` arguments for an optional revision range and `options`
+    pub(crate) fn diff_args(
+        &self,
+        spec: Option<&Revspec>,
+        options: &DiffOptions,
+    ) -> Result<Vec<String>> {
         let mut args = vec!["diff".to_string()];
 
         // Add options
@@ -465,58 +837,54 @@ impl Repository {
         if options.ignore_blank_lines {
             args.push("--ignore-blank-lines".to_string());
         }
-        if options.name_only {
+        if options.name_status {
+            args.push("--name-status".to_string());
+            args.push("-z".to_string());
+        } else if options.name_only {
             args.push("--name-only".to_string());
-        }
-        if options.stat_only {
-            args.push("--stat".to_string());
-        }
-        if options.numstat {
+        } else if options.stat_only || options.numstat {
+            // Exact per-file counts (the --stat bar graph is scaled) plus raw records
+            // for each file's status and modes
+            args.push("--raw".to_string());
             args.push("--numstat".to_string());
+            args.push("-z".to_string());
+        }
+        if let Some(filter) = &options.diff_filter {
+            args.push(format!("--diff-filter={}", filter));
         }
         if options.cached {
             args.push("--cached".to_string());
         }
         if options.no_index {
             args.push("--no-index".to_string());
+        } else if self.subdir().is_some() {
+            // Limit to the subdirectory and report paths relative to it
+            args.push("--relative".to_string());
+        }
+        if let Some(format) = options.submodule {
+            args.push(format!("--submodule={}", format.as_str()));
+        }
+        if let Some(mode) = options.ignore_submodules {
+            args.push(format!("--ignore-submodules={}", mode.as_str()));
         }
 
-        // Add commit range if specified
-        match (from, to) {
-            (Some(from_hash), Some(to_hash)) => {
-                args.push(format!("{}..{}", from_hash.as_str(), to_hash.as_str()));
-            }
-            (None, Some(to_hash)) => {
-                args.push(to_hash.as_str().to_string());
-            }
-            (Some(from_hash), None) => {
-                args.push(format!("{}..HEAD", from_hash.as_str()));
-            }
-            (None, None) => {
-                // Default diff behavior
+        // Add revision range if specified
+        if let Some(spec) = spec {
+            for rev in spec.revisions() {
+                validate_arg("revision", rev)?;
             }
+            args.push(spec.to_string());
         }
 
-        // Add paths if specified
+        // Add paths if specified; `--` keeps commits from being read as paths
+        args.push("--".to_string());
         if let Some(paths) = &options.paths {
-            args.push("--".to_string());
             for path in paths {
                 args.push(path.to_string_lossy().to_string());
             }
         }
 
-        let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        let output = git(&args_str, Some(self.repo_path()))?;
-
-        if options.name_only {
-            parse_name_only_output(&output)
-        } else if options.stat_only {
-            parse_stat_output(&output)
-        } else if options.numstat {
-            parse_numstat_output(&output)
-        } else {
-            parse_diff_output(&output)
-        }
+        Ok(args)
     }
 }
 
@@ -530,83 +898,386 @@ fn parse_name_only_output(output: &str) -> Result<DiffOutput> {
     Ok(DiffOutput::new(files))
 }
 
-fn parse_stat_output(output: &str) -> Result<DiffOutput> {
+/// Parse `--name-status -z` output: a status field followed by one path, or by the
+/// old and new paths for renames and copies (`R100`, `C075`)
+fn parse_name_status_output(output: &str) -> Result<DiffOutput> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
     let mut files = Vec::new();
-    let mut stats = DiffStats::new();
-
-    for line in output.lines() {
-        if line.contains(" | ") {
-            let parts: Vec<&str> = line.split(" | ").collect();
-            if parts.len() == 2 {
-                let path = PathBuf::from(parts[0].trim());
-                let file_diff = FileDiff::new(path, DiffStatus::Modified);
-                files.push(file_diff);
+
+    while let Some(status_field) = fields.next() {
+        let letter = status_field.chars().next().unwrap_or('M');
+        let missing_path = || {
+            crate::error::GitError::CommandFailed(format!(
+                "Missing path after diff status '{}'",
+                status_field
+            ))
+        };
+
+        let file = match letter {
+            'R' | 'C' => {
+                let old_path = fields.next().ok_or_else(missing_path)?;
+                let new_path = fields.next().ok_or_else(missing_path)?;
+                let status = if letter == 'R' {
+                    DiffStatus::Renamed
+                } else {
+                    DiffStatus::Copied
+                };
+                let file = FileDiff::new(PathBuf::from(new_path), status)
+                    .with_old_path(PathBuf::from(old_path));
+                match status_field[1..].parse() {
+                    Ok(similarity) => file.with_similarity(similarity),
+                    Err(_) => file,
+                }
             }
-        } else if line.contains("files changed") || line.contains("file changed") {
-            // Parse summary line like "3 files changed, 15 insertions(+), 5 deletions(-)"
-            if let Some(files_part) = line.split(',').next()
-                && let Some(num_str) = files_part.split_whitespace().next()
-                && let Ok(num) = num_str.parse::<usize>()
-            {
-                stats.files_changed = num;
+            _ => {
+                let path = fields.next().ok_or_else(missing_path)?;
+                // Type changes (T) and unmerged paths (U) have no dedicated status
+                let status = DiffStatus::from_char(letter).unwrap_or(DiffStatus::Modified);
+                FileDiff::new(PathBuf::from(path), status)
             }
-        }
+        };
+        files.push(file);
     }
 
-    Ok(DiffOutput {
-        files: files.into_boxed_slice(),
-        stats,
-    })
+    Ok(DiffOutput::new(files))
 }
 
-fn parse_numstat_output(output: &str) -> Result<DiffOutput> {
-    let files: Vec<FileDiff> = output
-        .lines()
-        .filter(|line| !line.is_empty())
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split('\t').collect();
-            if parts.len() >= 3 {
-                let additions = parts[0].parse().unwrap_or(0);
-                let deletions = parts[1].parse().unwrap_or(0);
-                let path = PathBuf::from(parts[2]);
-
-                let status = if additions > 0 && deletions == 0 {
-                    DiffStatus::Added
-                } else if additions == 0 && deletions > 0 {
-                    DiffStatus::Deleted
+/// Parse `--raw --numstat -z` output
+///
+/// All raw records (`:<old mode> <new mode> <old oid> <new oid> <status>` followed by
+/// one path, or two for renames and copies) come first, then one numstat record per
+/// file in the same order.
+fn parse_raw_numstat_output(output: &str) -> Result<DiffOutput> {
+    let mut fields = output.split('\0').peekable();
+    let mut files = Vec::new();
+
+    while let Some(header) = fields.next_if(|field| field.starts_with(':')) {
+        let missing_path = || {
+            crate::error::GitError::CommandFailed(format!(
+                "Missing path after diff record '{}'",
+                header
+            ))
+        };
+        let parts: Vec<&str> = header[1..].split(' ').collect();
+        let (Some(old_mode), Some(new_mode), Some(status)) =
+            (parts.first(), parts.get(1), parts.get(4))
+        else {
+            return Err(crate::error::GitError::CommandFailed(format!(
+                "Malformed diff record '{}'",
+                header
+            )));
+        };
+
+        let letter = status.chars().next().unwrap_or('M');
+        let mut file = match letter {
+            'R' | 'C' => {
+                let old_path = fields.next().ok_or_else(missing_path)?;
+                let new_path = fields.next().ok_or_else(missing_path)?;
+                let diff_status = if letter == 'R' {
+                    DiffStatus::Renamed
                 } else {
-                    DiffStatus::Modified
+                    DiffStatus::Copied
                 };
-
-                Some(FileDiff::new(path, status).with_stats(additions, deletions))
-            } else {
-                None
+                let file = FileDiff::new(PathBuf::from(new_path), diff_status)
+                    .with_old_path(PathBuf::from(old_path));
+                match status[1..].parse() {
+                    Ok(similarity) => file.with_similarity(similarity),
+                    Err(_) => file,
+                }
             }
-        })
-        .collect();
+            _ => {
+                let path = fields.next().ok_or_else(missing_path)?;
+                // Type changes (T) and unmerged paths (U) have no dedicated status
+                let status = DiffStatus::from_char(letter).unwrap_or(DiffStatus::Modified);
+                FileDiff::new(PathBuf::from(path), status)
+            }
+        };
+
+        // Absent sides are reported as mode 000000
+        let mode = |octal: &str| FileMode::from_octal(octal).filter(|_| octal != "000000");
+        file.old_mode = mode(old_mode);
+        file.new_mode = mode(new_mode);
+        files.push(file);
+    }
+
+    let numstat: Vec<&str> = fields.collect();
+    let stats = parse_numstat(&numstat.join("\0"));
+    for (file, stat) in files.iter_mut().zip(stats) {
+        file.additions = stat.insertions;
+        file.deletions = stat.deletions;
+        file.binary = stat.binary;
+    }
 
     Ok(DiffOutput::new(files))
 }
 
-fn parse_diff_output(output: &str) -> Result<DiffOutput> {
-    // For now, return a simplified parser
-    // In a full implementation, this would parse the complete diff format
-    let files: Vec<FileDiff> = output
-        .lines()
-        .filter(|line| line.starts_with("diff --git"))
-        .filter_map(|line| {
-            // Extract file paths from "diff --git a/file b/file"
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 4 {
-                let path_str = parts[3].strip_prefix("b/").unwrap_or(parts[3]);
-                Some(FileDiff::new(PathBuf::from(path_str), DiffStatus::Modified))
-            } else {
-                None
+/// Parse NUL-separated `--numstat -z` output
+///
+/// Records are `<ins>\t<del>\t<path>`, or `<ins>\t<del>\t` followed by the old and
+/// new paths as separate fields for renames and copies. Binary files have `-` counts.
+pub(crate) fn parse_numstat(output: &str) -> Vec<FileStat> {
+    let mut stats = Vec::new();
+    let mut fields = output.split('\0');
+
+    while let Some(record) = fields.next() {
+        let mut parts = record.trim_start_matches('\n').splitn(3, '\t');
+        let (Some(insertions), Some(deletions), Some(path)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+
+        let (old_path, path) = if path.is_empty() {
+            let (Some(old_path), Some(new_path)) = (fields.next(), fields.next()) else {
+                break;
+            };
+            (Some(PathBuf::from(old_path)), PathBuf::from(new_path))
+        } else {
+            (None, PathBuf::from(path))
+        };
+
+        let binary = insertions == "-" && deletions == "-";
+        stats.push(FileStat {
+            path,
+            old_path,
+            insertions: insertions.parse().unwrap_or(0),
+            deletions: deletions.parse().unwrap_or(0),
+            binary,
+        });
+    }
+
+    stats
+}
+
+/// Parse full `git diff` patch output into files with their hunks
+///
+/// Besides the hunks, this reads the extended header lines (modes, renames and copies
+/// with their similarity, binary markers). Hunk lengths come from the `@@` ranges, so
+/// content lines that look like headers (`--- x`) are not mistaken for them.
+pub(crate) fn parse_diff_output(output: &str) -> Result<DiffOutput> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    let mut hunk: Option<HunkBuilder> = None;
+    // Combined diffs of unmerged paths (`diff --cc`) have no single old side
+    let mut in_combined = false;
+
+    // Only '\n' ends a line, so a '\r' of CRLF content stays part of the line
+    for line in output.split('\n') {
+        if let Some(builder) = hunk.as_mut()
+            && !builder.is_complete()
+        {
+            let Some(file) = files.last_mut() else {
+                break;
+            };
+            builder.push_line(file, line);
+            continue;
+        }
+
+        // `\ No newline at end of file` may follow the last line of a hunk
+        if line.starts_with('\\') {
+            if let Some(last) = hunk.as_mut().and_then(|builder| builder.lines.last_mut()) {
+                last.no_newline = true;
+            }
+            continue;
+        }
+
+        if let Some(builder) = hunk.take() {
+            chunks.push(builder.finish());
+        }
+
+        let combined = line.starts_with("diff --cc ") || line.starts_with("diff --combined ");
+        if combined || line.starts_with("diff --git ") {
+            if let Some(file) = files.last_mut() {
+                file.chunks = std::mem::take(&mut chunks).into_boxed_slice();
+            }
+            in_combined = combined;
+            if let Some(header) = line.strip_prefix("diff --git ") {
+                files.push(FileDiff::new(header_path(header), DiffStatus::Modified));
             }
+            continue;
+        }
+
+        let Some(file) = files.last_mut().filter(|_| !in_combined) else {
+            continue;
+        };
+
+        if line.starts_with("@@ -") {
+            hunk = Some(HunkBuilder::parse(line)?);
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            file.old_mode = FileMode::from_octal(mode.trim());
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            file.new_mode = FileMode::from_octal(mode.trim());
+        } else if let Some(mode) = line.strip_prefix("new file mode ") {
+            file.status = DiffStatus::Added;
+            file.new_mode = FileMode::from_octal(mode.trim());
+        } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+            file.status = DiffStatus::Deleted;
+            file.old_mode = FileMode::from_octal(mode.trim());
+        } else if let Some(percent) = line.strip_prefix("similarity index ") {
+            file.similarity = percent.trim_end_matches('%').parse().ok();
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.status = DiffStatus::Renamed;
+            file.old_path = Some(PathBuf::from(unquote_path(from)));
+        } else if let Some(from) = line.strip_prefix("copy from ") {
+            file.status = DiffStatus::Copied;
+            file.old_path = Some(PathBuf::from(unquote_path(from)));
+        } else if let Some(to) = line
+            .strip_prefix("rename to ")
+            .or_else(|| line.strip_prefix("copy to "))
+        {
+            // More reliable than the "diff --git" header for paths containing spaces
+            file.path = PathBuf::from(unquote_path(to));
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        } else if let Some(to) = line.strip_prefix("+++ ")
+            // Unquoted paths containing a space are followed by a tab
+            && let Some(to) = unquote_path(to.strip_suffix('\t').unwrap_or(to)).strip_prefix("b/")
+        {
+            file.path = PathBuf::from(to);
+        }
+    }
+
+    if let Some(builder) = hunk {
+        chunks.push(builder.finish());
+    }
+    if let Some(file) = files.last_mut() {
+        file.chunks = chunks.into_boxed_slice();
+    }
+
+    Ok(DiffOutput::new(merge_type_changes(files)))
+}
+
+/// The path from a `diff --git a/<old> b/<new>` header
+///
+/// Renames and copies are corrected by their `rename to`/`copy to` lines, and other
+/// files by their `+++` line when there is one. Paths git had to quote end the header
+/// as `"b/<path>"`; a quote inside one is escaped, so the last ` "` starts it.
+fn header_path(header: &str) -> PathBuf {
+    if header.ends_with('"')
+        && let Some((_, quoted)) = header.rsplit_once(" \"")
+    {
+        let path = unquote_path(&format!("\"{}", quoted));
+        return PathBuf::from(path.strip_prefix("b/").unwrap_or(&path));
+    }
+
+    let path = header
+        .rsplit_once(" b/")
+        .map(|(_, new)| new)
+        .unwrap_or_else(|| header.rsplit(' ').next().unwrap_or(header));
+    PathBuf::from(path)
+}
+
+/// A hunk being parsed, with the number of old and new lines still expected
+struct HunkBuilder {
+    chunk: DiffChunk,
+    lines: Vec<DiffLine>,
+    old_remaining: usize,
+    new_remaining: usize,
+}
+
+impl HunkBuilder {
+    /// Start a hunk from its `@@ -<start>[,<count>] +<start>[,<count>] @@[ heading]` line
+    fn parse(line: &str) -> Result<Self> {
+        let malformed =
+            || crate::error::GitError::CommandFailed(format!("Malformed hunk header '{}'", line));
+
+        let rest = line.strip_prefix("@@ -").ok_or_else(malformed)?;
+        let (ranges, heading) = rest.split_once(" @@").ok_or_else(malformed)?;
+        let (old, new) = ranges.split_once(" +").ok_or_else(malformed)?;
+        let range = |range: &str| -> Result<(usize, usize)> {
+            let (start, count) = range.split_once(',').unwrap_or((range, "1"));
+            Ok((
+                start.parse().map_err(|_| malformed())?,
+                count.parse().map_err(|_| malformed())?,
+            ))
+        };
+        let (old_start, old_count) = range(old)?;
+        let (new_start, new_count) = range(new)?;
+        let heading = heading.strip_prefix(' ').filter(|h| !h.is_empty());
+
+        Ok(Self {
+            chunk: DiffChunk {
+                old_start,
+                old_count,
+                new_start,
+                new_count,
+                heading: heading.map(str::to_string),
+                lines: Box::new([]),
+            },
+            lines: Vec::new(),
+            old_remaining: old_count,
+            new_remaining: new_count,
         })
-        .collect();
+    }
 
-    Ok(DiffOutput::new(files))
+    fn is_complete(&self) -> bool {
+        self.old_remaining == 0 && self.new_remaining == 0
+    }
+
+    /// Add a content line, counting it towards `file`'s additions and deletions
+    fn push_line(&mut self, file: &mut FileDiff, line: &str) {
+        if line.starts_with('\\') {
+            if let Some(last) = self.lines.last_mut() {
+                last.no_newline = true;
+            }
+            return;
+        }
+
+        // Editors may strip the space of an empty context line
+        let mut chars = line.chars();
+        let line_type = chars
+            .next()
+            .and_then(DiffLineType::from_char)
+            .unwrap_or(DiffLineType::Context);
+        let content = if line.is_empty() { "" } else { chars.as_str() };
+
+        match line_type {
+            DiffLineType::Added => {
+                self.new_remaining = self.new_remaining.saturating_sub(1);
+                file.additions += 1;
+            }
+            DiffLineType::Removed => {
+                self.old_remaining = self.old_remaining.saturating_sub(1);
+                file.deletions += 1;
+            }
+            DiffLineType::Context => {
+                self.old_remaining = self.old_remaining.saturating_sub(1);
+                self.new_remaining = self.new_remaining.saturating_sub(1);
+            }
+        }
+        self.lines.push(DiffLine::new(line_type, content));
+    }
+
+    fn finish(mut self) -> DiffChunk {
+        self.chunk.lines = self.lines.into_boxed_slice();
+        self.chunk
+    }
+}
+
+/// Git reports a type change (e.g. symlink -> regular file) as a deletion followed by
+/// an addition of the same path; fold those pairs into a single modified entry
+fn merge_type_changes(files: Vec<FileDiff>) -> Vec<FileDiff> {
+    let mut merged: Vec<FileDiff> = Vec::with_capacity(files.len());
+
+    for file in files {
+        if let Some(previous) = merged.last_mut()
+            && previous.status == DiffStatus::Deleted
+            && file.status == DiffStatus::Added
+            && previous.path == file.path
+        {
+            previous.status = DiffStatus::Modified;
+            previous.new_mode = file.new_mode;
+            previous.additions += file.additions;
+            previous.binary |= file.binary;
+            let mut chunks = std::mem::take(&mut previous.chunks).into_vec();
+            chunks.extend(file.chunks.into_vec());
+            previous.chunks = chunks.into_boxed_slice();
+            continue;
+        }
+        merged.push(file);
+    }
+
+    merged
 }
 
 #[cfg(test)]
@@ -785,6 +1456,72 @@ mod tests {
         assert!(options.no_index);
     }
 
+    #[test]
+    fn test_diff_options_submodules() {
+        let options = DiffOptions::new()
+            .submodule(SubmoduleFormat::Diff)
+            .ignore_submodules(IgnoreSubmodules::None);
+
+        assert_eq!(options.submodule, Some(SubmoduleFormat::Diff));
+        assert_eq!(options.ignore_submodules, Some(IgnoreSubmodules::None));
+        assert_eq!(SubmoduleFormat::Short.as_str(), "short");
+        assert_eq!(SubmoduleFormat::Log.as_str(), "log");
+        assert_eq!(IgnoreSubmodules::Untracked.as_str(), "untracked");
+    }
+
+    #[test]
+    fn test_repository_diff_submodule_contents() {
+        let base = env::temp_dir().join("rustic_git_diff_submodule_test");
+        if base.exists() {
+            std::fs::remove_dir_all(&base).ok();
+        }
+        let sub_path = base.join("sub");
+        let super_path = base.join("super");
+
+        let sub = Repository::init(&sub_path, false).unwrap();
+        sub.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        std::fs::write(sub_path.join("lib.txt"), "lib\n").unwrap();
+        sub.add(&["lib.txt"]).unwrap();
+        sub.commit("Sub initial").unwrap();
+
+        let repo = Repository::init(&super_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        git(
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                sub_path.to_str().unwrap(),
+                "vendor",
+            ],
+            Some(&super_path),
+        )
+        .unwrap();
+        repo.commit("Add submodule").unwrap();
+
+        std::fs::write(super_path.join("vendor/lib.txt"), "changed\n").unwrap();
+
+        let diff = repo
+            .diff_with_options(&DiffOptions::new().submodule(SubmoduleFormat::Diff))
+            .unwrap();
+        assert!(
+            diff.iter()
+                .any(|file| file.path == std::path::Path::new("vendor/lib.txt"))
+        );
+
+        let diff = repo
+            .diff_with_options(&DiffOptions::new().ignore_submodules(IgnoreSubmodules::All))
+            .unwrap();
+        assert!(diff.is_empty());
+
+        std::fs::remove_dir_all(&base).ok();
+    }
+
     #[test]
     fn test_diff_options_with_paths() {
         let paths = vec![PathBuf::from("src/"), PathBuf::from("tests/")];
@@ -805,26 +1542,416 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_numstat_output() {
-        let output = "5\t0\tfile1.txt\n3\t2\tfile2.rs\n0\t10\tfile3.py\n";
-        let result = parse_numstat_output(output).unwrap();
+    fn test_parse_name_status_output() {
+        let output = "A\0new.txt\0M\0src/lib.rs\0D\0gone.txt\0R087\0old name.txt\0new name.txt\0C100\0a.txt\0b.txt\0T\0link\0";
+        let result = parse_name_status_output(output).unwrap();
 
-        assert_eq!(result.len(), 3);
-
-        assert_eq!(result.files[0].path, PathBuf::from("file1.txt"));
+        assert_eq!(result.len(), 6);
         assert_eq!(result.files[0].status, DiffStatus::Added);
-        assert_eq!(result.files[0].additions, 5);
-        assert_eq!(result.files[0].deletions, 0);
-
-        assert_eq!(result.files[1].path, PathBuf::from("file2.rs"));
         assert_eq!(result.files[1].status, DiffStatus::Modified);
-        assert_eq!(result.files[1].additions, 3);
-        assert_eq!(result.files[1].deletions, 2);
+        assert_eq!(result.files[2].status, DiffStatus::Deleted);
+        assert_eq!(result.files[3].status, DiffStatus::Renamed);
+        assert_eq!(result.files[3].path, PathBuf::from("new name.txt"));
+        assert_eq!(
+            result.files[3].old_path,
+            Some(PathBuf::from("old name.txt"))
+        );
+        assert_eq!(result.files[3].similarity, Some(87));
+        assert_eq!(result.files[4].status, DiffStatus::Copied);
+        assert_eq!(result.files[4].old_path, Some(PathBuf::from("a.txt")));
+        assert_eq!(result.files[4].similarity, Some(100));
+        assert_eq!(result.files[5].status, DiffStatus::Modified);
+
+        assert!(parse_name_status_output("R100\0only-old.txt\0").is_err());
+    }
+
+    #[test]
+    fn test_repository_diff_name_status_and_filter() {
+        let temp_dir = env::temp_dir().join("rustic_git_diff_name_status_test");
+        if temp_dir.exists() {
+            std::fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        std::fs::write(temp_dir.join("keep.txt"), "keep\n").unwrap();
+        std::fs::write(
+            temp_dir.join("move.txt"),
+            "a long enough body\nto be detected\nas a rename\n",
+        )
+        .unwrap();
+        std::fs::write(temp_dir.join("drop.txt"), "drop\n").unwrap();
+        repo.add(&["keep.txt", "move.txt", "drop.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+
+        std::fs::write(temp_dir.join("keep.txt"), "changed\n").unwrap();
+        std::fs::rename(temp_dir.join("move.txt"), temp_dir.join("moved.txt")).unwrap();
+        std::fs::remove_file(temp_dir.join("drop.txt")).unwrap();
+        std::fs::write(temp_dir.join("new.txt"), "new\n").unwrap();
+        git(&["add", "-A"], Some(&temp_dir)).unwrap();
+
+        let diff = repo
+            .diff_with_options(&DiffOptions::new().cached().name_status())
+            .unwrap();
+        assert_eq!(diff.len(), 4);
+        let status_of = |path: &str| {
+            diff.iter()
+                .find(|file| file.path == std::path::Path::new(path))
+                .map(|file| file.status.clone())
+        };
+        assert_eq!(status_of("keep.txt"), Some(DiffStatus::Modified));
+        assert_eq!(status_of("moved.txt"), Some(DiffStatus::Renamed));
+        assert_eq!(status_of("drop.txt"), Some(DiffStatus::Deleted));
+        assert_eq!(status_of("new.txt"), Some(DiffStatus::Added));
+
+        let renames = repo
+            .diff_with_options(&DiffOptions::new().cached().name_status().diff_filter("R"))
+            .unwrap();
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames.files[0].old_path, Some(PathBuf::from("move.txt")));
+
+        // Lowercase letters exclude kinds; the filter also applies to full diffs
+        let no_deletes = repo
+            .diff_with_options(&DiffOptions::new().cached().diff_filter("d"))
+            .unwrap();
+        assert_eq!(no_deletes.len(), 3);
+        assert!(
+            no_deletes
+                .files_with_status(DiffStatus::Deleted)
+                .next()
+                .is_none()
+        );
+
+        std::fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_repository_diff_stat_counts() {
+        let temp_dir = env::temp_dir().join("rustic_git_diff_stat_counts_test");
+        if temp_dir.exists() {
+            std::fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        let lines: String = (0..600).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(temp_dir.join("big.txt"), &lines).unwrap();
+        repo.add(&["big.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+
+        // Uneven changes are where the --stat bar graph drifts from the real counts
+        let edited: String = (0..600)
+            .filter(|i| i % 4 != 0)
+            .map(|i| format!("line {}\n", i))
+            .chain(std::iter::once("tail\n".to_string()))
+            .collect();
+        std::fs::write(temp_dir.join("big.txt"), edited).unwrap();
+        std::fs::write(temp_dir.join("added.txt"), "only\nadded\n").unwrap();
+        repo.add(&["big.txt", "added.txt"]).unwrap();
+
+        for options in [
+            DiffOptions::new().cached().stat_only(),
+            DiffOptions::new().cached().numstat(),
+        ] {
+            let diff = repo.diff_with_options(&options).unwrap();
+            assert_eq!(diff.len(), 2);
+            assert_eq!(diff.files[0].path, PathBuf::from("added.txt"));
+            assert_eq!(diff.files[0].status, DiffStatus::Added);
+            assert_eq!(diff.files[0].additions, 2);
+            assert_eq!(diff.files[1].status, DiffStatus::Modified);
+            assert_eq!((diff.files[1].additions, diff.files[1].deletions), (1, 150));
+            assert_eq!(diff.stats.files_changed, 2);
+            assert_eq!(diff.stats.insertions, 3);
+            assert_eq!(diff.stats.deletions, 150);
+        }
+
+        // The full patch agrees
+        let full = repo.diff_staged().unwrap();
+        assert_eq!(full.stats.insertions, 3);
+        assert_eq!(full.stats.deletions, 150);
+
+        std::fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_numstat() {
+        let output = "9\t6\tsrc/main.rs\x003\t0\tREADME.md\x000\t1\t\x00old name.rs\x00new name.rs\x00-\t-\tlogo.png\x00";
+        let stats = parse_numstat(output);
+
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats[0].path, PathBuf::from("src/main.rs"));
+        assert_eq!((stats[0].insertions, stats[0].deletions), (9, 6));
+        assert_eq!(stats[1].path, PathBuf::from("README.md"));
+        assert!(!stats[1].is_rename());
+        assert_eq!(stats[2].path, PathBuf::from("new name.rs"));
+        assert_eq!(stats[2].old_path, Some(PathBuf::from("old name.rs")));
+        assert_eq!((stats[2].insertions, stats[2].deletions), (0, 1));
+        assert!(stats[3].binary);
+        assert_eq!((stats[3].insertions, stats[3].deletions), (0, 0));
+
+        assert!(parse_numstat("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_raw_numstat_output() {
+        let output = ":100644 100644 1111111 2222222 M\0file2.rs\0\
+:000000 100644 0000000 3333333 A\0file1.txt\0\
+:100644 000000 4444444 0000000 D\0file3.py\0\
+:100644 100755 5555555 6666666 R087\0old name.sh\0new name.sh\0\
+3\t2\tfile2.rs\x005\t0\tfile1.txt\x000\t10\tfile3.py\x001\t1\t\x00old name.sh\x00new name.sh\x00";
+        let result = parse_raw_numstat_output(output).unwrap();
+
+        assert_eq!(result.len(), 4);
+
+        assert_eq!(result.files[0].path, PathBuf::from("file2.rs"));
+        assert_eq!(result.files[0].status, DiffStatus::Modified);
+        assert_eq!(result.files[0].additions, 3);
+        assert_eq!(result.files[0].deletions, 2);
+
+        assert_eq!(result.files[1].path, PathBuf::from("file1.txt"));
+        assert_eq!(result.files[1].status, DiffStatus::Added);
+        assert_eq!(result.files[1].old_mode, None);
+        assert_eq!(result.files[1].new_mode, Some(FileMode::Regular));
+        assert_eq!(result.files[1].additions, 5);
 
         assert_eq!(result.files[2].path, PathBuf::from("file3.py"));
         assert_eq!(result.files[2].status, DiffStatus::Deleted);
-        assert_eq!(result.files[2].additions, 0);
         assert_eq!(result.files[2].deletions, 10);
+
+        let renamed = &result.files[3];
+        assert_eq!(renamed.status, DiffStatus::Renamed);
+        assert_eq!(renamed.path, PathBuf::from("new name.sh"));
+        assert_eq!(renamed.old_path, Some(PathBuf::from("old name.sh")));
+        assert!(renamed.mode_changed());
+        assert_eq!((renamed.additions, renamed.deletions), (1, 1));
+
+        assert_eq!(result.stats.files_changed, 4);
+        assert_eq!(result.stats.insertions, 9);
+        assert_eq!(result.stats.deletions, 13);
+
+        assert!(parse_raw_numstat_output("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_diff_output_mode_changes() {
+        let output = "diff --git a/script.sh b/script.sh
+old mode 100644
+new mode 100755
+diff --git a/link b/link
+deleted file mode 120000
+index 2e65efe..0000000
+--- a/link
++++ /dev/null
+@@ -1 +0,0 @@
+-target
+diff --git a/link b/link
+new file mode 100644
+index 0000000..8e27be7
+--- /dev/null
++++ b/link
+@@ -0,0 +1 @@
++text
+diff --git a/new.txt b/new.txt
+new file mode 100644
+index 0000000..8e27be7
+";
+        let result = parse_diff_output(output).unwrap();
+        assert_eq!(result.len(), 3);
+
+        let script = &result.files[0];
+        assert_eq!(script.status, DiffStatus::Modified);
+        assert_eq!(script.old_mode, Some(FileMode::Regular));
+        assert_eq!(script.new_mode, Some(FileMode::Executable));
+        assert!(script.mode_changed());
+        assert!(!script.type_changed());
+
+        let link = &result.files[1];
+        assert_eq!(link.path, PathBuf::from("link"));
+        assert_eq!(link.status, DiffStatus::Modified);
+        assert_eq!(link.old_mode, Some(FileMode::Symlink));
+        assert_eq!(link.new_mode, Some(FileMode::Regular));
+        assert!(link.type_changed());
+
+        let added = &result.files[2];
+        assert_eq!(added.status, DiffStatus::Added);
+        assert_eq!(added.old_mode, None);
+        assert_eq!(added.new_mode, Some(FileMode::Regular));
+        assert!(!added.mode_changed());
+    }
+
+    #[test]
+    fn test_parse_diff_output_renames_and_line_counts() {
+        let output = "diff --git a/old name.txt b/new name.txt
+similarity index 80%
+rename from old name.txt
+rename to new name.txt
+index 2e65efe..8e27be7 100644
+--- a/old name.txt
++++ b/new name.txt
+@@ -1,3 +1,3 @@
+ keep
+--- removed line that looks like a header
++++ added line that looks like a header
+ keep
+diff --git a/src.rs b/copy.rs
+similarity index 100%
+copy from src.rs
+copy to copy.rs
+";
+        let result = parse_diff_output(output).unwrap();
+        assert_eq!(result.len(), 2);
+
+        let renamed = &result.files[0];
+        assert_eq!(renamed.status, DiffStatus::Renamed);
+        assert_eq!(renamed.path, PathBuf::from("new name.txt"));
+        assert_eq!(renamed.old_path, Some(PathBuf::from("old name.txt")));
+        assert_eq!(renamed.additions, 1);
+        assert_eq!(renamed.deletions, 1);
+
+        let copied = &result.files[1];
+        assert_eq!(copied.status, DiffStatus::Copied);
+        assert_eq!(copied.path, PathBuf::from("copy.rs"));
+        assert_eq!(copied.old_path, Some(PathBuf::from("src.rs")));
+
+        assert_eq!(result.stats.insertions, 1);
+        assert_eq!(result.stats.deletions, 1);
+    }
+
+    #[test]
+    fn test_parse_diff_output_chunks() {
+        let output = "diff --git a/src/lib.rs b/src/lib.rs
+index 2e65efe..8e27be7 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,4 @@ fn main() {
+ let a = 1;
+-let b = 2;
++let b = 3;
++let c = 4;
+
+@@ -10 +11 @@
+-old tail
+\\ No newline at end of file
++new tail
+\\ No newline at end of file
+diff --git a/logo.png b/logo.png
+index 2e65efe..8e27be7 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/a.rs b/b.rs
+similarity index 92%
+rename from a.rs
+rename to b.rs
+";
+        let result = parse_diff_output(output).unwrap();
+        assert_eq!(result.len(), 3);
+
+        let file = &result.files[0];
+        assert_eq!(file.path, PathBuf::from("src/lib.rs"));
+        assert_eq!(file.chunks.len(), 2);
+        assert_eq!((file.additions, file.deletions), (3, 2));
+        assert!(!file.is_binary());
+
+        let first = &file.chunks[0];
+        assert_eq!(
+            (
+                first.old_start,
+                first.old_count,
+                first.new_start,
+                first.new_count
+            ),
+            (1, 3, 1, 4)
+        );
+        assert_eq!(first.heading.as_deref(), Some("fn main() {"));
+        assert_eq!(
+            first.lines.as_ref(),
+            [
+                DiffLine::new(DiffLineType::Context, "let a = 1;"),
+                DiffLine::new(DiffLineType::Removed, "let b = 2;"),
+                DiffLine::new(DiffLineType::Added, "let b = 3;"),
+                DiffLine::new(DiffLineType::Added, "let c = 4;"),
+                DiffLine::new(DiffLineType::Context, ""),
+            ]
+        );
+
+        let second = &file.chunks[1];
+        assert_eq!(
+            (
+                second.old_start,
+                second.old_count,
+                second.new_start,
+                second.new_count
+            ),
+            (10, 1, 11, 1)
+        );
+        assert_eq!(second.heading, None);
+        assert_eq!(second.lines.len(), 2);
+        assert!(second.lines.iter().all(|line| line.no_newline));
+
+        let binary = &result.files[1];
+        assert!(binary.is_binary());
+        assert!(binary.chunks.is_empty());
+
+        let renamed = &result.files[2];
+        assert_eq!(renamed.status, DiffStatus::Renamed);
+        assert_eq!(renamed.similarity, Some(92));
+        assert_eq!(renamed.path, PathBuf::from("b.rs"));
+
+        assert!(parse_diff_output("diff --git a/x b/x\n@@ -a +1 @@\n").is_err());
+
+        // Combined diffs of conflicted files are skipped
+        let combined = "diff --cc conflict.txt
+index 2e65efe,8e27be7..0000000
+--- a/conflict.txt
++++ b/conflict.txt
+@@@ -1,1 -1,1 +1,5 @@@
+++<<<<<<< HEAD
+ +ours
+diff --git a/after.txt b/after.txt
+--- a/after.txt
++++ b/after.txt
+@@ -1 +1 @@
+-a
++b
+";
+        let result = parse_diff_output(combined).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.files[0].path, PathBuf::from("after.txt"));
+        assert_eq!(result.files[0].chunks[0].lines.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_repository_diff_mode_change() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let repo_path = env::temp_dir().join("rustic_git_diff_mode_test");
+        if repo_path.exists() {
+            std::fs::remove_dir_all(&repo_path).ok();
+        }
+
+        let repo = Repository::init(&repo_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        let script = repo_path.join("run.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        repo.add(&["run.sh"]).unwrap();
+        repo.commit("Add script").unwrap();
+
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let diff = repo.diff().unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff.files[0].old_mode, Some(FileMode::Regular));
+        assert_eq!(diff.files[0].new_mode, Some(FileMode::Executable));
+        assert!(diff.files[0].mode_changed());
+
+        std::fs::remove_dir_all(&repo_path).ok();
     }
 
     #[test]
@@ -845,4 +1972,232 @@ mod tests {
         // Clean up
         std::fs::remove_dir_all(&repo_path).ok();
     }
+
+    #[test]
+    fn test_repository_diff_chunks() {
+        let repo = crate::testing::TempRepo::new("diff_chunks").unwrap();
+        repo.commit_file("code.txt", "one\ntwo\nthree\n", "Initial")
+            .unwrap();
+        repo.write_file("code.txt", "one\n2\nthree\nfour").unwrap();
+
+        let diff = repo.diff().unwrap();
+        let file = &diff.files[0];
+        assert_eq!(file.chunks.len(), 1);
+        let chunk = &file.chunks[0];
+        assert_eq!((chunk.old_start, chunk.old_count), (1, 3));
+        assert_eq!((chunk.new_start, chunk.new_count), (1, 4));
+        let added: Vec<&str> = chunk
+            .lines
+            .iter()
+            .filter(|line| line.line_type == DiffLineType::Added)
+            .map(|line| line.content.as_str())
+            .collect();
+        assert_eq!(added, vec!["2", "four"]);
+        assert!(chunk.lines.last().unwrap().no_newline);
+        assert_eq!((file.additions, file.deletions), (2, 1));
+    }
+
+    #[test]
+    fn test_repository_diff_crlf_and_quoted_path() {
+        let repo = crate::testing::TempRepo::new("diff_crlf_quoted").unwrap();
+        repo.commit_file("café.txt", "one\r\ntwo\r\n", "Initial")
+            .unwrap();
+        repo.write_file("café.txt", "one\r\n2\r\n").unwrap();
+
+        let diff = repo.diff().unwrap();
+        assert_eq!(diff.len(), 1);
+        let file = &diff.files[0];
+        assert_eq!(file.path, PathBuf::from("café.txt"));
+        let lines: Vec<(DiffLineType, &str)> = file.chunks[0]
+            .lines
+            .iter()
+            .map(|line| (line.line_type.clone(), line.content.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (DiffLineType::Context, "one\r"),
+                (DiffLineType::Removed, "two\r"),
+                (DiffLineType::Added, "2\r"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_diff_output_quoted_rename() {
+        let output = "\
+diff --git \"a/caf\\303\\251 old.txt\" \"b/caf\\303\\251.txt\"
+similarity index 100%
+rename from \"caf\\303\\251 old.txt\"
+rename to \"caf\\303\\251.txt\"
+diff --git \"a/tab\\there\" \"b/tab\\there\"
+index 1111111..2222222 100644
+--- \"a/tab\\there\"
++++ \"b/tab\\there\"
+@@ -1 +1 @@
+-a
++b
+diff --git a/with space.txt b/with space.txt
+index 1111111..2222222 100644
+--- a/with space.txt\t
++++ b/with space.txt\t
+@@ -1 +1 @@
+-a
++b
+";
+        let result = parse_diff_output(output).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.files[0].status, DiffStatus::Renamed);
+        assert_eq!(result.files[0].path, PathBuf::from("café.txt"));
+        assert_eq!(
+            result.files[0].old_path,
+            Some(PathBuf::from("café old.txt"))
+        );
+        assert_eq!(result.files[1].path, PathBuf::from("tab\there"));
+        assert_eq!(result.files[1].chunks[0].lines.len(), 2);
+        assert_eq!(result.files[2].path, PathBuf::from("with space.txt"));
+    }
+
+    #[test]
+    fn test_revspec_parse() {
+        assert_eq!(
+            Revspec::from("HEAD~3"),
+            Revspec::Single("HEAD~3".to_string())
+        );
+        assert_eq!(
+            Revspec::from("main..feature"),
+            Revspec::Range("main".to_string(), "feature".to_string())
+        );
+        assert_eq!(
+            Revspec::from("main...feature"),
+            Revspec::MergeBase("main".to_string(), "feature".to_string())
+        );
+        assert_eq!(
+            Revspec::from("..feature"),
+            Revspec::Range("HEAD".to_string(), "feature".to_string())
+        );
+        assert_eq!(Revspec::from("v1.0...").to_string(), "v1.0...HEAD");
+        assert_eq!(Revspec::from(&Hash::from("abc123")).to_string(), "abc123");
+    }
+
+    #[test]
+    fn test_repository_diff_revspec_and_merge_base() {
+        let repo = crate::testing::TempRepo::new("diff_revspec").unwrap();
+        repo.commit_file("base.txt", "base\n", "Base").unwrap();
+        let main = repo.current_branch().unwrap().unwrap().name;
+        repo.create_tag("v1.0", None).unwrap();
+
+        repo.checkout_new("feature", None).unwrap();
+        repo.commit_file("feature.txt", "feature\n", "Feature")
+            .unwrap();
+        let main_branch = repo.branches().unwrap().find(&main).unwrap().clone();
+        repo.checkout(&main_branch).unwrap();
+        repo.commit_file("main.txt", "main\n", "Main").unwrap();
+
+        let paths = |diff: &DiffOutput| {
+            let mut paths: Vec<String> = diff
+                .iter()
+                .map(|file| file.path.to_string_lossy().to_string())
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        // A two-dot range also shows the main-only commit, reversed
+        let range = repo.diff_revspec(format!("{}..feature", main)).unwrap();
+        assert_eq!(paths(&range), vec!["feature.txt", "main.txt"]);
+        assert_eq!(
+            range
+                .files_with_status(DiffStatus::Deleted)
+                .next()
+                .unwrap()
+                .path,
+            PathBuf::from("main.txt")
+        );
+
+        let review = repo.diff_merge_base(&main, "feature").unwrap();
+        assert_eq!(paths(&review), vec!["feature.txt"]);
+        assert_eq!(review.files[0].status, DiffStatus::Added);
+
+        // A single revision is compared with the working tree
+        repo.write_file("base.txt", "changed\n").unwrap();
+        let since_tag = repo.diff_revspec("v1.0").unwrap();
+        assert_eq!(paths(&since_tag), vec!["base.txt", "main.txt"]);
+        let staged = repo
+            .diff_revspec_with_options("v1.0", &DiffOptions::new().cached())
+            .unwrap();
+        assert_eq!(paths(&staged), vec!["main.txt"]);
+
+        assert!(repo.diff_revspec("--output=x..HEAD").is_err());
+        assert!(repo.diff_revspec("missing..HEAD").is_err());
+    }
+
+    #[test]
+    fn test_show_commit_diff() {
+        let repo = crate::testing::TempRepo::new("diff_show_commit").unwrap();
+        let root = repo
+            .commit_files(&[("a.txt", "one\ntwo\n"), ("b.txt", "b\n")], "Root")
+            .unwrap();
+        let change = repo.commit_file("a.txt", "one\n2\n", "Change a").unwrap();
+        // Uncommitted changes are not part of any commit
+        repo.write_file("b.txt", "dirty\n").unwrap();
+
+        let diff = repo.show_commit_diff(&root, &DiffOptions::new()).unwrap();
+        assert_eq!(diff.len(), 2);
+        assert!(diff.iter().all(|file| file.status == DiffStatus::Added));
+        assert_eq!(diff.stats.insertions, 3);
+
+        let diff = repo.show_commit_diff(&change, &DiffOptions::new()).unwrap();
+        assert_eq!(diff.len(), 1);
+        let file = &diff.files[0];
+        assert_eq!(file.path, PathBuf::from("a.txt"));
+        assert_eq!(
+            file.chunks[0].lines.as_ref(),
+            [
+                DiffLine::new(DiffLineType::Context, "one"),
+                DiffLine::new(DiffLineType::Removed, "two"),
+                DiffLine::new(DiffLineType::Added, "2"),
+            ]
+        );
+
+        let names = repo
+            .show_commit_diff(&root, &DiffOptions::new().name_status())
+            .unwrap();
+        assert_eq!(names.len(), 2);
+        let limited = repo
+            .show_commit_diff(
+                &root,
+                &DiffOptions::new().paths(vec![PathBuf::from("b.txt")]),
+            )
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+
+        assert!(
+            repo.show_commit_diff(&Hash::from("--all"), &DiffOptions::new())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_show_commit_diff_merge_first_parent() {
+        let repo = crate::testing::TempRepo::new("diff_show_merge").unwrap();
+        repo.commit_file("base.txt", "base\n", "Base").unwrap();
+        let main = repo.current_branch().unwrap().unwrap();
+        repo.checkout_new("feature", None).unwrap();
+        repo.commit_file("feature.txt", "feature\n", "Feature")
+            .unwrap();
+        repo.checkout(&main).unwrap();
+        repo.commit_file("main.txt", "main\n", "Main").unwrap();
+        repo.merge_with_options(
+            "feature",
+            crate::MergeOptions::new().with_fast_forward(crate::FastForwardMode::Never),
+        )
+        .unwrap();
+        let merge = repo.head_hash().unwrap();
+
+        let diff = repo.show_commit_diff(&merge, &DiffOptions::new()).unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff.files[0].path, PathBuf::from("feature.txt"));
+        assert_eq!(diff.files[0].status, DiffStatus::Added);
+    }
 }
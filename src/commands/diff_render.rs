@@ -0,0 +1,615 @@
+//! Rendering parsed diffs for terminals and web pages
+//!
+//! [`FileDiff::to_ansi`] and [`DiffOutput::to_ansi`] produce unified diffs colored like
+//! `git diff --color`. [`FileDiff::to_side_by_side_html`] and
+//! [`DiffOutput::to_side_by_side_html`] produce a two-column HTML table with line
+//! numbers; it carries CSS classes but no styling, so pages can theme it.
+//!
+//! Both render the hunks in [`FileDiff::chunks`]. A file without chunks renders as its
+//! header only, followed by a "Binary files differ" note when
+//! [`FileDiff::is_binary`] reports it.
+//!
+//! [`FileDiff::to_patch_string`] and [`DiffOutput::to_patch_string`] turn the parsed
+//! structures back into plain patch text for [`Repository::apply_patch`], so tools can
+//! drop or edit hunks before applying them, e.g. to stage part of a file.
+//!
+//! [`Repository::apply_patch`]: crate::Repository::apply_patch
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::Repository;
+//!
+//! let repo = Repository::open(".")?;
+//! let diff = repo.diff()?;
+//! print!("{}", diff.to_ansi());
+//!
+//! let html = diff.to_side_by_side_html();
+//! std::fs::write("diff.html", html)?;
+//!
+//! // Stage only the first hunk of each file
+//! use rustic_git::ApplyOptions;
+//! for file in diff.iter() {
+//!     let mut first = file.clone();
+//!     first.chunks = file.chunks.iter().take(1).cloned().collect();
+//!     repo.apply_patch(&first.to_patch_string(), ApplyOptions::new().with_cached())?;
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::commands::diff::{DiffChunk, DiffLine, DiffLineType, DiffOutput, DiffStatus, FileDiff};
+use crate::commands::objects::FileMode;
+use crate::commands::status::quote_path;
+use std::fmt::Write;
+use std::path::Path;
+
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+impl FileDiff {
+    /// Render as a unified diff with ANSI colors, as `git diff --color` would
+    ///
+    /// Headers are bold, hunk headers cyan, removed lines red and added lines green.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        let (old, new) = self.header_paths();
+        let _ = writeln!(out, "{}--- {}{}", BOLD, old, RESET);
+        let _ = writeln!(out, "{}+++ {}{}", BOLD, new, RESET);
+        if self.is_binary() {
+            let _ = writeln!(out, "Binary files {} and {} differ", old, new);
+        }
+
+        for chunk in &self.chunks {
+            let _ = writeln!(out, "{}{}{}", CYAN, hunk_header(chunk), RESET);
+            for line in &chunk.lines {
+                let color = match line.line_type {
+                    DiffLineType::Added => GREEN,
+                    DiffLineType::Removed => RED,
+                    DiffLineType::Context => "",
+                };
+                let reset = if color.is_empty() { "" } else { RESET };
+                let _ = writeln!(
+                    out,
+                    "{}{}{}{}",
+                    color,
+                    line.line_type.to_char(),
+                    line.content,
+                    reset
+                );
+                if line.no_newline {
+                    let _ = writeln!(out, "\\ No newline at end of file");
+                }
+            }
+        }
+        out
+    }
+
+    /// Render as a side-by-side HTML table: old line number, old text, new line number,
+    /// new text
+    ///
+    /// Runs of removed lines are paired with the added lines that follow them, so a
+    /// changed line appears on one row. The table has class `diff`; rows have class
+    /// `hunk`, `context` or `change`, and cells `removed`, `added` or `empty`.
+    pub fn to_side_by_side_html(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "<table class=\"diff\">");
+        let _ = writeln!(
+            out,
+            "<thead><tr><th colspan=\"4\">{}</th></tr></thead>",
+            escape_html(&self.to_string())
+        );
+        let _ = writeln!(out, "<tbody>");
+        if self.is_binary() {
+            let _ = writeln!(
+                out,
+                "<tr class=\"hunk\"><td colspan=\"4\">Binary file differs</td></tr>"
+            );
+        }
+
+        for chunk in &self.chunks {
+            let _ = writeln!(
+                out,
+                "<tr class=\"hunk\"><td colspan=\"4\">{}</td></tr>",
+                escape_html(&hunk_header(chunk))
+            );
+            write_html_rows(&mut out, chunk);
+        }
+
+        let _ = writeln!(out, "</tbody>");
+        let _ = writeln!(out, "</table>");
+        out
+    }
+
+    /// Render as unified diff text that `git apply` accepts
+    ///
+    /// Index lines are not reproduced, so binary files render as a "Binary files
+    /// differ" note that cannot be applied.
+    pub fn to_patch_string(&self) -> String {
+        if self.type_changed() {
+            // git applies a type change as a deletion followed by an addition
+            let (removed, added): (Vec<DiffChunk>, Vec<DiffChunk>) = self
+                .chunks
+                .iter()
+                .cloned()
+                .partition(|chunk| chunk.new_count == 0);
+            let mut deletion =
+                FileDiff::new(self.path.clone(), DiffStatus::Deleted).with_chunks(removed);
+            deletion.old_mode = self.old_mode;
+            let mut addition =
+                FileDiff::new(self.path.clone(), DiffStatus::Added).with_chunks(added);
+            addition.new_mode = self.new_mode;
+            return deletion.to_patch_string() + &addition.to_patch_string();
+        }
+
+        let mut out = String::new();
+        let old_path = self.old_path.as_ref().unwrap_or(&self.path);
+        let _ = writeln!(
+            out,
+            "diff --git {} {}",
+            prefixed_path("a/", old_path),
+            prefixed_path("b/", &self.path)
+        );
+
+        let bits = |mode: Option<FileMode>| mode.unwrap_or(FileMode::Regular).bits();
+        match self.status {
+            DiffStatus::Added => {
+                let _ = writeln!(out, "new file mode {:06o}", bits(self.new_mode));
+            }
+            DiffStatus::Deleted => {
+                let _ = writeln!(out, "deleted file mode {:06o}", bits(self.old_mode));
+            }
+            _ if self.mode_changed() => {
+                let _ = writeln!(out, "old mode {:06o}", bits(self.old_mode));
+                let _ = writeln!(out, "new mode {:06o}", bits(self.new_mode));
+            }
+            _ => {}
+        }
+
+        if let Some(old_path) = &self.old_path {
+            let kind = match self.status {
+                DiffStatus::Renamed => Some("rename"),
+                DiffStatus::Copied => Some("copy"),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                if let Some(similarity) = self.similarity {
+                    let _ = writeln!(out, "similarity index {}%", similarity);
+                }
+                let _ = writeln!(out, "{} from {}", kind, prefixed_path("", old_path));
+                let _ = writeln!(out, "{} to {}", kind, prefixed_path("", &self.path));
+            }
+        }
+
+        let (old, new) = self.header_paths();
+        if self.is_binary() {
+            let _ = writeln!(out, "Binary files {} and {} differ", old, new);
+            return out;
+        }
+        if self.chunks.is_empty() {
+            return out;
+        }
+
+        let _ = writeln!(out, "--- {}", old);
+        let _ = writeln!(out, "+++ {}", new);
+        for chunk in &self.chunks {
+            let _ = writeln!(out, "{}", hunk_header(chunk));
+            for line in &chunk.lines {
+                let _ = writeln!(out, "{}{}", line.line_type.to_char(), line.content);
+                if line.no_newline {
+                    let _ = writeln!(out, "\\ No newline at end of file");
+                }
+            }
+        }
+        out
+    }
+
+    /// Old and new paths as shown in `---`/`+++` headers
+    fn header_paths(&self) -> (String, String) {
+        let new = prefixed_path("b/", &self.path);
+        let old = prefixed_path("a/", self.old_path.as_ref().unwrap_or(&self.path));
+        match self.status {
+            DiffStatus::Added => ("/dev/null".to_string(), new),
+            DiffStatus::Deleted => (old, "/dev/null".to_string()),
+            _ => (old, new),
+        }
+    }
+}
+
+impl DiffOutput {
+    /// Render every file with [`FileDiff::to_ansi`]
+    pub fn to_ansi(&self) -> String {
+        self.files.iter().map(FileDiff::to_ansi).collect()
+    }
+
+    /// Render every file with [`FileDiff::to_patch_string`]
+    pub fn to_patch_string(&self) -> String {
+        self.files.iter().map(FileDiff::to_patch_string).collect()
+    }
+
+    /// Render every file with [`FileDiff::to_side_by_side_html`], one table per file
+    pub fn to_side_by_side_html(&self) -> String {
+        self.files
+            .iter()
+            .map(FileDiff::to_side_by_side_html)
+            .collect()
+    }
+}
+
+/// A patch header path, quoted the way git quotes paths with special characters
+fn prefixed_path(prefix: &str, path: &Path) -> String {
+    quote_path(&format!("{}{}", prefix, path.to_string_lossy()))
+}
+
+fn hunk_header(chunk: &DiffChunk) -> String {
+    let header = format!(
+        "@@ -{},{} +{},{} @@",
+        chunk.old_start, chunk.old_count, chunk.new_start, chunk.new_count
+    );
+    match &chunk.heading {
+        Some(heading) => format!("{} {}", header, heading),
+        None => header,
+    }
+}
+
+/// Write the rows of one hunk, pairing removals with the additions that follow them
+fn write_html_rows(out: &mut String, chunk: &DiffChunk) {
+    let mut old_line = chunk.old_start;
+    let mut new_line = chunk.new_start;
+    let mut removed: Vec<&DiffLine> = Vec::new();
+    let mut added: Vec<&DiffLine> = Vec::new();
+
+    for line in &chunk.lines {
+        match line.line_type {
+            DiffLineType::Removed => {
+                // A removal after additions starts a new change block
+                if !added.is_empty() {
+                    flush_change(out, &mut removed, &mut added, &mut old_line, &mut new_line);
+                }
+                removed.push(line);
+            }
+            DiffLineType::Added => added.push(line),
+            DiffLineType::Context => {
+                flush_change(out, &mut removed, &mut added, &mut old_line, &mut new_line);
+                let _ = write!(out, "<tr class=\"context\">");
+                write_cells(out, "context", Some(old_line), &line.content);
+                write_cells(out, "context", Some(new_line), &line.content);
+                let _ = writeln!(out, "</tr>");
+                old_line += 1;
+                new_line += 1;
+            }
+        }
+    }
+    flush_change(out, &mut removed, &mut added, &mut old_line, &mut new_line);
+}
+
+/// Write paired rows for a run of removed and added lines, then empty both runs
+fn flush_change(
+    out: &mut String,
+    removed: &mut Vec<&DiffLine>,
+    added: &mut Vec<&DiffLine>,
+    old_line: &mut usize,
+    new_line: &mut usize,
+) {
+    for row in 0..removed.len().max(added.len()) {
+        let _ = write!(out, "<tr class=\"change\">");
+        match removed.get(row) {
+            Some(line) => {
+                write_cells(out, "removed", Some(*old_line), &line.content);
+                *old_line += 1;
+            }
+            None => write_cells(out, "empty", None, ""),
+        }
+        match added.get(row) {
+            Some(line) => {
+                write_cells(out, "added", Some(*new_line), &line.content);
+                *new_line += 1;
+            }
+            None => write_cells(out, "empty", None, ""),
+        }
+        let _ = writeln!(out, "</tr>");
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// Write a line number cell and a content cell
+fn write_cells(out: &mut String, class: &str, number: Option<usize>, content: &str) {
+    let number = number.map(|n| n.to_string()).unwrap_or_default();
+    let _ = write!(
+        out,
+        "<td class=\"line-number {}\">{}</td><td class=\"{}\">{}</td>",
+        class,
+        number,
+        class,
+        escape_html(content)
+    );
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::diff::parse_diff_output;
+    use std::path::PathBuf;
+
+    fn line(line_type: DiffLineType, content: &str) -> DiffLine {
+        DiffLine::new(line_type, content)
+    }
+
+    fn sample() -> FileDiff {
+        let chunk = DiffChunk {
+            old_start: 10,
+            old_count: 4,
+            new_start: 10,
+            new_count: 4,
+            heading: None,
+            lines: vec![
+                line(DiffLineType::Context, "fn main() {"),
+                line(DiffLineType::Removed, "    let a = 1;"),
+                line(DiffLineType::Removed, "    let b = a < 2;"),
+                line(DiffLineType::Added, "    let b = 2;"),
+                line(DiffLineType::Context, "}"),
+                line(DiffLineType::Added, "// end"),
+            ]
+            .into_boxed_slice(),
+        };
+        FileDiff::new(PathBuf::from("src/main.rs"), DiffStatus::Modified)
+            .with_chunks(vec![chunk])
+            .with_stats(2, 2)
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let ansi = sample().to_ansi();
+        let expected = "\
+\x1b[1m--- a/src/main.rs\x1b[0m
+\x1b[1m+++ b/src/main.rs\x1b[0m
+\x1b[36m@@ -10,4 +10,4 @@\x1b[0m
+ fn main() {
+\x1b[31m-    let a = 1;\x1b[0m
+\x1b[31m-    let b = a < 2;\x1b[0m
+\x1b[32m+    let b = 2;\x1b[0m
+ }
+\x1b[32m+// end\x1b[0m
+";
+        assert_eq!(ansi, expected);
+
+        let added = FileDiff::new(PathBuf::from("new.bin"), DiffStatus::Added).with_binary();
+        let ansi = DiffOutput::new(vec![added]).to_ansi();
+        assert!(ansi.contains("--- /dev/null"));
+        assert!(ansi.contains("Binary files /dev/null and b/new.bin differ"));
+    }
+
+    #[test]
+    fn test_to_side_by_side_html() {
+        let html = sample().to_side_by_side_html();
+        let rows: Vec<&str> = html.lines().filter(|l| l.starts_with("<tr")).collect();
+
+        assert_eq!(rows.len(), 6);
+        assert!(rows[0].contains("@@ -10,4 +10,4 @@"));
+        assert!(rows[1].starts_with("<tr class=\"context\">"));
+        // The first removal is paired with the addition
+        assert!(rows[2].contains(">11</td><td class=\"removed\">    let a = 1;</td>"));
+        assert!(rows[2].contains(">11</td><td class=\"added\">    let b = 2;</td>"));
+        // The second has no counterpart, and is escaped
+        assert!(rows[3].contains("let b = a &lt; 2;"));
+        assert!(rows[3].contains("<td class=\"line-number empty\"></td><td class=\"empty\"></td>"));
+        assert!(rows[4].contains(">13</td><td class=\"context\">}</td>"));
+        assert!(rows[5].contains(">13</td><td class=\"added\">// end</td>"));
+
+        let output = DiffOutput::new(vec![sample(), sample()]);
+        assert_eq!(output.to_side_by_side_html().matches("<table").count(), 2);
+    }
+
+    #[test]
+    fn test_to_patch_string() {
+        let mut file = sample();
+        file.chunks[0].heading = Some("impl App {".to_string());
+        let mut last = line(DiffLineType::Added, "// end");
+        last.no_newline = true;
+        let mut lines = file.chunks[0].lines.to_vec();
+        *lines.last_mut().unwrap() = last;
+        file.chunks[0].lines = lines.into_boxed_slice();
+
+        let expected = "\
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -10,4 +10,4 @@ impl App {
+ fn main() {
+-    let a = 1;
+-    let b = a < 2;
++    let b = 2;
+ }
++// end
+\\ No newline at end of file
+";
+        assert_eq!(file.to_patch_string(), expected);
+        let reparsed = parse_diff_output(expected).unwrap();
+        assert_eq!(reparsed.files[0].chunks, file.chunks);
+
+        let mut renamed = FileDiff::new(PathBuf::from("new.sh"), DiffStatus::Renamed)
+            .with_old_path(PathBuf::from("old.sh"))
+            .with_similarity(100);
+        renamed.old_mode = Some(FileMode::Regular);
+        renamed.new_mode = Some(FileMode::Executable);
+        assert_eq!(
+            renamed.to_patch_string(),
+            "\
+diff --git a/old.sh b/new.sh
+old mode 100644
+new mode 100755
+similarity index 100%
+rename from old.sh
+rename to new.sh
+"
+        );
+
+        let deleted = FileDiff::new(PathBuf::from("gone.bin"), DiffStatus::Deleted).with_binary();
+        assert_eq!(
+            DiffOutput::new(vec![deleted]).to_patch_string(),
+            "\
+diff --git a/gone.bin b/gone.bin
+deleted file mode 100644
+Binary files a/gone.bin and /dev/null differ
+"
+        );
+    }
+
+    #[test]
+    fn test_to_patch_string_round_trip() {
+        let repo = crate::testing::TempRepo::new("diff_patch_round_trip").unwrap();
+        let original: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        repo.commit_file("file.txt", &original, "Initial").unwrap();
+        let changed = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 19\n", "line nineteen\n");
+        repo.write_file("file.txt", &changed).unwrap();
+
+        let diff = repo.diff().unwrap();
+        assert_eq!(diff.files[0].chunks.len(), 2);
+
+        // Stage only the second hunk
+        let mut partial = diff.files[0].clone();
+        partial.chunks = partial.chunks[1..].to_vec().into_boxed_slice();
+        repo.apply_patch(
+            &partial.to_patch_string(),
+            crate::ApplyOptions::new().with_cached(),
+        )
+        .unwrap();
+
+        let staged = repo.diff_staged().unwrap();
+        assert_eq!(staged.files[0].chunks.len(), 1);
+        assert_eq!(
+            staged.files[0].chunks[0].lines,
+            diff.files[0].chunks[1].lines
+        );
+        let unstaged = repo.diff().unwrap();
+        assert_eq!(unstaged.files[0].chunks.len(), 1);
+        assert_eq!(
+            unstaged.files[0].chunks[0].lines,
+            diff.files[0].chunks[0].lines
+        );
+
+        // The whole diff reverses cleanly
+        repo.apply_patch(
+            &unstaged.to_patch_string(),
+            crate::ApplyOptions::new().with_reverse(),
+        )
+        .unwrap();
+        assert!(repo.diff().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_to_patch_string_type_change() {
+        let repo = crate::testing::TempRepo::new("diff_patch_type_change").unwrap();
+        let link = repo.path().join("link");
+        std::os::unix::fs::symlink("target", &link).unwrap();
+        repo.add(&["link"]).unwrap();
+        repo.commit("Add link").unwrap();
+        std::fs::remove_file(&link).unwrap();
+        repo.write_file("link", "text\n").unwrap();
+
+        let diff = repo.diff().unwrap();
+        assert!(diff.files[0].type_changed());
+        let patch = diff.to_patch_string();
+
+        repo.reset_hard("HEAD").unwrap();
+        repo.apply_patch(&patch, crate::ApplyOptions::new())
+            .unwrap();
+        assert!(!link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert_eq!(std::fs::read_to_string(&link).unwrap(), "text\n");
+    }
+
+    #[test]
+    fn test_to_patch_string_crlf_and_quoted_paths() {
+        let repo = crate::testing::TempRepo::new("diff_patch_crlf_quoted").unwrap();
+        repo.commit_files(
+            &[
+                ("crlf.txt", "one\r\ntwo\r\nthree\r\n"),
+                ("café.txt", "plain\n"),
+                ("with space.txt", "spaced\n"),
+            ],
+            "Initial",
+        )
+        .unwrap();
+        repo.write_file("crlf.txt", "one\r\n2\r\nthree\r\n")
+            .unwrap();
+        repo.write_file("café.txt", "accented\n").unwrap();
+        repo.write_file("with space.txt", "still spaced\n").unwrap();
+
+        let diff = repo.diff().unwrap();
+        assert_eq!(diff.len(), 3);
+        let patch = diff.to_patch_string();
+        assert!(patch.contains("diff --git \"a/caf\\303\\251.txt\" \"b/caf\\303\\251.txt\""));
+        assert!(patch.contains("+2\r\n"));
+
+        // Applies in reverse to the worktree, and forwards once reset
+        repo.apply_patch(
+            &patch,
+            crate::ApplyOptions::new().with_reverse().with_check(),
+        )
+        .unwrap();
+        repo.reset_hard("HEAD").unwrap();
+        repo.apply_patch(&patch, crate::ApplyOptions::new().with_check())
+            .unwrap();
+        repo.apply_patch(&patch, crate::ApplyOptions::new())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("crlf.txt")).unwrap(),
+            "one\r\n2\r\nthree\r\n"
+        );
+
+        let renamed = FileDiff::new(PathBuf::from("new name.txt"), DiffStatus::Renamed)
+            .with_old_path(PathBuf::from("café.txt"))
+            .with_similarity(100);
+        let patch = renamed.to_patch_string();
+        assert!(patch.contains("rename from \"caf\\303\\251.txt\"\n"));
+        assert!(patch.contains("rename to \"new name.txt\"\n"));
+        repo.apply_patch(&patch, crate::ApplyOptions::new().with_check())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_binary_follows_git_marker() {
+        let output = parse_diff_output(
+            "\
+diff --git a/notes.txt b/notes.txt
+index 1111111..2222222 100644
+--- a/notes.txt
++++ b/notes.txt
+@@ -1 +1 @@
+-old
++new
+diff --git a/logo.png b/logo.png
+index 3333333..4444444 100644
+Binary files a/logo.png and b/logo.png differ
+",
+        )
+        .unwrap();
+
+        assert!(!output.files[0].is_binary());
+        assert!(output.files[1].is_binary());
+        let ansi = output.to_ansi();
+        assert!(!ansi.contains("Binary files a/notes.txt"));
+        assert!(ansi.contains("Binary files a/logo.png and b/logo.png differ"));
+    }
+}
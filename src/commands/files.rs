@@ -5,12 +5,108 @@
 //! - Unstaging files (reset_file)
 //! - Removing files from repository (rm)
 //! - Moving/renaming files (mv)
-//! - Managing .gitignore patterns
+//! - Managing .gitignore patterns, including batch ignore checks
 //!
 //! All operations follow Git's standard behavior and safety principles.
 
-use crate::{Repository, Result, utils::git};
-use std::path::Path;
+use crate::error::GitError;
+use crate::utils::{git, git_raw_with_stdin, validate_arg};
+use crate::{Repository, Result};
+use std::path::{Path, PathBuf};
+
+/// Built-in .gitignore presets for common project types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IgnoreTemplate {
+    Rust,
+    Node,
+    Python,
+    Go,
+    Java,
+    Cpp,
+    /// Editor, IDE, and operating system files
+    Editors,
+}
+
+impl IgnoreTemplate {
+    /// Get the template name, used as the section header in .gitignore
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "Rust",
+            Self::Node => "Node",
+            Self::Python => "Python",
+            Self::Go => "Go",
+            Self::Java => "Java",
+            Self::Cpp => "C++",
+            Self::Editors => "Editors",
+        }
+    }
+
+    /// Get the ignore patterns for this template
+    pub const fn patterns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &["/target/", "**/*.rs.bk", "*.pdb"],
+            Self::Node => &[
+                "node_modules/",
+                "dist/",
+                "build/",
+                ".env",
+                "npm-debug.log*",
+                "yarn-debug.log*",
+                "yarn-error.log*",
+                "*.tsbuildinfo",
+                "coverage/",
+            ],
+            Self::Python => &[
+                "__pycache__/",
+                "*.py[cod]",
+                "*.egg-info/",
+                ".eggs/",
+                "build/",
+                "dist/",
+                ".venv/",
+                "venv/",
+                ".env",
+                ".pytest_cache/",
+                ".mypy_cache/",
+                ".coverage",
+            ],
+            Self::Go => &["*.exe", "*.test", "*.out", "/bin/", "/vendor/", "go.work"],
+            Self::Java => &[
+                "*.class",
+                "*.jar",
+                "*.war",
+                "target/",
+                "build/",
+                ".gradle/",
+                "out/",
+                "hs_err_pid*",
+            ],
+            Self::Cpp => &[
+                "*.o",
+                "*.obj",
+                "*.a",
+                "*.lib",
+                "*.so",
+                "*.dylib",
+                "*.dll",
+                "*.exe",
+                "build/",
+                "cmake-build-*/",
+                "CMakeCache.txt",
+                "CMakeFiles/",
+            ],
+            Self::Editors => &[
+                ".DS_Store",
+                "Thumbs.db",
+                ".idea/",
+                ".vscode/",
+                "*.swp",
+                "*.swo",
+                "*~",
+            ],
+        }
+    }
+}
 
 /// Options for restore operations
 #[derive(Debug, Clone, Default)]
@@ -21,6 +117,10 @@ pub struct RestoreOptions {
     pub staged: bool,
     /// Restore working tree files
     pub worktree: bool,
+    /// During a conflict, restore our side (stage #2)
+    pub ours: bool,
+    /// During a conflict, restore their side (stage #3)
+    pub theirs: bool,
 }
 
 impl RestoreOptions {
@@ -46,6 +146,20 @@ impl RestoreOptions {
         self.worktree = true;
         self
     }
+
+    /// Resolve conflicted paths by taking our version (`--ours`)
+    pub fn with_ours(mut self) -> Self {
+        self.ours = true;
+        self.theirs = false;
+        self
+    }
+
+    /// Resolve conflicted paths by taking their version (`--theirs`)
+    pub fn with_theirs(mut self) -> Self {
+        self.theirs = true;
+        self.ours = false;
+        self
+    }
 }
 
 /// Options for file removal operations
@@ -150,13 +264,10 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn checkout_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        Repository::ensure_git()?;
+        self.check_git()?;
 
         let path_str = path.as_ref().to_string_lossy();
-        git(
-            &["checkout", "HEAD", "--", &path_str],
-            Some(self.repo_path()),
-        )?;
+        git(&["checkout", "HEAD", "--", &path_str], Some(self))?;
 
         Ok(())
     }
@@ -183,14 +294,19 @@ impl Repository {
     ///     .with_source("HEAD~1")
     ///     .with_worktree();
     /// repo.restore(&["file.txt"], options)?;
+    ///
+    /// // Resolve a merge conflict by taking the incoming version
+    /// repo.restore(&["conflicted.txt"], RestoreOptions::new().with_theirs())?;
+    /// repo.add(&["conflicted.txt"])?;
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn restore<P: AsRef<Path>>(&self, paths: &[P], options: RestoreOptions) -> Result<()> {
-        Repository::ensure_git()?;
+        self.check_git()?;
 
         let mut args = vec!["restore"];
 
         if let Some(ref source) = options.source {
+            validate_arg("revision", source)?;
             args.push("--source");
             args.push(source);
         }
@@ -208,6 +324,12 @@ impl Repository {
             args.push("--worktree");
         }
 
+        if options.ours {
+            args.push("--ours");
+        } else if options.theirs {
+            args.push("--theirs");
+        }
+
         args.push("--");
 
         let path_strings: Vec<String> = paths
@@ -217,7 +339,7 @@ impl Repository {
         let path_refs: Vec<&str> = path_strings.iter().map(String::as_str).collect();
         args.extend(path_refs);
 
-        git(&args, Some(self.repo_path()))?;
+        git(&args, Some(self))?;
 
         Ok(())
     }
@@ -243,10 +365,10 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn reset_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        Repository::ensure_git()?;
+        self.check_git()?;
 
         let path_str = path.as_ref().to_string_lossy();
-        git(&["reset", "HEAD", "--", &path_str], Some(self.repo_path()))?;
+        git(&["reset", "HEAD", "--", &path_str], Some(self))?;
 
         Ok(())
     }
@@ -302,7 +424,7 @@ impl Repository {
         paths: &[P],
         options: RemoveOptions,
     ) -> Result<()> {
-        Repository::ensure_git()?;
+        self.check_git()?;
 
         let mut args = vec!["rm"];
 
@@ -331,7 +453,7 @@ impl Repository {
         let path_refs: Vec<&str> = path_strings.iter().map(String::as_str).collect();
         args.extend(path_refs);
 
-        git(&args, Some(self.repo_path()))?;
+        git(&args, Some(self))?;
 
         Ok(())
     }
@@ -390,7 +512,7 @@ impl Repository {
         destination: Q,
         options: MoveOptions,
     ) -> Result<()> {
-        Repository::ensure_git()?;
+        self.check_git()?;
 
         let mut args = vec!["mv"];
 
@@ -408,10 +530,11 @@ impl Repository {
 
         let source_str = source.as_ref().to_string_lossy();
         let dest_str = destination.as_ref().to_string_lossy();
+        args.push("--");
         args.push(&source_str);
         args.push(&dest_str);
 
-        git(&args, Some(self.repo_path()))?;
+        git(&args, Some(self))?;
 
         Ok(())
     }
@@ -454,6 +577,67 @@ impl Repository {
         Ok(())
     }
 
+    /// Write a .gitignore preset for a common project type
+    ///
+    /// Creates .gitignore if it does not exist. If it does, only the template's
+    /// patterns that are not already listed are appended, under a `# <name>` header,
+    /// so calling this repeatedly or with several templates is safe.
+    ///
+    /// # Arguments
+    /// * `template` - The preset to apply
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use rustic_git::{IgnoreTemplate, Repository};
+    /// # use std::env;
+    /// # let repo_path = env::temp_dir().join("test_ignore_init");
+    /// # std::fs::create_dir_all(&repo_path).unwrap();
+    /// # let repo = Repository::init(&repo_path, false).unwrap();
+    ///
+    /// // Scaffold a Rust project ignoring editor files as well
+    /// repo.ignore_init(IgnoreTemplate::Rust)?;
+    /// repo.ignore_init(IgnoreTemplate::Editors)?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn ignore_init(&self, template: IgnoreTemplate) -> Result<()> {
+        use std::fs;
+
+        let gitignore_path = self.repo_path().join(".gitignore");
+        let existing_content = if gitignore_path.exists() {
+            fs::read_to_string(&gitignore_path)?
+        } else {
+            String::new()
+        };
+
+        let existing = self.ignore_list()?;
+        let missing: Vec<&str> = template
+            .patterns()
+            .iter()
+            .copied()
+            .filter(|pattern| !existing.iter().any(|line| line == pattern))
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let mut section = String::new();
+        if !existing_content.is_empty() {
+            if !existing_content.ends_with('\n') {
+                section.push('\n');
+            }
+            section.push('\n');
+        }
+        section.push_str(&format!("# {}\n", template.name()));
+        for pattern in missing {
+            section.push_str(pattern);
+            section.push('\n');
+        }
+
+        fs::write(gitignore_path, existing_content + &section)?;
+        Ok(())
+    }
+
     /// Check if a file is ignored by .gitignore patterns
     ///
     /// This uses `git check-ignore` to determine if a file would be ignored
@@ -480,16 +664,93 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn ignore_check<P: AsRef<Path>>(&self, path: P) -> Result<bool> {
-        Repository::ensure_git()?;
+        self.check_git()?;
 
         let path_str = path.as_ref().to_string_lossy();
 
-        match git(&["check-ignore", &path_str], Some(self.repo_path())) {
+        match git(&["check-ignore", "--", &path_str], Some(self)) {
             Ok(_) => Ok(true),   // File is ignored
             Err(_) => Ok(false), // File is not ignored (check-ignore returns non-zero)
         }
     }
 
+    /// Check whether many paths are ignored using a single git process
+    ///
+    /// Paths are streamed to `git check-ignore --stdin -z`, which is far faster than
+    /// calling [`ignore_check`](Self::ignore_check) once per path for large trees.
+    /// Paths re-included by a negated pattern (`!pattern`) are reported as not ignored.
+    ///
+    /// # Arguments
+    /// * `paths` - Paths to check, relative to the repository root
+    ///
+    /// # Returns
+    /// * Each input path paired with whether it is ignored, in input order
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    /// # use std::env;
+    /// # let repo_path = env::temp_dir().join("test_ignore_check_batch");
+    /// # std::fs::create_dir_all(&repo_path).unwrap();
+    /// # let repo = Repository::init(&repo_path, false).unwrap();
+    ///
+    /// let results = repo.ignore_check_batch(&["target/debug/app", "src/main.rs"])?;
+    /// for (path, ignored) in results {
+    ///     println!("{}: {}", path.display(), ignored);
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn ignore_check_batch<P: AsRef<Path>>(&self, paths: &[P]) -> Result<Vec<(PathBuf, bool)>> {
+        self.check_git()?;
+
+        if paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut input = Vec::new();
+        for path in paths {
+            input.extend_from_slice(path.as_ref().to_string_lossy().as_bytes());
+            input.push(0);
+        }
+
+        // --verbose --non-matching reports one record per input path, in order
+        let output = git_raw_with_stdin(
+            &[
+                "check-ignore",
+                "--stdin",
+                "-z",
+                "--verbose",
+                "--non-matching",
+            ],
+            Some(self),
+            &input,
+        )?;
+
+        // Exit code 0 means some paths are ignored, 1 means none are
+        if !matches!(output.status.code(), Some(0) | Some(1)) {
+            return Err(GitError::CommandFailed(format!(
+                "git check-ignore failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let flags = parse_check_ignore_output(&stdout);
+        if flags.len() != paths.len() {
+            return Err(GitError::CommandFailed(format!(
+                "git check-ignore returned {} results for {} paths",
+                flags.len(),
+                paths.len()
+            )));
+        }
+
+        Ok(paths
+            .iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .zip(flags)
+            .collect())
+    }
+
     /// List current ignore patterns from .gitignore
     ///
     /// This reads the .gitignore file and returns all non-empty, non-comment lines.
@@ -533,6 +794,19 @@ impl Repository {
     }
 }
 
+/// Parse `git check-ignore -z --verbose --non-matching` output into per-path flags
+///
+/// Each record is four NUL-terminated fields: source, line number, pattern, path.
+/// Non-matching paths have empty source and pattern fields.
+fn parse_check_ignore_output(output: &str) -> Vec<bool> {
+    let fields: Vec<&str> = output.split('\0').collect();
+
+    fields
+        .chunks_exact(4)
+        .map(|record| !record[0].is_empty() && !record[2].starts_with('!'))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -695,6 +969,97 @@ mod tests {
         fs::remove_dir_all(&repo_path).unwrap();
     }
 
+    #[test]
+    fn test_parse_check_ignore_output() {
+        let output = ".gitignore\x001\x00*.log\x00a.log\x00\x00\x00\x00b.txt\x00\
+                      .gitignore\x002\x00!keep.log\x00keep.log\x00";
+        assert_eq!(parse_check_ignore_output(output), vec![true, false, false]);
+        assert!(parse_check_ignore_output("").is_empty());
+    }
+
+    #[test]
+    fn test_ignore_check_batch() {
+        let (repo, repo_path) = create_test_repo();
+
+        repo.ignore_add(&["*.tmp", "build/", "!keep.tmp"]).unwrap();
+
+        let results = repo
+            .ignore_check_batch(&[
+                "test.txt",
+                "test.tmp",
+                "build/output.bin",
+                "keep.tmp",
+                "dir with space/x.tmp",
+            ])
+            .unwrap();
+
+        assert_eq!(
+            results,
+            vec![
+                (PathBuf::from("test.txt"), false),
+                (PathBuf::from("test.tmp"), true),
+                (PathBuf::from("build/output.bin"), true),
+                (PathBuf::from("keep.tmp"), false),
+                (PathBuf::from("dir with space/x.tmp"), true),
+            ]
+        );
+
+        let empty: [&str; 0] = [];
+        assert!(repo.ignore_check_batch(&empty).unwrap().is_empty());
+
+        fs::remove_dir_all(&repo_path).unwrap();
+    }
+
+    #[test]
+    fn test_ignore_template_patterns() {
+        let templates = [
+            IgnoreTemplate::Rust,
+            IgnoreTemplate::Node,
+            IgnoreTemplate::Python,
+            IgnoreTemplate::Go,
+            IgnoreTemplate::Java,
+            IgnoreTemplate::Cpp,
+            IgnoreTemplate::Editors,
+        ];
+
+        for template in templates {
+            assert!(!template.name().is_empty());
+            assert!(!template.patterns().is_empty());
+        }
+
+        assert!(IgnoreTemplate::Rust.patterns().contains(&"/target/"));
+        assert!(IgnoreTemplate::Node.patterns().contains(&"node_modules/"));
+    }
+
+    #[test]
+    fn test_ignore_init() {
+        let (repo, repo_path) = create_test_repo();
+
+        repo.ignore_init(IgnoreTemplate::Rust).unwrap();
+        let content = fs::read_to_string(repo_path.join(".gitignore")).unwrap();
+        assert!(content.starts_with("# Rust\n"));
+        assert!(content.contains("/target/\n"));
+
+        // Applying the same template again is a no-op
+        repo.ignore_init(IgnoreTemplate::Rust).unwrap();
+        assert_eq!(
+            fs::read_to_string(repo_path.join(".gitignore")).unwrap(),
+            content
+        );
+
+        // Existing patterns are not duplicated when combining templates
+        repo.ignore_add(&[".DS_Store"]).unwrap();
+        repo.ignore_init(IgnoreTemplate::Editors).unwrap();
+        let patterns = repo.ignore_list().unwrap();
+        assert_eq!(patterns.iter().filter(|p| *p == ".DS_Store").count(), 1);
+        assert!(patterns.contains(&".idea/".to_string()));
+
+        fs::create_dir_all(repo_path.join("target/debug")).unwrap();
+        assert!(repo.ignore_check("target/debug/app").unwrap());
+
+        fs::remove_dir_all(&repo_path).unwrap();
+    }
+
     #[test]
     fn test_mv_basic() {
         let (repo, repo_path) = create_test_repo();
@@ -788,4 +1153,48 @@ mod tests {
 
         fs::remove_dir_all(&repo_path).unwrap();
     }
+
+    #[test]
+    fn test_restore_ours_and_theirs() {
+        let (repo, repo_path) = create_test_repo();
+
+        fs::write(repo_path.join("a.txt"), "base").unwrap();
+        fs::write(repo_path.join("b.txt"), "base").unwrap();
+        repo.add(&["a.txt", "b.txt"]).unwrap();
+        repo.commit("Base").unwrap();
+
+        repo.checkout_new("feature", None).unwrap();
+        fs::write(repo_path.join("a.txt"), "theirs").unwrap();
+        fs::write(repo_path.join("b.txt"), "theirs").unwrap();
+        repo.add(&["a.txt", "b.txt"]).unwrap();
+        repo.commit("Feature").unwrap();
+
+        let branches = repo.branches().unwrap();
+        repo.checkout(branches.find("master").unwrap()).unwrap();
+        fs::write(repo_path.join("a.txt"), "ours").unwrap();
+        fs::write(repo_path.join("b.txt"), "ours").unwrap();
+        repo.add(&["a.txt", "b.txt"]).unwrap();
+        repo.commit("Master").unwrap();
+
+        assert!(matches!(
+            repo.merge("feature").unwrap(),
+            crate::MergeStatus::Conflicts(_)
+        ));
+
+        repo.restore(&["a.txt"], RestoreOptions::new().with_ours())
+            .unwrap();
+        repo.restore(&["b.txt"], RestoreOptions::new().with_theirs())
+            .unwrap();
+
+        assert_eq!(fs::read_to_string(repo_path.join("a.txt")).unwrap(), "ours");
+        assert_eq!(
+            fs::read_to_string(repo_path.join("b.txt")).unwrap(),
+            "theirs"
+        );
+
+        let options = RestoreOptions::new().with_ours().with_theirs();
+        assert!(options.theirs && !options.ours);
+
+        fs::remove_dir_all(&repo_path).unwrap();
+    }
 }
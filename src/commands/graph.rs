@@ -0,0 +1,354 @@
+//! Commit graph layout for history views
+//!
+//! [`Repository::log_graph`] loads commits in topological order (children before
+//! parents) and lays them out the way `git log --graph` does: each commit is placed in
+//! a lane (column), and each edge to a parent records the lane it continues in. GUI
+//! clients can draw the graph row by row from this without sorting commits or tracking
+//! branches themselves.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{LogOptions, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//! let graph = repo.log_graph(&LogOptions::new().all().max_count(200))?;
+//!
+//! for node in graph.iter() {
+//!     let mut row = vec![' '; graph.lane_count()];
+//!     row[node.lane] = '*';
+//!     let row: String = row.into_iter().collect();
+//!     println!("{} {}", row, node.commit.message.subject);
+//! }
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::log::{
+    Commit, DECORATE_FULL, GIT_LOG_FORMAT, LogOptions, build_log_args, parse_log_output,
+};
+use crate::error::Result;
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::git;
+use std::collections::HashMap;
+
+/// An edge from a commit to one of its parents
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphEdge {
+    /// The parent commit
+    pub parent: Hash,
+    /// The lane the edge runs in towards the parent
+    pub lane: usize,
+}
+
+/// A commit placed in the graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphNode {
+    pub commit: Commit,
+    /// The lane (column) the commit is drawn in, starting at 0
+    pub lane: usize,
+    /// Edges to the parents present in the graph, in parent order
+    pub edges: Vec<GraphEdge>,
+    /// Children present in the graph, in graph order
+    pub children: Vec<Hash>,
+}
+
+impl GraphNode {
+    /// Check whether some parents were left out of the graph, e.g. by `max_count` or
+    /// a range, so the commit's history continues beyond what was loaded
+    pub fn has_missing_parents(&self) -> bool {
+        self.edges.len() < self.commit.parents.len()
+    }
+}
+
+/// Commits in topological order with their lanes and edges
+///
+/// Returned by [`Repository::log_graph`].
+#[derive(Debug, Clone)]
+pub struct LogGraph {
+    /// Children before parents
+    nodes: Vec<GraphNode>,
+    by_hash: HashMap<String, usize>,
+    lane_count: usize,
+}
+
+impl LogGraph {
+    /// Lay out commits given children first, as `git log --topo-order` lists them
+    ///
+    /// Lanes are allocated top-down: a commit takes the leftmost lane of the edges that
+    /// reach it (freeing the others), its first parent continues in the same lane, and
+    /// further parents get a free lane unless an edge already leads to them.
+    pub(crate) fn new(commits: Vec<Commit>) -> Self {
+        let by_hash: HashMap<String, usize> = commits
+            .iter()
+            .enumerate()
+            .map(|(position, commit)| (commit.hash.as_str().to_string(), position))
+            .collect();
+
+        // The parent each lane's edge is heading for, `None` for a free lane
+        let mut lanes: Vec<Option<Hash>> = Vec::new();
+        let mut lane_count = 0;
+        let mut nodes: Vec<GraphNode> = Vec::with_capacity(commits.len());
+
+        for commit in commits {
+            let lane = match lanes.iter().position(|l| l.as_ref() == Some(&commit.hash)) {
+                Some(lane) => lane,
+                None => free_lane(&mut lanes),
+            };
+            for expected in lanes.iter_mut() {
+                if expected.as_ref() == Some(&commit.hash) {
+                    *expected = None;
+                }
+            }
+
+            let mut edges = Vec::new();
+            for parent in commit
+                .parents
+                .iter()
+                .filter(|parent| by_hash.contains_key(parent.as_str()))
+            {
+                let edge_lane = if edges.is_empty() {
+                    lane
+                } else {
+                    match lanes.iter().position(|l| l.as_ref() == Some(parent)) {
+                        Some(existing) => existing,
+                        None => free_lane(&mut lanes),
+                    }
+                };
+                lanes[edge_lane] = Some(parent.clone());
+                edges.push(GraphEdge {
+                    parent: parent.clone(),
+                    lane: edge_lane,
+                });
+            }
+
+            lane_count = lane_count.max(lanes.len());
+            while lanes.last().is_some_and(Option::is_none) {
+                lanes.pop();
+            }
+
+            nodes.push(GraphNode {
+                commit,
+                lane,
+                edges,
+                children: Vec::new(),
+            });
+        }
+
+        for child in 0..nodes.len() {
+            let child_hash = nodes[child].commit.hash.clone();
+            for edge in nodes[child].edges.clone() {
+                if let Some(&parent) = by_hash.get(edge.parent.as_str()) {
+                    nodes[parent].children.push(child_hash.clone());
+                }
+            }
+        }
+
+        Self {
+            nodes,
+            by_hash,
+            lane_count,
+        }
+    }
+
+    /// Iterate over the nodes, children before parents
+    pub fn iter(&self) -> impl Iterator<Item = &GraphNode> {
+        self.nodes.iter()
+    }
+
+    /// Get all nodes, children before parents
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    /// Find the node of a commit
+    pub fn get(&self, hash: &Hash) -> Option<&GraphNode> {
+        self.by_hash
+            .get(hash.as_str())
+            .map(|&position| &self.nodes[position])
+    }
+
+    /// The number of lanes needed to draw the graph
+    pub fn lane_count(&self) -> usize {
+        self.lane_count
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Reuse the leftmost free lane, or open a new one on the right
+fn free_lane(lanes: &mut Vec<Option<Hash>>) -> usize {
+    match lanes.iter().position(Option::is_none) {
+        Some(lane) => lane,
+        None => {
+            lanes.push(None);
+            lanes.len() - 1
+        }
+    }
+}
+
+impl Repository {
+    /// Load commits as a graph for drawing history
+    ///
+    /// Commits are selected with the usual [`LogOptions`] and returned in topological
+    /// order (`--topo-order`). Edges only connect commits present in the graph;
+    /// [`GraphNode::has_missing_parents`] tells where history was cut off.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The log options selecting the commits
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `LogGraph` or a `GitError`.
+    pub fn log_graph(&self, options: &LogOptions) -> Result<LogGraph> {
+        self.check_git()?;
+
+        let args_vec = build_log_args(GIT_LOG_FORMAT, &["--topo-order"], options)?;
+        let all_args: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
+        debug_assert!(all_args.contains(&DECORATE_FULL));
+
+        let output = git(&all_args, Some(self))?;
+        Ok(LogGraph::new(parse_log_output(&output)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::log::{Author, CommitMessage};
+    use crate::testing::TempRepo;
+    use crate::{FastForwardMode, MergeOptions};
+    use chrono::DateTime;
+
+    fn commit(hash: &str, parents: &[&str]) -> Commit {
+        let author = Author {
+            name: "Test".to_string(),
+            email: "test@example.com".to_string(),
+            timestamp: DateTime::from_timestamp(1640995200, 0).unwrap(),
+        };
+        Commit {
+            hash: Hash::from(hash),
+            committer: author.clone(),
+            timestamp: author.timestamp,
+            author,
+            message: CommitMessage::new(hash.to_string(), None),
+            parents: parents.iter().map(|&parent| Hash::from(parent)).collect(),
+            signature: None,
+            refs: Vec::new(),
+        }
+    }
+
+    fn lanes(graph: &LogGraph) -> Vec<(usize, Vec<usize>)> {
+        graph
+            .iter()
+            .map(|node| (node.lane, node.edges.iter().map(|edge| edge.lane).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_layout_merge() {
+        // merge of `side` into `main`, both forked from `base`
+        let graph = LogGraph::new(vec![
+            commit("merge", &["main", "side"]),
+            commit("side", &["base"]),
+            commit("main", &["base"]),
+            commit("base", &[]),
+        ]);
+
+        assert_eq!(graph.lane_count(), 2);
+        assert_eq!(
+            lanes(&graph),
+            vec![
+                (0, vec![0, 1]),
+                (1, vec![1]),
+                (0, vec![0]),
+                // `side`'s edge ends here too and frees lane 1
+                (0, vec![]),
+            ]
+        );
+        let base = graph.get(&Hash::from("base")).unwrap();
+        assert_eq!(base.children, vec![Hash::from("side"), Hash::from("main")]);
+        assert!(base.commit.is_root());
+        assert!(!base.has_missing_parents());
+    }
+
+    #[test]
+    fn test_layout_reuses_lanes_and_cuts_history() {
+        // Two branch tips joining at `mid`, and `old` whose parent was not loaded
+        let graph = LogGraph::new(vec![
+            commit("tip-a", &["mid"]),
+            commit("tip-b", &["mid"]),
+            commit("mid", &["old"]),
+            commit("old", &["unloaded"]),
+        ]);
+
+        assert_eq!(graph.lane_count(), 2);
+        assert_eq!(
+            lanes(&graph),
+            vec![(0, vec![0]), (1, vec![1]), (0, vec![0]), (0, vec![])]
+        );
+        assert!(graph.get(&Hash::from("old")).unwrap().has_missing_parents());
+        assert!(graph.get(&Hash::from("unloaded")).is_none());
+        assert_eq!(
+            graph.get(&Hash::from("mid")).unwrap().children,
+            vec![Hash::from("tip-a"), Hash::from("tip-b")]
+        );
+    }
+
+    #[test]
+    fn test_repository_log_graph() {
+        let repo = TempRepo::new("log_graph").unwrap();
+        let base = repo.commit_file("base.txt", "base\n", "Base").unwrap();
+        let main = repo.current_branch().unwrap().unwrap();
+        repo.checkout_new("feature", None).unwrap();
+        let feature = repo
+            .commit_file("feature.txt", "feature\n", "Feature")
+            .unwrap();
+        repo.checkout(&main).unwrap();
+        let main_commit = repo.commit_file("main.txt", "main\n", "Main").unwrap();
+        repo.merge_with_options(
+            "feature",
+            MergeOptions::new().with_fast_forward(FastForwardMode::Never),
+        )
+        .unwrap();
+        let merge = repo.head_hash().unwrap();
+
+        let graph = repo.log_graph(&LogOptions::new()).unwrap();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.lane_count(), 2);
+
+        // Topological order: the merge first, the root last
+        assert_eq!(graph.nodes()[0].commit.hash, merge);
+        assert_eq!(graph.nodes()[3].commit.hash, base);
+        let merge_node = graph.get(&merge).unwrap();
+        assert_eq!(merge_node.lane, 0);
+        assert_eq!(
+            merge_node.edges,
+            vec![
+                GraphEdge {
+                    parent: main_commit.clone(),
+                    lane: 0
+                },
+                GraphEdge {
+                    parent: feature.clone(),
+                    lane: 1
+                },
+            ]
+        );
+        assert_eq!(graph.get(&feature).unwrap().lane, 1);
+        assert_eq!(graph.get(&base).unwrap().children.len(), 2);
+        assert!(merge_node.commit.refs.contains(&"HEAD".to_string()));
+
+        // Cutting the history leaves edges only within the graph
+        let graph = repo.log_graph(&LogOptions::new().max_count(2)).unwrap();
+        assert_eq!(graph.len(), 2);
+        assert!(graph.iter().all(|node| node.has_missing_parents()));
+    }
+}
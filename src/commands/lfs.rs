@@ -0,0 +1,203 @@
+//! Git LFS file locking
+//!
+//! Binary assets (images, models, office documents) cannot be merged, so teams using
+//! Git LFS lock a file on the LFS server before editing it. This module wraps
+//! `git lfs lock`, `git lfs unlock` and `git lfs locks`; it requires the `git-lfs`
+//! extension to be installed and a remote with an LFS locking API.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::Repository;
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! let lock = repo.lfs_lock("art/hero.psd")?;
+//! println!("Locked {} (id {})", lock.path.display(), lock.id);
+//!
+//! for lock in repo.lfs_locks()? {
+//!     println!("{} is locked by {}", lock.path.display(), lock.owner);
+//! }
+//!
+//! repo.lfs_unlock("art/hero.psd")?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::GitError;
+use crate::utils::git_raw;
+use crate::{Repository, Result};
+use std::path::{Path, PathBuf};
+
+/// A file lock held on the LFS server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsLock {
+    /// Server-assigned lock id
+    pub id: String,
+    /// Locked path, relative to the repository root
+    pub path: PathBuf,
+    /// Name of the user holding the lock
+    pub owner: String,
+}
+
+/// Options for releasing an LFS lock
+#[derive(Debug, Clone, Default)]
+pub struct LfsUnlockOptions {
+    /// Release a lock held by another user (requires server permission)
+    pub force: bool,
+}
+
+impl LfsUnlockOptions {
+    /// Create new unlock options with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Release the lock even if another user holds it
+    pub fn with_force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+}
+
+impl Repository {
+    /// Lock a file on the LFS server so others cannot push changes to it
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file to lock, relative to the working directory
+    ///
+    /// # Returns
+    /// The lock as reported by the server
+    pub fn lfs_lock<P: AsRef<Path>>(&self, path: P) -> Result<LfsLock> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        self.lfs(&["lock", "--", &path_str])?;
+
+        // `git lfs lock` only prints a confirmation, so read the lock back
+        let output = self.lfs(&["locks", "--local", &format!("--path={}", path_str)])?;
+        parse_locks(&output).into_iter().next().ok_or_else(|| {
+            GitError::CommandFailed(format!(
+                "git lfs lock did not report a lock for {}",
+                path_str
+            ))
+        })
+    }
+
+    /// Release a lock held by the current user
+    pub fn lfs_unlock<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.lfs_unlock_with_options(path, LfsUnlockOptions::new())
+    }
+
+    /// Release a lock with custom options
+    ///
+    /// # Arguments
+    /// * `path` - Path of the locked file, relative to the working directory
+    /// * `options` - Unlock options
+    pub fn lfs_unlock_with_options<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: LfsUnlockOptions,
+    ) -> Result<()> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let mut args = vec!["unlock"];
+        if options.force {
+            args.push("--force");
+        }
+        args.push("--");
+        args.push(&path_str);
+        self.lfs(&args)?;
+        Ok(())
+    }
+
+    /// List the locks currently held on the LFS server, with their owners
+    pub fn lfs_locks(&self) -> Result<Vec<LfsLock>> {
+        self.check_git()?;
+
+        let output = self.lfs(&["locks"])?;
+        Ok(parse_locks(&output))
+    }
+
+    /// Run a `git lfs` subcommand, reporting a missing extension clearly
+    fn lfs(&self, args: &[&str]) -> Result<String> {
+        let mut full_args = vec!["lfs"];
+        full_args.extend_from_slice(args);
+        let output = git_raw(&full_args, Some(self))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("'lfs' is not a git command") {
+                return Err(GitError::CommandFailed(
+                    "git-lfs is not installed".to_string(),
+                ));
+            }
+            return Err(GitError::CommandFailed(format!(
+                "git lfs {} failed: {}",
+                args.first().unwrap_or(&"<unknown>"),
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// Parse `git lfs locks` output: `<path><padding>\t<owner>\tID:<id>` per line
+fn parse_locks(output: &str) -> Vec<LfsLock> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.rsplitn(3, '\t');
+            let id = fields.next()?.strip_prefix("ID:")?;
+            let owner = fields.next()?;
+            let path = fields.next()?.trim_end();
+            Some(LfsLock {
+                id: id.to_string(),
+                path: PathBuf::from(path),
+                owner: owner.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locks() {
+        let output = "\
+art/hero.psd      \tJane Doe\tID:42
+models/ship v2.fbx\tbob\tID:7
+";
+        let locks = parse_locks(output);
+        assert_eq!(
+            locks,
+            vec![
+                LfsLock {
+                    id: "42".to_string(),
+                    path: PathBuf::from("art/hero.psd"),
+                    owner: "Jane Doe".to_string(),
+                },
+                LfsLock {
+                    id: "7".to_string(),
+                    path: PathBuf::from("models/ship v2.fbx"),
+                    owner: "bob".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_locks_ignores_other_lines() {
+        assert!(parse_locks("").is_empty());
+        assert!(parse_locks("No locks found\n").is_empty());
+    }
+
+    #[test]
+    fn test_unlock_options_builder() {
+        assert!(!LfsUnlockOptions::new().force);
+        assert!(LfsUnlockOptions::new().with_force().force);
+    }
+}
@@ -0,0 +1,369 @@
+//! Line-ending inspection and renormalization
+//!
+//! Changing a team's CRLF policy (`core.autocrlf`, or `text`/`eol` attributes in
+//! `.gitattributes`) does not touch files that are already committed. The helpers here
+//! show what the current settings are, how each file is stored and checked out, and
+//! which files a renormalization would rewrite before actually doing it.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::Repository;
+//!
+//! let repo = Repository::open(".")?;
+//! repo.attributes_add(&["*"], "text=auto eol=lf")?;
+//!
+//! let changed = repo.renormalize_preview()?;
+//! println!("{} files will be renormalized", changed.len());
+//!
+//! repo.renormalize()?;
+//! repo.commit("Normalize line endings")?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::GitError;
+use crate::utils::{git, git_raw, git_raw_with_env};
+use crate::{Repository, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// Value of `core.autocrlf`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoCrlf {
+    /// No conversion (git's default)
+    #[default]
+    False,
+    /// Convert LF to CRLF on checkout and CRLF to LF when staging
+    True,
+    /// Convert CRLF to LF when staging only
+    Input,
+}
+
+/// Value of `core.eol`, used for text files without an `eol` attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EolSetting {
+    /// The platform's native line ending (git's default)
+    #[default]
+    Native,
+    Lf,
+    Crlf,
+}
+
+/// Repository-wide line-ending configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LineEndingConfig {
+    pub autocrlf: AutoCrlf,
+    pub eol: EolSetting,
+}
+
+/// Line endings found in a file's content, as reported by `git ls-files --eol`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Both LF and CRLF
+    Mixed,
+    /// Text without any line ending
+    None,
+    /// Detected as binary content
+    Binary,
+}
+
+impl LineEnding {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "lf" => Some(LineEnding::Lf),
+            "crlf" => Some(LineEnding::Crlf),
+            "mixed" => Some(LineEnding::Mixed),
+            "none" => Some(LineEnding::None),
+            "-text" => Some(LineEnding::Binary),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "lf",
+            LineEnding::Crlf => "crlf",
+            LineEnding::Mixed => "mixed",
+            LineEnding::None => "none",
+            LineEnding::Binary => "-text",
+        }
+    }
+}
+
+/// Line-ending state of a tracked file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLineEndings {
+    pub path: PathBuf,
+    /// Line endings of the staged content, `None` if it is empty
+    pub index: Option<LineEnding>,
+    /// Line endings in the working tree, `None` if the file is missing or empty
+    pub worktree: Option<LineEnding>,
+    /// The `text` and `eol` attributes in effect, e.g. `text=auto eol=lf`; empty if unset
+    pub attributes: String,
+}
+
+impl Repository {
+    /// Read `core.autocrlf` and `core.eol`
+    ///
+    /// Unset or unrecognized values yield git's defaults.
+    pub fn line_ending_config(&self) -> Result<LineEndingConfig> {
+        self.check_git()?;
+
+        let autocrlf = match self.optional_config("core.autocrlf")?.as_deref() {
+            Some("input") => AutoCrlf::Input,
+            Some("true" | "yes" | "on" | "1") => AutoCrlf::True,
+            _ => AutoCrlf::False,
+        };
+        let eol = match self.optional_config("core.eol")?.as_deref() {
+            Some("lf") => EolSetting::Lf,
+            Some("crlf") => EolSetting::Crlf,
+            _ => EolSetting::Native,
+        };
+
+        Ok(LineEndingConfig { autocrlf, eol })
+    }
+
+    /// List the stored and checked-out line endings of tracked files
+    ///
+    /// # Arguments
+    /// * `paths` - Pathspecs to restrict the listing to; empty for all tracked files
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use rustic_git::{LineEnding, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for file in repo.line_endings(&[])? {
+    ///     if file.index == Some(LineEnding::Crlf) {
+    ///         println!("{} is committed with CRLF", file.path.display());
+    ///     }
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn line_endings(&self, paths: &[&str]) -> Result<Vec<FileLineEndings>> {
+        self.check_git()?;
+
+        let mut args = vec!["ls-files", "--eol", "-z"];
+        if !paths.is_empty() {
+            args.push("--");
+            args.extend_from_slice(paths);
+        }
+        let output = git(&args, Some(self))?;
+
+        let base = self.command_dir();
+        output
+            .split('\0')
+            .filter(|record| !record.is_empty())
+            .map(|record| {
+                let file = parse_eol_record(record).ok_or_else(|| {
+                    GitError::CommandFailed(format!(
+                        "unexpected git ls-files --eol output: {:?}",
+                        record
+                    ))
+                })?;
+                Ok(FileLineEndings {
+                    path: self.styled_path(&base, file.path),
+                    ..file
+                })
+            })
+            .collect()
+    }
+
+    /// Re-apply the current line-ending rules to all tracked files and stage the result
+    ///
+    /// Runs `git add --renormalize .` in the handle's directory. The working tree is
+    /// not modified; commit the staged changes to finish the migration.
+    pub fn renormalize(&self) -> Result<()> {
+        self.check_git()?;
+
+        git(&["add", "--renormalize", "."], Some(self))?;
+        Ok(())
+    }
+
+    /// List the files [`renormalize`](Self::renormalize) would change, without staging anything
+    ///
+    /// The renormalization runs against a scratch copy of the index, so the real index
+    /// and working tree are left untouched.
+    ///
+    /// # Returns
+    /// Paths relative to the repository root (or absolute, per the path style).
+    pub fn renormalize_preview(&self) -> Result<Vec<PathBuf>> {
+        self.check_git()?;
+
+        let index = git(&["rev-parse", "--git-path", "index"], Some(self))?;
+        let index = std::path::absolute(self.command_dir().join(index.trim()))?;
+        if !index.exists() {
+            return Ok(Vec::new());
+        }
+
+        let scratch = index.with_file_name("rustic-git-renormalize.index");
+        fs::copy(&index, &scratch)?;
+        let result = self.diff_renormalized(&scratch.to_string_lossy());
+        let _ = fs::remove_file(&scratch);
+        result
+    }
+
+    /// Renormalize into the index file at `index_file` and list the paths that changed
+    fn diff_renormalized(&self, index_file: &str) -> Result<Vec<PathBuf>> {
+        let env = [("GIT_INDEX_FILE", index_file)];
+        let write_tree = || -> Result<String> {
+            let output = git_raw_with_env(&["write-tree"], Some(self), &env)?;
+            if !output.status.success() {
+                return Err(GitError::CommandFailed(format!(
+                    "git write-tree failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+
+        let before = write_tree()?;
+        let output = git_raw_with_env(&["add", "--renormalize", "."], Some(self), &env)?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git add --renormalize failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let after = write_tree()?;
+
+        let output = git(
+            &["diff-tree", "-r", "--name-only", "-z", &before, &after],
+            Some(self),
+        )?;
+        Ok(output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| self.styled_path(self.repo_path(), PathBuf::from(path)))
+            .collect())
+    }
+
+    /// Read a config value, or `None` if it is unset
+    fn optional_config(&self, key: &str) -> Result<Option<String>> {
+        let output = git_raw(&["config", "--get", key], Some(self))?;
+        if output.status.success() {
+            Ok(Some(
+                String::from_utf8_lossy(&output.stdout)
+                    .trim()
+                    .to_lowercase(),
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Parse an `ls-files --eol` record: `i/<eol> w/<eol> attr/<attrs>\t<path>`
+fn parse_eol_record(record: &str) -> Option<FileLineEndings> {
+    let (info, path) = record.split_once('\t')?;
+    let mut fields = info.split_whitespace();
+    let index = fields.next()?.strip_prefix("i/")?;
+    let worktree = fields.next()?.strip_prefix("w/")?;
+    let attributes = info[info.find("attr/")? + "attr/".len()..].trim();
+
+    Some(FileLineEndings {
+        path: PathBuf::from(path),
+        index: LineEnding::parse(index),
+        worktree: LineEnding::parse(worktree),
+        attributes: attributes.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::{env, fs};
+
+    fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
+        let temp_dir = env::temp_dir().join(format!("rustic_git_line_endings_test_{}", test_name));
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_parse_eol_record() {
+        let file = parse_eol_record("i/crlf  w/crlf  attr/text=auto eol=lf \tdir/a b.txt").unwrap();
+        assert_eq!(file.path, Path::new("dir/a b.txt"));
+        assert_eq!(file.index, Some(LineEnding::Crlf));
+        assert_eq!(file.worktree, Some(LineEnding::Crlf));
+        assert_eq!(file.attributes, "text=auto eol=lf");
+
+        let file = parse_eol_record("i/-text w/       attr/                 \timage.png").unwrap();
+        assert_eq!(file.index, Some(LineEnding::Binary));
+        assert_eq!(file.worktree, None);
+        assert_eq!(file.attributes, "");
+
+        assert!(parse_eol_record("garbage").is_none());
+    }
+
+    #[test]
+    fn test_line_ending_config() {
+        let (temp_dir, repo) = create_test_repo("config");
+        repo.config().set("core.autocrlf", "false").unwrap();
+        assert_eq!(
+            repo.line_ending_config().unwrap(),
+            LineEndingConfig::default()
+        );
+
+        repo.config().set("core.autocrlf", "input").unwrap();
+        repo.config().set("core.eol", "crlf").unwrap();
+        let config = repo.line_ending_config().unwrap();
+        assert_eq!(config.autocrlf, AutoCrlf::Input);
+        assert_eq!(config.eol, EolSetting::Crlf);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_renormalize_preview_and_apply() {
+        let (temp_dir, repo) = create_test_repo("renormalize");
+        repo.config().set("core.autocrlf", "false").unwrap();
+        fs::write(temp_dir.join("windows.txt"), "one\r\ntwo\r\n").unwrap();
+        fs::write(temp_dir.join("unix.txt"), "one\ntwo\n").unwrap();
+        repo.add(&["windows.txt", "unix.txt"]).unwrap();
+        repo.commit("Add files").unwrap();
+
+        let files = repo.line_endings(&[]).unwrap();
+        assert_eq!(files.len(), 2);
+        let windows = files
+            .iter()
+            .find(|f| f.path == Path::new("windows.txt"))
+            .unwrap();
+        assert_eq!(windows.index, Some(LineEnding::Crlf));
+        assert_eq!(windows.attributes, "");
+
+        // Nothing changes until the policy does
+        assert!(repo.renormalize_preview().unwrap().is_empty());
+
+        repo.attributes_add(&["*.txt"], "text eol=lf").unwrap();
+        let preview = repo.renormalize_preview().unwrap();
+        assert_eq!(preview, vec![PathBuf::from("windows.txt")]);
+
+        // The preview leaves the index untouched
+        assert_eq!(repo.status().unwrap().staged_files().count(), 0);
+        assert!(
+            !fs::read_dir(repo.git_dir().unwrap())
+                .unwrap()
+                .any(|entry| entry.unwrap().file_name() == "rustic-git-renormalize.index")
+        );
+
+        repo.renormalize().unwrap();
+        let files = repo.line_endings(&["windows.txt"]).unwrap();
+        assert_eq!(files[0].index, Some(LineEnding::Lf));
+        assert_eq!(files[0].worktree, Some(LineEnding::Crlf));
+        assert_eq!(files[0].attributes, "text eol=lf");
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -1,13 +1,42 @@
+use crate::commands::diff::{FileDiff, FileStat, parse_diff_output, parse_numstat};
 use crate::types::Hash;
-use crate::utils::git;
+use crate::utils::{GitProcess, git, git_spawn_stdout, validate_arg};
 use crate::{Repository, Result};
 use chrono::{DateTime, Utc};
 use std::fmt;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::ChildStdout;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
 
 /// Git log format string for parsing commit information
-/// Format: hash|author_name|author_email|author_timestamp|committer_name|committer_email|committer_timestamp|parent_hashes|subject|body
-const GIT_LOG_FORMAT: &str = "--pretty=format:%H|%an|%ae|%at|%cn|%ce|%ct|%P|%s|%b";
+/// Fields: hash, author name, author email, author timestamp, committer name, committer
+/// email, committer timestamp, parent hashes, decorations, subject, body
+///
+/// Fields are separated by [`LOG_FIELD_SEPARATOR`] (a unit separator) and each record
+/// ends with a NUL byte, so names, subjects, and multi-paragraph bodies can contain any
+/// text, and records can be read incrementally. Decorations (`%D`) name refs in full
+/// when git also gets [`DECORATE_FULL`].
+pub(crate) const GIT_LOG_FORMAT: &str =
+    "--pretty=format:%H%x1f%an%x1f%ae%x1f%at%x1f%cn%x1f%ce%x1f%ct%x1f%P%x1f%D%x1f%s%x1f%b%x00";
+
+/// Git log format for per-file history: each record starts with a record separator and
+/// the commit fields end with a group separator, followed by the commit's patch (or,
+/// for the commit index, its changed paths)
+pub(crate) const FILE_HISTORY_FORMAT: &str =
+    "--pretty=format:%x1e%H%x1f%an%x1f%ae%x1f%at%x1f%cn%x1f%ce%x1f%ct%x1f%P%x1f%D%x1f%s%x1f%b%x1d";
+
+/// Separates the fields of a log record
+const LOG_FIELD_SEPARATOR: char = '\x1f';
+
+/// Passed with the log formats so decorations read `refs/heads/main` rather than
+/// `main`, which could be a branch or a remote-tracking ref
+pub(crate) const DECORATE_FULL: &str = "--decorate=full";
+
+/// Signature fields prepended to the log format when signatures are requested:
+/// validity (%G?), signer (%GS), and key (%GK), each terminated by a field separator
+const SIGNATURE_FORMAT: &str = "%G?%x1f%GS%x1f%GK%x1f";
 
 /// Date format for git date filters
 const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
@@ -56,6 +85,83 @@ impl fmt::Display for CommitMessage {
     }
 }
 
+/// Result of verifying a commit signature, as reported by `%G?`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureValidity {
+    /// Good, valid signature (`G`)
+    Good,
+    /// Good signature with unknown validity (`U`)
+    GoodUnknownValidity,
+    /// Good signature that has expired (`X`)
+    ExpiredSignature,
+    /// Good signature made by an expired key (`Y`)
+    ExpiredKey,
+    /// Good signature made by a revoked key (`R`)
+    RevokedKey,
+    /// Bad signature (`B`)
+    Bad,
+    /// Signature cannot be checked, e.g. the key is missing (`E`)
+    CannotCheck,
+    /// No signature (`N`)
+    Unsigned,
+}
+
+impl SignatureValidity {
+    /// Convert a `%G?` character to SignatureValidity
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            'G' => Some(Self::Good),
+            'U' => Some(Self::GoodUnknownValidity),
+            'X' => Some(Self::ExpiredSignature),
+            'Y' => Some(Self::ExpiredKey),
+            'R' => Some(Self::RevokedKey),
+            'B' => Some(Self::Bad),
+            'E' => Some(Self::CannotCheck),
+            'N' => Some(Self::Unsigned),
+            _ => None,
+        }
+    }
+
+    /// Convert SignatureValidity to its `%G?` character
+    pub const fn to_char(&self) -> char {
+        match self {
+            Self::Good => 'G',
+            Self::GoodUnknownValidity => 'U',
+            Self::ExpiredSignature => 'X',
+            Self::ExpiredKey => 'Y',
+            Self::RevokedKey => 'R',
+            Self::Bad => 'B',
+            Self::CannotCheck => 'E',
+            Self::Unsigned => 'N',
+        }
+    }
+}
+
+/// Signature information attached to a commit when signatures are requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureStatus {
+    pub validity: SignatureValidity,
+    /// The signer name (`%GS`), if any
+    pub signer: Option<String>,
+    /// The signing key id or fingerprint (`%GK`), if any
+    pub key: Option<String>,
+}
+
+impl SignatureStatus {
+    /// Check if the commit carries any signature
+    pub fn is_signed(&self) -> bool {
+        self.validity != SignatureValidity::Unsigned
+    }
+
+    /// Check if the signature verified as good (`G` or `U`)
+    pub fn is_good(&self) -> bool {
+        matches!(
+            self.validity,
+            SignatureValidity::Good | SignatureValidity::GoodUnknownValidity
+        )
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Commit {
     pub hash: Hash,
@@ -64,6 +170,11 @@ pub struct Commit {
     pub message: CommitMessage,
     pub timestamp: DateTime<Utc>,
     pub parents: Box<[Hash]>,
+    /// Signature status, only populated when `LogOptions::show_signatures()` is used
+    pub signature: Option<SignatureStatus>,
+    /// Full names of the refs pointing at this commit, e.g. `HEAD`,
+    /// `refs/heads/main` or `refs/tags/v1.0`
+    pub refs: Vec<String>,
 }
 
 impl Commit {
@@ -212,11 +323,81 @@ impl fmt::Display for CommitLog {
     }
 }
 
+/// A date bound for filtering history with [`LogOptions::since`] and [`LogOptions::until`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogDate {
+    /// An exact point in time
+    Exact(DateTime<Utc>),
+    /// A date string interpreted by git itself, such as `"2 weeks ago"`, `"yesterday"`,
+    /// or `"last monday"`, relative to the time git runs
+    Approx(String),
+}
+
+impl LogDate {
+    fn to_arg(&self) -> String {
+        match self {
+            LogDate::Exact(date) => date.format(DATE_FORMAT).to_string(),
+            LogDate::Approx(date) => date.clone(),
+        }
+    }
+}
+
+impl From<DateTime<Utc>> for LogDate {
+    fn from(date: DateTime<Utc>) -> Self {
+        LogDate::Exact(date)
+    }
+}
+
+impl From<&str> for LogDate {
+    fn from(date: &str) -> Self {
+        LogDate::Approx(date.to_string())
+    }
+}
+
+impl From<String> for LogDate {
+    fn from(date: String) -> Self {
+        LogDate::Approx(date)
+    }
+}
+
+/// A set of refs to start the history walk from instead of `HEAD`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefSelector {
+    /// Every ref, plus `HEAD` (`--all`)
+    All,
+    /// Local branches, optionally limited by a glob (`--branches[=<pattern>]`)
+    Branches(Option<String>),
+    /// Tags, optionally limited by a glob (`--tags[=<pattern>]`)
+    Tags(Option<String>),
+    /// Refs to skip in the next `All`, `Branches`, or `Tags` selector (`--exclude=<glob>`)
+    Exclude(String),
+    /// A single revision or range expression, e.g. `main..feature` or `main...feature`
+    Revision(String),
+}
+
+impl RefSelector {
+    fn to_arg(&self) -> String {
+        let with_pattern = |flag: &str, pattern: &Option<String>| match pattern {
+            Some(pattern) => format!("{}={}", flag, pattern),
+            None => flag.to_string(),
+        };
+        match self {
+            RefSelector::All => "--all".to_string(),
+            RefSelector::Branches(pattern) => with_pattern("--branches", pattern),
+            RefSelector::Tags(pattern) => with_pattern("--tags", pattern),
+            RefSelector::Exclude(pattern) => format!("--exclude={}", pattern),
+            RefSelector::Revision(revision) => revision.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct LogOptions {
     pub max_count: Option<usize>,
-    pub since: Option<DateTime<Utc>>,
-    pub until: Option<DateTime<Utc>>,
+    /// Number of matching commits to leave out before the first one returned
+    pub skip: Option<usize>,
+    pub since: Option<LogDate>,
+    pub until: Option<LogDate>,
     pub author: Option<String>,
     pub committer: Option<String>,
     pub grep: Option<String>,
@@ -224,6 +405,16 @@ pub struct LogOptions {
     pub follow_renames: bool,
     pub merges_only: bool,
     pub no_merges: bool,
+    pub show_signatures: bool,
+    /// Starting refs, in command-line order; empty means `HEAD`
+    pub refs: Vec<RefSelector>,
+    pub ancestry_path: bool,
+    /// Follow only the first parent of merge commits
+    pub first_parent: bool,
+    /// Only commits that change the number of occurrences of this string (`-S`)
+    pub pickaxe_string: Option<String>,
+    /// Only commits whose diff adds or removes lines matching this regex (`-G`)
+    pub pickaxe_regex: Option<String>,
 }
 
 impl LogOptions {
@@ -237,15 +428,26 @@ impl LogOptions {
         self
     }
 
-    /// Filter commits since a date
-    pub fn since(mut self, date: DateTime<Utc>) -> Self {
-        self.since = Some(date);
+    /// Skip the first `count` matching commits (`--skip`)
+    ///
+    /// Combined with [`max_count`](Self::max_count) this selects one page of history;
+    /// see [`Repository::log_page`].
+    pub fn skip(mut self, count: usize) -> Self {
+        self.skip = Some(count);
+        self
+    }
+
+    /// Filter commits since a date, given as a `DateTime<Utc>` or a git date string
+    /// such as `"2 weeks ago"`
+    pub fn since<D: Into<LogDate>>(mut self, date: D) -> Self {
+        self.since = Some(date.into());
         self
     }
 
-    /// Filter commits until a date
-    pub fn until(mut self, date: DateTime<Utc>) -> Self {
-        self.until = Some(date);
+    /// Filter commits until a date, given as a `DateTime<Utc>` or a git date string
+    /// such as `"yesterday"`
+    pub fn until<D: Into<LogDate>>(mut self, date: D) -> Self {
+        self.until = Some(date.into());
         self
     }
 
@@ -267,6 +469,29 @@ impl LogOptions {
         self
     }
 
+    /// Only include commits that add or remove occurrences of `string` in file content
+    /// (`-S`), e.g. to find where a function was introduced or deleted
+    ///
+    /// Moving a line within a file does not change its count, so such commits are not
+    /// matched. Replaces any [`pickaxe_regex`](Self::pickaxe_regex), as git accepts
+    /// only one.
+    pub fn pickaxe_string(mut self, string: &str) -> Self {
+        self.pickaxe_string = Some(string.to_string());
+        self.pickaxe_regex = None;
+        self
+    }
+
+    /// Only include commits whose diff has an added or removed line matching the
+    /// regular expression `regex` (`-G`)
+    ///
+    /// Unlike [`pickaxe_string`](Self::pickaxe_string), this also matches edits that
+    /// leave the number of occurrences unchanged. Replaces any `pickaxe_string`.
+    pub fn pickaxe_regex(mut self, regex: &str) -> Self {
+        self.pickaxe_regex = Some(regex.to_string());
+        self.pickaxe_string = None;
+        self
+    }
+
     /// Filter by file paths
     pub fn paths(mut self, paths: Vec<PathBuf>) -> Self {
         self.paths = paths;
@@ -290,6 +515,174 @@ impl LogOptions {
         self.no_merges = exclude;
         self
     }
+
+    /// Verify signatures and attach a `SignatureStatus` to each commit
+    ///
+    /// Verification runs gpg or ssh-keygen for every signed commit, so this is
+    /// noticeably slower than a plain log on large histories.
+    pub fn show_signatures(mut self) -> Self {
+        self.show_signatures = true;
+        self
+    }
+
+    /// Walk history from every ref instead of only `HEAD`
+    pub fn all(mut self) -> Self {
+        self.refs.push(RefSelector::All);
+        self
+    }
+
+    /// Walk history from local branches matching a glob such as `"feature/*"`;
+    /// an empty pattern selects every branch
+    pub fn branches(mut self, pattern: &str) -> Self {
+        let pattern = (!pattern.is_empty()).then(|| pattern.to_string());
+        self.refs.push(RefSelector::Branches(pattern));
+        self
+    }
+
+    /// Walk history from tags matching a glob such as `"v1.*"`;
+    /// an empty pattern selects every tag
+    pub fn tags(mut self, pattern: &str) -> Self {
+        let pattern = (!pattern.is_empty()).then(|| pattern.to_string());
+        self.refs.push(RefSelector::Tags(pattern));
+        self
+    }
+
+    /// Skip refs matching a glob in the *next* [`all`](Self::all),
+    /// [`branches`](Self::branches), or [`tags`](Self::tags) selector, so call it first:
+    /// `LogOptions::new().exclude("refs/heads/wip/*").all()`
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        self.refs.push(RefSelector::Exclude(pattern.to_string()));
+        self
+    }
+
+    /// Follow only the first parent of merges (`--first-parent`), so a branch's
+    /// history shows each merge as one commit and leaves out the merged commits
+    pub fn first_parent(mut self) -> Self {
+        self.first_parent = true;
+        self
+    }
+
+    /// Walk commits reachable from `to` but not from `from` (`from..to`)
+    pub fn range(mut self, from: &str, to: &str) -> Self {
+        self.refs
+            .push(RefSelector::Revision(format!("{}..{}", from, to)));
+        self
+    }
+
+    /// Walk commits reachable from either side but not from both (`left...right`),
+    /// i.e. what each branch has that the other lacks
+    pub fn symmetric_difference(mut self, left: &str, right: &str) -> Self {
+        self.refs
+            .push(RefSelector::Revision(format!("{}...{}", left, right)));
+        self
+    }
+
+    /// Only show commits that are on a direct path between the ends of a range
+    /// (`--ancestry-path`), e.g. the commits that brought a fix from `main..release`
+    pub fn ancestry_path(mut self) -> Self {
+        self.ancestry_path = true;
+        self
+    }
+}
+
+/// One page of history from [`Repository::log_page`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// The commits on this page, newest first
+    pub commits: CommitLog,
+    /// Whether more commits match after this page
+    pub has_more: bool,
+    /// Number of commits skipped before this page
+    pub offset: usize,
+}
+
+impl Page {
+    /// The offset to request the following page with
+    pub fn next_offset(&self) -> usize {
+        self.offset + self.commits.len()
+    }
+}
+
+/// A commit found on only one side of a [`BranchComparison`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComparedCommit {
+    pub commit: Commit,
+    /// Whether a patch-equivalent commit exists on the other side (e.g. a cherry-pick)
+    pub equivalent: bool,
+}
+
+/// The result of comparing two revisions with `git log --left-right --cherry-mark A...B`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchComparison {
+    /// The best common ancestor, or `None` if the histories are unrelated
+    pub merge_base: Option<Hash>,
+    /// Commits reachable from the left revision only, newest first
+    pub left: Vec<ComparedCommit>,
+    /// Commits reachable from the right revision only, newest first
+    pub right: Vec<ComparedCommit>,
+}
+
+impl BranchComparison {
+    /// Number of commits only on the left side ("behind" when left is the upstream)
+    pub fn left_count(&self) -> usize {
+        self.left.len()
+    }
+
+    /// Number of commits only on the right side ("ahead" when left is the upstream)
+    pub fn right_count(&self) -> usize {
+        self.right.len()
+    }
+
+    /// Left-only commits without a patch-equivalent commit on the right
+    pub fn left_unique(&self) -> impl Iterator<Item = &Commit> {
+        self.left
+            .iter()
+            .filter(|c| !c.equivalent)
+            .map(|c| &c.commit)
+    }
+
+    /// Right-only commits without a patch-equivalent commit on the left
+    pub fn right_unique(&self) -> impl Iterator<Item = &Commit> {
+        self.right
+            .iter()
+            .filter(|c| !c.equivalent)
+            .map(|c| &c.commit)
+    }
+
+    /// Whether the left revision can be fast-forwarded to the right one
+    pub fn can_fast_forward(&self) -> bool {
+        self.left.is_empty() && self.merge_base.is_some()
+    }
+
+    /// Whether both sides point at the same history
+    pub fn is_identical(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+}
+
+/// One revision of a file, as returned by [`Repository::file_history`]
+#[derive(Debug, Clone)]
+pub struct FileRevision {
+    pub commit: Commit,
+    /// The change to the file in this commit; `old_path` is set when it was renamed
+    pub diff: FileDiff,
+    /// The unified diff text for the file in this commit
+    pub patch: String,
+    /// The file's contents after this commit, loaded by
+    /// [`Repository::file_history_with_contents`]; `None` otherwise and for deletions
+    pub content: Option<Vec<u8>>,
+}
+
+impl FileRevision {
+    /// The path the file had after this commit
+    pub fn path(&self) -> &Path {
+        &self.diff.path
+    }
+
+    /// The path the file had before this commit, if the commit renamed or copied it
+    pub fn previous_path(&self) -> Option<&Path> {
+        self.diff.old_path.as_deref()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -298,6 +691,8 @@ pub struct CommitDetails {
     pub files_changed: Vec<PathBuf>,
     pub insertions: usize,
     pub deletions: usize,
+    /// Per-file insertions and deletions, in the same order as `files_changed`
+    pub file_stats: Vec<FileStat>,
 }
 
 impl CommitDetails {
@@ -312,6 +707,33 @@ impl CommitDetails {
     }
 }
 
+/// A commit with its per-file line counts
+///
+/// Returned by [`Repository::log_with_stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitWithStats {
+    pub commit: Commit,
+    /// Per-file insertions and deletions; merges are counted against their first parent
+    pub files: Vec<FileStat>,
+}
+
+impl CommitWithStats {
+    /// Get the lines added across all files
+    pub fn insertions(&self) -> usize {
+        self.files.iter().map(|stat| stat.insertions).sum()
+    }
+
+    /// Get the lines removed across all files
+    pub fn deletions(&self) -> usize {
+        self.files.iter().map(|stat| stat.deletions).sum()
+    }
+
+    /// Get total changes (insertions + deletions)
+    pub fn total_changes(&self) -> usize {
+        self.insertions() + self.deletions()
+    }
+}
+
 impl fmt::Display for CommitDetails {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "{}", self.commit)?;
@@ -319,10 +741,24 @@ impl fmt::Display for CommitDetails {
         writeln!(f, "Insertions: +{}", self.insertions)?;
         writeln!(f, "Deletions: -{}", self.deletions)?;
 
-        if !self.files_changed.is_empty() {
+        if !self.file_stats.is_empty() {
             writeln!(f, "\nFiles:")?;
-            for file in &self.files_changed {
-                writeln!(f, "  {}", file.display())?;
+            for stat in &self.file_stats {
+                write!(f, "  ")?;
+                if let Some(old_path) = &stat.old_path {
+                    write!(f, "{} => ", old_path.display())?;
+                }
+                if stat.binary {
+                    writeln!(f, "{} (binary)", stat.path.display())?;
+                } else {
+                    writeln!(
+                        f,
+                        "{} (+{} -{})",
+                        stat.path.display(),
+                        stat.insertions,
+                        stat.deletions
+                    )?;
+                }
             }
         }
 
@@ -331,63 +767,125 @@ impl fmt::Display for CommitDetails {
 }
 
 /// Parse git log output with our custom format
-fn parse_log_output(output: &str) -> Result<Vec<Commit>> {
+pub(crate) fn parse_log_output(output: &str) -> Result<Vec<Commit>> {
     let mut commits = Vec::new();
 
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+    for record in output.split('\0') {
+        if let Some(commit) = parse_log_record(record)? {
+            commits.push(commit);
         }
+    }
 
-        // Parse format: hash|author_name|author_email|author_timestamp|committer_name|committer_email|committer_timestamp|parent_hashes|subject|body
-        let parts: Vec<&str> = line.splitn(10, '|').collect();
-        if parts.len() < 9 {
-            continue; // Skip malformed lines
-        }
+    Ok(commits)
+}
 
-        let hash = Hash::from(parts[0].to_string());
-        let author_name = parts[1].to_string();
-        let author_email = parts[2].to_string();
-        let author_timestamp = parse_timestamp(parts[3])?;
-        let committer_name = parts[4].to_string();
-        let committer_email = parts[5].to_string();
-        let committer_timestamp = parse_timestamp(parts[6])?;
-        let parent_hashes = parse_parent_hashes(parts[7]);
-        let subject = parts[8].to_string();
-        let body = if parts.len() > 9 && !parts[9].is_empty() {
-            Some(parts[9].to_string())
-        } else {
-            None
-        };
+/// Parse a single commit record in our custom format
+///
+/// The record may still carry the newline git puts between records and its NUL
+/// terminator. Returns `Ok(None)` for blank or malformed records so callers can skip
+/// them.
+pub(crate) fn parse_log_record(record: &str) -> Result<Option<Commit>> {
+    let record = record.trim_start().trim_end_matches('\0');
+    if record.trim().is_empty() {
+        return Ok(None);
+    }
 
-        let author = Author {
-            name: author_name,
-            email: author_email,
-            timestamp: author_timestamp,
-        };
+    let (signature, record) = split_signature(record);
 
-        let committer = Author {
-            name: committer_name,
-            email: committer_email,
-            timestamp: committer_timestamp,
-        };
+    // The body comes last, so nothing it contains can shift the other fields
+    let parts: Vec<&str> = record.splitn(11, LOG_FIELD_SEPARATOR).collect();
+    if parts.len() < 11 {
+        return Ok(None); // Skip malformed records
+    }
 
-        let message = CommitMessage::new(subject, body);
+    let hash = Hash::from(parts[0].to_string());
+    let author_name = parts[1].to_string();
+    let author_email = parts[2].to_string();
+    let author_timestamp = parse_timestamp(parts[3])?;
+    let committer_name = parts[4].to_string();
+    let committer_email = parts[5].to_string();
+    let committer_timestamp = parse_timestamp(parts[6])?;
+    let parent_hashes = parse_parent_hashes(parts[7]);
+    let refs = parse_decorations(parts[8]);
+    let subject = parts[9].to_string();
+    let body = if !parts[10].trim().is_empty() {
+        Some(parts[10].trim_end().to_string())
+    } else {
+        None
+    };
+
+    let author = Author {
+        name: author_name,
+        email: author_email,
+        timestamp: author_timestamp,
+    };
+
+    let committer = Author {
+        name: committer_name,
+        email: committer_email,
+        timestamp: committer_timestamp,
+    };
+
+    let message = CommitMessage::new(subject, body);
+
+    Ok(Some(Commit {
+        hash,
+        author,
+        committer,
+        message,
+        timestamp: author_timestamp, // Use author timestamp for commit timestamp
+        parents: parent_hashes,
+        signature,
+        refs,
+    }))
+}
 
-        let commit = Commit {
-            hash,
-            author,
-            committer,
-            message,
-            timestamp: author_timestamp, // Use author timestamp for commit timestamp
-            parents: parent_hashes,
-        };
+/// Parse `%D` decorations such as `HEAD -> refs/heads/main, tag: refs/tags/v1.0`
+/// into ref names; `HEAD` is listed before the branch it points at
+fn parse_decorations(decorations: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    for decoration in decorations.split(", ").filter(|d| !d.is_empty()) {
+        if let Some((head, target)) = decoration.split_once(" -> ") {
+            refs.push(head.to_string());
+            refs.push(target.to_string());
+        } else {
+            let name = decoration.strip_prefix("tag: ").unwrap_or(decoration);
+            refs.push(name.to_string());
+        }
+    }
+    refs
+}
 
-        commits.push(commit);
+/// Split off the `SIGNATURE_FORMAT` prefix if the record has one
+///
+/// The prefix starts with the one-character validity, while a record without it
+/// starts with the full commit hash.
+fn split_signature(record: &str) -> (Option<SignatureStatus>, &str) {
+    let first_field = record.split(LOG_FIELD_SEPARATOR).next().unwrap_or_default();
+    if first_field.chars().count() > 1 {
+        return (None, record);
     }
 
-    Ok(commits)
+    let parts: Vec<&str> = record.splitn(4, LOG_FIELD_SEPARATOR).collect();
+    if parts.len() < 4 {
+        return (None, record);
+    }
+
+    let validity = parts[0]
+        .chars()
+        .next()
+        .and_then(SignatureValidity::from_char)
+        .unwrap_or(SignatureValidity::Unsigned);
+    let optional = |value: &str| (!value.is_empty()).then(|| value.to_string());
+
+    (
+        Some(SignatureStatus {
+            validity,
+            signer: optional(parts[1]),
+            key: optional(parts[2]),
+        }),
+        parts[3],
+    )
 }
 
 /// Parse Unix timestamp to DateTime<Utc>
@@ -414,6 +912,190 @@ fn parse_parent_hashes(parents_str: &str) -> Box<[Hash]> {
         .into_boxed_slice()
 }
 
+/// Build the `git log` arguments for the given format and options
+///
+/// `extra_args` are output options such as `--numstat` or `--topo-order`, placed
+/// before the revision and path arguments.
+pub(crate) fn build_log_args(
+    format: &str,
+    extra_args: &[&str],
+    options: &LogOptions,
+) -> Result<Vec<String>> {
+    let format = if options.show_signatures {
+        format.replacen("format:", &format!("format:{}", SIGNATURE_FORMAT), 1)
+    } else {
+        format.to_string()
+    };
+
+    let mut args_vec: Vec<String> = vec![
+        "log".to_string(),
+        format,
+        "--no-show-signature".to_string(),
+        DECORATE_FULL.to_string(),
+    ];
+    args_vec.extend(extra_args.iter().map(|arg| arg.to_string()));
+
+    // rev-list has no rename following, so this is log-only
+    if options.follow_renames {
+        args_vec.push("--follow".to_string());
+    }
+
+    push_pickaxe_args(&mut args_vec, options);
+    push_walk_args(&mut args_vec, options, false)?;
+    Ok(args_vec)
+}
+
+/// Build `git rev-list` arguments, walking from `HEAD` when no refs are selected
+///
+/// rev-list cannot search diff content, so pickaxe searches list hashes with `git log`.
+fn build_rev_list_args(options: &LogOptions) -> Result<Vec<String>> {
+    if options.pickaxe_string.is_some() || options.pickaxe_regex.is_some() {
+        let mut args_vec = vec!["log".to_string(), "--format=%H".to_string()];
+        push_pickaxe_args(&mut args_vec, options);
+        push_walk_args(&mut args_vec, options, false)?;
+        return Ok(args_vec);
+    }
+
+    let mut args_vec = vec!["rev-list".to_string()];
+    push_walk_args(&mut args_vec, options, true)?;
+    Ok(args_vec)
+}
+
+/// Append `-S`/`-G`; the value is attached so it cannot be read as an option
+fn push_pickaxe_args(args_vec: &mut Vec<String>, options: &LogOptions) {
+    if let Some(string) = &options.pickaxe_string {
+        args_vec.push(format!("-S{}", string));
+    }
+    if let Some(regex) = &options.pickaxe_regex {
+        args_vec.push(format!("-G{}", regex));
+    }
+}
+
+/// Append the filtering, revision, and path arguments shared by log and rev-list
+///
+/// Revisions are checked so none can be read as an option, and `--` always follows
+/// them so none can be read as a path.
+fn push_walk_args(
+    args_vec: &mut Vec<String>,
+    options: &LogOptions,
+    default_head: bool,
+) -> Result<()> {
+    // Add options to git command
+    if let Some(count) = options.max_count {
+        args_vec.push("-n".to_string());
+        args_vec.push(count.to_string());
+    }
+
+    if let Some(skip) = options.skip {
+        args_vec.push(format!("--skip={}", skip));
+    }
+
+    if let Some(since) = &options.since {
+        args_vec.push(format!("--since={}", since.to_arg()));
+    }
+
+    if let Some(until) = &options.until {
+        args_vec.push(format!("--until={}", until.to_arg()));
+    }
+
+    if let Some(author) = &options.author {
+        args_vec.push(format!("--author={}", author));
+    }
+
+    if let Some(committer) = &options.committer {
+        args_vec.push(format!("--committer={}", committer));
+    }
+
+    if let Some(grep) = &options.grep {
+        args_vec.push(format!("--grep={}", grep));
+    }
+
+    // Add boolean flags
+    if options.merges_only {
+        args_vec.push("--merges".to_string());
+    }
+
+    if options.no_merges {
+        args_vec.push("--no-merges".to_string());
+    }
+
+    if options.first_parent {
+        args_vec.push("--first-parent".to_string());
+    }
+
+    if options.ancestry_path {
+        args_vec.push("--ancestry-path".to_string());
+    }
+
+    if options.refs.is_empty() && default_head {
+        args_vec.push("HEAD".to_string());
+    }
+    for selector in &options.refs {
+        if let RefSelector::Revision(revision) = selector {
+            validate_arg("revision", revision)?;
+        }
+        args_vec.push(selector.to_arg());
+    }
+
+    // Add path filters at the end
+    args_vec.push("--".to_string());
+    for path in &options.paths {
+        args_vec.push(path.to_string_lossy().to_string());
+    }
+    Ok(())
+}
+
+/// Commits read one NUL-terminated record at a time from a running `git log`
+///
+/// Dropping the iterator drops the [`GitProcess`], which kills and reaps git.
+struct LogIter {
+    /// `None` once git has exited or been stopped
+    process: Option<GitProcess>,
+    reader: BufReader<ChildStdout>,
+    buffer: Vec<u8>,
+}
+
+impl LogIter {
+    /// Wait for git to exit after the last record, reporting a failure
+    fn finish(&mut self) -> Option<Result<Commit>> {
+        self.process.take()?.finish().err().map(Err)
+    }
+
+    /// Stop git early
+    fn stop(&mut self) {
+        self.process = None;
+    }
+}
+
+impl Iterator for LogIter {
+    type Item = Result<Commit>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.process.is_some() {
+            self.buffer.clear();
+            match self.reader.read_until(b'\0', &mut self.buffer) {
+                Ok(0) => return self.finish(),
+                Ok(_) => {}
+                Err(err) => {
+                    self.stop();
+                    return Some(Err(err.into()));
+                }
+            }
+
+            let record = String::from_utf8_lossy(&self.buffer);
+            match parse_log_record(&record) {
+                Ok(Some(commit)) => return Some(Ok(commit)),
+                Ok(None) => {}
+                Err(err) => {
+                    self.stop();
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
 impl Repository {
     /// Get commit history with default options
     pub fn log(&self) -> Result<CommitLog> {
@@ -427,82 +1109,288 @@ impl Repository {
 
     /// Get commit history with custom options
     pub fn log_with_options(&self, options: &LogOptions) -> Result<CommitLog> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
-        // Build all formatted arguments first
-        let mut args_vec: Vec<String> = vec![
-            "log".to_string(),
-            GIT_LOG_FORMAT.to_string(),
-            "--no-show-signature".to_string(),
-        ];
+        let args_vec = build_log_args(GIT_LOG_FORMAT, &[], options)?;
 
-        // Add options to git command
-        if let Some(count) = options.max_count {
-            args_vec.push("-n".to_string());
-            args_vec.push(count.to_string());
-        }
+        // Convert to &str slice for git function
+        let all_args: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
 
-        if let Some(since) = &options.since {
-            args_vec.push(format!("--since={}", since.format(DATE_FORMAT)));
-        }
+        let stdout = git(&all_args, Some(self))?;
+        let commits = parse_log_output(&stdout)?;
+        Ok(CommitLog::new(commits))
+    }
 
-        if let Some(until) = &options.until {
-            args_vec.push(format!("--until={}", until.format(DATE_FORMAT)));
-        }
+    /// Get commit history with per-file line counts
+    ///
+    /// Loads each commit's `--numstat` in the same `git log` run, instead of one
+    /// [`show_commit`](Self::show_commit) per commit. Renames are detected (`-M`), and
+    /// merge commits are counted against their first parent.
+    ///
+    /// This takes the same [`LogOptions`] as [`log_with_options`](Self::log_with_options)
+    /// rather than a `LogOptions::with_stats()` flag: those options also drive APIs that
+    /// return plain [`Commit`]s, which a flag could not change.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The log options selecting the commits
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the commits with their file stats, newest first, or a
+    /// `GitError`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{LogOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for entry in repo.log_with_stats(&LogOptions::new().max_count(20))? {
+    ///     println!(
+    ///         "{} +{} -{} in {} files",
+    ///         entry.commit.hash.short(),
+    ///         entry.insertions(),
+    ///         entry.deletions(),
+    ///         entry.files.len()
+    ///     );
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn log_with_stats(&self, options: &LogOptions) -> Result<Vec<CommitWithStats>> {
+        self.check_git()?;
+
+        let stats_args = ["--numstat", "-z", "-M", "--diff-merges=first-parent"];
+        let args_vec = build_log_args(FILE_HISTORY_FORMAT, &stats_args, options)?;
+        let all_args: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
 
-        if let Some(author) = &options.author {
-            args_vec.push(format!("--author={}", author));
+        let output = git(&all_args, Some(self))?;
+        let mut entries = parse_stats_log(&output)?;
+        for stat in entries.iter_mut().flat_map(|entry| entry.files.iter_mut()) {
+            stat.path = self.styled_path(self.repo_path(), std::mem::take(&mut stat.path));
+            stat.old_path = stat
+                .old_path
+                .take()
+                .map(|old_path| self.styled_path(self.repo_path(), old_path));
         }
+        Ok(entries)
+    }
 
-        if let Some(committer) = &options.committer {
-            args_vec.push(format!("--committer={}", committer));
-        }
+    /// Get one page of commit history
+    ///
+    /// Fetches one commit more than `size` to tell whether another page follows, so
+    /// history views can load more on demand without counting the whole history.
+    /// `skip` and `max_count` in `options` are replaced by `offset` and `size`.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The log options to use
+    /// * `offset` - Number of matching commits to skip
+    /// * `size` - Maximum number of commits on the page
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{LogOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let mut page = repo.log_page(&LogOptions::new(), 0, 50)?;
+    /// while page.has_more {
+    ///     page = repo.log_page(&LogOptions::new(), page.next_offset(), 50)?;
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn log_page(&self, options: &LogOptions, offset: usize, size: usize) -> Result<Page> {
+        let options = options.clone().skip(offset).max_count(size + 1);
+        let log = self.log_with_options(&options)?;
+
+        let mut commits = log.all().to_vec();
+        let has_more = commits.len() > size;
+        commits.truncate(size);
+
+        Ok(Page {
+            commits: CommitLog::new(commits),
+            has_more,
+            offset,
+        })
+    }
 
-        if let Some(grep) = &options.grep {
-            args_vec.push(format!("--grep={}", grep));
-        }
+    /// Stream commit history through a channel as it is parsed
+    ///
+    /// Spawns `git log` and a background thread that sends each `Commit` into the
+    /// returned channel as soon as it is read, so GUI history views can render the
+    /// first page while the rest of the history is still loading.
+    ///
+    /// The join handle resolves to `Ok(())` once all commits have been sent, or to a
+    /// `GitError` if git fails. Dropping the receiver stops the walk early.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The log options to use
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the worker `JoinHandle` and the commit `Receiver`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{LogOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let (handle, commits) = repo.log_stream(&LogOptions::new())?;
+    ///
+    /// for commit in commits.iter().take(50) {
+    ///     println!("{}", commit);
+    /// }
+    ///
+    /// handle.join().expect("log worker panicked")?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn log_stream(
+        &self,
+        options: &LogOptions,
+    ) -> Result<(JoinHandle<Result<()>>, Receiver<Commit>)> {
+        self.check_git()?;
+
+        let args_vec = build_log_args(GIT_LOG_FORMAT, &[], options)?;
+        let all_args: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
 
-        // Add boolean flags
-        if options.follow_renames {
-            args_vec.push("--follow".to_string());
-        }
+        let (process, stdout) = git_spawn_stdout(&all_args, Some(self))?;
 
-        if options.merges_only {
-            args_vec.push("--merges".to_string());
-        }
+        let (sender, receiver) = mpsc::channel();
 
-        if options.no_merges {
-            args_vec.push("--no-merges".to_string());
-        }
+        // Returning early, on an error or a dropped receiver, drops `process`, which
+        // stops git
+        let handle = thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut buffer = Vec::new();
+
+            loop {
+                buffer.clear();
+                if reader.read_until(b'\0', &mut buffer)? == 0 {
+                    break;
+                }
+
+                let record = String::from_utf8_lossy(&buffer);
 
-        // Add path filters at the end
-        if !options.paths.is_empty() {
-            args_vec.push("--".to_string());
-            for path in &options.paths {
-                args_vec.push(path.to_string_lossy().to_string());
+                if let Some(commit) = parse_log_record(&record)?
+                    && sender.send(commit).is_err()
+                {
+                    // Receiver dropped, stop walking history
+                    return Ok(());
+                }
             }
-        }
 
-        // Convert to &str slice for git function
+            process.finish()
+        });
+
+        Ok((handle, receiver))
+    }
+
+    /// Iterate over commit history as `git log` produces it
+    ///
+    /// Unlike [`log_with_options`](Self::log_with_options), commits are parsed one at a
+    /// time from git's output, so memory use stays flat however long the history is.
+    /// Unlike [`log_stream`](Self::log_stream), no thread is involved: git only runs
+    /// ahead of the caller by its pipe buffer. Dropping the iterator stops git.
+    ///
+    /// A failing git command, such as an unknown revision, is reported as the last
+    /// item.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{LogOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let mut merges = 0;
+    /// for commit in repo.log_iter(&LogOptions::new())? {
+    ///     if commit?.is_merge() {
+    ///         merges += 1;
+    ///     }
+    /// }
+    /// println!("{} merges", merges);
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn log_iter(
+        &self,
+        options: &LogOptions,
+    ) -> Result<impl Iterator<Item = Result<Commit>> + use<>> {
+        self.check_git()?;
+
+        let args_vec = build_log_args(GIT_LOG_FORMAT, &[], options)?;
         let all_args: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
 
-        let stdout = git(&all_args, Some(self.repo_path()))?;
-        let commits = parse_log_output(&stdout)?;
-        Ok(CommitLog::new(commits))
+        let (process, stdout) = git_spawn_stdout(&all_args, Some(self))?;
+
+        Ok(LogIter {
+            process: Some(process),
+            reader: BufReader::new(stdout),
+            buffer: Vec::new(),
+        })
     }
 
     /// Get commits in a range between two commits
     pub fn log_range(&self, from: &Hash, to: &Hash) -> Result<CommitLog> {
-        Self::ensure_git()?;
+        self.check_git()?;
+        validate_arg("commit", from.as_str())?;
+        validate_arg("commit", to.as_str())?;
 
         let range = format!("{}..{}", from.as_str(), to.as_str());
-        let args = vec!["log", GIT_LOG_FORMAT, "--no-show-signature", &range];
+        let args = vec![
+            "log",
+            GIT_LOG_FORMAT,
+            "--no-show-signature",
+            DECORATE_FULL,
+            &range,
+            "--",
+        ];
 
-        let stdout = git(&args, Some(self.repo_path()))?;
+        let stdout = git(&args, Some(self))?;
         let commits = parse_log_output(&stdout)?;
         Ok(CommitLog::new(commits))
     }
 
+    /// List commit hashes with the same filters as [`log_with_options`](Self::log_with_options)
+    ///
+    /// Runs `git rev-list`, which skips formatting and is much cheaper when only the
+    /// hashes (or their count) are needed. `follow_renames` and `show_signatures` are
+    /// ignored. Pickaxe searches fall back to `git log`, as rev-list cannot run them.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The log options to use
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the matching hashes, newest first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{LogOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let only_feature = repo.rev_list(&LogOptions::new().range("main", "feature"))?;
+    /// println!("feature is {} commits ahead", only_feature.len());
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn rev_list(&self, options: &LogOptions) -> Result<Vec<Hash>> {
+        self.check_git()?;
+
+        let args_vec = build_rev_list_args(options)?;
+        let all_args: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
+
+        let stdout = git(&all_args, Some(self))?;
+        Ok(stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(Hash::from)
+            .collect())
+    }
+
     /// Get commits that affected specific paths
     pub fn log_for_paths(&self, paths: &[impl AsRef<std::path::Path>]) -> Result<CommitLog> {
         let path_bufs: Vec<PathBuf> = paths.iter().map(|p| p.as_ref().to_path_buf()).collect();
@@ -510,21 +1398,271 @@ impl Repository {
         self.log_with_options(&options)
     }
 
-    /// Get detailed information about a specific commit
-    pub fn show_commit(&self, hash: &Hash) -> Result<CommitDetails> {
-        Self::ensure_git()?;
+    /// Compare two revisions, listing the commits unique to each side
+    ///
+    /// Equivalent to `git log --left-right --cherry-mark <left>...<right>`, with each
+    /// side loaded separately so patch-equivalent commits keep their side.
+    ///
+    /// # Arguments
+    ///
+    /// * `left` - The base revision, e.g. `main` or `origin/main`
+    /// * `right` - The revision to compare against it, e.g. `feature`
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `BranchComparison` or a `GitError` if either revision is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let comparison = repo.compare("main", "feature")?;
+    /// println!(
+    ///     "feature is {} ahead, {} behind main",
+    ///     comparison.right_count(),
+    ///     comparison.left_count()
+    /// );
+    /// if comparison.can_fast_forward() {
+    ///     println!("main can be fast-forwarded to feature");
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn compare(&self, left: &str, right: &str) -> Result<BranchComparison> {
+        self.check_git()?;
+        validate_arg("revision", left)?;
+        validate_arg("revision", right)?;
+
+        let range = format!("{}...{}", left, right);
+        let left_commits = self.compared_commits(&range, "--left-only")?;
+        let right_commits = self.compared_commits(&range, "--right-only")?;
+
+        let merge_base = self.merge_base(left, right)?;
+
+        Ok(BranchComparison {
+            merge_base,
+            left: left_commits,
+            right: right_commits,
+        })
+    }
 
-        // Get commit info
-        let commit_args = vec![
+    /// Load one side of a symmetric difference, marking patch-equivalent commits
+    fn compared_commits(&self, range: &str, side: &str) -> Result<Vec<ComparedCommit>> {
+        // %m is '=' for patch-equivalent commits and '<' or '>' otherwise
+        let format = GIT_LOG_FORMAT.replacen("format:", "format:%m", 1);
+        let args = [
             "log",
-            GIT_LOG_FORMAT,
+            &format,
             "--no-show-signature",
-            "-n",
+            DECORATE_FULL,
+            "--cherry-mark",
+            side,
+            range,
+            "--",
+        ];
+        let output = git(&args, Some(self))?;
+
+        let mut commits = Vec::new();
+        for record in output.split('\0') {
+            let record = record.trim_start();
+            let Some(mark) = record.chars().next() else {
+                continue;
+            };
+            if let Some(commit) = parse_log_record(&record[mark.len_utf8()..])? {
+                commits.push(ComparedCommit {
+                    commit,
+                    equivalent: mark == '=',
+                });
+            }
+        }
+        Ok(commits)
+    }
+
+    /// Get the most recent commit that touched a path
+    ///
+    /// Equivalent to `git log -1 -- <path>`. Works for files and directories, e.g. for
+    /// showing "last modified" next to each entry in a file tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to look up, relative to the repository root
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the commit, or `None` if the path was never committed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// if let Some(commit) = repo.last_commit_for("README.md")? {
+    ///     println!("README.md last changed in {}", commit.hash.short());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn last_commit_for<P: AsRef<Path>>(&self, path: P) -> Result<Option<Commit>> {
+        self.check_git()?;
+
+        let path = path.as_ref().to_string_lossy();
+        let args = [
+            "log",
+            GIT_LOG_FORMAT,
+            "--no-show-signature",
+            DECORATE_FULL,
+            "-n",
+            "1",
+            "--",
+            &path,
+        ];
+        let output = git(&args, Some(self))?;
+        parse_log_record(&output)
+    }
+
+    /// Get the history of a single file, following renames
+    ///
+    /// Equivalent to `git log --follow -p -- <path>`. Merge commits are diffed against
+    /// their first parent. Entries are newest first, and each carries the `FileDiff`
+    /// for the file in that commit, including its previous name across renames.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file's current path, relative to the repository root
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the file's revisions, empty if the path was never committed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for revision in repo.file_history("src/lib.rs")? {
+    ///     let renamed = revision
+    ///         .diff
+    ///         .old_path
+    ///         .as_ref()
+    ///         .map(|old| format!(" (from {})", old.display()))
+    ///         .unwrap_or_default();
+    ///     println!(
+    ///         "{} {} +{} -{}{}",
+    ///         revision.commit.hash.short(),
+    ///         revision.commit.message.subject,
+    ///         revision.diff.additions,
+    ///         revision.diff.deletions,
+    ///         renamed
+    ///     );
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn file_history<P: AsRef<Path>>(&self, path: P) -> Result<Vec<FileRevision>> {
+        self.load_file_history(path.as_ref(), false)
+    }
+
+    /// Get the history of a single file with its contents at each revision
+    ///
+    /// Like [`file_history`](Self::file_history), and additionally fills
+    /// [`FileRevision::content`] for every revision that did not delete the file. All
+    /// contents are read with one `git cat-file --batch`, from the path the file had
+    /// in that commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file's current path, relative to the repository root
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the file's revisions, empty if the path was never committed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for revision in repo.file_history_with_contents("README.md")? {
+    ///     let size = revision.content.as_ref().map_or(0, Vec::len);
+    ///     println!(
+    ///         "{} {}: {} bytes",
+    ///         revision.commit.hash.short(),
+    ///         revision.path().display(),
+    ///         size
+    ///     );
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn file_history_with_contents<P: AsRef<Path>>(&self, path: P) -> Result<Vec<FileRevision>> {
+        self.load_file_history(path.as_ref(), true)
+    }
+
+    fn load_file_history(&self, path: &Path, contents: bool) -> Result<Vec<FileRevision>> {
+        self.check_git()?;
+
+        let path = path.to_string_lossy();
+        let args = [
+            "log",
+            FILE_HISTORY_FORMAT,
+            "--no-show-signature",
+            DECORATE_FULL,
+            "--follow",
+            "-p",
+            "--diff-merges=first-parent",
+            "--",
+            &path,
+        ];
+        let output = git(&args, Some(self))?;
+        let mut revisions = parse_file_history(&output)?;
+
+        if contents {
+            // Paths are still repo-relative here, as `<commit>:<path>` needs
+            let present: Vec<usize> = (0..revisions.len())
+                .filter(|&i| revisions[i].diff.status != crate::DiffStatus::Deleted)
+                .collect();
+            let specs: Vec<String> = present
+                .iter()
+                .map(|&i| {
+                    let revision = &revisions[i];
+                    format!("{}:{}", revision.commit.hash, revision.diff.path.display())
+                })
+                .collect();
+            for (i, content) in present.into_iter().zip(self.read_objects(&specs)?) {
+                revisions[i].content = content;
+            }
+        }
+
+        for revision in &mut revisions {
+            let diff = &mut revision.diff;
+            diff.path = self.styled_path(self.repo_path(), std::mem::take(&mut diff.path));
+            diff.old_path = diff
+                .old_path
+                .take()
+                .map(|old_path| self.styled_path(self.repo_path(), old_path));
+        }
+        Ok(revisions)
+    }
+
+    /// Get detailed information about a specific commit
+    pub fn show_commit(&self, hash: &Hash) -> Result<CommitDetails> {
+        self.check_git()?;
+        validate_arg("commit", hash.as_str())?;
+
+        // Get commit info
+        let commit_args = vec![
+            "log",
+            GIT_LOG_FORMAT,
+            "--no-show-signature",
+            DECORATE_FULL,
+            "-n",
             "1",
             hash.as_str(),
+            "--",
         ];
 
-        let commit_output = git(&commit_args, Some(self.repo_path()))?;
+        let commit_output = git(&commit_args, Some(self))?;
         let mut commits = parse_log_output(&commit_output)?;
 
         if commits.is_empty() {
@@ -536,82 +1674,95 @@ impl Repository {
 
         let commit = commits.remove(0);
 
-        // Get diff stats
-        let stats_args = vec!["show", "--stat", "--format=", hash.as_str()];
+        // Exact per-file counts; -M keeps renames as one entry whatever diff.renames says
+        let stats_args = vec![
+            "show",
+            "--numstat",
+            "-z",
+            "-M",
+            "--format=",
+            hash.as_str(),
+            "--",
+        ];
 
-        let stats_output = git(&stats_args, Some(self.repo_path()))?;
-        let (files_changed, insertions, deletions) = parse_diff_stats(&stats_output);
+        let stats_output = git(&stats_args, Some(self))?;
+        let file_stats: Vec<FileStat> = parse_numstat(&stats_output)
+            .into_iter()
+            .map(|stat| FileStat {
+                path: self.styled_path(self.repo_path(), stat.path),
+                old_path: stat
+                    .old_path
+                    .map(|old_path| self.styled_path(self.repo_path(), old_path)),
+                ..stat
+            })
+            .collect();
+        let files_changed = file_stats.iter().map(|stat| stat.path.clone()).collect();
+        let insertions = file_stats.iter().map(|stat| stat.insertions).sum();
+        let deletions = file_stats.iter().map(|stat| stat.deletions).sum();
 
         Ok(CommitDetails {
             commit,
             files_changed,
             insertions,
             deletions,
+            file_stats,
         })
     }
 }
 
-/// Parse diff stats from git show --stat output
-fn parse_diff_stats(output: &str) -> (Vec<PathBuf>, usize, usize) {
-    let mut files_changed = Vec::new();
-    let mut total_insertions = 0;
-    let mut total_deletions = 0;
+/// Parse `FILE_HISTORY_FORMAT` output into per-commit file revisions
+fn parse_file_history(output: &str) -> Result<Vec<FileRevision>> {
+    let mut revisions = Vec::new();
 
-    for line in output.lines() {
-        let line = line.trim();
-        if line.is_empty() {
+    for record in output.split('\x1e') {
+        let Some((header, patch)) = record.split_once('\x1d') else {
             continue;
-        }
+        };
+        let Some(commit) = parse_log_record(header)? else {
+            continue;
+        };
 
-        // Parse lines like: "src/main.rs | 15 +++++++++------"
-        if let Some(pipe_pos) = line.find(" | ") {
-            let filename = line[..pipe_pos].trim();
-            files_changed.push(PathBuf::from(filename));
-
-            // Parse insertions/deletions from the rest of the line
-            let stats_part = &line[pipe_pos + 3..];
-            if let Some(space_pos) = stats_part.find(' ')
-                && let Ok(changes) = stats_part[..space_pos].parse::<usize>()
-            {
-                let symbols = &stats_part[space_pos + 1..];
-                let plus_count = symbols.chars().filter(|&c| c == '+').count();
-                let minus_count = symbols.chars().filter(|&c| c == '-').count();
-
-                // Distribute changes based on +/- ratio
-                let total_symbols = plus_count + minus_count;
-                if total_symbols > 0 {
-                    let insertions = (changes * plus_count) / total_symbols;
-                    let deletions = changes - insertions;
-                    total_insertions += insertions;
-                    total_deletions += deletions;
-                }
-            }
-        }
-        // Parse summary line like: "2 files changed, 15 insertions(+), 8 deletions(-)"
-        else if line.contains("files changed") || line.contains("file changed") {
-            if let Some(insertions_pos) = line.find(" insertions(+)")
-                && let Some(start) = line[..insertions_pos].rfind(' ')
-                && let Ok(insertions) = line[start + 1..insertions_pos].parse::<usize>()
-            {
-                total_insertions = insertions;
-            }
-            if let Some(deletions_pos) = line.find(" deletions(-)")
-                && let Some(start) = line[..deletions_pos].rfind(' ')
-                && let Ok(deletions) = line[start + 1..deletions_pos].parse::<usize>()
-            {
-                total_deletions = deletions;
-            }
-        }
+        // --follow limits the patch to the followed file
+        let patch = patch.trim_start_matches('\n');
+        let Some(diff) = parse_diff_output(patch)?.files.first().cloned() else {
+            continue;
+        };
+
+        revisions.push(FileRevision {
+            commit,
+            diff,
+            patch: patch.to_string(),
+            content: None,
+        });
     }
 
-    (files_changed, total_insertions, total_deletions)
+    Ok(revisions)
+}
+
+/// Parse `FILE_HISTORY_FORMAT` records followed by `--numstat -z` entries
+fn parse_stats_log(output: &str) -> Result<Vec<CommitWithStats>> {
+    let mut entries = Vec::new();
+
+    for record in output.split('\x1e') {
+        let Some((header, numstat)) = record.split_once('\x1d') else {
+            continue;
+        };
+        let Some(commit) = parse_log_record(header)? else {
+            continue;
+        };
+
+        let files = parse_numstat(numstat.trim_start_matches(['\0', '\n']));
+        entries.push(CommitWithStats { commit, files });
+    }
+
+    Ok(entries)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::DiffStatus;
     use std::fs;
-    use std::path::Path;
 
     #[test]
     fn test_author_display() {
@@ -661,6 +1812,8 @@ mod tests {
                 Hash::from("parent2".to_string()),
             ]
             .into_boxed_slice(),
+            signature: None,
+            refs: Vec::new(),
         };
 
         assert!(commit.is_merge());
@@ -736,15 +1889,213 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_diff_stats() {
-        let output = "src/main.rs | 15 +++++++++------\nREADME.md | 3 +++\n 2 files changed, 18 insertions(+), 6 deletions(-)";
-        let (files, insertions, deletions) = parse_diff_stats(output);
+    fn test_log_page() {
+        let repo = crate::testing::TempRepo::new("log_page").unwrap();
+        let hashes = repo.commit_many(5).unwrap();
+
+        let options = LogOptions::new().skip(1).max_count(2);
+        let args = build_log_args(GIT_LOG_FORMAT, &[], &options).unwrap();
+        assert!(args.contains(&"--skip=1".to_string()));
+        let log = repo.log_with_options(&options).unwrap();
+        let skipped: Vec<&Hash> = log.iter().map(|commit| &commit.hash).collect();
+        assert_eq!(skipped, vec![&hashes[3], &hashes[2]]);
+
+        let first = repo.log_page(&LogOptions::new(), 0, 2).unwrap();
+        assert_eq!(first.commits.len(), 2);
+        assert!(first.has_more);
+        assert_eq!(first.next_offset(), 2);
+
+        let second = repo.log_page(&LogOptions::new(), 2, 2).unwrap();
+        assert!(second.has_more);
+        let last = repo
+            .log_page(&LogOptions::new(), second.next_offset(), 2)
+            .unwrap();
+        assert_eq!(last.commits.len(), 1);
+        assert!(!last.has_more);
+        assert_eq!(last.commits.iter().next().unwrap().hash, hashes[0]);
+
+        let exact = repo.log_page(&LogOptions::new(), 0, 5).unwrap();
+        assert!(!exact.has_more);
+    }
+
+    #[test]
+    fn test_log_options_dates() {
+        let exact = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let options = LogOptions::new().since(exact).until("yesterday");
+
+        assert_eq!(options.since, Some(LogDate::Exact(exact)));
+        assert_eq!(
+            options.until,
+            Some(LogDate::Approx("yesterday".to_string()))
+        );
 
-        assert_eq!(files.len(), 2);
-        assert_eq!(files[0], PathBuf::from("src/main.rs"));
-        assert_eq!(files[1], PathBuf::from("README.md"));
-        assert_eq!(insertions, 18);
-        assert_eq!(deletions, 6);
+        let args = build_log_args(GIT_LOG_FORMAT, &[], &options).unwrap();
+        assert!(args.contains(&"--since=2023-11-14 22:13:20".to_string()));
+        assert!(args.contains(&"--until=yesterday".to_string()));
+    }
+
+    #[test]
+    fn test_repository_log_ref_selectors() {
+        let test_path = "/tmp/test_log_ref_selectors_repo";
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(format!("{}/base.txt", test_path), "base").unwrap();
+        repo.add(&["base.txt"]).unwrap();
+        repo.commit("Base commit").unwrap();
+        let main = repo.current_branch().unwrap().unwrap();
+
+        repo.checkout_new("feature/login", None).unwrap();
+        fs::write(format!("{}/login.txt", test_path), "login").unwrap();
+        repo.add(&["login.txt"]).unwrap();
+        repo.commit("Feature commit").unwrap();
+        repo.create_tag("v1.0-rc", None).unwrap();
+        repo.checkout(&main).unwrap();
+
+        let subjects = |options: LogOptions| -> Vec<String> {
+            repo.log_with_options(&options)
+                .unwrap()
+                .iter()
+                .map(|c| c.message.subject.clone())
+                .collect()
+        };
+
+        assert_eq!(subjects(LogOptions::new()), vec!["Base commit"]);
+        assert_eq!(subjects(LogOptions::new().all()).len(), 2);
+        assert_eq!(
+            subjects(LogOptions::new().branches("feature/*"))[0],
+            "Feature commit"
+        );
+        assert_eq!(subjects(LogOptions::new().tags("v1.*")).len(), 2);
+        assert!(subjects(LogOptions::new().tags("v2.*")).is_empty());
+        assert_eq!(
+            subjects(LogOptions::new().exclude("feature/*").branches("")),
+            vec!["Base commit"]
+        );
+
+        let args = build_log_args(
+            GIT_LOG_FORMAT,
+            &[],
+            &LogOptions::new()
+                .exclude("refs/heads/wip/*")
+                .all()
+                .paths(vec![PathBuf::from("a.txt")]),
+        )
+        .unwrap();
+        let tail: Vec<&str> = args
+            .iter()
+            .rev()
+            .take(4)
+            .rev()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(tail, ["--exclude=refs/heads/wip/*", "--all", "--", "a.txt"]);
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_repository_log_ranges() {
+        let test_path = "/tmp/test_log_ranges_repo";
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        // base -- m1 (main)
+        //     \-- f1 -- f2 (feature)
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        let commit_file = |name: &str, message: &str| {
+            fs::write(format!("{}/{}", test_path, name), message).unwrap();
+            repo.add(&[name]).unwrap();
+            repo.commit(message).unwrap()
+        };
+        let base = commit_file("base.txt", "Base");
+        let main = repo.current_branch().unwrap().unwrap();
+        repo.checkout_new("feature", None).unwrap();
+        let f1 = commit_file("f1.txt", "Feature one");
+        let f2 = commit_file("f2.txt", "Feature two");
+        repo.checkout(&main).unwrap();
+        let m1 = commit_file("m1.txt", "Main one");
+
+        let hashes = |options: LogOptions| -> Vec<Hash> {
+            repo.log_with_options(&options)
+                .unwrap()
+                .iter()
+                .map(|c| c.hash.clone())
+                .collect()
+        };
+
+        let range = LogOptions::new().range(&main.name, "feature");
+        assert_eq!(hashes(range.clone()), vec![f2.clone(), f1.clone()]);
+        assert_eq!(repo.rev_list(&range).unwrap(), vec![f2.clone(), f1.clone()]);
+
+        let symmetric = hashes(LogOptions::new().symmetric_difference(&main.name, "feature"));
+        assert_eq!(symmetric.len(), 3);
+        assert!(symmetric.contains(&m1) && !symmetric.contains(&base));
+
+        // m1 is reachable from main but does not descend from f1
+        let beyond_f1 = LogOptions::new().range(f1.as_str(), &main.name);
+        assert_eq!(repo.rev_list(&beyond_f1).unwrap(), vec![m1.clone()]);
+        assert!(
+            repo.rev_list(&beyond_f1.ancestry_path())
+                .unwrap()
+                .is_empty()
+        );
+
+        // With no refs selected, rev-list walks HEAD like log does
+        assert_eq!(repo.rev_list(&LogOptions::new()).unwrap(), vec![m1, base]);
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_repository_log_approxidate() {
+        let test_path = "/tmp/test_log_approxidate_repo";
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        // Back-date the first commit through a fixed hermetic commit date
+        let three_weeks_ago = Utc::now() - chrono::Duration::weeks(3);
+        let old_repo = Repository::init(test_path, false)
+            .unwrap()
+            .with_hermetic(crate::HermeticOptions::new().with_date(three_weeks_ago));
+        fs::write(format!("{}/old.txt", test_path), "old").unwrap();
+        old_repo.add(&["old.txt"]).unwrap();
+        old_repo.commit("Old commit").unwrap();
+
+        let repo = Repository::open(test_path).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(format!("{}/new.txt", test_path), "new").unwrap();
+        repo.add(&["new.txt"]).unwrap();
+        repo.commit("New commit").unwrap();
+
+        let recent = repo
+            .log_with_options(&LogOptions::new().since("1 week ago"))
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent.first().unwrap().message.subject, "New commit");
+
+        let older = repo
+            .log_with_options(&LogOptions::new().until("2 weeks ago"))
+            .unwrap();
+        assert_eq!(older.len(), 1);
+        assert_eq!(older.first().unwrap().message.subject, "Old commit");
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
     }
 
     #[test]
@@ -761,6 +2112,22 @@ mod tests {
             files_changed: vec![PathBuf::from("src/main.rs"), PathBuf::from("README.md")],
             insertions: 15,
             deletions: 8,
+            file_stats: vec![
+                FileStat {
+                    path: PathBuf::from("src/main.rs"),
+                    old_path: Some(PathBuf::from("main.rs")),
+                    insertions: 15,
+                    deletions: 8,
+                    binary: false,
+                },
+                FileStat {
+                    path: PathBuf::from("README.md"),
+                    old_path: None,
+                    insertions: 0,
+                    deletions: 0,
+                    binary: true,
+                },
+            ],
         };
 
         assert_eq!(details.total_changes(), 23);
@@ -770,6 +2137,8 @@ mod tests {
         assert!(display_output.contains("Files changed: 2"));
         assert!(display_output.contains("Insertions: +15"));
         assert!(display_output.contains("Deletions: -8"));
+        assert!(display_output.contains("  main.rs => src/main.rs (+15 -8)"));
+        assert!(display_output.contains("  README.md (binary)"));
     }
 
     // Helper function to create test commits
@@ -795,7 +2164,667 @@ mod tests {
             message: CommitMessage::new(subject.to_string(), None),
             timestamp: DateTime::from_timestamp(timestamp, 0).unwrap(),
             parents: Box::new([]),
+            signature: None,
+            refs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_log_record_multiline_body() {
+        let record = "\nabc123\x1fJohn\x1fjohn@example.com\x1f1640995200\x1fJohn\x1fjohn@example.com\x1f1640995200\x1f\x1f\x1fSubject | with pipe\x1fFirst para\n\nSecond | para\n\0";
+        let commit = parse_log_record(record).unwrap().unwrap();
+
+        assert_eq!(commit.hash.as_str(), "abc123");
+        assert_eq!(commit.message.subject, "Subject | with pipe");
+        assert_eq!(
+            commit.message.body.as_deref(),
+            Some("First para\n\nSecond | para")
+        );
+        assert!(commit.is_root());
+
+        assert!(parse_log_record("  \n").unwrap().is_none());
+        assert!(parse_log_record("malformed|record").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_repository_log_with_stats() {
+        let repo = crate::testing::TempRepo::new("log_with_stats").unwrap();
+        repo.commit_files(&[("a.txt", "one\ntwo\nthree\n"), ("b.txt", "b\n")], "Base")
+            .unwrap();
+        let main = repo.current_branch().unwrap().unwrap();
+        repo.checkout_new("feature", None).unwrap();
+        repo.commit_file("feature.txt", "x\ny\n", "Feature")
+            .unwrap();
+        repo.checkout(&main).unwrap();
+        repo.mv("a.txt", "renamed.txt").unwrap();
+        repo.write_file("renamed.txt", "one\ntwo\nthree\nfour\n")
+            .unwrap();
+        repo.write_file("data.bin", "\0\u{1}\u{2}").unwrap();
+        repo.add(&["renamed.txt", "data.bin"]).unwrap();
+        repo.commit("Rename and add binary").unwrap();
+        repo.merge_with_options(
+            "feature",
+            crate::MergeOptions::new().with_fast_forward(crate::FastForwardMode::Never),
+        )
+        .unwrap();
+
+        let entries = repo.log_with_stats(&LogOptions::new()).unwrap();
+        let subjects: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry.commit.message.subject.as_str())
+            .collect();
+        assert_eq!(entries.len(), 4);
+        assert!(subjects[0].starts_with("Merge"));
+
+        // The merge brings in the feature commit's file
+        assert_eq!(entries[0].files.len(), 1);
+        assert_eq!(entries[0].files[0].path, PathBuf::from("feature.txt"));
+        assert_eq!(entries[0].insertions(), 2);
+
+        let renamed = entries
+            .iter()
+            .find(|entry| entry.commit.message.subject == "Rename and add binary")
+            .unwrap();
+        let rename = renamed
+            .files
+            .iter()
+            .find(|stat| stat.path == Path::new("renamed.txt"))
+            .unwrap();
+        assert_eq!(rename.old_path, Some(PathBuf::from("a.txt")));
+        assert_eq!((rename.insertions, rename.deletions), (1, 0));
+        assert!(
+            renamed
+                .files
+                .iter()
+                .any(|stat| stat.path == Path::new("data.bin") && stat.binary)
+        );
+
+        let base = entries
+            .iter()
+            .find(|entry| entry.commit.message.subject == "Base")
+            .unwrap();
+        assert_eq!(base.total_changes(), 4);
+        assert_eq!(base.files.len(), 2);
+
+        let limited = repo
+            .log_with_stats(&LogOptions::new().max_count(1).no_merges(true))
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+        assert_eq!(limited[0].commit.parents.len(), 1);
+    }
+
+    #[test]
+    fn test_repository_log_separator_characters() {
+        let repo = crate::testing::TempRepo::new("log_separators").unwrap();
+        repo.commit_file("base.txt", "base\n", "Base").unwrap();
+        repo.write_file("a.txt", "a\n").unwrap();
+        repo.add(&["a.txt"]).unwrap();
+        let message = "Parse a|b | c\n\nFirst paragraph\n\nSecond | paragraph";
+        let hash = repo
+            .commit_with_author(message, "Ann | Ops <ann@example.com>")
+            .unwrap();
+
+        let check = |commit: &Commit| {
+            assert_eq!(commit.hash, hash);
+            assert_eq!(commit.author.name, "Ann | Ops");
+            assert_eq!(commit.author.email, "ann@example.com");
+            assert_eq!(commit.message.subject, "Parse a|b | c");
+            assert_eq!(
+                commit.message.body.as_deref(),
+                Some("First paragraph\n\nSecond | paragraph")
+            );
+            assert_eq!(commit.parents.len(), 1);
+        };
+
+        let log = repo.log().unwrap();
+        assert_eq!(log.len(), 2);
+        check(log.iter().next().unwrap());
+        check(&repo.show_commit(&hash).unwrap().commit);
+        check(
+            &repo
+                .log_iter(&LogOptions::new())
+                .unwrap()
+                .next()
+                .unwrap()
+                .unwrap(),
+        );
+        check(&repo.last_commit_for("a.txt").unwrap().unwrap());
+    }
+
+    #[test]
+    fn test_parse_log_record_with_signature() {
+        let record = "G\x1fJane Signer <jane@example.com>\x1fABCDEF0123456789\x1fabc123\x1fJane\x1fjane@example.com\x1f1640995200\x1fJane\x1fjane@example.com\x1f1640995200\x1f\x1f\x1fSigned commit\x1f";
+        let commit = parse_log_record(record).unwrap().unwrap();
+
+        assert_eq!(commit.hash.as_str(), "abc123");
+        assert_eq!(commit.message.subject, "Signed commit");
+        let signature = commit.signature.unwrap();
+        assert_eq!(signature.validity, SignatureValidity::Good);
+        assert_eq!(
+            signature.signer.as_deref(),
+            Some("Jane Signer <jane@example.com>")
+        );
+        assert_eq!(signature.key.as_deref(), Some("ABCDEF0123456789"));
+        assert!(signature.is_signed());
+        assert!(signature.is_good());
+
+        let record = "N\x1f\x1f\x1fdef456\x1fJohn\x1fjohn@example.com\x1f1640995200\x1fJohn\x1fjohn@example.com\x1f1640995200\x1f\x1f\x1fUnsigned\x1f";
+        let signature = parse_log_record(record)
+            .unwrap()
+            .unwrap()
+            .signature
+            .unwrap();
+        assert_eq!(signature.validity, SignatureValidity::Unsigned);
+        assert_eq!(signature.signer, None);
+        assert!(!signature.is_signed());
+
+        // Records without the signature prefix carry no status
+        let record = "abc123\x1fJohn\x1fjohn@example.com\x1f1640995200\x1fJohn\x1fjohn@example.com\x1f1640995200\x1f\x1f\x1fSubject\x1f";
+        assert!(
+            parse_log_record(record)
+                .unwrap()
+                .unwrap()
+                .signature
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_signature_validity_char_conversion() {
+        for c in ['G', 'U', 'X', 'Y', 'R', 'B', 'E', 'N'] {
+            assert_eq!(SignatureValidity::from_char(c).unwrap().to_char(), c);
         }
+        assert_eq!(SignatureValidity::from_char('?'), None);
+    }
+
+    #[test]
+    fn test_repository_log_show_signatures() {
+        let test_path = "/tmp/test_log_signatures_repo";
+
+        // Clean up if exists
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        std::fs::write(format!("{}/file.txt", test_path), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Unsigned commit").unwrap();
+
+        let commits = repo
+            .log_with_options(&LogOptions::new().show_signatures())
+            .unwrap();
+        assert_eq!(commits.len(), 1);
+        let commit = commits.iter().next().unwrap();
+        assert_eq!(commit.message.subject, "Unsigned commit");
+        assert_eq!(
+            commit.signature.as_ref().unwrap().validity,
+            SignatureValidity::Unsigned
+        );
+
+        // Without the option no status is attached
+        let commits = repo.log().unwrap();
+        assert!(commits.iter().next().unwrap().signature.is_none());
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_repository_log_stream() {
+        let test_path = "/tmp/test_log_stream_repo";
+
+        // Clean up if exists
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        for i in 0..3 {
+            std::fs::write(format!("{}/file{}.txt", test_path, i), "content").unwrap();
+            repo.add(&[format!("file{}.txt", i)]).unwrap();
+            repo.commit(&format!("Commit {}\n\nBody line one\nBody line two", i))
+                .unwrap();
+        }
+
+        let (handle, receiver) = repo.log_stream(&LogOptions::new()).unwrap();
+        let commits: Vec<Commit> = receiver.iter().collect();
+        handle.join().unwrap().unwrap();
+
+        assert_eq!(commits.len(), 3);
+        assert_eq!(commits[0].message.subject, "Commit 2");
+        assert_eq!(
+            commits[0].message.body.as_deref(),
+            Some("Body line one\nBody line two")
+        );
+        assert_eq!(commits[2].message.subject, "Commit 0");
+
+        // Dropping the receiver early stops the worker cleanly
+        let (handle, receiver) = repo.log_stream(&LogOptions::new()).unwrap();
+        let first = receiver.recv().unwrap();
+        assert_eq!(first.message.subject, "Commit 2");
+        drop(receiver);
+        assert!(handle.join().unwrap().is_ok());
+
+        // A git failure carries its stderr
+        let options = LogOptions::new().range("HEAD", "missing");
+        let (handle, receiver) = repo.log_stream(&options).unwrap();
+        assert_eq!(receiver.iter().count(), 0);
+        let error = handle.join().unwrap().unwrap_err();
+        assert!(error.to_string().contains("missing"));
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_decorations() {
+        assert!(parse_decorations("").is_empty());
+        assert_eq!(
+            parse_decorations(
+                "HEAD -> refs/heads/main, tag: refs/tags/v1.0, refs/remotes/origin/main"
+            ),
+            vec![
+                "HEAD",
+                "refs/heads/main",
+                "refs/tags/v1.0",
+                "refs/remotes/origin/main"
+            ]
+        );
+        assert_eq!(parse_decorations("HEAD"), vec!["HEAD"]);
+    }
+
+    #[test]
+    fn test_repository_log_decorations_and_first_parent() {
+        let repo = crate::testing::TempRepo::new("log_decorations").unwrap();
+        let base = repo.commit_file("base.txt", "base\n", "Base").unwrap();
+        let main = repo.current_branch().unwrap().unwrap();
+        repo.create_tag("v1.0", None).unwrap();
+
+        repo.checkout_new("feature", None).unwrap();
+        let feature = repo
+            .commit_file("feature.txt", "feature\n", "Feature")
+            .unwrap();
+        repo.checkout(&main).unwrap();
+        repo.commit_file("main.txt", "main\n", "Main").unwrap();
+        repo.merge_with_options(
+            "feature",
+            crate::MergeOptions::new().with_fast_forward(crate::FastForwardMode::Never),
+        )
+        .unwrap();
+
+        let log = repo.log_with_options(&LogOptions::new().all()).unwrap();
+        let refs_of = |hash: &Hash| -> Vec<String> {
+            log.iter()
+                .find(|commit| &commit.hash == hash)
+                .unwrap()
+                .refs
+                .clone()
+        };
+        assert_eq!(
+            refs_of(&repo.head_hash().unwrap()),
+            vec!["HEAD".to_string(), format!("refs/heads/{}", main.name)]
+        );
+        assert_eq!(refs_of(&feature), vec!["refs/heads/feature"]);
+        assert_eq!(refs_of(&base), vec!["refs/tags/v1.0"]);
+        assert_eq!(
+            log.iter().filter(|commit| commit.refs.is_empty()).count(),
+            1
+        );
+
+        // Streaming and single-commit lookups carry decorations too
+        let streamed: Vec<Commit> = repo
+            .log_iter(&LogOptions::new().branches("feat*"))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(streamed[0].refs, vec!["refs/heads/feature"]);
+        let last = repo.last_commit_for("base.txt").unwrap().unwrap();
+        assert_eq!(last.refs, vec!["refs/tags/v1.0"]);
+
+        let first_parent = LogOptions::new().first_parent();
+        let subjects: Vec<String> = repo
+            .log_with_options(&first_parent)
+            .unwrap()
+            .iter()
+            .map(|commit| commit.message.subject.clone())
+            .collect();
+        assert_eq!(subjects.len(), 3);
+        assert!(!subjects.contains(&"Feature".to_string()));
+        assert_eq!(repo.rev_list(&first_parent).unwrap().len(), 3);
+        assert_eq!(repo.rev_list(&LogOptions::new()).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_repository_log_pickaxe() {
+        let repo = crate::testing::TempRepo::new("log_pickaxe").unwrap();
+        let added = repo
+            .commit_file("lib.rs", "fn parse() {}\n", "Add parser")
+            .unwrap();
+        let edited = repo
+            .commit_file("lib.rs", "fn parse() { todo!() }\n", "Edit parser")
+            .unwrap();
+        repo.commit_file("other.rs", "// parse later\n", "Unrelated")
+            .unwrap();
+        let removed = repo
+            .commit_file("lib.rs", "fn render() {}\n", "Remove parser")
+            .unwrap();
+
+        let hashes = |options: LogOptions| -> Vec<Hash> {
+            repo.log_with_options(&options)
+                .unwrap()
+                .iter()
+                .map(|commit| commit.hash.clone())
+                .collect()
+        };
+
+        // -S counts occurrences, so the in-place edit is not a match
+        let options = LogOptions::new().pickaxe_string("fn parse()");
+        assert_eq!(
+            hashes(options.clone()),
+            vec![removed.clone(), added.clone()]
+        );
+        assert_eq!(
+            repo.rev_list(&options).unwrap(),
+            vec![removed.clone(), added.clone()]
+        );
+
+        // -G matches any changed line, and takes a regex
+        let options = LogOptions::new().pickaxe_regex(r"fn parse\(\) \{");
+        assert_eq!(
+            hashes(options),
+            vec![removed.clone(), edited, added.clone()]
+        );
+
+        // Only one pickaxe applies; values starting with a dash are not options
+        let options = LogOptions::new()
+            .pickaxe_regex("anything")
+            .pickaxe_string("fn parse()")
+            .paths(vec![PathBuf::from("lib.rs")]);
+        assert!(options.pickaxe_regex.is_none());
+        assert_eq!(hashes(options), vec![removed, added]);
+        assert!(hashes(LogOptions::new().pickaxe_string("--all")).is_empty());
+    }
+
+    #[test]
+    fn test_repository_log_iter() {
+        let repo = crate::testing::TempRepo::new("log_iter").unwrap();
+        for i in 0..3 {
+            repo.commit_file(
+                format!("file{}.txt", i),
+                "content",
+                &format!("Commit {}\n\nBody line one\nBody line two", i),
+            )
+            .unwrap();
+        }
+
+        let commits: Vec<Commit> = repo
+            .log_iter(&LogOptions::new())
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(commits.len(), 3);
+        assert_eq!(commits[0].message.subject, "Commit 2");
+        assert_eq!(
+            commits[0].message.body.as_deref(),
+            Some("Body line one\nBody line two")
+        );
+        assert_eq!(commits[2].message.subject, "Commit 0");
+
+        // Options apply, and stopping early is fine
+        let mut iter = repo.log_iter(&LogOptions::new().max_count(2)).unwrap();
+        assert_eq!(iter.next().unwrap().unwrap().message.subject, "Commit 2");
+        drop(iter);
+        assert_eq!(
+            repo.log_iter(&LogOptions::new().max_count(2))
+                .unwrap()
+                .count(),
+            2
+        );
+
+        // A git failure is the last item
+        let options = LogOptions::new().range("HEAD", "missing");
+        let mut iter = repo.log_iter(&options).unwrap();
+        let error = iter.next().unwrap().unwrap_err();
+        assert!(error.to_string().contains("missing"));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_repository_compare() {
+        let test_path = "/tmp/test_log_compare_repo";
+
+        // Clean up if exists
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        let commit_file = |name: &str, message: &str| {
+            std::fs::write(format!("{}/{}", test_path, name), name).unwrap();
+            repo.add(&[name]).unwrap();
+            repo.commit(message).unwrap()
+        };
+
+        let base = commit_file("base.txt", "Base");
+        let main = repo.current_branch().unwrap().unwrap();
+
+        repo.checkout_new("feature", None).unwrap();
+        commit_file("shared.txt", "Add shared on feature");
+        commit_file("feature.txt", "Add feature");
+
+        // Before main moves, main can fast-forward to feature
+        let comparison = repo.compare(&main.name, "feature").unwrap();
+        assert_eq!(comparison.merge_base, Some(base.clone()));
+        assert_eq!(comparison.left_count(), 0);
+        assert_eq!(comparison.right_count(), 2);
+        assert!(comparison.can_fast_forward());
+        assert!(!comparison.is_identical());
+
+        repo.checkout(&main).unwrap();
+        commit_file("shared.txt", "Add shared on main");
+        commit_file("main.txt", "Add main");
+
+        let comparison = repo.compare(&main.name, "feature").unwrap();
+        assert_eq!(comparison.left_count(), 2);
+        assert_eq!(comparison.right_count(), 2);
+        assert!(!comparison.can_fast_forward());
+        assert_eq!(comparison.left[0].commit.message.subject, "Add main");
+
+        // The identical "shared.txt" commits are marked as equivalents on both sides
+        let left_unique: Vec<&str> = comparison
+            .left_unique()
+            .map(|c| c.message.subject.as_str())
+            .collect();
+        let right_unique: Vec<&str> = comparison
+            .right_unique()
+            .map(|c| c.message.subject.as_str())
+            .collect();
+        assert_eq!(left_unique, vec!["Add main"]);
+        assert_eq!(right_unique, vec!["Add feature"]);
+
+        assert!(repo.compare(&main.name, &main.name).unwrap().is_identical());
+        assert!(repo.compare(&main.name, "missing").is_err());
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_repository_last_commit_for() {
+        let test_path = "/tmp/test_log_last_commit_for_repo";
+
+        // Clean up if exists
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        std::fs::create_dir(format!("{}/src", test_path)).unwrap();
+        std::fs::write(format!("{}/src/a.txt", test_path), "a").unwrap();
+        std::fs::write(format!("{}/b.txt", test_path), "b").unwrap();
+        repo.add(&["src/a.txt", "b.txt"]).unwrap();
+        let first = repo.commit("Add files\n\nWith a body").unwrap();
+
+        std::fs::write(format!("{}/b.txt", test_path), "b2").unwrap();
+        repo.add(&["b.txt"]).unwrap();
+        let second = repo.commit("Update b").unwrap();
+
+        let commit = repo.last_commit_for("src/a.txt").unwrap().unwrap();
+        assert_eq!(commit.hash, first);
+        assert_eq!(commit.message.body.as_deref(), Some("With a body"));
+        assert_eq!(repo.last_commit_for("src").unwrap().unwrap().hash, first);
+        assert_eq!(repo.last_commit_for("b.txt").unwrap().unwrap().hash, second);
+        assert!(repo.last_commit_for("missing.txt").unwrap().is_none());
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_repository_file_history() {
+        let test_path = "/tmp/test_log_file_history_repo";
+
+        // Clean up if exists
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        std::fs::write(format!("{}/notes.txt", test_path), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(format!("{}/other.txt", test_path), "other\n").unwrap();
+        repo.add(&["notes.txt", "other.txt"]).unwrap();
+        repo.commit("Add notes").unwrap();
+
+        std::fs::write(format!("{}/notes.txt", test_path), "one\n2\nthree\nfour\n").unwrap();
+        repo.add(&["notes.txt"]).unwrap();
+        repo.commit("Edit notes").unwrap();
+
+        repo.mv("notes.txt", "docs.txt").unwrap();
+        std::fs::write(format!("{}/other.txt", test_path), "changed\n").unwrap();
+        repo.add(&["other.txt"]).unwrap();
+        repo.commit("Rename notes and tidy").unwrap();
+
+        let history = repo.file_history("docs.txt").unwrap();
+        assert_eq!(history.len(), 3);
+
+        assert_eq!(history[0].commit.message.subject, "Rename notes and tidy");
+        assert_eq!(history[0].diff.status, DiffStatus::Renamed);
+        assert_eq!(history[0].path(), Path::new("docs.txt"));
+        assert_eq!(
+            history[0].diff.old_path.as_deref(),
+            Some(Path::new("notes.txt"))
+        );
+
+        assert_eq!(history[1].commit.message.subject, "Edit notes");
+        assert_eq!(history[1].path(), Path::new("notes.txt"));
+        assert_eq!(history[1].diff.additions, 2);
+        assert_eq!(history[1].diff.deletions, 1);
+        assert!(history[1].patch.contains("+four"));
+        assert!(!history[1].patch.contains("other.txt"));
+
+        assert_eq!(history[2].diff.status, DiffStatus::Added);
+        assert_eq!(history[2].diff.additions, 3);
+
+        assert!(repo.file_history("missing.txt").unwrap().is_empty());
+        assert!(history.iter().all(|revision| revision.content.is_none()));
+
+        // Contents are read from the path the file had at each revision
+        let history = repo
+            .with_path_style(crate::PathStyle::Absolute)
+            .file_history_with_contents("docs.txt")
+            .unwrap();
+        let contents: Vec<&[u8]> = history
+            .iter()
+            .map(|revision| revision.content.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            contents,
+            vec![
+                &b"one\n2\nthree\nfour\n"[..],
+                b"one\n2\nthree\nfour\n",
+                b"one\ntwo\nthree\n"
+            ]
+        );
+        assert!(history[0].previous_path().unwrap().ends_with("notes.txt"));
+        assert!(history[0].path().is_absolute());
+        assert_eq!(history[1].previous_path(), None);
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
+    }
+
+    #[test]
+    fn test_repository_show_commit_exact_stats() {
+        let test_path = "/tmp/test_log_show_commit_stats_repo";
+
+        // Clean up if exists
+        if Path::new(test_path).exists() {
+            fs::remove_dir_all(test_path).unwrap();
+        }
+
+        let repo = Repository::init(test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        let lines: String = (0..1000).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(format!("{}/big.txt", test_path), &lines).unwrap();
+        std::fs::write(format!("{}/old.txt", test_path), "a\nb\nc\nd\n").unwrap();
+        repo.add(&["big.txt", "old.txt"]).unwrap();
+        repo.commit("Add files").unwrap();
+
+        // Large uneven change that the --stat bar graph can only approximate
+        let edited: String = (0..1000)
+            .filter(|i| i % 3 != 0)
+            .map(|i| format!("line {}\n", i))
+            .chain((0..7).map(|i| format!("new {}\n", i)))
+            .collect();
+        std::fs::write(format!("{}/big.txt", test_path), edited).unwrap();
+        repo.mv("old.txt", "new.txt").unwrap();
+        std::fs::write(format!("{}/new.txt", test_path), "a\nb\nc\nd\ne\n").unwrap();
+        repo.add(&["big.txt", "new.txt"]).unwrap();
+        let hash = repo.commit("Edit and rename").unwrap();
+
+        let details = repo.show_commit(&hash).unwrap();
+        assert_eq!(
+            details.files_changed,
+            vec![PathBuf::from("big.txt"), PathBuf::from("new.txt")]
+        );
+
+        let big = &details.file_stats[0];
+        assert_eq!((big.insertions, big.deletions), (7, 334));
+        assert!(!big.is_rename());
+
+        let renamed = &details.file_stats[1];
+        assert_eq!(renamed.old_path, Some(PathBuf::from("old.txt")));
+        assert_eq!((renamed.insertions, renamed.deletions), (1, 0));
+
+        assert_eq!(details.insertions, 8);
+        assert_eq!(details.deletions, 334);
+
+        // Clean up
+        fs::remove_dir_all(test_path).unwrap();
     }
 
     #[test]
@@ -0,0 +1,269 @@
+//! Background repository maintenance
+//!
+//! This module wraps `git maintenance` so applications embedding the crate can enroll
+//! repositories in scheduled background tasks (prefetch, loose-object packing,
+//! commit-graph updates) instead of running `gc` themselves, and exposes the commit-graph
+//! file directly since it speeds up log and merge-base heavy workloads considerably.
+//!
+//! Registration is recorded in the user's global config (`maintenance.repo`), so these
+//! calls affect every git process run by the current user, not just this repository.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{MaintenanceScheduler, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//! repo.maintenance_start(MaintenanceScheduler::Auto)?;
+//!
+//! // Later, stop maintaining this repository
+//! repo.maintenance_unregister()?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::utils::git;
+use crate::{Repository, Result};
+
+/// The system scheduler used to trigger `git maintenance run`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaintenanceScheduler {
+    /// Let git pick the scheduler for the current platform
+    #[default]
+    Auto,
+    /// cron on Linux and other Unix systems
+    Crontab,
+    /// systemd user timers on Linux
+    SystemdTimer,
+    /// launchd on macOS
+    Launchctl,
+    /// Task Scheduler on Windows
+    Schtasks,
+}
+
+impl MaintenanceScheduler {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            MaintenanceScheduler::Auto => "auto",
+            MaintenanceScheduler::Crontab => "crontab",
+            MaintenanceScheduler::SystemdTimer => "systemd-timer",
+            MaintenanceScheduler::Launchctl => "launchctl",
+            MaintenanceScheduler::Schtasks => "schtasks",
+        }
+    }
+}
+
+/// Options for writing the commit-graph file
+#[derive(Debug, Clone, Default)]
+pub struct CommitGraphOptions {
+    /// Store Bloom filters of changed paths, speeding up path-limited log and blame
+    pub changed_paths: bool,
+    /// Write an incremental layer instead of rewriting the whole graph
+    pub split: bool,
+}
+
+impl CommitGraphOptions {
+    /// Create new default commit-graph options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute changed-path Bloom filters
+    pub fn with_changed_paths(mut self) -> Self {
+        self.changed_paths = true;
+        self
+    }
+
+    /// Write a split (incremental) commit-graph chain
+    pub fn with_split(mut self) -> Self {
+        self.split = true;
+        self
+    }
+}
+
+impl Repository {
+    /// Add this repository to the list maintained by scheduled `git maintenance` runs.
+    ///
+    /// Also sets `maintenance.strategy=incremental` in the repository config unless a
+    /// strategy is already configured. Does not install a schedule; see
+    /// [`maintenance_start`](Self::maintenance_start).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `GitError` if the operation fails.
+    pub fn maintenance_register(&self) -> Result<()> {
+        self.check_git()?;
+        git(&["maintenance", "register"], Some(self))?;
+        Ok(())
+    }
+
+    /// Remove this repository from scheduled maintenance.
+    ///
+    /// Does nothing if the repository is not registered. The system schedule stays
+    /// installed for any other registered repositories.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `GitError` if the operation fails.
+    pub fn maintenance_unregister(&self) -> Result<()> {
+        self.check_git()?;
+        git(&["maintenance", "unregister", "--force"], Some(self))?;
+        Ok(())
+    }
+
+    /// Register this repository and install the hourly, daily, and weekly schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `scheduler` - The system scheduler to install the schedule with
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `GitError` if the scheduler is unavailable.
+    pub fn maintenance_start(&self, scheduler: MaintenanceScheduler) -> Result<()> {
+        self.check_git()?;
+        let scheduler_arg = format!("--scheduler={}", scheduler.as_str());
+        git(&["maintenance", "start", &scheduler_arg], Some(self))?;
+        Ok(())
+    }
+
+    /// Write a commit-graph covering all commits reachable from any ref.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `GitError` if the operation fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// if !repo.has_commit_graph()? {
+    ///     repo.write_commit_graph()?;
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn write_commit_graph(&self) -> Result<()> {
+        self.write_commit_graph_with_options(CommitGraphOptions::default())
+    }
+
+    /// Write a commit-graph with custom options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Commit-graph options to customize the operation
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{CommitGraphOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// repo.write_commit_graph_with_options(CommitGraphOptions::new().with_changed_paths())?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn write_commit_graph_with_options(&self, options: CommitGraphOptions) -> Result<()> {
+        self.check_git()?;
+
+        let mut args = vec!["commit-graph", "write", "--reachable"];
+        if options.changed_paths {
+            args.push("--changed-paths");
+        }
+        if options.split {
+            args.push("--split");
+        }
+
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// Check whether a commit-graph exists, either as a single file or a split chain.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if git will use a commit-graph for this repository.
+    pub fn has_commit_graph(&self) -> Result<bool> {
+        self.check_git()?;
+
+        for path in [
+            "objects/info/commit-graph",
+            "objects/info/commit-graphs/commit-graph-chain",
+        ] {
+            let output = git(&["rev-parse", "--git-path", path], Some(self))?;
+            if self.command_dir().join(output.trim()).exists() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_maintenance_scheduler_as_str() {
+        assert_eq!(MaintenanceScheduler::default().as_str(), "auto");
+        assert_eq!(MaintenanceScheduler::Crontab.as_str(), "crontab");
+        assert_eq!(MaintenanceScheduler::SystemdTimer.as_str(), "systemd-timer");
+        assert_eq!(MaintenanceScheduler::Launchctl.as_str(), "launchctl");
+        assert_eq!(MaintenanceScheduler::Schtasks.as_str(), "schtasks");
+    }
+
+    // Registering writes to the user's global config, so only the read-only
+    // path of unregistering an unknown repository is exercised here.
+    #[test]
+    fn test_maintenance_unregister_unknown_repository() {
+        let test_path = env::temp_dir().join("test_maintenance_unregister");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.maintenance_unregister().unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_write_commit_graph() {
+        let test_path = env::temp_dir().join("test_write_commit_graph");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(test_path.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+
+        assert!(!repo.has_commit_graph().unwrap());
+        repo.write_commit_graph().unwrap();
+        assert!(repo.has_commit_graph().unwrap());
+        assert!(test_path.join(".git/objects/info/commit-graph").exists());
+
+        // Split graphs are detected through the chain file
+        fs::remove_file(test_path.join(".git/objects/info/commit-graph")).unwrap();
+        assert!(!repo.has_commit_graph().unwrap());
+        repo.write_commit_graph_with_options(
+            CommitGraphOptions::new().with_changed_paths().with_split(),
+        )
+        .unwrap();
+        assert!(repo.has_commit_graph().unwrap());
+        assert!(
+            test_path
+                .join(".git/objects/info/commit-graphs/commit-graph-chain")
+                .exists()
+        );
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+}
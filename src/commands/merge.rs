@@ -32,9 +32,10 @@
 //! ```
 
 use crate::error::{GitError, Result};
-use crate::repository::Repository;
-use crate::types::Hash;
-use crate::utils::{git, git_raw};
+use crate::repository::{Repository, resolve_git_dir};
+use crate::types::{Hash, Identity};
+use crate::utils::{git, git_raw_with_env, validate_arg};
+use std::fs;
 use std::path::{Path, PathBuf};
 
 /// The result of a merge operation
@@ -99,6 +100,7 @@ pub struct MergeOptions {
     strategy: Option<MergeStrategy>,
     commit_message: Option<String>,
     no_commit: bool,
+    identity: Option<Identity>,
 }
 
 impl MergeOptions {
@@ -109,6 +111,7 @@ impl MergeOptions {
             strategy: None,
             commit_message: None,
             no_commit: false,
+            identity: None,
         }
     }
 
@@ -135,6 +138,12 @@ impl MergeOptions {
         self.no_commit = true;
         self
     }
+
+    /// Create the merge commit as `identity` without changing the config
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
 }
 
 impl Default for MergeOptions {
@@ -143,12 +152,75 @@ impl Default for MergeOptions {
     }
 }
 
+/// The operation that left conflicts in the working tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictOperation {
+    /// `git merge` (`MERGE_HEAD`)
+    Merge,
+    /// `git rebase` (`rebase-merge/` or `rebase-apply/`)
+    Rebase,
+    /// `git am` (`rebase-apply/applying`)
+    Am,
+    /// `git cherry-pick` (`CHERRY_PICK_HEAD`)
+    CherryPick,
+    /// `git revert` (`REVERT_HEAD`)
+    Revert,
+}
+
+impl ConflictOperation {
+    /// The git subcommand that continues this operation
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ConflictOperation::Merge => "merge",
+            ConflictOperation::Rebase => "rebase",
+            ConflictOperation::Am => "am",
+            ConflictOperation::CherryPick => "cherry-pick",
+            ConflictOperation::Revert => "revert",
+        }
+    }
+}
+
+/// Options for marking conflicted files as resolved
+#[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    check_markers: bool,
+}
+
+impl ResolveOptions {
+    /// Create new ResolveOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse to stage files that still contain conflict markers
+    pub fn with_marker_check(mut self) -> Self {
+        self.check_markers = true;
+        self
+    }
+}
+
+/// The outcome of marking conflicted files as resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveStatus {
+    /// Paths that were staged
+    pub staged: Vec<PathBuf>,
+    /// Paths that are still unmerged
+    pub remaining: Vec<PathBuf>,
+    /// The in-progress operation, if any
+    pub operation: Option<ConflictOperation>,
+}
+
+impl ResolveStatus {
+    /// Check if all conflicts are resolved and an operation is waiting to be continued
+    pub fn can_continue(&self) -> bool {
+        self.remaining.is_empty() && self.operation.is_some()
+    }
+}
+
 /// Perform a merge operation
-pub fn merge<P: AsRef<Path>>(
-    repo_path: P,
-    branch: &str,
-    options: &MergeOptions,
-) -> Result<MergeStatus> {
+pub fn merge(repo: &Repository, branch: &str, options: &MergeOptions) -> Result<MergeStatus> {
+    validate_arg("revision", branch)?;
+
     let mut args = vec!["merge"];
 
     // Add fast-forward option if not auto
@@ -177,7 +249,9 @@ pub fn merge<P: AsRef<Path>>(
     // Add the branch to merge
     args.push(branch);
 
-    let output = git_raw(&args, Some(repo_path.as_ref()))?;
+    let env = options.identity.as_ref().map(Identity::env);
+    let env: &[(&str, &str)] = env.as_ref().map_or(&[], |env| env.as_slice());
+    let output = git_raw_with_env(&args, Some(repo), env)?;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
 
@@ -195,12 +269,12 @@ pub fn merge<P: AsRef<Path>>(
                 return Ok(MergeStatus::FastForward(hash));
             }
             // Fallback: get current HEAD
-            let head_output = git(&["rev-parse", "HEAD"], Some(repo_path.as_ref()))?;
+            let head_output = git(&["rev-parse", "HEAD"], Some(repo))?;
             let hash = Hash::from(head_output.trim());
             Ok(MergeStatus::FastForward(hash))
         } else {
             // Regular merge success - get the merge commit hash
-            let head_output = git(&["rev-parse", "HEAD"], Some(repo_path.as_ref()))?;
+            let head_output = git(&["rev-parse", "HEAD"], Some(repo))?;
             let hash = Hash::from(head_output.trim());
             Ok(MergeStatus::Success(hash))
         }
@@ -210,7 +284,7 @@ pub fn merge<P: AsRef<Path>>(
         || stdout.contains("Automatic merge failed")
     {
         // Merge has conflicts
-        let conflicts = extract_conflicted_files(repo_path.as_ref())?;
+        let conflicts = extract_conflicted_files(repo)?;
         Ok(MergeStatus::Conflicts(conflicts))
     } else {
         // Other error
@@ -224,11 +298,8 @@ pub fn merge<P: AsRef<Path>>(
 }
 
 /// Extract list of files with conflicts
-fn extract_conflicted_files<P: AsRef<Path>>(repo_path: P) -> Result<Vec<PathBuf>> {
-    let output = git(
-        &["diff", "--name-only", "--diff-filter=U"],
-        Some(repo_path.as_ref()),
-    )?;
+pub(crate) fn extract_conflicted_files(repo: &Repository) -> Result<Vec<PathBuf>> {
+    let output = git(&["diff", "--name-only", "--diff-filter=U"], Some(repo))?;
 
     let conflicts: Vec<PathBuf> = output
         .lines()
@@ -240,18 +311,91 @@ fn extract_conflicted_files<P: AsRef<Path>>(repo_path: P) -> Result<Vec<PathBuf>
 }
 
 /// Check if a merge is currently in progress
-pub fn merge_in_progress<P: AsRef<Path>>(repo_path: P) -> Result<bool> {
-    let git_dir = repo_path.as_ref().join(".git");
+///
+/// `MERGE_HEAD` lives in the per-worktree git directory, which is resolved through
+/// git so linked worktrees (where `.git` is a file) are handled.
+pub fn merge_in_progress(repo: &Repository) -> Result<bool> {
+    let git_dir = resolve_git_dir(repo)?;
     let merge_head = git_dir.join("MERGE_HEAD");
     Ok(merge_head.exists())
 }
 
 /// Abort an in-progress merge
-pub fn abort_merge<P: AsRef<Path>>(repo_path: P) -> Result<()> {
-    git(&["merge", "--abort"], Some(repo_path.as_ref()))?;
+pub fn abort_merge(repo: &Repository) -> Result<()> {
+    git(&["merge", "--abort"], Some(repo))?;
     Ok(())
 }
 
+/// Detect which operation, if any, is waiting on conflict resolution
+pub fn conflict_operation(repo: &Repository) -> Result<Option<ConflictOperation>> {
+    let git_dir = resolve_git_dir(repo)?;
+
+    // `git am` keeps its state in rebase-apply/ too, marked by an `applying` file
+    let operation = if git_dir.join("rebase-apply").join("applying").exists() {
+        Some(ConflictOperation::Am)
+    } else if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+        Some(ConflictOperation::Rebase)
+    } else if git_dir.join("MERGE_HEAD").exists() {
+        Some(ConflictOperation::Merge)
+    } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        Some(ConflictOperation::CherryPick)
+    } else if git_dir.join("REVERT_HEAD").exists() {
+        Some(ConflictOperation::Revert)
+    } else {
+        None
+    };
+
+    Ok(operation)
+}
+
+/// Stage resolved files and report what is left to resolve
+pub fn mark_resolved<P: AsRef<Path>>(
+    repo: &Repository,
+    paths: &[P],
+    options: &ResolveOptions,
+) -> Result<ResolveStatus> {
+    if options.check_markers {
+        let unresolved: Vec<String> = paths
+            .iter()
+            .filter(|path| has_conflict_markers(&repo.command_dir().join(path)))
+            .map(|path| path.as_ref().display().to_string())
+            .collect();
+        if !unresolved.is_empty() {
+            return Err(GitError::CommandFailed(format!(
+                "Conflict markers remain in: {}",
+                unresolved.join(", ")
+            )));
+        }
+    }
+
+    let staged: Vec<PathBuf> = paths.iter().map(|p| p.as_ref().to_path_buf()).collect();
+    if !staged.is_empty() {
+        let mut args: Vec<String> = vec!["add".to_string(), "--".to_string()];
+        args.extend(staged.iter().map(|p| p.to_string_lossy().to_string()));
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        git(&args, Some(repo))?;
+    }
+
+    Ok(ResolveStatus {
+        staged,
+        remaining: extract_conflicted_files(repo)?,
+        operation: conflict_operation(repo)?,
+    })
+}
+
+/// Check a file for lines that start with `<<<<<<<` or `>>>>>>>`
+///
+/// Missing or unreadable files (e.g. resolved by deletion) have no markers.
+fn has_conflict_markers(path: &Path) -> bool {
+    let Ok(content) = fs::read(path) else {
+        return false;
+    };
+
+    content
+        .split(|b| *b == b'\n')
+        .any(|line| line.starts_with(b"<<<<<<<") || line.starts_with(b">>>>>>>"))
+}
+
 impl Repository {
     /// Merge the specified branch into the current branch.
     ///
@@ -280,8 +424,8 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn merge(&self, branch: &str) -> Result<MergeStatus> {
-        Self::ensure_git()?;
-        merge(self.repo_path(), branch, &MergeOptions::new())
+        self.check_git()?;
+        merge(self, branch, &MergeOptions::new())
     }
 
     /// Merge the specified branch with custom options.
@@ -312,8 +456,8 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn merge_with_options(&self, branch: &str, options: MergeOptions) -> Result<MergeStatus> {
-        Self::ensure_git()?;
-        merge(self.repo_path(), branch, &options)
+        self.check_git()?;
+        merge(self, branch, &options)
     }
 
     /// Check if a merge is currently in progress.
@@ -324,8 +468,8 @@ impl Repository {
     ///
     /// A `Result` containing a boolean indicating whether a merge is in progress.
     pub fn merge_in_progress(&self) -> Result<bool> {
-        Self::ensure_git()?;
-        merge_in_progress(self.repo_path())
+        self.check_git()?;
+        merge_in_progress(self)
     }
 
     /// Abort an in-progress merge.
@@ -351,8 +495,78 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn abort_merge(&self) -> Result<()> {
-        Self::ensure_git()?;
-        abort_merge(self.repo_path())
+        self.check_git()?;
+        abort_merge(self)
+    }
+
+    /// Stage conflicted files after they have been edited.
+    ///
+    /// Equivalent to `git add` on the given paths, followed by a check of what is
+    /// still unmerged and which operation (merge, rebase, cherry-pick, revert) is
+    /// waiting to be continued.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The resolved file paths to stage
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `ResolveStatus` with the staged and remaining paths.
+    pub fn mark_resolved<P: AsRef<Path>>(&self, paths: &[P]) -> Result<ResolveStatus> {
+        self.check_git()?;
+        mark_resolved(self, paths, &ResolveOptions::new())
+    }
+
+    /// Stage conflicted files with custom options.
+    ///
+    /// With [`ResolveOptions::with_marker_check`], nothing is staged if any of the
+    /// files still contains a line starting with `<<<<<<<` or `>>>>>>>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The resolved file paths to stage
+    /// * `options` - Options controlling the resolution checks
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `ResolveStatus`, or a `GitError::CommandFailed` naming
+    /// the files that still contain conflict markers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{Repository, ResolveOptions};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let status = repo.mark_resolved_with_options(
+    ///     &["src/lib.rs"],
+    ///     ResolveOptions::new().with_marker_check(),
+    /// )?;
+    /// if status.can_continue() {
+    ///     println!("All conflicts resolved, ready to commit");
+    /// } else {
+    ///     println!("Still unmerged: {:?}", status.remaining);
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn mark_resolved_with_options<P: AsRef<Path>>(
+        &self,
+        paths: &[P],
+        options: ResolveOptions,
+    ) -> Result<ResolveStatus> {
+        self.check_git()?;
+        mark_resolved(self, paths, &options)
+    }
+
+    /// Detect which operation is waiting on conflict resolution.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the in-progress `ConflictOperation`, or `None` if no
+    /// merge, rebase, cherry-pick or revert is in progress.
+    pub fn conflict_operation(&self) -> Result<Option<ConflictOperation>> {
+        self.check_git()?;
+        conflict_operation(self)
     }
 }
 
@@ -360,7 +574,7 @@ impl Repository {
 mod tests {
     use super::*;
     use crate::Repository;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::{env, fs};
 
     fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
@@ -590,6 +804,48 @@ mod tests {
         fs::remove_dir_all(&temp_dir).unwrap();
     }
 
+    #[test]
+    fn test_merge_in_progress_linked_worktree() {
+        let (temp_dir, repo) = create_test_repo("merge_linked_worktree");
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "base", "Initial commit");
+
+        repo.checkout_new("feature", None).unwrap();
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "feature", "Feature");
+        let branches = repo.branches().unwrap();
+        repo.checkout(branches.find("master").unwrap()).unwrap();
+
+        // Create a linked worktree on a new branch and conflict there
+        let worktree_path = env::temp_dir().join("rustic_git_merge_test_linked_worktree_wt");
+        if worktree_path.exists() {
+            fs::remove_dir_all(&worktree_path).unwrap();
+        }
+        git(
+            &[
+                "worktree",
+                "add",
+                "-b",
+                "other",
+                worktree_path.to_str().unwrap(),
+            ],
+            Some(&temp_dir),
+        )
+        .unwrap();
+        let linked = Repository::open(&worktree_path).unwrap();
+        create_file_and_commit(&linked, &worktree_path, "file1.txt", "other", "Other");
+
+        let status = linked.merge("feature").unwrap();
+        assert!(matches!(status, MergeStatus::Conflicts(_)));
+        assert!(linked.merge_in_progress().unwrap());
+        assert!(!repo.merge_in_progress().unwrap());
+
+        linked.abort_merge().unwrap();
+        assert!(!linked.merge_in_progress().unwrap());
+
+        // Clean up
+        fs::remove_dir_all(&worktree_path).unwrap();
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
     #[test]
     fn test_merge_with_custom_message() {
         let (temp_dir, repo) = create_test_repo("merge_custom_message");
@@ -633,4 +889,98 @@ mod tests {
         // Clean up
         fs::remove_dir_all(&temp_dir).unwrap();
     }
+
+    fn create_conflict(repo: &Repository, temp_dir: &Path) {
+        create_file_and_commit(repo, temp_dir, "file1.txt", "base\n", "Initial commit");
+        repo.checkout_new("feature", None).unwrap();
+        create_file_and_commit(repo, temp_dir, "file1.txt", "feature\n", "Feature changes");
+
+        let branches = repo.branches().unwrap();
+        let master_branch = branches.find("master").unwrap();
+        repo.checkout(master_branch).unwrap();
+        create_file_and_commit(repo, temp_dir, "file1.txt", "master\n", "Master changes");
+
+        let status = repo.merge("feature").unwrap();
+        assert!(matches!(status, MergeStatus::Conflicts(_)));
+    }
+
+    #[test]
+    fn test_mark_resolved() {
+        let (temp_dir, repo) = create_test_repo("mark_resolved");
+        create_conflict(&repo, &temp_dir);
+
+        assert_eq!(
+            repo.conflict_operation().unwrap(),
+            Some(ConflictOperation::Merge)
+        );
+
+        fs::write(temp_dir.join("file1.txt"), "resolved\n").unwrap();
+        let status = repo.mark_resolved(&["file1.txt"]).unwrap();
+
+        assert_eq!(status.staged, vec![PathBuf::from("file1.txt")]);
+        assert!(status.remaining.is_empty());
+        assert_eq!(status.operation, Some(ConflictOperation::Merge));
+        assert!(status.can_continue());
+        assert_eq!(repo.status().unwrap().conflicted_files().count(), 0);
+
+        repo.commit("Merge feature").unwrap();
+        assert_eq!(repo.conflict_operation().unwrap(), None);
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_mark_resolved_marker_check() {
+        let (temp_dir, repo) = create_test_repo("mark_resolved_markers");
+        create_conflict(&repo, &temp_dir);
+
+        let result = repo
+            .mark_resolved_with_options(&["file1.txt"], ResolveOptions::new().with_marker_check());
+        match result {
+            Err(GitError::CommandFailed(msg)) => assert!(msg.contains("file1.txt")),
+            other => panic!("Expected marker check failure, got: {:?}", other),
+        }
+        assert_eq!(repo.status().unwrap().conflicted_files().count(), 1);
+
+        fs::write(temp_dir.join("file1.txt"), "resolved\n").unwrap();
+        let status = repo
+            .mark_resolved_with_options(&["file1.txt"], ResolveOptions::new().with_marker_check())
+            .unwrap();
+        assert!(status.can_continue());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_mark_resolved_without_operation() {
+        let (temp_dir, repo) = create_test_repo("mark_resolved_no_op");
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "content", "Initial commit");
+
+        assert_eq!(repo.conflict_operation().unwrap(), None);
+        fs::write(temp_dir.join("file1.txt"), "changed").unwrap();
+        let status = repo.mark_resolved(&["file1.txt"]).unwrap();
+        assert!(status.remaining.is_empty());
+        assert!(!status.can_continue());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_has_conflict_markers() {
+        let temp_dir = env::temp_dir().join("rustic_git_merge_test_markers");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let file = temp_dir.join("markers.txt");
+
+        fs::write(&file, "a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> feature\n").unwrap();
+        assert!(has_conflict_markers(&file));
+        fs::write(&file, "Title\n=======\n").unwrap();
+        assert!(!has_conflict_markers(&file));
+        assert!(!has_conflict_markers(&temp_dir.join("missing.txt")));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
 }
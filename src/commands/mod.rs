@@ -1,27 +1,92 @@
 pub mod add;
+pub mod ancestry;
+pub mod archive;
+pub mod attributes;
+pub mod blame;
 pub mod branch;
+pub mod bundle;
+pub mod cherry_pick;
 pub mod commit;
+pub mod commit_index;
 pub mod config;
 pub mod diff;
+pub mod diff_render;
 pub mod files;
+pub mod graph;
+pub mod lfs;
+pub mod line_endings;
 pub mod log;
+pub mod maintenance;
 pub mod merge;
+pub mod notes;
+pub mod objects;
+pub mod patch;
+pub mod progress;
+pub mod rebase;
+pub mod reflog;
 pub mod remote;
 pub mod reset;
+pub mod revert;
+pub mod rewrite;
+pub mod sparse;
 pub mod stash;
+pub mod stats;
 pub mod status;
+pub mod submodule;
+pub mod sync;
 pub mod tag;
+pub mod transaction;
+pub mod worktree;
 
-pub use branch::{Branch, BranchList, BranchType};
-pub use config::RepoConfig;
+pub use archive::{ArchiveFormat, ArchiveOptions};
+pub use attributes::{FilterDriver, MergeDriver};
+pub use blame::{BlameHunk, BlameLine, BlameOptions, BlameOutput};
+pub use branch::{Branch, BranchList, BranchPruneOptions, BranchPruneReport, BranchType};
+pub use bundle::{BundleInfo, BundleRef};
+pub use cherry_pick::{CherryPickOptions, CherryPickStatus};
+pub use commit::CommitOptions;
+pub use commit_index::CommitIndex;
+pub use config::{RepoConfig, SigningConfig, SigningFormat, UrlRewrite};
 pub use diff::{
     DiffChunk, DiffLine, DiffLineType, DiffOptions, DiffOutput, DiffStats, DiffStatus, FileDiff,
+    FileStat, Revspec, SubmoduleFormat,
+};
+pub use files::{IgnoreTemplate, MoveOptions, RemoveOptions, RestoreOptions};
+pub use graph::{GraphEdge, GraphNode, LogGraph};
+pub use lfs::{LfsLock, LfsUnlockOptions};
+pub use line_endings::{AutoCrlf, EolSetting, FileLineEndings, LineEnding, LineEndingConfig};
+pub use log::{
+    Author, BranchComparison, Commit, CommitDetails, CommitLog, CommitMessage, CommitWithStats,
+    ComparedCommit, FileRevision, LogDate, LogOptions, Page, RefSelector, SignatureStatus,
+    SignatureValidity,
+};
+pub use maintenance::{CommitGraphOptions, MaintenanceScheduler};
+pub use merge::{
+    ConflictOperation, FastForwardMode, MergeOptions, MergeStatus, MergeStrategy, ResolveOptions,
+    ResolveStatus,
+};
+pub use notes::{Note, Notes, NotesRef};
+pub use objects::{FileMode, IndexEntry, LargeBlob, ObjectType, TreeEntry};
+pub use patch::{AmOptions, AmStatus, ApplyOptions};
+pub use progress::{Progress, ProgressCallback, ProgressStage};
+pub use rebase::{RebaseOptions, RebaseStatus};
+pub use reflog::{ReflogEntry, ReflogExpireOptions};
+pub use remote::{
+    CloneOptions, EffectiveRemoteUrls, FetchOptions, PushOptions, Remote, RemoteList,
 };
-pub use files::{MoveOptions, RemoveOptions, RestoreOptions};
-pub use log::{Author, Commit, CommitDetails, CommitLog, CommitMessage, LogOptions};
-pub use merge::{FastForwardMode, MergeOptions, MergeStatus, MergeStrategy};
-pub use remote::{FetchOptions, PushOptions, Remote, RemoteList};
 pub use reset::ResetMode;
+pub use revert::{RevertOptions, RevertStatus};
+pub use rewrite::{PurgeOptions, PurgeReport};
 pub use stash::{Stash, StashApplyOptions, StashList, StashOptions};
-pub use status::{FileEntry, GitStatus, IndexStatus, WorktreeStatus};
+pub use stats::{AuthorStats, CommitStats, ContributorStats, DayStats};
+pub use status::{
+    EntryKind, FileEntry, GitStatus, IgnoreSubmodules, IndexStatus, StatusGroups, StatusOptions,
+    StatusSummary, SubmoduleState, WorktreeStatus,
+};
+pub use submodule::{
+    Submodule, SubmoduleAddOptions, SubmoduleCheckout, SubmoduleStatus, SubmoduleUpdateOptions,
+};
+pub use sync::{SyncOptions, SyncOutcome, SyncReport, SyncStrategy};
 pub use tag::{Tag, TagList, TagOptions, TagType};
+pub use transaction::Transaction;
+pub use worktree::{Worktree, WorktreeAddOptions, WorktreeList};
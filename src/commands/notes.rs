@@ -0,0 +1,295 @@
+//! Git notes operations
+//!
+//! Notes attach extra text to an object, usually a commit, without changing its hash.
+//! Build and release pipelines use them to record metadata such as test results or
+//! deployment targets after the fact. Notes are stored as commits on a notes ref,
+//! `refs/notes/commits` by default; [`NotesRef`] selects another one.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{NotesRef, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//! let head = repo.log()?.iter().next().unwrap().hash.clone();
+//!
+//! repo.note_add(&head, "Reviewed-by: Jane Doe")?;
+//! assert_eq!(repo.note_show(&head)?.as_deref(), Some("Reviewed-by: Jane Doe"));
+//!
+//! // Keep CI results apart from review notes, under refs/notes/ci
+//! let ci = repo.notes(NotesRef::new("ci"));
+//! ci.set(&head, "build: passed")?;
+//! for note in ci.list()? {
+//!     println!("{} has a CI note", note.object.short());
+//! }
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, git_raw, validate_arg};
+use std::fmt;
+
+/// A notes ref, the namespace a set of notes is stored under
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotesRef(String);
+
+impl NotesRef {
+    /// The notes ref git uses when none is configured
+    pub const DEFAULT: &'static str = "refs/notes/commits";
+
+    /// Select a notes ref; short names such as `ci` expand to `refs/notes/ci`
+    pub fn new(name: &str) -> Self {
+        if name.starts_with("refs/") {
+            Self(name.to_string())
+        } else {
+            Self(format!("refs/notes/{}", name))
+        }
+    }
+
+    /// Get the full ref name
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for NotesRef {
+    fn default() -> Self {
+        Self(Self::DEFAULT.to_string())
+    }
+}
+
+impl fmt::Display for NotesRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An object that has a note attached
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    /// The annotated object, usually a commit
+    pub object: Hash,
+    /// The blob holding the note text
+    pub blob: Hash,
+}
+
+/// Notes operations on one notes ref
+///
+/// Obtained from [`Repository::notes`].
+pub struct Notes<'a> {
+    repo: &'a Repository,
+    notes_ref: NotesRef,
+    ref_arg: String,
+}
+
+impl<'a> Notes<'a> {
+    /// Create a new Notes instance
+    pub(crate) fn new(repo: &'a Repository, notes_ref: NotesRef) -> Self {
+        let ref_arg = format!("--ref={}", notes_ref);
+        Self {
+            repo,
+            notes_ref,
+            ref_arg,
+        }
+    }
+
+    /// The notes ref these operations use
+    pub fn notes_ref(&self) -> &NotesRef {
+        &self.notes_ref
+    }
+
+    /// Attach a note to `hash`; fails if it already has one
+    pub fn add(&self, hash: &Hash, text: &str) -> Result<()> {
+        self.write_note("add", &[], hash, text)
+    }
+
+    /// Attach a note to `hash`, replacing any existing note
+    pub fn set(&self, hash: &Hash, text: &str) -> Result<()> {
+        self.write_note("add", &["--force"], hash, text)
+    }
+
+    /// Append a paragraph to the note of `hash`, creating it if needed
+    pub fn append(&self, hash: &Hash, text: &str) -> Result<()> {
+        self.write_note("append", &[], hash, text)
+    }
+
+    /// Get the note text of `hash`, or `None` if it has no note
+    pub fn show(&self, hash: &Hash) -> Result<Option<String>> {
+        self.repo.check_git()?;
+        validate_arg("revision", hash.as_str())?;
+
+        let args = self.args(&["show", hash.as_str()]);
+        let output = git_raw(&args, Some(self.repo))?;
+        if output.status.success() {
+            let text = String::from_utf8_lossy(&output.stdout);
+            return Ok(Some(text.trim_end_matches('\n').to_string()));
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no note found") {
+            return Ok(None);
+        }
+        Err(GitError::CommandFailed(format!(
+            "git notes show failed: {}",
+            stderr.trim()
+        )))
+    }
+
+    /// List every object that has a note
+    pub fn list(&self) -> Result<Vec<Note>> {
+        self.repo.check_git()?;
+
+        let output = git(&self.args(&["list"]), Some(self.repo))?;
+        output
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (blob, object) = line.split_once(' ').ok_or_else(|| {
+                    GitError::CommandFailed(format!("Invalid notes list entry: {}", line))
+                })?;
+                Ok(Note {
+                    object: Hash::from(object),
+                    blob: Hash::from(blob),
+                })
+            })
+            .collect()
+    }
+
+    /// Remove the note of `hash`; fails if it has none
+    pub fn remove(&self, hash: &Hash) -> Result<()> {
+        self.repo.check_git()?;
+        validate_arg("revision", hash.as_str())?;
+
+        git(&self.args(&["remove", hash.as_str()]), Some(self.repo))?;
+        Ok(())
+    }
+
+    /// Run `git notes add` or `append` with `text` as the message
+    fn write_note(&self, command: &str, flags: &[&str], hash: &Hash, text: &str) -> Result<()> {
+        self.repo.check_git()?;
+        validate_arg("revision", hash.as_str())?;
+        if text.trim().is_empty() {
+            return Err(GitError::CommandFailed(
+                "Note text cannot be empty".to_string(),
+            ));
+        }
+
+        let mut subcommand = vec![command];
+        subcommand.extend_from_slice(flags);
+        subcommand.extend_from_slice(&["-m", text, hash.as_str()]);
+
+        git(&self.args(&subcommand), Some(self.repo))?;
+        Ok(())
+    }
+
+    /// Prefix a `git notes` subcommand with the selected notes ref
+    fn args<'b>(&'b self, subcommand: &[&'b str]) -> Vec<&'b str> {
+        let mut args = vec!["notes", self.ref_arg.as_str()];
+        args.extend_from_slice(subcommand);
+        args
+    }
+}
+
+impl Repository {
+    /// Access notes stored under `notes_ref`.
+    ///
+    /// The `note_*` methods use the default `refs/notes/commits`.
+    pub fn notes(&self, notes_ref: NotesRef) -> Notes<'_> {
+        Notes::new(self, notes_ref)
+    }
+
+    /// Attach a note to `hash` under `refs/notes/commits`; fails if it already has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The object to annotate, usually a commit
+    /// * `text` - The note text
+    pub fn note_add(&self, hash: &Hash, text: &str) -> Result<()> {
+        self.notes(NotesRef::default()).add(hash, text)
+    }
+
+    /// Get the note attached to `hash` under `refs/notes/commits`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the note text, or `None` if `hash` has no note.
+    pub fn note_show(&self, hash: &Hash) -> Result<Option<String>> {
+        self.notes(NotesRef::default()).show(hash)
+    }
+
+    /// List the objects that have a note under `refs/notes/commits`.
+    pub fn note_list(&self) -> Result<Vec<Note>> {
+        self.notes(NotesRef::default()).list()
+    }
+
+    /// Remove the note attached to `hash` under `refs/notes/commits`.
+    pub fn note_remove(&self, hash: &Hash) -> Result<()> {
+        self.notes(NotesRef::default()).remove(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn test_notes_ref() {
+        assert_eq!(NotesRef::default().as_str(), "refs/notes/commits");
+        assert_eq!(NotesRef::new("ci").as_str(), "refs/notes/ci");
+        assert_eq!(NotesRef::new("refs/notes/ci"), NotesRef::new("ci"));
+        assert_eq!(NotesRef::new("ci").to_string(), "refs/notes/ci");
+    }
+
+    #[test]
+    fn test_note_add_show_list_remove() {
+        let repo = TempRepo::new("notes_default").unwrap();
+        let first = repo.commit_file("a.txt", "a\n", "First").unwrap();
+        let second = repo.commit_file("b.txt", "b\n", "Second").unwrap();
+
+        assert_eq!(repo.note_show(&first).unwrap(), None);
+        assert!(repo.note_list().unwrap().is_empty());
+
+        repo.note_add(&first, "build: 42\nstatus: passed").unwrap();
+        assert_eq!(
+            repo.note_show(&first).unwrap().as_deref(),
+            Some("build: 42\nstatus: passed")
+        );
+        // Adding twice fails; the commit hash is unchanged
+        assert!(repo.note_add(&first, "again").is_err());
+        assert!(repo.note_add(&second, "  ").is_err());
+        assert_eq!(repo.head_hash().unwrap(), second);
+
+        let notes = repo.note_list().unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].object, first);
+
+        repo.note_remove(&first).unwrap();
+        assert_eq!(repo.note_show(&first).unwrap(), None);
+        assert!(repo.note_remove(&first).is_err());
+        assert!(repo.note_show(&Hash::from("--all")).is_err());
+    }
+
+    #[test]
+    fn test_notes_custom_ref() {
+        let repo = TempRepo::new("notes_custom_ref").unwrap();
+        let head = repo.commit_file("a.txt", "a\n", "First").unwrap();
+
+        let ci = repo.notes(NotesRef::new("ci"));
+        assert_eq!(ci.notes_ref().as_str(), "refs/notes/ci");
+        ci.add(&head, "build: failed").unwrap();
+        ci.set(&head, "build: passed").unwrap();
+        ci.append(&head, "deployed: staging").unwrap();
+        assert_eq!(
+            ci.show(&head).unwrap().as_deref(),
+            Some("build: passed\n\ndeployed: staging")
+        );
+
+        // Notes under other refs are separate
+        assert_eq!(repo.note_show(&head).unwrap(), None);
+        assert!(repo.note_list().unwrap().is_empty());
+        assert_eq!(ci.list().unwrap().len(), 1);
+    }
+}
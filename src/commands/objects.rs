@@ -0,0 +1,1287 @@
+//! Object database queries
+//!
+//! This module provides cheap checks against the object database built on
+//! `git cat-file -e`, so sync logic can test whether a fetch is needed before running one,
+//! revision resolution (`resolve()`, `resolve_to_type()`, `head()`) built on
+//! `git rev-parse --verify`,
+//! and per-path metadata (mode, stage, blob size) from the index and from trees for
+//! permission-audit tooling. Repositories that borrow objects from a shared store through
+//! `objects/info/alternates` can list those stores with `alternates()`, and
+//! `largest_blobs()` reports the biggest files anywhere in history for repository size
+//! diagnostics, and `binary_files()` lists the binary files in a commit for
+//! repository-hygiene checks.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{Hash, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! // Only fetch when the advertised commit is missing locally
+//! let remote_tip = Hash::from("a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2");
+//! if !repo.has_commit(&remote_tip)? {
+//!     repo.fetch("origin")?;
+//! }
+//!
+//! // Check for any object type (blob, tree, commit, or tag)
+//! let exists = repo.object_exists("HEAD:README.md")?;
+//! println!("README.md blob exists: {}", exists);
+//!
+//! // Resolve revisions safely, failing on unknown names
+//! let release = repo.resolve("v1.0^{commit}")?;
+//! let (_, kind) = repo.resolve_to_type("v1.0")?;
+//! println!("v1.0 is a {} at commit {}", kind, release.short());
+//!
+//! // Find executable files committed at HEAD
+//! for entry in repo.tree_entries("HEAD")? {
+//!     if entry.mode.is_executable() {
+//!         println!("{} ({:?} bytes)", entry.path.display(), entry.size);
+//!     }
+//! }
+//!
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, git_raw, git_raw_with_stdin, validate_arg};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The mode of an entry in the index or a tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileMode {
+    /// Regular file (100644)
+    Regular,
+    /// Executable file (100755)
+    Executable,
+    /// Symbolic link (120000)
+    Symlink,
+    /// Submodule commit (160000)
+    Gitlink,
+    /// Subdirectory (040000)
+    Tree,
+    /// Any other mode, stored as its octal value
+    Other(u32),
+}
+
+impl FileMode {
+    /// Parse an octal mode string such as `"100644"`
+    pub fn from_octal(mode: &str) -> Option<Self> {
+        let value = u32::from_str_radix(mode, 8).ok()?;
+        Some(match value {
+            0o100644 => FileMode::Regular,
+            0o100755 => FileMode::Executable,
+            0o120000 => FileMode::Symlink,
+            0o160000 => FileMode::Gitlink,
+            0o040000 => FileMode::Tree,
+            other => FileMode::Other(other),
+        })
+    }
+
+    /// Get the numeric mode value
+    pub const fn bits(&self) -> u32 {
+        match self {
+            FileMode::Regular => 0o100644,
+            FileMode::Executable => 0o100755,
+            FileMode::Symlink => 0o120000,
+            FileMode::Gitlink => 0o160000,
+            FileMode::Tree => 0o040000,
+            FileMode::Other(value) => *value,
+        }
+    }
+
+    /// Check if this is an executable file
+    pub const fn is_executable(&self) -> bool {
+        matches!(self, FileMode::Executable)
+    }
+
+    /// Check if this is a symbolic link
+    pub const fn is_symlink(&self) -> bool {
+        matches!(self, FileMode::Symlink)
+    }
+
+    /// Check if this is a submodule
+    pub const fn is_gitlink(&self) -> bool {
+        matches!(self, FileMode::Gitlink)
+    }
+}
+
+impl fmt::Display for FileMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:06o}", self.bits())
+    }
+}
+
+/// The type of a git object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectType {
+    /// A commit, pointing at a tree and its parents
+    Commit,
+    /// A directory listing of blobs and subtrees
+    Tree,
+    /// File contents
+    Blob,
+    /// An annotated tag
+    Tag,
+}
+
+impl ObjectType {
+    /// Parse a type name as printed by git, such as `"commit"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "commit" => Some(ObjectType::Commit),
+            "tree" => Some(ObjectType::Tree),
+            "blob" => Some(ObjectType::Blob),
+            "tag" => Some(ObjectType::Tag),
+            _ => None,
+        }
+    }
+
+    /// Get the type name as printed by git
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ObjectType::Commit => "commit",
+            ObjectType::Tree => "tree",
+            ObjectType::Blob => "blob",
+            ObjectType::Tag => "tag",
+        }
+    }
+}
+
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An entry in the index, as reported by `git ls-files -s`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// The file mode
+    pub mode: FileMode,
+    /// The object hash staged for this path
+    pub hash: Hash,
+    /// The merge stage (0 normally, 1-3 for conflicted paths)
+    pub stage: u8,
+    /// The path relative to the repository root
+    pub path: PathBuf,
+}
+
+impl IndexEntry {
+    /// Check if this entry is part of an unresolved merge conflict
+    pub fn is_conflicted(&self) -> bool {
+        self.stage != 0
+    }
+}
+
+/// An entry in a tree, as reported by `git ls-tree -l`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    /// The file mode
+    pub mode: FileMode,
+    /// The object hash
+    pub hash: Hash,
+    /// The blob size in bytes (`None` for submodules and subtrees)
+    pub size: Option<u64>,
+    /// The path relative to the repository root
+    pub path: PathBuf,
+}
+
+/// A blob found in history, as reported by [`Repository::largest_blobs`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeBlob {
+    /// The blob hash
+    pub hash: Hash,
+    /// The uncompressed size in bytes
+    pub size: u64,
+    /// The size in bytes the blob occupies on disk, after compression and deltification
+    pub disk_size: u64,
+    /// The first path this blob was seen at while walking history from the newest commits
+    pub path: PathBuf,
+}
+
+impl Repository {
+    /// Check whether a commit exists in the local object database.
+    ///
+    /// Equivalent to `git cat-file -e <hash>^{commit}`. Returns `false` if the object
+    /// is missing or is not a commit (e.g. a blob or tree with the same hash).
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The commit hash to look up
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if the commit exists locally.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{Hash, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let hash = Hash::from("a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2");
+    /// if !repo.has_commit(&hash)? {
+    ///     println!("Need to fetch {}", hash.short());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn has_commit(&self, hash: &Hash) -> Result<bool> {
+        self.check_git()?;
+
+        self.probe_object(&format!("{}^{{commit}}", hash.as_str()))
+    }
+
+    /// Check whether an object of any type exists in the local object database.
+    ///
+    /// Equivalent to `git cat-file -e <oid>`. Any revision that resolves to an
+    /// object is accepted, such as a full or abbreviated hash or `HEAD:path`.
+    /// A `rev:path` whose path is not in the tree is reported as an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `oid` - The object id or revision to look up
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if the object exists locally.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let exists = repo.object_exists("a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2")?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn object_exists(&self, oid: &str) -> Result<bool> {
+        self.check_git()?;
+        validate_arg("object name", oid)?;
+
+        self.probe_object(oid)
+    }
+
+    /// Expand an abbreviated object name to the full object id.
+    ///
+    /// Unlike [`CommitLog::find_by_short_hash`](crate::CommitLog::find_by_short_hash), this
+    /// searches the whole object database (objects of any type), using
+    /// `git rev-parse --disambiguate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - At least 4 hexadecimal characters of the object id
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the full `Hash`, `GitError::Ambiguous` listing every match
+    /// if the prefix is not unique, or `GitError::CommandFailed` if it is not a valid
+    /// prefix or matches nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{GitError, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// match repo.expand_hash("a1b2c3") {
+    ///     Ok(hash) => println!("Full id: {}", hash),
+    ///     Err(GitError::Ambiguous { candidates, .. }) => {
+    ///         println!("{} objects match, use more characters", candidates.len())
+    ///     }
+    ///     Err(e) => return Err(e),
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn expand_hash(&self, prefix: &str) -> Result<Hash> {
+        self.check_git()?;
+
+        // Git silently matches nothing for prefixes shorter than 4 characters
+        let prefix = prefix.trim();
+        if prefix.len() < 4 || prefix.len() > 64 || !prefix.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(GitError::CommandFailed(format!(
+                "Invalid abbreviated object name: '{}'",
+                prefix
+            )));
+        }
+
+        let disambiguate = format!("--disambiguate={}", prefix.to_ascii_lowercase());
+        let output = git(&["rev-parse", &disambiguate], Some(self))?;
+        let mut candidates: Vec<Hash> = output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(Hash::from)
+            .collect();
+
+        match candidates.len() {
+            0 => Err(GitError::CommandFailed(format!(
+                "No object matches '{}'",
+                prefix
+            ))),
+            1 => Ok(candidates.remove(0)),
+            _ => {
+                candidates.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+                Err(GitError::Ambiguous {
+                    prefix: prefix.to_string(),
+                    candidates,
+                })
+            }
+        }
+    }
+
+    /// Resolve a revision to the object it names.
+    ///
+    /// Equivalent to `git rev-parse --verify <revspec>`. Any revision syntax is accepted,
+    /// such as `HEAD~2`, `main@{upstream}`, `v1.0^{commit}`, or `HEAD:README.md`.
+    /// Annotated tags resolve to the tag object; append `^{commit}` to peel them.
+    ///
+    /// # Arguments
+    ///
+    /// * `revspec` - The revision to resolve
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the full `Hash`, or a `GitError` if the revision does not
+    /// name an object.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let parent = repo.resolve("HEAD~1")?;
+    /// println!("HEAD~1 is {}", parent.short());
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn resolve(&self, revspec: &str) -> Result<Hash> {
+        self.check_git()?;
+        validate_arg("revision", revspec)?;
+
+        self.try_resolve(revspec)?
+            .ok_or_else(|| GitError::CommandFailed(format!("Unknown revision: '{}'", revspec)))
+    }
+
+    /// Resolve a revision and report the type of the object it names.
+    ///
+    /// Equivalent to `git cat-file --batch-check` fed the revision, so the same syntax
+    /// as [`resolve`](Self::resolve) is accepted in a single call.
+    ///
+    /// # Arguments
+    ///
+    /// * `revspec` - The revision to resolve
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the full `Hash` and its `ObjectType`, or a `GitError` if
+    /// the revision does not name an object.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{ObjectType, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let (hash, kind) = repo.resolve_to_type("v1.0")?;
+    /// if kind == ObjectType::Tag {
+    ///     println!("v1.0 is an annotated tag ({})", hash.short());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn resolve_to_type(&self, revspec: &str) -> Result<(Hash, ObjectType)> {
+        self.check_git()?;
+        validate_arg("revision", revspec)?;
+        if revspec.contains('\n') {
+            return Err(GitError::CommandFailed(format!(
+                "Invalid revision: '{}'",
+                revspec.escape_debug()
+            )));
+        }
+
+        let input = format!("{}\n", revspec);
+        let output =
+            git_raw_with_stdin(&["cat-file", "--batch-check"], Some(self), input.as_bytes())?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git cat-file --batch-check failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let line = String::from_utf8_lossy(&output.stdout);
+        parse_batch_check(line.trim_end())
+            .ok_or_else(|| GitError::CommandFailed(format!("Unknown revision: '{}'", revspec)))
+    }
+
+    /// Get the commit HEAD points at.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the commit, or `None` if HEAD is unborn (no commits yet
+    /// on the current branch).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// match repo.head()? {
+    ///     Some(head) => println!("HEAD is at {}", head.short()),
+    ///     None => println!("No commits yet"),
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn head(&self) -> Result<Option<Hash>> {
+        self.check_git()?;
+
+        self.try_resolve("HEAD^{commit}")
+    }
+
+    /// Check whether HEAD is detached, i.e. points at a commit rather than a branch.
+    ///
+    /// Equivalent to testing `git symbolic-ref --quiet HEAD`. An unborn branch is not
+    /// detached.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if HEAD is detached.
+    pub fn head_detached(&self) -> Result<bool> {
+        self.check_git()?;
+
+        let output = git_raw(&["symbolic-ref", "--quiet", "HEAD"], Some(self))?;
+        match output.status.code() {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            _ => Err(GitError::CommandFailed(format!(
+                "git symbolic-ref failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+        }
+    }
+
+    /// List the entries staged in the index with their mode, hash, and stage.
+    ///
+    /// Equivalent to `git ls-files -s`. Conflicted paths appear once per stage.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the index entries in path order.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{FileMode, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let symlinks = repo
+    ///     .index_entries()?
+    ///     .into_iter()
+    ///     .filter(|entry| entry.mode == FileMode::Symlink)
+    ///     .count();
+    /// println!("{} symlinks staged", symlinks);
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn index_entries(&self) -> Result<Vec<IndexEntry>> {
+        self.check_git()?;
+
+        let output = git(&["ls-files", "-s", "-z"], Some(self))?;
+        parse_index_entries(&output)
+    }
+
+    /// List every blob and submodule in a tree with its mode and size.
+    ///
+    /// Equivalent to `git ls-tree -r -l <treeish>`, so nested directories are
+    /// expanded and only leaf entries are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `treeish` - The commit or tree to list (e.g. `"HEAD"`, a tag, or a tree hash)
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the tree entries in path order.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for entry in repo.tree_entries("v1.0.0")? {
+    ///     println!("{} {}", entry.mode, entry.path.display());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn tree_entries(&self, treeish: &str) -> Result<Vec<TreeEntry>> {
+        self.check_git()?;
+        validate_arg("tree-ish", treeish)?;
+
+        let output = git(&["ls-tree", "-r", "-l", "-z", treeish], Some(self))?;
+        parse_tree_entries(&output)
+    }
+
+    /// List the alternate object directories this repository borrows objects from.
+    ///
+    /// Reads `objects/info/alternates`, as written by `git clone --reference` or
+    /// `--shared`. Relative entries are resolved against the objects directory.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the alternate object directories, empty if there are none.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for dir in repo.alternates()? {
+    ///     println!("Borrowing objects from {}", dir.display());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn alternates(&self) -> Result<Vec<PathBuf>> {
+        self.check_git()?;
+
+        let output = git(&["rev-parse", "--git-path", "objects"], Some(self))?;
+        let objects_dir = self.command_dir().join(output.trim());
+        let alternates_file = objects_dir.join("info").join("alternates");
+        if !alternates_file.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(&alternates_file)?;
+        Ok(parse_alternates(&content, &objects_dir))
+    }
+
+    /// Find the largest blobs reachable from any ref.
+    ///
+    /// Walks all objects with `git rev-list --objects --all` and sizes them with a single
+    /// batched `git cat-file --batch-check` call. Each blob is reported once, with the
+    /// first path it was found at, even if it appears under several names.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of blobs to return
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the blobs, largest uncompressed size first.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for blob in repo.largest_blobs(10)? {
+    ///     println!("{:>12} {}", blob.size, blob.path.display());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn largest_blobs(&self, limit: usize) -> Result<Vec<LargeBlob>> {
+        self.check_git()?;
+
+        let objects = git(&["rev-list", "--objects", "--all"], Some(self))?;
+        if objects.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let output = git_raw_with_stdin(
+            &[
+                "cat-file",
+                "--batch-check=%(objecttype) %(objectname) %(objectsize) %(objectsize:disk) %(rest)",
+            ],
+            Some(self),
+            objects.as_bytes(),
+        )?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git cat-file failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let mut blobs = parse_blob_sizes(&String::from_utf8_lossy(&output.stdout))?;
+        blobs.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        blobs.truncate(limit);
+        Ok(blobs)
+    }
+
+    /// List the files in a commit or tree that git considers binary.
+    ///
+    /// Uses the same rules as `git diff`: a `binary` or `-diff` attribute, or a NUL byte
+    /// in the first few kilobytes of content, marks a file as binary, and a `diff` or
+    /// `text` attribute overrides the content check. Found by diffing the tree against
+    /// the empty tree with `--numstat`, which reports binary files as `-`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rev` - The commit or tree to inspect (e.g. `"HEAD"` or a tag)
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the binary paths in path order, relative to the repository
+    /// root (or absolute, per the path style).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for path in repo.binary_files("HEAD")? {
+    ///     println!("binary file committed: {}", path.display());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn binary_files(&self, rev: &str) -> Result<Vec<PathBuf>> {
+        self.check_git()?;
+        validate_arg("revision", rev)?;
+
+        // The empty tree id depends on the repository's hash algorithm
+        let output =
+            git_raw_with_stdin(&["hash-object", "-t", "tree", "--stdin"], Some(self), b"")?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git hash-object failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        let empty_tree = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+        let output = git(
+            &["diff-tree", "-r", "--numstat", "-z", &empty_tree, rev],
+            Some(self),
+        )?;
+        Ok(parse_binary_numstat(&output)
+            .into_iter()
+            .map(|path| self.styled_path(self.repo_path(), path))
+            .collect())
+    }
+
+    /// Run `git cat-file -e` and map its exit code to existence
+    fn probe_object(&self, spec: &str) -> Result<bool> {
+        let output = git_raw(&["cat-file", "-e", spec], Some(self))?;
+
+        if output.status.success() {
+            return Ok(true);
+        }
+
+        // Missing well-formed ids exit with 1; unresolvable names exit with 128 and
+        // a "Not a valid object name" message. Anything else is a real failure.
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match output.status.code() {
+            Some(1) => Ok(false),
+            Some(128) if stderr.contains("Not a valid object name") => Ok(false),
+            _ => Err(GitError::CommandFailed(format!(
+                "git cat-file failed: {}",
+                stderr
+            ))),
+        }
+    }
+
+    /// Run `git rev-parse --verify --quiet`, mapping an unknown revision to `None`
+    fn try_resolve(&self, revspec: &str) -> Result<Option<Hash>> {
+        let output = git_raw(&["rev-parse", "--verify", "--quiet", revspec], Some(self))?;
+
+        match output.status.code() {
+            Some(0) => Ok(Some(Hash::from(
+                String::from_utf8_lossy(&output.stdout).trim(),
+            ))),
+            Some(1) => Ok(None),
+            _ => Err(GitError::CommandFailed(format!(
+                "git rev-parse failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+        }
+    }
+
+    /// Read the contents of several objects with one `git cat-file --batch`
+    ///
+    /// Each spec is any revision naming an object, such as `<commit>:<path>`; specs
+    /// that do not resolve give `None`.
+    pub(crate) fn read_objects(&self, specs: &[String]) -> Result<Vec<Option<Vec<u8>>>> {
+        if specs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut input = specs.join("\n");
+        input.push('\n');
+        let output = git_raw_with_stdin(&["cat-file", "--batch"], Some(self), input.as_bytes())?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git cat-file --batch failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        parse_batch_output(&output.stdout, specs.len())
+    }
+}
+
+/// Parse a `cat-file --batch-check` line: `<oid> <type> <size>`, or `<name> missing`
+/// (or `ambiguous`) when the name does not resolve
+fn parse_batch_check(line: &str) -> Option<(Hash, ObjectType)> {
+    let mut fields = line.split(' ');
+    let (hash, kind, size) = (fields.next()?, fields.next()?, fields.next()?);
+    if size.parse::<u64>().is_err() {
+        return None;
+    }
+    Some((Hash::from(hash), ObjectType::from_name(kind)?))
+}
+
+/// Parse `cat-file --batch` output: `<oid> <type> <size>` and the contents per found
+/// object, or `<name> missing` (or `ambiguous`) otherwise
+fn parse_batch_output(output: &[u8], count: usize) -> Result<Vec<Option<Vec<u8>>>> {
+    let malformed = || GitError::CommandFailed("Malformed git cat-file --batch output".to_string());
+    let mut objects = Vec::with_capacity(count);
+    let mut rest = output;
+
+    while objects.len() < count {
+        let newline = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(malformed)?;
+        let header = String::from_utf8_lossy(&rest[..newline]);
+        rest = &rest[newline + 1..];
+
+        if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+            objects.push(None);
+            continue;
+        }
+
+        let size: usize = header
+            .rsplit(' ')
+            .next()
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(malformed)?;
+        if rest.len() < size + 1 {
+            return Err(malformed());
+        }
+        objects.push(Some(rest[..size].to_vec()));
+        rest = &rest[size + 1..];
+    }
+
+    Ok(objects)
+}
+
+/// Collect paths with `-\t-` counts from NUL-separated `--numstat -z` output
+fn parse_binary_numstat(output: &str) -> Vec<PathBuf> {
+    output
+        .split('\0')
+        .filter_map(|record| record.strip_prefix("-\t-\t"))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Parse NUL-separated `git ls-files -s -z` output ("<mode> <hash> <stage>\t<path>")
+fn parse_index_entries(output: &str) -> Result<Vec<IndexEntry>> {
+    let mut entries = Vec::new();
+
+    for record in output.split('\0').filter(|r| !r.is_empty()) {
+        let Some((meta, path)) = record.split_once('\t') else {
+            continue; // Skip malformed records
+        };
+
+        let fields: Vec<&str> = meta.split(' ').collect();
+        if fields.len() != 3 {
+            continue;
+        }
+
+        let mode = parse_mode(fields[0])?;
+        let stage = fields[2]
+            .parse()
+            .map_err(|_| GitError::CommandFailed(format!("Invalid index stage: {}", fields[2])))?;
+
+        entries.push(IndexEntry {
+            mode,
+            hash: Hash::from(fields[1]),
+            stage,
+            path: PathBuf::from(path),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Parse NUL-separated `git ls-tree -l -z` output ("<mode> <type> <hash> <size>\t<path>")
+fn parse_tree_entries(output: &str) -> Result<Vec<TreeEntry>> {
+    let mut entries = Vec::new();
+
+    for record in output.split('\0').filter(|r| !r.is_empty()) {
+        let Some((meta, path)) = record.split_once('\t') else {
+            continue; // Skip malformed records
+        };
+
+        // The size column is right-aligned with padding spaces
+        let fields: Vec<&str> = meta.split_whitespace().collect();
+        if fields.len() != 4 {
+            continue;
+        }
+
+        entries.push(TreeEntry {
+            mode: parse_mode(fields[0])?,
+            hash: Hash::from(fields[2]),
+            size: fields[3].parse().ok(),
+            path: PathBuf::from(path),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Parse `git cat-file --batch-check` output in the format used by `largest_blobs`
+/// ("<type> <hash> <size> <disk size> <path>"), keeping only blobs with a path
+fn parse_blob_sizes(output: &str) -> Result<Vec<LargeBlob>> {
+    let mut blobs = Vec::new();
+
+    for line in output.lines() {
+        let mut fields = line.splitn(5, ' ');
+        let (Some("blob"), Some(hash), Some(size), Some(disk_size), Some(path)) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            continue; // Commits, trees, and tags
+        };
+        if path.is_empty() {
+            continue;
+        }
+
+        let parse_size = |value: &str| {
+            value
+                .parse()
+                .map_err(|_| GitError::CommandFailed(format!("Invalid object size: {}", value)))
+        };
+        blobs.push(LargeBlob {
+            hash: Hash::from(hash),
+            size: parse_size(size)?,
+            disk_size: parse_size(disk_size)?,
+            path: PathBuf::from(path),
+        });
+    }
+
+    Ok(blobs)
+}
+
+/// Parse an `objects/info/alternates` file, skipping comments and blank lines
+fn parse_alternates(content: &str, objects_dir: &Path) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| objects_dir.join(line))
+        .collect()
+}
+
+/// Parse an octal mode field, reporting unparseable values as errors
+fn parse_mode(mode: &str) -> Result<FileMode> {
+    FileMode::from_octal(mode)
+        .ok_or_else(|| GitError::CommandFailed(format!("Invalid file mode: {}", mode)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::{env, fs};
+
+    fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
+        let temp_dir = env::temp_dir().join(format!("rustic_git_objects_test_{}", test_name));
+
+        // Clean up if exists
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+
+        // Configure git user for testing
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_file_mode_from_octal() {
+        assert_eq!(FileMode::from_octal("100644"), Some(FileMode::Regular));
+        assert_eq!(FileMode::from_octal("100755"), Some(FileMode::Executable));
+        assert_eq!(FileMode::from_octal("120000"), Some(FileMode::Symlink));
+        assert_eq!(FileMode::from_octal("160000"), Some(FileMode::Gitlink));
+        assert_eq!(FileMode::from_octal("040000"), Some(FileMode::Tree));
+        assert_eq!(
+            FileMode::from_octal("100664"),
+            Some(FileMode::Other(0o100664))
+        );
+        assert_eq!(FileMode::from_octal("abc"), None);
+
+        assert_eq!(FileMode::Regular.to_string(), "100644");
+        assert_eq!(FileMode::Tree.to_string(), "040000");
+        assert!(FileMode::Executable.is_executable());
+        assert!(FileMode::Symlink.is_symlink());
+        assert!(FileMode::Gitlink.is_gitlink());
+    }
+
+    #[test]
+    fn test_parse_index_entries() {
+        let output = "100644 aaa 1\tconflict.txt\x00100644 bbb 2\tconflict.txt\0\
+                      100755 ccc 0\tscript with space.sh\0";
+        let entries = parse_index_entries(output).unwrap();
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].stage, 1);
+        assert!(entries[0].is_conflicted());
+        assert_eq!(entries[2].mode, FileMode::Executable);
+        assert_eq!(entries[2].hash.as_str(), "ccc");
+        assert_eq!(entries[2].path, PathBuf::from("script with space.sh"));
+        assert!(!entries[2].is_conflicted());
+    }
+
+    #[test]
+    fn test_parse_tree_entries() {
+        let output =
+            "100644 blob aaa      12\tsrc/lib.rs\x00160000 commit bbb       -\tvendor/dep\0";
+        let entries = parse_tree_entries(output).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].mode, FileMode::Regular);
+        assert_eq!(entries[0].size, Some(12));
+        assert_eq!(entries[0].path, PathBuf::from("src/lib.rs"));
+        assert_eq!(entries[1].mode, FileMode::Gitlink);
+        assert_eq!(entries[1].size, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_alternates() {
+        let objects_dir = Path::new("/work/repo/.git/objects");
+        let content = "# shared store\n/srv/cache/objects\n\n../../other/.git/objects\n";
+        assert_eq!(
+            parse_alternates(content, objects_dir),
+            vec![
+                PathBuf::from("/srv/cache/objects"),
+                PathBuf::from("/work/repo/.git/objects/../../other/.git/objects"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_object_type_names() {
+        for kind in [
+            ObjectType::Commit,
+            ObjectType::Tree,
+            ObjectType::Blob,
+            ObjectType::Tag,
+        ] {
+            assert_eq!(ObjectType::from_name(kind.as_str()), Some(kind));
+        }
+        assert_eq!(ObjectType::from_name("note"), None);
+        assert_eq!(ObjectType::Blob.to_string(), "blob");
+    }
+
+    #[test]
+    fn test_parse_batch_check() {
+        assert_eq!(
+            parse_batch_check("aaaa commit 230"),
+            Some((Hash::from("aaaa"), ObjectType::Commit))
+        );
+        assert_eq!(parse_batch_check("HEAD:gone.txt missing"), None);
+        assert_eq!(parse_batch_check("abc ambiguous"), None);
+        // Names with spaces that do not resolve
+        assert_eq!(parse_batch_check("HEAD:a b c missing"), None);
+    }
+
+    #[test]
+    fn test_resolve_revisions() {
+        let (temp_dir, repo) = create_test_repo("resolve");
+
+        assert_eq!(repo.head().unwrap(), None);
+        assert!(!repo.head_detached().unwrap());
+        assert!(repo.resolve("HEAD").is_err());
+
+        fs::write(temp_dir.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let first = repo.commit("First").unwrap();
+        fs::write(temp_dir.join("file.txt"), "changed").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let second = repo.commit("Second").unwrap();
+        repo.create_tag_with_options(
+            "v1.0",
+            None,
+            crate::TagOptions::new().with_message("Release".to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(repo.head().unwrap(), Some(second.clone()));
+        assert_eq!(repo.resolve("HEAD~1").unwrap(), first);
+        assert_eq!(repo.resolve("v1.0^{commit}").unwrap(), second);
+        assert_ne!(repo.resolve("v1.0").unwrap(), second);
+
+        assert_eq!(
+            repo.resolve_to_type("HEAD").unwrap(),
+            (second.clone(), ObjectType::Commit)
+        );
+        assert_eq!(repo.resolve_to_type("v1.0").unwrap().1, ObjectType::Tag);
+        assert_eq!(
+            repo.resolve_to_type("HEAD^{tree}").unwrap().1,
+            ObjectType::Tree
+        );
+        let (blob, kind) = repo.resolve_to_type("HEAD:file.txt").unwrap();
+        assert_eq!(kind, ObjectType::Blob);
+        assert_eq!(repo.resolve("HEAD:file.txt").unwrap(), blob);
+
+        assert!(repo.resolve("does-not-exist").is_err());
+        assert!(repo.resolve_to_type("HEAD:missing.txt").is_err());
+        assert!(repo.resolve("--all").is_err());
+        assert!(repo.resolve_to_type("HEAD\nHEAD").is_err());
+
+        crate::utils::git(&["checkout", "--detach", "HEAD~1"], Some(&temp_dir)).unwrap();
+        assert!(repo.head_detached().unwrap());
+        assert_eq!(repo.head().unwrap(), Some(first.clone()));
+        assert!(repo.is_ancestor(first.as_str(), second.as_str()).unwrap());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_batch_output() {
+        let output = b"aaaa blob 5\nab\ncd\nHEAD:gone.txt missing\nbbbb blob 0\n\n";
+        let objects = parse_batch_output(output, 3).unwrap();
+        assert_eq!(
+            objects,
+            vec![Some(b"ab\ncd".to_vec()), None, Some(Vec::new())]
+        );
+
+        assert!(parse_batch_output(b"aaaa blob 10\nshort\n", 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_blob_sizes() {
+        let output = "commit aaaa 200 150 \n\
+                      tree bbbb 80 70 \n\
+                      blob cccc 1024 300 assets/big file.bin\n\
+                      blob dddd 12 20 README.md\n";
+        let blobs = parse_blob_sizes(output).unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(blobs[0].hash, Hash::from("cccc"));
+        assert_eq!(blobs[0].size, 1024);
+        assert_eq!(blobs[0].disk_size, 300);
+        assert_eq!(blobs[0].path, Path::new("assets/big file.bin"));
+        assert_eq!(blobs[1].path, Path::new("README.md"));
+
+        assert!(parse_blob_sizes("blob eeee huge 1 x\n").is_err());
+    }
+
+    #[test]
+    fn test_largest_blobs() {
+        let (test_path, repo) = create_test_repo("largest_blobs");
+        assert!(repo.largest_blobs(5).unwrap().is_empty());
+
+        fs::write(test_path.join("big.bin"), "x".repeat(5000)).unwrap();
+        fs::write(test_path.join("small.txt"), "tiny").unwrap();
+        repo.add(&["big.bin", "small.txt"]).unwrap();
+        repo.commit("Add files").unwrap();
+
+        // Deleted files still count because they remain in history
+        repo.rm(&["big.bin"]).unwrap();
+        fs::write(test_path.join("medium.txt"), "y".repeat(500)).unwrap();
+        repo.add(&["medium.txt"]).unwrap();
+        repo.commit("Remove big file").unwrap();
+
+        let blobs = repo.largest_blobs(2).unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(blobs[0].path, Path::new("big.bin"));
+        assert_eq!(blobs[0].size, 5000);
+        assert!(blobs[0].disk_size > 0);
+        assert_eq!(blobs[1].path, Path::new("medium.txt"));
+
+        assert_eq!(repo.largest_blobs(10).unwrap().len(), 3);
+
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_binary_files() {
+        let (test_path, repo) = create_test_repo("binary_files");
+
+        fs::write(test_path.join("notes.txt"), "plain text\n").unwrap();
+        fs::write(test_path.join("image.png"), b"\x89PNG\r\n\x1a\n\x00\x00").unwrap();
+        fs::write(test_path.join("data.dat"), "looks like text\n").unwrap();
+        fs::write(test_path.join("dump.log"), b"log\x00with nul\n").unwrap();
+        fs::write(
+            test_path.join(".gitattributes"),
+            "*.dat binary\n*.log text diff\n",
+        )
+        .unwrap();
+        repo.add_all().unwrap();
+        repo.commit("Add files").unwrap();
+
+        assert_eq!(
+            repo.binary_files("HEAD").unwrap(),
+            vec![PathBuf::from("data.dat"), PathBuf::from("image.png")]
+        );
+        assert!(repo.binary_files("HEAD~1").is_err());
+
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_binary_numstat() {
+        let output = "1\t0\tnotes.txt\0-\t-\tassets/logo.png\0-\t-\twith space.bin\0";
+        assert_eq!(
+            parse_binary_numstat(output),
+            vec![
+                PathBuf::from("assets/logo.png"),
+                PathBuf::from("with space.bin")
+            ]
+        );
+        assert!(parse_binary_numstat("").is_empty());
+    }
+
+    #[test]
+    fn test_index_and_tree_entries() {
+        use std::os::unix::fs::{PermissionsExt, symlink};
+
+        let (temp_dir, repo) = create_test_repo("entries");
+
+        fs::create_dir(temp_dir.join("bin")).unwrap();
+        fs::write(temp_dir.join("bin/run.sh"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(
+            temp_dir.join("bin/run.sh"),
+            fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+        fs::write(temp_dir.join("data.txt"), "hello").unwrap();
+        symlink("data.txt", temp_dir.join("link")).unwrap();
+        repo.add_all().unwrap();
+
+        let index = repo.index_entries().unwrap();
+        assert_eq!(index.len(), 3);
+        let find = |path: &str| index.iter().find(|e| e.path == Path::new(path)).unwrap();
+        assert_eq!(find("bin/run.sh").mode, FileMode::Executable);
+        assert_eq!(find("data.txt").mode, FileMode::Regular);
+        assert_eq!(find("link").mode, FileMode::Symlink);
+        assert!(index.iter().all(|e| e.stage == 0));
+
+        repo.commit("Add files").unwrap();
+
+        let tree = repo.tree_entries("HEAD").unwrap();
+        assert_eq!(tree.len(), 3);
+        let data = tree
+            .iter()
+            .find(|e| e.path == Path::new("data.txt"))
+            .unwrap();
+        assert_eq!(data.size, Some(5));
+        assert_eq!(data.hash, find("data.txt").hash);
+
+        assert!(repo.tree_entries("no-such-rev").is_err());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_has_commit() {
+        let (temp_dir, repo) = create_test_repo("has_commit");
+
+        fs::write(temp_dir.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let commit = repo.commit("Initial commit").unwrap();
+
+        assert!(repo.has_commit(&commit).unwrap());
+        assert!(
+            !repo
+                .has_commit(&Hash::from("0123456789abcdef0123456789abcdef01234567"))
+                .unwrap()
+        );
+
+        // A blob hash is not a commit
+        let blob = crate::utils::git(&["rev-parse", "HEAD:file.txt"], Some(&temp_dir)).unwrap();
+        assert!(!repo.has_commit(&Hash::from(blob.trim())).unwrap());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_object_exists() {
+        let (temp_dir, repo) = create_test_repo("object_exists");
+
+        fs::write(temp_dir.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let commit = repo.commit("Initial commit").unwrap();
+
+        assert!(repo.object_exists(commit.as_str()).unwrap());
+        assert!(repo.object_exists("HEAD:file.txt").unwrap());
+        assert!(
+            !repo
+                .object_exists("0123456789abcdef0123456789abcdef01234567")
+                .unwrap()
+        );
+        assert!(!repo.object_exists("not-a-ref").unwrap());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_expand_hash() {
+        let (temp_dir, repo) = create_test_repo("expand_hash");
+
+        fs::write(temp_dir.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let commit = repo.commit("Initial commit").unwrap();
+
+        assert_eq!(repo.expand_hash(&commit.as_str()[..10]).unwrap(), commit);
+        assert_eq!(
+            repo.expand_hash(&commit.as_str()[..10].to_uppercase())
+                .unwrap(),
+            commit
+        );
+
+        // The blobs "195\n" and "389\n" both hash to ids starting with 6bb2
+        for content in ["195\n", "389\n"] {
+            let output = git_raw_with_stdin(
+                &["hash-object", "-w", "--stdin"],
+                Some(&repo),
+                content.as_bytes(),
+            )
+            .unwrap();
+            assert!(output.status.success());
+        }
+        match repo.expand_hash("6bb2") {
+            Err(GitError::Ambiguous { prefix, candidates }) => {
+                assert_eq!(prefix, "6bb2");
+                assert_eq!(candidates.len(), 2);
+                assert!(candidates.iter().all(|c| c.as_str().starts_with("6bb2")));
+            }
+            other => panic!("Expected Ambiguous error, got {:?}", other),
+        }
+
+        assert!(matches!(
+            repo.expand_hash("abc"),
+            Err(GitError::CommandFailed(_))
+        ));
+        assert!(matches!(
+            repo.expand_hash("not-hex"),
+            Err(GitError::CommandFailed(_))
+        ));
+        assert!(matches!(
+            repo.expand_hash("0000000000"),
+            Err(GitError::CommandFailed(_))
+        ));
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -0,0 +1,488 @@
+//! Patch email operations
+//!
+//! This module turns commits into mailbox-formatted patch files with
+//! `git format-patch`, and applies such patches as commits with `git am`, keeping
+//! their authors and messages. Together they move commits between repositories that
+//! share no remote, or through a mailing list. Plain diffs, e.g. generated by a tool,
+//! are applied to the working tree or index with `git apply`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{AmOptions, AmStatus, Repository};
+//!
+//! let upstream = Repository::open("../upstream")?;
+//! let patches = upstream.format_patch("main..topic", "/tmp/patches")?;
+//!
+//! let fork = Repository::open(".")?;
+//! match fork.am_with_options(&patches, AmOptions::new().with_three_way())? {
+//!     AmStatus::Success(hash) => println!("Applied, HEAD is now {}", hash.short()),
+//!     AmStatus::Conflicts(files) => println!("Resolve {:?}, then am_continue()", files),
+//!     AmStatus::Stopped(patch) => {
+//!         println!("{} does not apply", patch);
+//!         fork.am_abort()?;
+//!     }
+//! }
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::merge::extract_conflicted_files;
+use crate::error::{GitError, Result};
+use crate::repository::{Repository, resolve_git_dir};
+use crate::types::Hash;
+use crate::utils::{git, git_raw_with_env, git_raw_with_stdin, validate_arg};
+use std::path::{Path, PathBuf};
+
+/// The result of applying patches with `git am`
+#[derive(Debug, Clone, PartialEq)]
+pub enum AmStatus {
+    /// Every patch was applied; HEAD points at the last new commit
+    Success(Hash),
+    /// A patch was applied with a three-way merge that left conflicts
+    Conflicts(Vec<PathBuf>),
+    /// A patch does not apply, e.g. `0002 Fix parser`; without
+    /// [`AmOptions::with_three_way`] this is how conflicting patches stop
+    Stopped(String),
+}
+
+/// Options for applying a diff with `git apply`
+#[derive(Debug, Clone, Default)]
+pub struct ApplyOptions {
+    cached: bool,
+    three_way: bool,
+    reverse: bool,
+    check: bool,
+}
+
+impl ApplyOptions {
+    /// Create new ApplyOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply to the index only, leaving the working tree alone (`--cached`)
+    pub fn with_cached(mut self) -> Self {
+        self.cached = true;
+        self
+    }
+
+    /// Fall back to a three-way merge when the diff does not apply cleanly (`--3way`)
+    ///
+    /// Conflicts are left in the index and working tree, and reported as an error.
+    pub fn with_three_way(mut self) -> Self {
+        self.three_way = true;
+        self
+    }
+
+    /// Apply the diff in reverse, undoing it (`--reverse`)
+    pub fn with_reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    /// Only check that the diff applies, without changing anything (`--check`)
+    pub fn with_check(mut self) -> Self {
+        self.check = true;
+        self
+    }
+}
+
+/// Options for applying patches
+#[derive(Debug, Clone, Default)]
+pub struct AmOptions {
+    three_way: bool,
+    signoff: bool,
+}
+
+impl AmOptions {
+    /// Create new AmOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fall back to a three-way merge when a patch does not apply cleanly (`--3way`)
+    ///
+    /// Needs the blobs the patch was made against in this repository.
+    pub fn with_three_way(mut self) -> Self {
+        self.three_way = true;
+        self
+    }
+
+    /// Add a `Signed-off-by` trailer for the committer (`--signoff`)
+    pub fn with_signoff(mut self) -> Self {
+        self.signoff = true;
+        self
+    }
+}
+
+impl Repository {
+    /// Write each commit in `range` to a numbered patch file in `out_dir`.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - A range such as `main..topic`, or a single commit to export every
+    ///   commit after it (`HEAD~3` exports the last three)
+    /// * `out_dir` - Directory for the patch files, created if needed; relative paths
+    ///   are resolved against the repository's working directory
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the paths of the patch files, oldest commit first.
+    pub fn format_patch<P: AsRef<Path>>(&self, range: &str, out_dir: P) -> Result<Vec<PathBuf>> {
+        self.check_git()?;
+        validate_arg("revision range", range)?;
+
+        let out_dir = out_dir.as_ref().to_string_lossy();
+        let output = git(&["format-patch", "-o", &out_dir, range], Some(self))?;
+
+        Ok(output
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let path = PathBuf::from(line);
+                if path.is_absolute() {
+                    path
+                } else {
+                    self.command_dir().join(path)
+                }
+            })
+            .collect())
+    }
+
+    /// Apply a unified diff, such as the output of `git diff`.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - The diff text
+    /// * `options` - Where and how to apply it; with [`ApplyOptions::with_check`] an
+    ///   `Ok` result means the diff applies
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{ApplyOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let diff = "--- a/README.md\n+++ b/README.md\n@@ -1 +1 @@\n-Old title\n+New title\n";
+    ///
+    /// // Validate first, then stage the change without touching the working tree
+    /// repo.apply_patch(diff, ApplyOptions::new().with_cached().with_check())?;
+    /// repo.apply_patch(diff, ApplyOptions::new().with_cached())?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn apply_patch(&self, diff: &str, options: ApplyOptions) -> Result<()> {
+        self.check_git()?;
+
+        let mut args = vec!["apply"];
+        if options.cached {
+            args.push("--cached");
+        }
+        if options.three_way {
+            args.push("--3way");
+        }
+        if options.reverse {
+            args.push("--reverse");
+        }
+        if options.check {
+            args.push("--check");
+        }
+
+        let output = git_raw_with_stdin(&args, Some(self), diff.as_bytes())?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git apply failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Apply mailbox patches as commits, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `patches` - Patch files, e.g. from [`format_patch`](Self::format_patch)
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `AmStatus`. Unless it is `Success`, the session is left
+    /// in progress for [`am_continue`](Self::am_continue),
+    /// [`am_skip`](Self::am_skip) or [`am_abort`](Self::am_abort).
+    pub fn am<P: AsRef<Path>>(&self, patches: &[P]) -> Result<AmStatus> {
+        self.am_with_options(patches, AmOptions::new())
+    }
+
+    /// Apply mailbox patches with custom options.
+    pub fn am_with_options<P: AsRef<Path>>(
+        &self,
+        patches: &[P],
+        options: AmOptions,
+    ) -> Result<AmStatus> {
+        self.check_git()?;
+        if patches.is_empty() {
+            return Err(GitError::CommandFailed(
+                "No patches specified to apply".to_string(),
+            ));
+        }
+
+        let patches: Vec<String> = patches
+            .iter()
+            .map(|patch| patch.as_ref().to_string_lossy().to_string())
+            .collect();
+
+        let mut args = vec!["am"];
+        if options.three_way {
+            args.push("--3way");
+        }
+        if options.signoff {
+            args.push("--signoff");
+        }
+        args.push("--");
+        args.extend(patches.iter().map(String::as_str));
+
+        self.am_step(&args)
+    }
+
+    /// Check if a `git am` session is waiting to be continued, skipped or aborted.
+    pub fn am_in_progress(&self) -> Result<bool> {
+        self.check_git()?;
+
+        let git_dir = resolve_git_dir(self)?;
+        Ok(git_dir.join("rebase-apply").join("applying").exists())
+    }
+
+    /// Commit the current patch after resolving it and staging the result, then apply
+    /// the remaining patches.
+    pub fn am_continue(&self) -> Result<AmStatus> {
+        self.check_git()?;
+        self.am_step(&["am", "--continue"])
+    }
+
+    /// Drop the current patch and apply the remaining ones.
+    pub fn am_skip(&self) -> Result<AmStatus> {
+        self.check_git()?;
+        self.am_step(&["am", "--skip"])
+    }
+
+    /// Abort the `git am` session and restore the branch to where it was.
+    pub fn am_abort(&self) -> Result<()> {
+        self.check_git()?;
+
+        git(&["am", "--abort"], Some(self))?;
+        Ok(())
+    }
+
+    /// Run an am step and map its outcome to an [`AmStatus`]
+    fn am_step(&self, args: &[&str]) -> Result<AmStatus> {
+        let output = git_raw_with_env(args, Some(self), &[("GIT_EDITOR", "true")])?;
+        if output.status.success() {
+            let head = git(&["rev-parse", "HEAD"], Some(self))?;
+            return Ok(AmStatus::Success(Hash::from(head.trim())));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if self.am_in_progress()? {
+            let conflicts = extract_conflicted_files(self)?;
+            if !conflicts.is_empty() {
+                return Ok(AmStatus::Conflicts(conflicts));
+            }
+            if let Some(patch) = stdout
+                .lines()
+                .chain(stderr.lines())
+                .find_map(|line| line.strip_prefix("Patch failed at "))
+            {
+                return Ok(AmStatus::Stopped(patch.trim().to_string()));
+            }
+        }
+
+        Err(GitError::CommandFailed(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use std::fs;
+
+    fn current_head(repo: &Repository) -> Hash {
+        Hash::from(git(&["rev-parse", "HEAD"], Some(repo)).unwrap().trim())
+    }
+
+    /// A source repo with two commits after `Base`, their patches, and a clone at `Base`
+    fn setup(name: &str) -> (TempRepo, Vec<PathBuf>, Repository, PathBuf) {
+        let source = TempRepo::new(name).unwrap();
+        source
+            .commit_file("file.txt", "one\ntwo\n", "Base")
+            .unwrap();
+        let dest = source.path().with_extension("clone");
+        let _ = fs::remove_dir_all(&dest);
+        let target = Repository::clone(&source.path().to_string_lossy(), &dest).unwrap();
+        target
+            .config()
+            .set_user("Target User", "target@example.com")
+            .unwrap();
+
+        source
+            .commit_file("file.txt", "one\ntwo changed\n", "Change two")
+            .unwrap();
+        source.commit_file("new.txt", "new\n", "Add new").unwrap();
+
+        let out_dir = source.path().join("patches");
+        let patches = source.format_patch("HEAD~2", &out_dir).unwrap();
+        (source, patches, target, dest)
+    }
+
+    #[test]
+    fn test_format_patch_and_am() {
+        let (source, patches, target, dest) = setup("patch_am");
+        assert_eq!(patches.len(), 2);
+        assert!(patches[0].ends_with("0001-Change-two.patch"));
+        assert!(patches[1].ends_with("0002-Add-new.patch"));
+        assert!(patches.iter().all(|p| p.is_absolute() && p.exists()));
+
+        let status = target
+            .am_with_options(&patches, AmOptions::new().with_signoff())
+            .unwrap();
+        let AmStatus::Success(head) = status else {
+            panic!("expected success, got {:?}", status);
+        };
+        assert_eq!(head, current_head(&target));
+        assert_eq!(
+            fs::read_to_string(dest.join("file.txt")).unwrap(),
+            "one\ntwo changed\n"
+        );
+
+        // Author and message come from the patch
+        let details = target.show_commit(&head).unwrap();
+        assert_eq!(details.commit.message.subject, "Add new");
+        assert_eq!(
+            details.commit.author.email,
+            source.config().get_user().unwrap().1
+        );
+        assert!(
+            details
+                .commit
+                .message
+                .body
+                .unwrap_or_default()
+                .contains("Signed-off-by: Target User <target@example.com>")
+        );
+
+        assert!(target.am(&[] as &[PathBuf]).is_err());
+        assert!(source.format_patch("--stdout", "out").is_err());
+        fs::remove_dir_all(&dest).unwrap();
+    }
+
+    #[test]
+    fn test_am_stopped_and_conflicts() {
+        let (_source, patches, target, dest) = setup("patch_am_conflicts");
+        fs::write(dest.join("file.txt"), "one\ntwo local\n").unwrap();
+        target.add(&["file.txt"]).unwrap();
+        target.commit("Local change").unwrap();
+        let before = current_head(&target);
+
+        // Without --3way the first patch just does not apply
+        assert_eq!(
+            target.am(&patches).unwrap(),
+            AmStatus::Stopped("0001 Change two".to_string())
+        );
+        assert!(target.am_in_progress().unwrap());
+        assert_eq!(
+            target.conflict_operation().unwrap(),
+            Some(crate::ConflictOperation::Am)
+        );
+        assert!(!target.rebase_in_progress().unwrap());
+        target.am_abort().unwrap();
+        assert!(!target.am_in_progress().unwrap());
+        assert_eq!(current_head(&target), before);
+
+        // With --3way it conflicts; skipping it applies the rest
+        let options = AmOptions::new().with_three_way();
+        assert_eq!(
+            target.am_with_options(&patches, options.clone()).unwrap(),
+            AmStatus::Conflicts(vec![PathBuf::from("file.txt")])
+        );
+        assert!(matches!(target.am_skip().unwrap(), AmStatus::Success(_)));
+        assert!(dest.join("new.txt").exists());
+
+        // Resolving and continuing keeps the patch's message
+        target.reset_hard(before.as_str()).unwrap();
+        assert!(matches!(
+            target.am_with_options(&patches, options).unwrap(),
+            AmStatus::Conflicts(_)
+        ));
+        fs::write(dest.join("file.txt"), "one\ntwo resolved\n").unwrap();
+        target.add(&["file.txt"]).unwrap();
+        assert!(matches!(
+            target.am_continue().unwrap(),
+            AmStatus::Success(_)
+        ));
+        let log = target.recent_commits(2).unwrap();
+        let messages: Vec<_> = log.iter().map(|c| c.message.subject.as_str()).collect();
+        assert_eq!(messages, vec!["Add new", "Change two"]);
+
+        fs::remove_dir_all(&dest).unwrap();
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let repo = TempRepo::new("apply_patch").unwrap();
+        repo.commit_file("file.txt", "one\ntwo\n", "Base").unwrap();
+        repo.write_file("file.txt", "one\ntwo changed\n").unwrap();
+        let diff = git(&["diff"], Some(&*repo)).unwrap();
+        repo.reset_hard("HEAD").unwrap();
+        let read = || fs::read_to_string(repo.path().join("file.txt")).unwrap();
+
+        // --check changes nothing
+        repo.apply_patch(&diff, ApplyOptions::new().with_check())
+            .unwrap();
+        assert_eq!(read(), "one\ntwo\n");
+
+        // --cached stages without touching the working tree
+        repo.apply_patch(&diff, ApplyOptions::new().with_cached())
+            .unwrap();
+        assert_eq!(read(), "one\ntwo\n");
+        let staged = git(&["diff", "--cached", "--name-only"], Some(&*repo)).unwrap();
+        assert_eq!(staged.trim(), "file.txt");
+        repo.reset_hard("HEAD").unwrap();
+
+        repo.apply_patch(&diff, ApplyOptions::new()).unwrap();
+        assert_eq!(read(), "one\ntwo changed\n");
+        // Applying again fails, reversing undoes it
+        assert!(
+            repo.apply_patch(&diff, ApplyOptions::new().with_check())
+                .is_err()
+        );
+        repo.apply_patch(&diff, ApplyOptions::new().with_reverse())
+            .unwrap();
+        assert_eq!(read(), "one\ntwo\n");
+
+        assert!(repo.apply_patch("not a diff", ApplyOptions::new()).is_err());
+    }
+
+    #[test]
+    fn test_apply_patch_three_way() {
+        let repo = TempRepo::new("apply_patch_3way").unwrap();
+        repo.commit_file("file.txt", "one\ntwo\n", "Base").unwrap();
+        repo.write_file("file.txt", "one\ntwo changed\n").unwrap();
+        let diff = git(&["diff"], Some(&*repo)).unwrap();
+        repo.reset_hard("HEAD").unwrap();
+        repo.commit_file("file.txt", "one\ntwo local\n", "Local")
+            .unwrap();
+
+        assert!(repo.apply_patch(&diff, ApplyOptions::new()).is_err());
+        assert!(
+            repo.apply_patch(&diff, ApplyOptions::new().with_three_way())
+                .is_err()
+        );
+        let status = repo.status().unwrap();
+        let conflicted: Vec<_> = status.conflicted_files().map(|f| f.path.clone()).collect();
+        assert_eq!(conflicted, vec![PathBuf::from("file.txt")]);
+    }
+}
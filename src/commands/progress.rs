@@ -0,0 +1,317 @@
+//! Progress reporting for network operations
+//!
+//! With [`CloneOptions::with_progress`](crate::CloneOptions::with_progress),
+//! [`FetchOptions::with_progress`](crate::FetchOptions::with_progress) or
+//! [`PushOptions::with_progress`](crate::PushOptions::with_progress), git runs with
+//! `--progress` and each status line it writes to stderr is parsed into a [`Progress`]
+//! event as it arrives. Lines relayed from the server (`remote: ...`) are reported like
+//! local ones.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{FetchOptions, Progress, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//! let options = FetchOptions::new().with_progress(|progress| match progress {
+//!     Progress::Objects { stage, current, total, .. } => {
+//!         println!("{}: {}/{}", stage, current, total.unwrap_or(0))
+//!     }
+//!     Progress::RefUpdate { from, to, summary, .. } => println!("{} -> {} {}", from, to, summary),
+//! });
+//! repo.fetch_with_options("origin", options)?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+/// A transfer stage that git reports progress for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressStage {
+    /// `Enumerating objects`
+    Enumerating,
+    /// `Counting objects`
+    Counting,
+    /// `Compressing objects`
+    Compressing,
+    /// `Writing objects` (push)
+    Writing,
+    /// `Receiving objects` (clone, fetch)
+    Receiving,
+    /// `Resolving deltas`
+    Resolving,
+    /// Any other stage, such as `Updating files` after a clone
+    Other(String),
+}
+
+impl ProgressStage {
+    fn parse(title: &str) -> Self {
+        match title {
+            "Enumerating objects" => Self::Enumerating,
+            "Counting objects" => Self::Counting,
+            "Compressing objects" => Self::Compressing,
+            "Writing objects" => Self::Writing,
+            "Receiving objects" => Self::Receiving,
+            "Resolving deltas" => Self::Resolving,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ProgressStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title = match self {
+            Self::Enumerating => "Enumerating objects",
+            Self::Counting => "Counting objects",
+            Self::Compressing => "Compressing objects",
+            Self::Writing => "Writing objects",
+            Self::Receiving => "Receiving objects",
+            Self::Resolving => "Resolving deltas",
+            Self::Other(title) => title,
+        };
+        f.write_str(title)
+    }
+}
+
+/// A progress event of a clone, fetch or push
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    /// A transfer stage advanced, e.g. `Receiving objects:  40% (2/5)`
+    Objects {
+        /// The stage
+        stage: ProgressStage,
+        /// Items processed so far
+        current: u64,
+        /// Items in total, when git knows it
+        total: Option<u64>,
+        /// Whether the stage finished (`, done.`)
+        done: bool,
+        /// Whether the server reported the line (`remote: ` prefix)
+        remote: bool,
+    },
+    /// A ref was created, updated or deleted
+    RefUpdate {
+        /// Git's one-character status flag: ` ` fast-forward, `+` forced, `-` deleted,
+        /// `*` new, `t` tag update, `!` rejected, `=` up to date
+        flag: char,
+        /// `old..new` for updates, or a bracketed note such as `[new branch]`
+        summary: String,
+        /// The source ref
+        from: String,
+        /// The destination ref
+        to: String,
+    },
+}
+
+/// Callback receiving [`Progress`] events, set through the `with_progress` builders
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&Progress) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Wrap a callback
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        Self(Arc::new(callback))
+    }
+
+    /// Report `progress`
+    pub fn call(&self, progress: &Progress) {
+        (self.0)(progress)
+    }
+
+    /// Parse one status line and report it if it is a progress event
+    pub(crate) fn line(&self, line: &str) {
+        if let Some(progress) = parse_progress_line(line) {
+            self.call(&progress);
+        }
+    }
+
+    /// Report every event in already captured stderr, for runs that could not stream
+    #[cfg(feature = "tokio")]
+    pub(crate) fn replay(&self, stderr: &[u8]) {
+        let stderr = String::from_utf8_lossy(stderr);
+        for line in stderr.split(['\r', '\n']) {
+            self.line(line);
+        }
+    }
+
+    /// Report the ref updates in `git push --porcelain` output
+    /// (`<flag>\t<from>:<to>\t<summary>`)
+    pub(crate) fn push_porcelain(&self, stdout: &[u8]) {
+        let stdout = String::from_utf8_lossy(stdout);
+        for line in stdout.lines() {
+            let mut fields = line.split('\t');
+            let (Some(flag), Some(refs), Some(summary)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let (Some(flag), Some((from, to))) = (flag.chars().next(), refs.split_once(':')) else {
+                continue;
+            };
+            self.call(&Progress::RefUpdate {
+                flag,
+                summary: summary.to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Parse one line of git's `--progress` stderr (lines end in `\r` while a stage runs)
+fn parse_progress_line(line: &str) -> Option<Progress> {
+    let (line, remote) = match line.strip_prefix("remote: ") {
+        Some(rest) => (rest, true),
+        None => (line, false),
+    };
+    let line = line.trim_end();
+
+    if line.starts_with(' ') && line.contains(" -> ") {
+        return parse_ref_update(line);
+    }
+
+    let (title, rest) = line.split_once(": ")?;
+    if title.is_empty() || title.starts_with(' ') {
+        return None;
+    }
+    let done = rest.ends_with(", done.");
+    let rest = rest.trim_start();
+
+    // `42% (21/50)...` or a bare count such as `5, done.`
+    let (current, total) = match rest.split_once("% (") {
+        Some((percent, counts)) => {
+            percent.parse::<u8>().ok()?;
+            let (counts, _) = counts.split_once(')')?;
+            let (current, total) = counts.split_once('/')?;
+            (current.parse().ok()?, Some(total.parse().ok()?))
+        }
+        None => {
+            let count = rest.split([',', ' ']).next()?;
+            (count.parse().ok()?, None)
+        }
+    };
+
+    Some(Progress::Objects {
+        stage: ProgressStage::parse(title),
+        current,
+        total,
+        done,
+        remote,
+    })
+}
+
+/// Parse ` <flag> <summary> <from> -> <to> [(<reason>)]`
+fn parse_ref_update(line: &str) -> Option<Progress> {
+    let mut chars = line[1..].chars();
+    let flag = chars.next()?;
+    let rest = chars.as_str().trim_start();
+
+    let (summary, rest) = if rest.starts_with('[') {
+        let end = rest.find(']')?;
+        (&rest[..=end], &rest[end + 1..])
+    } else {
+        rest.split_once(' ')?
+    };
+    let (from, to) = rest.split_once(" -> ")?;
+    let to = to.split_whitespace().next()?;
+
+    Some(Progress::RefUpdate {
+        flag,
+        summary: summary.to_string(),
+        from: from.trim().to_string(),
+        to: to.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn objects(stage: ProgressStage, current: u64, total: Option<u64>, done: bool) -> Progress {
+        Progress::Objects {
+            stage,
+            current,
+            total,
+            done,
+            remote: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_progress_line() {
+        assert_eq!(
+            parse_progress_line("Receiving objects:  40% (2/5), 1.00 KiB | 1.00 MiB/s"),
+            Some(objects(ProgressStage::Receiving, 2, Some(5), false))
+        );
+        assert_eq!(
+            parse_progress_line("Resolving deltas: 100% (1/1), done."),
+            Some(objects(ProgressStage::Resolving, 1, Some(1), true))
+        );
+        assert_eq!(
+            parse_progress_line("remote: Enumerating objects: 12, done."),
+            Some(Progress::Objects {
+                stage: ProgressStage::Enumerating,
+                current: 12,
+                total: None,
+                done: true,
+                remote: true,
+            })
+        );
+        assert_eq!(
+            parse_progress_line("Updating files: 100% (3/3), done."),
+            Some(objects(
+                ProgressStage::Other("Updating files".to_string()),
+                3,
+                Some(3),
+                true
+            ))
+        );
+        assert_eq!(
+            parse_progress_line("   1a2b3c4..5d6e7f8  main       -> origin/main"),
+            Some(Progress::RefUpdate {
+                flag: ' ',
+                summary: "1a2b3c4..5d6e7f8".to_string(),
+                from: "main".to_string(),
+                to: "origin/main".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_progress_line(" * [new branch]      feature/x -> origin/feature/x"),
+            Some(Progress::RefUpdate {
+                flag: '*',
+                summary: "[new branch]".to_string(),
+                from: "feature/x".to_string(),
+                to: "origin/feature/x".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_progress_line(" + 1a2b3c4...5d6e7f8 main -> origin/main  (forced update)"),
+            Some(Progress::RefUpdate {
+                flag: '+',
+                summary: "1a2b3c4...5d6e7f8".to_string(),
+                from: "main".to_string(),
+                to: "origin/main".to_string(),
+            })
+        );
+
+        for line in [
+            "",
+            "From /tmp/source",
+            "Cloning into 'dest'...",
+            "remote: Total 3 (delta 0), reused 0 (delta 0), pack-reused 0",
+            "warning: redirecting to https://example.com/",
+        ] {
+            assert_eq!(parse_progress_line(line), None, "{:?}", line);
+        }
+    }
+}
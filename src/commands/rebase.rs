@@ -0,0 +1,336 @@
+//! Git rebase operations
+//!
+//! This module replays the commits of the current branch onto another base with
+//! `git rebase`, and drives a rebase that stopped on conflicts through `--continue`,
+//! `--skip` and `--abort`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{RebaseStatus, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! match repo.rebase("main")? {
+//!     RebaseStatus::Success(hash) => println!("Rebased, HEAD is now {}", hash.short()),
+//!     RebaseStatus::UpToDate => println!("Already up to date"),
+//!     RebaseStatus::Conflicts(files) => {
+//!         println!("Resolve {:?}, then continue", files);
+//!         repo.mark_resolved(&files)?;
+//!         repo.rebase_continue()?;
+//!     }
+//! }
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::merge::{MergeStrategy, extract_conflicted_files};
+use crate::error::{GitError, Result};
+use crate::repository::{Repository, resolve_git_dir};
+use crate::types::Hash;
+use crate::utils::{git, git_raw_with_env, validate_arg};
+use std::path::PathBuf;
+use std::process::Output;
+
+/// The result of a rebase step
+#[derive(Debug, Clone, PartialEq)]
+pub enum RebaseStatus {
+    /// The rebase finished; HEAD points at the rebased tip
+    Success(Hash),
+    /// The branch already contains the new base, nothing was replayed
+    UpToDate,
+    /// The rebase stopped on a commit with conflicts that need manual resolution
+    Conflicts(Vec<PathBuf>),
+}
+
+/// Options for rebase operations
+#[derive(Debug, Clone, Default)]
+pub struct RebaseOptions {
+    onto: Option<String>,
+    autostash: bool,
+    strategy: Option<MergeStrategy>,
+}
+
+impl RebaseOptions {
+    /// Create new RebaseOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replay onto `newbase` instead of the upstream (`--onto <newbase>`)
+    ///
+    /// Only the commits after the upstream are replayed, which moves a branch that
+    /// was started from another topic branch.
+    pub fn with_onto(mut self, newbase: &str) -> Self {
+        self.onto = Some(newbase.to_string());
+        self
+    }
+
+    /// Stash local modifications before rebasing and reapply them afterwards
+    pub fn with_autostash(mut self) -> Self {
+        self.autostash = true;
+        self
+    }
+
+    /// Set the merge strategy used to replay commits
+    pub fn with_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+}
+
+impl Repository {
+    /// Rebase the current branch onto `upstream`.
+    ///
+    /// # Arguments
+    ///
+    /// * `upstream` - The branch or commit to replay the current branch's commits onto
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `RebaseStatus`. On `Conflicts` the rebase is left in
+    /// progress for [`rebase_continue`](Self::rebase_continue),
+    /// [`rebase_skip`](Self::rebase_skip) or [`rebase_abort`](Self::rebase_abort).
+    pub fn rebase(&self, upstream: &str) -> Result<RebaseStatus> {
+        self.rebase_with_options(upstream, RebaseOptions::new())
+    }
+
+    /// Rebase the current branch with custom options.
+    ///
+    /// # Arguments
+    ///
+    /// * `upstream` - The branch or commit whose history the rebased commits follow
+    /// * `options` - Rebase options (new base, autostash, strategy)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{RebaseOptions, Repository};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// // Move the commits made on top of `topic` onto `main`
+    /// let options = RebaseOptions::new().with_onto("main").with_autostash();
+    /// let status = repo.rebase_with_options("topic", options)?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn rebase_with_options(
+        &self,
+        upstream: &str,
+        options: RebaseOptions,
+    ) -> Result<RebaseStatus> {
+        self.check_git()?;
+        validate_arg("revision", upstream)?;
+
+        let mut args = vec!["rebase"];
+        if let Some(ref onto) = options.onto {
+            validate_arg("revision", onto)?;
+            args.push("--onto");
+            args.push(onto);
+        }
+        if options.autostash {
+            args.push("--autostash");
+        }
+        if let Some(strategy) = options.strategy {
+            args.push("--strategy");
+            args.push(strategy.as_str());
+        }
+        args.push(upstream);
+
+        self.run_rebase(&args)
+    }
+
+    /// Check if a rebase is currently in progress.
+    pub fn rebase_in_progress(&self) -> Result<bool> {
+        self.check_git()?;
+
+        let git_dir = resolve_git_dir(self)?;
+        // `git am` also uses rebase-apply/, marked by an `applying` file
+        let rebase_apply = git_dir.join("rebase-apply");
+        Ok(git_dir.join("rebase-merge").exists()
+            || (rebase_apply.exists() && !rebase_apply.join("applying").exists()))
+    }
+
+    /// Continue a rebase after its conflicts have been resolved and staged.
+    ///
+    /// The replayed commit keeps its original message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `RebaseStatus`; a later commit may conflict again.
+    pub fn rebase_continue(&self) -> Result<RebaseStatus> {
+        self.check_git()?;
+        self.run_rebase(&["rebase", "--continue"])
+    }
+
+    /// Skip the commit the rebase stopped on and continue with the next one.
+    pub fn rebase_skip(&self) -> Result<RebaseStatus> {
+        self.check_git()?;
+        self.run_rebase(&["rebase", "--skip"])
+    }
+
+    /// Abort an in-progress rebase and restore the branch to where it was.
+    pub fn rebase_abort(&self) -> Result<()> {
+        self.check_git()?;
+
+        git(&["rebase", "--abort"], Some(self))?;
+        Ok(())
+    }
+
+    /// Run a rebase step and map its outcome to a [`RebaseStatus`]
+    fn run_rebase(&self, args: &[&str]) -> Result<RebaseStatus> {
+        // `--continue` opens an editor for the commit message; keep the original one
+        let output = git_raw_with_env(args, Some(self), &[("GIT_EDITOR", "true")])?;
+        if output.status.success() {
+            if rebase_output(&output).contains("is up to date") {
+                return Ok(RebaseStatus::UpToDate);
+            }
+            let head = git(&["rev-parse", "HEAD"], Some(self))?;
+            return Ok(RebaseStatus::Success(Hash::from(head.trim())));
+        }
+
+        if self.rebase_in_progress()? {
+            let conflicts = extract_conflicted_files(self)?;
+            if !conflicts.is_empty() {
+                return Ok(RebaseStatus::Conflicts(conflicts));
+            }
+        }
+
+        Err(GitError::CommandFailed(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            rebase_output(&output).trim()
+        )))
+    }
+}
+
+/// Combined stdout and stderr, since git reports rebase progress on either
+fn rebase_output(output: &Output) -> String {
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    /// A repo on `topic`, diverged from the default branch after `Base`; with
+    /// `conflicting` both branches change `file.txt`
+    fn diverged(name: &str, conflicting: bool) -> (TempRepo, String) {
+        let repo = TempRepo::new(name).unwrap();
+        repo.commit_file("file.txt", "base\n", "Base").unwrap();
+        let main = repo.current_branch().unwrap().unwrap().name;
+
+        repo.checkout_new("topic", None).unwrap();
+        repo.commit_file("file.txt", "topic\n", "Topic change")
+            .unwrap();
+        repo.commit_file("topic.txt", "topic only\n", "Topic file")
+            .unwrap();
+
+        let main_branch = repo.branches().unwrap().find(&main).unwrap().clone();
+        repo.checkout(&main_branch).unwrap();
+        if conflicting {
+            repo.commit_file("file.txt", "main\n", "Main change")
+                .unwrap();
+        } else {
+            repo.commit_file("main.txt", "main\n", "Main file").unwrap();
+        }
+
+        let topic = repo.branches().unwrap().find("topic").unwrap().clone();
+        repo.checkout(&topic).unwrap();
+        (repo, main)
+    }
+
+    #[test]
+    fn test_rebase_success_and_up_to_date() {
+        let (repo, main) = diverged("rebase_success", false);
+
+        let status = repo.rebase(&main).unwrap();
+        let RebaseStatus::Success(head) = status else {
+            panic!("expected success, got {:?}", status);
+        };
+        assert_eq!(head, repo.head_hash().unwrap());
+        assert!(repo.path().join("main.txt").exists());
+        assert!(!repo.rebase_in_progress().unwrap());
+
+        assert_eq!(repo.rebase(&main).unwrap(), RebaseStatus::UpToDate);
+        assert!(repo.rebase("--root").is_err());
+    }
+
+    #[test]
+    fn test_rebase_conflicts_continue() {
+        let (repo, main) = diverged("rebase_continue", true);
+
+        let status = repo.rebase(&main).unwrap();
+        assert_eq!(
+            status,
+            RebaseStatus::Conflicts(vec![PathBuf::from("file.txt")])
+        );
+        assert!(repo.rebase_in_progress().unwrap());
+        assert_eq!(
+            repo.conflict_operation().unwrap(),
+            Some(crate::ConflictOperation::Rebase)
+        );
+
+        repo.write_file("file.txt", "resolved\n").unwrap();
+        repo.mark_resolved(&["file.txt"]).unwrap();
+        assert!(matches!(
+            repo.rebase_continue().unwrap(),
+            RebaseStatus::Success(_)
+        ));
+        assert!(!repo.rebase_in_progress().unwrap());
+
+        let log = repo.recent_commits(2).unwrap();
+        let messages: Vec<_> = log.iter().map(|c| c.message.subject.as_str()).collect();
+        assert_eq!(messages, vec!["Topic file", "Topic change"]);
+    }
+
+    #[test]
+    fn test_rebase_skip_and_abort() {
+        let (repo, main) = diverged("rebase_skip", true);
+        let before = repo.head_hash().unwrap();
+
+        assert!(matches!(
+            repo.rebase(&main).unwrap(),
+            RebaseStatus::Conflicts(_)
+        ));
+        repo.rebase_abort().unwrap();
+        assert!(!repo.rebase_in_progress().unwrap());
+        assert_eq!(repo.head_hash().unwrap(), before);
+
+        let options = RebaseOptions::new().with_strategy(MergeStrategy::Recursive);
+        assert!(matches!(
+            repo.rebase_with_options(&main, options).unwrap(),
+            RebaseStatus::Conflicts(_)
+        ));
+        // Skipping the conflicting commit replays the rest
+        assert!(matches!(
+            repo.rebase_skip().unwrap(),
+            RebaseStatus::Success(_)
+        ));
+        assert_eq!(
+            std::fs::read_to_string(repo.path().join("file.txt")).unwrap(),
+            "main\n"
+        );
+        assert!(repo.path().join("topic.txt").exists());
+    }
+
+    #[test]
+    fn test_rebase_onto() {
+        let (repo, main) = diverged("rebase_onto", false);
+        repo.checkout_new("subtopic", None).unwrap();
+        repo.commit_file("sub.txt", "sub\n", "Subtopic file")
+            .unwrap();
+
+        // Move only the subtopic commit onto the default branch
+        let options = RebaseOptions::new().with_onto(&main).with_autostash();
+        assert!(matches!(
+            repo.rebase_with_options("topic", options).unwrap(),
+            RebaseStatus::Success(_)
+        ));
+        assert!(repo.path().join("sub.txt").exists());
+        assert!(!repo.path().join("topic.txt").exists());
+    }
+}
@@ -0,0 +1,343 @@
+//! Git reflog operations
+//!
+//! Git records every update of HEAD and of each branch in the reflog, including
+//! updates that later became unreachable through resets, rebases or amends. Recovery
+//! tooling and undo features read these entries to find where a ref pointed before.
+//! This module reads reflogs and wraps `git reflog exists` and `git reflog expire`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{ReflogExpireOptions, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! // Where was HEAD before the last operation?
+//! for entry in repo.reflog("HEAD")?.iter().take(5) {
+//!     println!("{} {} {}", entry.selector, entry.new_hash.short(), entry.message);
+//! }
+//!
+//! // Drop entries older than a week from every reflog
+//! let options = ReflogExpireOptions::new().with_expire("1.week.ago");
+//! repo.reflog_expire(None, options)?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::log::Author;
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, git_raw, parse_unix_timestamp, validate_arg, validate_ref_name};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::PathBuf;
+
+/// A single update of a ref, as recorded in its reflog
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflogEntry {
+    /// The commit the ref pointed at before the update (all zeros when it was created)
+    pub old_hash: Hash,
+    /// The commit the ref pointed at after the update
+    pub new_hash: Hash,
+    /// Who made the update
+    pub committer: Author,
+    /// What caused the update, e.g. `commit: Fix parser` or `reset: moving to HEAD~1`
+    pub message: String,
+    /// The selector naming this entry, e.g. `HEAD@{2}`
+    pub selector: String,
+    /// When the update was made
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ReflogEntry {
+    /// Check whether this entry records the creation of the ref
+    pub fn is_creation(&self) -> bool {
+        self.old_hash.as_str().bytes().all(|b| b == b'0')
+    }
+}
+
+/// Options for expiring reflog entries
+#[derive(Debug, Clone, Default)]
+pub struct ReflogExpireOptions {
+    expire: Option<String>,
+    expire_unreachable: Option<String>,
+    stale_fix: bool,
+    dry_run: bool,
+}
+
+impl ReflogExpireOptions {
+    /// Create new ReflogExpireOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove entries older than `time`, e.g. `now` or `90.days.ago` (`--expire`)
+    ///
+    /// Defaults to the `gc.reflogExpire` configuration (90 days).
+    pub fn with_expire(mut self, time: &str) -> Self {
+        self.expire = Some(time.to_string());
+        self
+    }
+
+    /// Remove entries older than `time` that are no longer reachable from the ref
+    /// (`--expire-unreachable`)
+    ///
+    /// Defaults to the `gc.reflogExpireUnreachable` configuration (30 days).
+    pub fn with_expire_unreachable(mut self, time: &str) -> Self {
+        self.expire_unreachable = Some(time.to_string());
+        self
+    }
+
+    /// Also remove entries pointing at missing or broken commits (`--stale-fix`)
+    pub fn with_stale_fix(mut self) -> Self {
+        self.stale_fix = true;
+        self
+    }
+
+    /// Report what would be removed without changing the reflog (`--dry-run`)
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+}
+
+impl Repository {
+    /// Read the reflog of a ref, newest entry first.
+    ///
+    /// # Arguments
+    ///
+    /// * `refname` - `HEAD`, a branch name, or a full ref such as `refs/heads/main`
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the entries; empty when the ref has no reflog.
+    pub fn reflog(&self, refname: &str) -> Result<Vec<ReflogEntry>> {
+        self.check_git()?;
+
+        let full_name = self.reflog_refname(refname)?;
+        let path = self.reflog_path(&full_name)?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        parse_reflog(&fs::read_to_string(path)?, refname)
+    }
+
+    /// Check whether a ref has a reflog.
+    pub fn reflog_exists(&self, refname: &str) -> Result<bool> {
+        self.check_git()?;
+        validate_arg("reference", refname)?;
+
+        let Ok(full_name) = self.reflog_refname(refname) else {
+            return Ok(false);
+        };
+        let output = git_raw(&["reflog", "exists", &full_name], Some(self))?;
+        Ok(output.status.success())
+    }
+
+    /// Remove old entries from a reflog.
+    ///
+    /// # Arguments
+    ///
+    /// * `refname` - The ref whose reflog to prune, or `None` for all reflogs
+    /// * `options` - Expiry times and behavior
+    pub fn reflog_expire(&self, refname: Option<&str>, options: ReflogExpireOptions) -> Result<()> {
+        self.check_git()?;
+
+        let expire = options.expire.map(|time| format!("--expire={}", time));
+        let expire_unreachable = options
+            .expire_unreachable
+            .map(|time| format!("--expire-unreachable={}", time));
+
+        let mut args = vec!["reflog", "expire"];
+        if let Some(ref expire) = expire {
+            args.push(expire);
+        }
+        if let Some(ref expire_unreachable) = expire_unreachable {
+            args.push(expire_unreachable);
+        }
+        if options.stale_fix {
+            args.push("--stale-fix");
+        }
+        if options.dry_run {
+            args.push("--dry-run");
+        }
+
+        let full_name;
+        match refname {
+            Some(refname) => {
+                full_name = self.reflog_refname(refname)?;
+                args.push(&full_name);
+            }
+            None => args.push("--all"),
+        }
+
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// Expand a branch or other short ref name to the full name its reflog is kept under
+    fn reflog_refname(&self, refname: &str) -> Result<String> {
+        if refname == "HEAD" {
+            return Ok(refname.to_string());
+        }
+        // Full names are used as a path below the git directory, so no `..` etc.
+        validate_ref_name("reference", refname)?;
+        if refname.starts_with("refs/") {
+            return Ok(refname.to_string());
+        }
+
+        let output = git(&["rev-parse", "--symbolic-full-name", refname], Some(self))?;
+        let full_name = output.trim();
+        if full_name.is_empty() {
+            return Err(GitError::CommandFailed(format!(
+                "'{}' is not a ref with a reflog",
+                refname
+            )));
+        }
+        Ok(full_name.to_string())
+    }
+
+    /// Locate the reflog file of a full ref name, honouring per-worktree refs
+    fn reflog_path(&self, full_name: &str) -> Result<PathBuf> {
+        let output = git(
+            &["rev-parse", "--git-path", &format!("logs/{}", full_name)],
+            Some(self),
+        )?;
+        let path = PathBuf::from(output.trim());
+        if path.is_absolute() {
+            Ok(path)
+        } else {
+            Ok(self.command_dir().join(path))
+        }
+    }
+}
+
+/// Parse a reflog file (oldest entry first) into entries, newest first
+///
+/// Each line reads `<old> <new> <name> <<email>> <timestamp> <tz>\t<message>`.
+fn parse_reflog(content: &str, refname: &str) -> Result<Vec<ReflogEntry>> {
+    let mut entries = Vec::new();
+
+    for (index, line) in content.lines().rev().filter(|l| !l.is_empty()).enumerate() {
+        let invalid = || GitError::CommandFailed(format!("Invalid reflog entry: {}", line));
+
+        let (header, message) = line.split_once('\t').unwrap_or((line, ""));
+        let mut parts = header.splitn(3, ' ');
+        let old_hash = parts.next().ok_or_else(invalid)?;
+        let new_hash = parts.next().ok_or_else(invalid)?;
+        let identity = parts.next().ok_or_else(invalid)?;
+
+        // The email is the last `<...>`, followed by the timestamp and time zone
+        let email_start = identity.rfind('<').ok_or_else(invalid)?;
+        let email_end = identity.rfind('>').ok_or_else(invalid)?;
+        if email_end < email_start {
+            return Err(invalid());
+        }
+        let timestamp = identity[email_end + 1..]
+            .split_whitespace()
+            .next()
+            .ok_or_else(invalid)?;
+        let timestamp = parse_unix_timestamp(timestamp)?;
+
+        entries.push(ReflogEntry {
+            old_hash: Hash::from(old_hash),
+            new_hash: Hash::from(new_hash),
+            committer: Author {
+                name: identity[..email_start].trim().to_string(),
+                email: identity[email_start + 1..email_end].to_string(),
+                timestamp,
+            },
+            message: message.to_string(),
+            selector: format!("{}@{{{}}}", refname, index),
+            timestamp,
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn test_parse_reflog() {
+        let content = "\
+0000000000000000000000000000000000000000 1111111111111111111111111111111111111111 Jane Doe <jane@example.com> 1700000000 +0100\tcommit (initial): Base
+1111111111111111111111111111111111111111 2222222222222222222222222222222222222222 Jane Doe <jane@example.com> 1700000100 +0100\tcommit: Next
+";
+        let entries = parse_reflog(content, "main").unwrap();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].selector, "main@{0}");
+        assert_eq!(entries[0].message, "commit: Next");
+        assert_eq!(entries[0].new_hash.as_str(), "2".repeat(40));
+        assert_eq!(entries[0].committer.name, "Jane Doe");
+        assert_eq!(entries[0].committer.email, "jane@example.com");
+        assert_eq!(entries[0].timestamp.timestamp(), 1_700_000_100);
+        assert!(!entries[0].is_creation());
+
+        assert_eq!(entries[1].selector, "main@{1}");
+        assert!(entries[1].is_creation());
+
+        assert!(parse_reflog("abc def no-identity", "HEAD").is_err());
+    }
+
+    #[test]
+    fn test_reflog_head_and_branch() {
+        let repo = TempRepo::new("reflog_read").unwrap();
+        let first = repo.commit_file("a.txt", "a\n", "First").unwrap();
+        let second = repo.commit_file("b.txt", "b\n", "Second").unwrap();
+        repo.reset_hard(first.as_str()).unwrap();
+
+        let head = repo.reflog("HEAD").unwrap();
+        assert_eq!(head.len(), 3);
+        assert_eq!(head[0].selector, "HEAD@{0}");
+        assert_eq!(head[0].old_hash, second);
+        assert_eq!(head[0].new_hash, first);
+        assert!(head[0].message.starts_with("reset: moving to"));
+        assert_eq!(head[1].message, "commit: Second");
+        assert!(head[2].is_creation());
+
+        // The lost commit can be recovered from the reflog
+        assert_eq!(head[1].new_hash, second);
+
+        let branch = repo.current_branch().unwrap().unwrap().name;
+        let entries = repo.reflog(&branch).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].selector, format!("{}@{{0}}", branch));
+        let full = repo.reflog(&format!("refs/heads/{}", branch)).unwrap();
+        assert_eq!(full[0].new_hash, entries[0].new_hash);
+
+        assert!(repo.reflog_exists("HEAD").unwrap());
+        assert!(repo.reflog_exists(&branch).unwrap());
+        assert!(!repo.reflog_exists("missing").unwrap());
+        assert!(repo.reflog("--all").is_err());
+        assert!(repo.reflog("refs/../config").is_err());
+    }
+
+    #[test]
+    fn test_reflog_expire() {
+        let repo = TempRepo::new("reflog_expire").unwrap();
+        repo.commit_file("a.txt", "a\n", "First").unwrap();
+        repo.commit_file("b.txt", "b\n", "Second").unwrap();
+
+        let options = ReflogExpireOptions::new().with_expire("now").with_dry_run();
+        repo.reflog_expire(Some("HEAD"), options).unwrap();
+        assert_eq!(repo.reflog("HEAD").unwrap().len(), 2);
+
+        let options = ReflogExpireOptions::new()
+            .with_expire("now")
+            .with_expire_unreachable("now");
+        repo.reflog_expire(Some("HEAD"), options).unwrap();
+        assert!(repo.reflog("HEAD").unwrap().is_empty());
+
+        let branch = repo.current_branch().unwrap().unwrap().name;
+        assert_eq!(repo.reflog(&branch).unwrap().len(), 2);
+        repo.reflog_expire(None, ReflogExpireOptions::new().with_expire("now"))
+            .unwrap();
+        assert!(repo.reflog(&branch).unwrap().is_empty());
+    }
+}
@@ -1,7 +1,11 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Output;
 
-use crate::utils::git;
-use crate::{Repository, Result};
+use crate::commands::progress::{Progress, ProgressCallback};
+use crate::utils::{
+    CommandContext, git, git_raw, git_raw_with_stderr_lines, validate_arg, validate_ref_name,
+};
+use crate::{GitError, GitUrl, PushRejection, RejectedRef, Repository, Result};
 
 /// Represents a Git remote with its URLs
 #[derive(Debug, Clone, PartialEq)]
@@ -28,6 +32,34 @@ impl Remote {
     pub fn push_url(&self) -> &str {
         self.push_url.as_ref().unwrap_or(&self.fetch_url)
     }
+
+    /// Parse the fetch URL into its components
+    pub fn fetch_git_url(&self) -> Result<GitUrl> {
+        GitUrl::parse(&self.fetch_url)
+    }
+
+    /// Parse the effective push URL into its components
+    pub fn push_git_url(&self) -> Result<GitUrl> {
+        GitUrl::parse(self.push_url())
+    }
+}
+
+/// The URLs a remote resolves to after `insteadOf` / `pushInsteadOf` rewriting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveRemoteUrls {
+    /// The URL as written in `remote.<name>.url`
+    pub configured: String,
+    /// The URL git will fetch from
+    pub fetch: String,
+    /// The URL git will push to
+    pub push: String,
+}
+
+impl EffectiveRemoteUrls {
+    /// Whether any rewrite rule changed the fetch or push URL
+    pub fn is_rewritten(&self) -> bool {
+        self.fetch != self.configured || self.push != self.configured
+    }
 }
 
 /// A collection of remotes in a repository
@@ -72,6 +104,8 @@ pub struct FetchOptions {
     pub tags: bool,
     /// Fetch from all remotes instead of just one
     pub all_remotes: bool,
+    /// Receives transfer progress and ref updates (`--progress`)
+    pub progress: Option<ProgressCallback>,
 }
 
 impl FetchOptions {
@@ -97,6 +131,15 @@ impl FetchOptions {
         self.all_remotes = true;
         self
     }
+
+    /// Report progress to `callback` while fetching
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressCallback::new(callback));
+        self
+    }
 }
 
 /// Options for push operations
@@ -108,6 +151,8 @@ pub struct PushOptions {
     pub tags: bool,
     /// Set upstream tracking for the branch
     pub set_upstream: bool,
+    /// Receives transfer progress and ref updates (`--progress`)
+    pub progress: Option<ProgressCallback>,
 }
 
 impl PushOptions {
@@ -133,6 +178,154 @@ impl PushOptions {
         self.set_upstream = true;
         self
     }
+
+    /// Report progress to `callback` while pushing
+    ///
+    /// Ref updates are reported once the push completes.
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressCallback::new(callback));
+        self
+    }
+}
+
+/// Options for clone operations
+#[derive(Default, Debug)]
+pub struct CloneOptions {
+    /// Borrow objects from a local repository instead of copying them (`--reference`)
+    pub reference: Option<PathBuf>,
+    /// Copy borrowed objects after cloning and drop the alternates link (`--dissociate`)
+    pub dissociate: bool,
+    /// Fetch only the most recent commits of history (`--depth`)
+    pub depth: Option<u32>,
+    /// Fetch only the history of one branch (`--single-branch`)
+    pub single_branch: bool,
+    /// Branch or tag to check out instead of the remote's HEAD (`--branch`)
+    pub branch: Option<String>,
+    /// Create a bare repository without a working tree (`--bare`)
+    pub bare: bool,
+    /// Mirror all refs of the remote into a bare repository (`--mirror`)
+    pub mirror: bool,
+    /// Initialize and clone submodules after cloning (`--recurse-submodules`)
+    pub recurse_submodules: bool,
+    /// Partial clone filter such as `blob:none` or `tree:0` (`--filter`)
+    pub filter: Option<String>,
+    /// Receives transfer progress (`--progress`)
+    pub progress: Option<ProgressCallback>,
+}
+
+impl CloneOptions {
+    /// Create new CloneOptions with default values
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a local repository as an object store for the clone
+    pub fn with_reference<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.reference = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Make the clone independent of the reference repository once it completes
+    pub fn with_dissociate(mut self) -> Self {
+        self.dissociate = true;
+        self
+    }
+
+    /// Create a shallow clone with `depth` commits of history
+    ///
+    /// Implies a single-branch clone. Local paths ignore the depth; use a `file://` URL
+    /// to get a shallow local clone.
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Fetch only the branch that is checked out
+    pub fn with_single_branch(mut self) -> Self {
+        self.single_branch = true;
+        self
+    }
+
+    /// Check out `branch` (a branch or tag name) instead of the remote's HEAD
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = Some(branch.to_string());
+        self
+    }
+
+    /// Create a bare repository
+    pub fn with_bare(mut self) -> Self {
+        self.bare = true;
+        self
+    }
+
+    /// Create a bare mirror of the remote
+    pub fn with_mirror(mut self) -> Self {
+        self.mirror = true;
+        self
+    }
+
+    /// Clone submodules as well
+    pub fn with_recurse_submodules(mut self) -> Self {
+        self.recurse_submodules = true;
+        self
+    }
+
+    /// Create a partial clone, e.g. `"blob:none"` for a blobless clone that fetches file
+    /// contents on demand
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        self.filter = Some(filter.to_string());
+        self
+    }
+
+    /// Report progress to `callback` while cloning
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressCallback::new(callback));
+        self
+    }
+
+    /// Build the `git clone` flags for these options
+    fn args(&self) -> Result<Vec<String>> {
+        let mut args = Vec::new();
+        if let Some(reference) = &self.reference {
+            args.push("--reference".to_string());
+            args.push(reference.to_string_lossy().to_string());
+        }
+        if self.dissociate {
+            args.push("--dissociate".to_string());
+        }
+        if let Some(depth) = self.depth {
+            args.push(format!("--depth={}", depth));
+        }
+        if self.single_branch {
+            args.push("--single-branch".to_string());
+        }
+        if let Some(branch) = &self.branch {
+            validate_arg("branch", branch)?;
+            args.push(format!("--branch={}", branch));
+        }
+        if self.mirror {
+            args.push("--mirror".to_string());
+        } else if self.bare {
+            args.push("--bare".to_string());
+        }
+        if self.recurse_submodules {
+            args.push("--recurse-submodules".to_string());
+        }
+        if let Some(filter) = &self.filter {
+            validate_arg("filter", filter)?;
+            args.push(format!("--filter={}", filter));
+        }
+        if self.progress.is_some() {
+            args.push("--progress".to_string());
+        }
+        Ok(args)
+    }
 }
 
 impl Repository {
@@ -162,8 +355,10 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn add_remote(&self, name: &str, url: &str) -> Result<()> {
-        Self::ensure_git()?;
-        git(&["remote", "add", name, url], Some(self.repo_path()))?;
+        self.check_git()?;
+        validate_ref_name("remote", name)?;
+        validate_arg("URL", url)?;
+        git(&["remote", "add", name, url], Some(self))?;
         Ok(())
     }
 
@@ -193,8 +388,9 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn remove_remote(&self, name: &str) -> Result<()> {
-        Self::ensure_git()?;
-        git(&["remote", "remove", name], Some(self.repo_path()))?;
+        self.check_git()?;
+        validate_ref_name("remote", name)?;
+        git(&["remote", "remove", name], Some(self))?;
         Ok(())
     }
 
@@ -225,11 +421,10 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn rename_remote(&self, old_name: &str, new_name: &str) -> Result<()> {
-        Self::ensure_git()?;
-        git(
-            &["remote", "rename", old_name, new_name],
-            Some(self.repo_path()),
-        )?;
+        self.check_git()?;
+        validate_ref_name("remote", old_name)?;
+        validate_ref_name("remote", new_name)?;
+        git(&["remote", "rename", old_name, new_name], Some(self))?;
         Ok(())
     }
 
@@ -265,11 +460,50 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn get_remote_url(&self, name: &str) -> Result<String> {
-        Self::ensure_git()?;
-        let output = git(&["remote", "get-url", name], Some(self.repo_path()))?;
+        self.check_git()?;
+        validate_ref_name("remote", name)?;
+        let output = git(&["remote", "get-url", name], Some(self))?;
         Ok(output.trim().to_string())
     }
 
+    /// Resolve the URLs a remote will actually use
+    ///
+    /// Applies `url.<base>.insteadOf` and `url.<base>.pushInsteadOf` rules the
+    /// same way fetch and push do, which helps debug credential rewriting in CI.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the remote
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let urls = repo.remote_effective_urls("origin")?;
+    /// if urls.is_rewritten() {
+    ///     println!("{} -> fetch {}, push {}", urls.configured, urls.fetch, urls.push);
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn remote_effective_urls(&self, name: &str) -> Result<EffectiveRemoteUrls> {
+        self.check_git()?;
+        validate_ref_name("remote", name)?;
+        let configured = git(
+            &["config", "--get", &format!("remote.{}.url", name)],
+            Some(self),
+        )?;
+        let fetch = git(&["remote", "get-url", name], Some(self))?;
+        let push = git(&["remote", "get-url", "--push", name], Some(self))?;
+
+        Ok(EffectiveRemoteUrls {
+            configured: configured.trim().to_string(),
+            fetch: fetch.trim().to_string(),
+            push: push.trim().to_string(),
+        })
+    }
+
     /// List all remotes in the repository
     ///
     /// # Returns
@@ -301,39 +535,10 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn list_remotes(&self) -> Result<RemoteList> {
-        Self::ensure_git()?;
-
-        // Get remote names
-        let names_output = git(&["remote"], Some(self.repo_path()))?;
-        if names_output.trim().is_empty() {
-            return Ok(RemoteList::new(Vec::new()));
-        }
+        self.check_git()?;
 
-        let mut remotes = Vec::new();
-
-        for name in names_output.lines() {
-            let name = name.trim();
-            if name.is_empty() {
-                continue;
-            }
-
-            // Get fetch URL
-            let fetch_url = match git(&["remote", "get-url", name], Some(self.repo_path())) {
-                Ok(url) => url.trim().to_string(),
-                Err(_) => continue, // Skip this remote if we can't get its URL
-            };
-
-            // Try to get push URL (might be different from fetch URL)
-            let push_url = git(
-                &["remote", "get-url", "--push", name],
-                Some(self.repo_path()),
-            )
-            .ok()
-            .map(|url| url.trim().to_string())
-            .filter(|url| url != &fetch_url); // Only store if different
-
-            remotes.push(Remote::new(name.to_string(), fetch_url, push_url));
-        }
+        let output = git(&["remote", "-v"], Some(self))?;
+        let remotes = parse_remote_verbose(&output);
 
         Ok(RemoteList::new(remotes))
     }
@@ -375,25 +580,70 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn fetch_with_options(&self, remote: &str, options: FetchOptions) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
-        let mut args = vec!["fetch"];
+        run_with_progress(
+            &fetch_args(remote, &options)?,
+            Some(self),
+            options.progress.as_ref(),
+        )?;
+        Ok(())
+    }
 
-        if options.prune {
-            args.push("--prune");
-        }
+    /// Deepen a shallow clone by fetching more history from a remote
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - The name of the remote to fetch from
+    /// * `depth` - The number of additional commits to fetch beyond the current boundary
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// if repo.is_shallow()? {
+    ///     repo.deepen("origin", 50)?;
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn deepen(&self, remote: &str, depth: u32) -> Result<()> {
+        self.check_git()?;
+        validate_arg("remote", remote)?;
 
-        if options.tags {
-            args.push("--tags");
-        }
+        let deepen_arg = format!("--deepen={}", depth);
+        git(&["fetch", &deepen_arg, remote], Some(self))?;
+        Ok(())
+    }
 
-        if options.all_remotes {
-            args.push("--all");
-        } else {
-            args.push(remote);
+    /// Fetch the complete history of a shallow clone from a remote
+    ///
+    /// Does nothing if the repository is not shallow.
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - The name of the remote to fetch from
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// repo.unshallow("origin")?;
+    /// assert!(!repo.is_shallow()?);
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn unshallow(&self, remote: &str) -> Result<()> {
+        validate_arg("remote", remote)?;
+
+        // git rejects --unshallow on a complete repository
+        if !self.is_shallow()? {
+            return Ok(());
         }
 
-        git(&args, Some(self.repo_path()))?;
+        git(&["fetch", "--unshallow", remote], Some(self))?;
         Ok(())
     }
 
@@ -441,27 +691,20 @@ impl Repository {
         branch: &str,
         options: PushOptions,
     ) -> Result<()> {
-        Self::ensure_git()?;
-
-        let mut args = vec!["push"];
-
-        if options.force {
-            args.push("--force");
-        }
-
-        if options.set_upstream {
-            args.push("--set-upstream");
-        }
-
-        args.push(remote);
-        args.push(branch);
-
-        if options.tags {
-            args.push("--tags");
-        }
-
-        git(&args, Some(self.repo_path()))?;
-        Ok(())
+        self.check_git()?;
+
+        let args = push_args(remote, branch, &options)?;
+        let output = match &options.progress {
+            Some(progress) => {
+                let callback = progress.clone();
+                let output =
+                    git_raw_with_stderr_lines(&args, Some(self), move |line| callback.line(line))?;
+                progress.push_porcelain(&output.stdout);
+                output
+            }
+            None => git_raw(&args, Some(self))?,
+        };
+        check_push_output(&args, &output)
     }
 
     /// Clone a remote repository to a local path
@@ -484,18 +727,265 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn clone<P: AsRef<Path>>(url: &str, path: P) -> Result<Repository> {
+        Self::clone_with_options(url, path, CloneOptions::default())
+    }
+
+    /// Clone a remote repository to a local path with custom options
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the remote repository to clone
+    /// * `path` - The local path where the repository should be cloned
+    /// * `options` - Clone options to customize the operation
+    ///
+    /// # Returns
+    ///
+    /// A `Repository` instance pointing to the cloned repository (the repository directory
+    /// itself for bare and mirror clones)
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{CloneOptions, Repository};
+    ///
+    /// // Shallow, blobless clone of one branch for CI
+    /// let options = CloneOptions::new()
+    ///     .with_depth(1)
+    ///     .with_branch("main")
+    ///     .with_filter("blob:none");
+    /// let ci = Repository::clone_with_options("https://github.com/user/repo.git", "./ci", options)?;
+    /// assert!(ci.is_shallow()?);
+    ///
+    /// // Share objects with a local cache to save bandwidth and disk space
+    /// let options = CloneOptions::new().with_reference("/srv/git-cache/repo.git");
+    /// let repo = Repository::clone_with_options(
+    ///     "https://github.com/user/repo.git",
+    ///     "./local-repo",
+    ///     options,
+    /// )?;
+    /// println!("Alternates: {:?}", repo.alternates()?);
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn clone_with_options<P: AsRef<Path>>(
+        url: &str,
+        path: P,
+        options: CloneOptions,
+    ) -> Result<Repository> {
         Self::ensure_git()?;
+        validate_arg("URL", url)?;
 
-        let path_ref = path.as_ref();
-        git(&["clone", url, &path_ref.to_string_lossy()], None)?;
+        let flags = options.args()?;
+        let path_str = path.as_ref().to_string_lossy();
+        let mut args = vec!["clone"];
+        args.extend(flags.iter().map(|s| s.as_str()));
+        args.push("--");
+        args.push(url);
+        args.push(&path_str);
 
+        run_with_progress(&args, None::<&Path>, options.progress.as_ref())?;
+
+        if options.bare || options.mirror {
+            // A bare repository has no work tree for `open` to find
+            return Ok(Repository::unchecked(path.as_ref()));
+        }
         Repository::open(path)
     }
 }
 
+/// Build the `git fetch` arguments for `remote` and `options`
+pub(crate) fn fetch_args<'a>(remote: &'a str, options: &FetchOptions) -> Result<Vec<&'a str>> {
+    let mut args = vec!["fetch"];
+
+    if options.prune {
+        args.push("--prune");
+    }
+
+    if options.tags {
+        args.push("--tags");
+    }
+
+    if options.progress.is_some() {
+        args.push("--progress");
+    }
+
+    if options.all_remotes {
+        args.push("--all");
+    } else {
+        validate_arg("remote", remote)?;
+        args.push(remote);
+    }
+    Ok(args)
+}
+
+/// Build the `git push` arguments for `remote`, `branch` and `options`
+pub(crate) fn push_args<'a>(
+    remote: &'a str,
+    branch: &'a str,
+    options: &PushOptions,
+) -> Result<Vec<&'a str>> {
+    validate_arg("remote", remote)?;
+    validate_arg("refspec", branch)?;
+
+    let mut args = vec!["push"];
+
+    if options.force {
+        args.push("--force");
+    }
+
+    if options.set_upstream {
+        args.push("--set-upstream");
+    }
+
+    if options.progress.is_some() {
+        args.push("--progress");
+    }
+
+    // Machine-readable per-ref results on stdout; server messages stay on stderr
+    args.push("--porcelain");
+
+    args.push(remote);
+    args.push(branch);
+
+    if options.tags {
+        args.push("--tags");
+    }
+    Ok(args)
+}
+
+/// Run a network command, streaming its stderr to `progress` when one is set
+fn run_with_progress<C: CommandContext + ?Sized>(
+    args: &[&str],
+    context: Option<&C>,
+    progress: Option<&ProgressCallback>,
+) -> Result<String> {
+    let Some(progress) = progress else {
+        return git(args, context);
+    };
+
+    let callback = progress.clone();
+    let output = git_raw_with_stderr_lines(args, context, move |line| callback.line(line))?;
+    if !output.status.success() {
+        return Err(GitError::CommandFailed(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&"<unknown>"),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Map the output of `git push --porcelain` to success, a [`PushRejection`], or a
+/// generic failure
+pub(crate) fn check_push_output(args: &[&str], output: &Output) -> Result<()> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match parse_push_rejection(&stdout, &stderr) {
+        Some(rejection) => Err(GitError::PushRejected(rejection)),
+        None => Err(GitError::CommandFailed(format!(
+            "git {} failed: stdout='{}' stderr='{}'",
+            args.join(" "),
+            stdout,
+            stderr
+        ))),
+    }
+}
+
+/// Parse `git remote -v` output: `<name>\t<url> (fetch|push)` per URL, grouped by remote
+///
+/// URLs already have `insteadOf` rewrites applied. Only the first URL of each kind is
+/// kept, matching `git remote get-url`; remotes without a URL are skipped.
+fn parse_remote_verbose(output: &str) -> Vec<Remote> {
+    let mut remotes: Vec<Remote> = Vec::new();
+
+    for line in output.lines() {
+        let Some((name, rest)) = line.split_once('\t') else {
+            continue;
+        };
+        // Partial clones append the filter after the marker: `<url> (fetch) [blob:none]`
+        let Some((url, kind)) = [" (fetch)", " (push)"]
+            .into_iter()
+            .find_map(|marker| rest.find(marker).map(|index| (&rest[..index], marker)))
+        else {
+            continue;
+        };
+
+        if remotes.last().is_none_or(|remote| remote.name != name) {
+            remotes.push(Remote::new(name.to_string(), String::new(), None));
+        }
+        let remote = remotes.last_mut().expect("pushed above");
+        match kind {
+            " (fetch)" if remote.fetch_url.is_empty() => remote.fetch_url = url.to_string(),
+            " (push)" if remote.push_url.is_none() => remote.push_url = Some(url.to_string()),
+            _ => {}
+        }
+    }
+
+    remotes.retain(|remote| !remote.fetch_url.is_empty());
+    for remote in &mut remotes {
+        // Only store the push URL if it differs from the fetch URL
+        if remote.push_url.as_ref() == Some(&remote.fetch_url) {
+            remote.push_url = None;
+        }
+    }
+    remotes
+}
+
+/// Parse `git push --porcelain` output into a rejection, if any ref was refused
+///
+/// Rejected refs appear on stdout as `!\t<src>:<dst>\t[<summary>] (<reason>)`; anything
+/// the server printed arrives on stderr prefixed with `remote:`.
+fn parse_push_rejection(stdout: &str, stderr: &str) -> Option<PushRejection> {
+    let refs: Vec<RejectedRef> = stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let (Some("!"), Some(refspec), Some(result)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return None;
+            };
+
+            let (source, destination) = refspec.split_once(':')?;
+            let (summary, reason) = match result.split_once(" (") {
+                Some((summary, reason)) => (summary, reason.strip_suffix(')')),
+                None => (result, None),
+            };
+
+            Some(RejectedRef {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                summary: summary.trim_matches(['[', ']']).to_string(),
+                reason: reason.map(str::to_string),
+            })
+        })
+        .collect();
+
+    if refs.is_empty() {
+        return None;
+    }
+
+    // The server pads each line to overwrite progress output
+    let remote_messages = stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("remote:"))
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+        .collect();
+
+    Some(PushRejection {
+        refs,
+        remote_messages,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::TempRepo;
     use std::env;
     use std::fs;
 
@@ -532,6 +1022,23 @@ mod tests {
         assert_eq!(remote.push_url(), "https://github.com/user/repo.git");
     }
 
+    #[test]
+    fn test_remote_git_urls() {
+        let remote = Remote::new(
+            "origin".to_string(),
+            "https://github.com/user/repo.git".to_string(),
+            Some("git@github.com:user/repo.git".to_string()),
+        );
+
+        let fetch = remote.fetch_git_url().unwrap();
+        assert_eq!(fetch.scheme, crate::UrlScheme::Https);
+        assert_eq!(fetch.slug().as_deref(), Some("user/repo"));
+
+        let push = remote.push_git_url().unwrap();
+        assert!(push.scheme.is_ssh());
+        assert_eq!(push.repo, "repo");
+    }
+
     #[test]
     fn test_remote_list_operations() {
         let remotes = vec![
@@ -597,6 +1104,461 @@ mod tests {
         fs::remove_dir_all(&test_path).unwrap();
     }
 
+    #[test]
+    fn test_remote_effective_urls() {
+        use crate::UrlRewrite;
+
+        let test_path = env::temp_dir().join("test_remote_effective_urls");
+        let repo = create_test_repo(&test_path);
+
+        repo.add_remote("origin", "git@github.com:user/repo.git")
+            .unwrap();
+        let urls = repo.remote_effective_urls("origin").unwrap();
+        assert!(!urls.is_rewritten());
+        assert_eq!(urls.fetch, "git@github.com:user/repo.git");
+
+        repo.config()
+            .add_url_rewrite(&UrlRewrite::new("https://github.com/", "git@github.com:"))
+            .unwrap();
+        repo.config()
+            .add_url_rewrite(&UrlRewrite::push(
+                "ssh://git@push.example.com/",
+                "https://github.com/",
+            ))
+            .unwrap();
+
+        let urls = repo.remote_effective_urls("origin").unwrap();
+        assert!(urls.is_rewritten());
+        assert_eq!(urls.configured, "git@github.com:user/repo.git");
+        assert_eq!(urls.fetch, "https://github.com/user/repo.git");
+        // pushInsteadOf matches the configured URL, not the insteadOf result
+        assert_eq!(urls.push, "https://github.com/user/repo.git");
+
+        assert!(repo.remote_effective_urls("missing").is_err());
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_shallow_clone_deepen_and_unshallow() {
+        use std::process::Command;
+
+        let source_path = env::temp_dir().join("test_shallow_source");
+        let clone_path = env::temp_dir().join("test_shallow_clone");
+        if clone_path.exists() {
+            fs::remove_dir_all(&clone_path).unwrap();
+        }
+
+        let source = create_test_repo(&source_path);
+        source
+            .config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        for i in 0..4 {
+            fs::write(source_path.join("file.txt"), format!("version {}", i)).unwrap();
+            source.add(&["file.txt"]).unwrap();
+            source.commit(&format!("Commit {}", i)).unwrap();
+        }
+        assert!(!source.is_shallow().unwrap());
+        assert!(source.shallow_commits().unwrap().is_empty());
+
+        // Local clones ignore --depth unless the source is given as a file:// URL
+        let url = format!("file://{}", source_path.display());
+        let status = Command::new("git")
+            .args(["clone", "-q", "--depth", "1", &url])
+            .arg(&clone_path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let clone = Repository::open(&clone_path).unwrap();
+        assert!(clone.is_shallow().unwrap());
+        let boundary = clone.shallow_commits().unwrap();
+        assert_eq!(boundary.len(), 1);
+        assert_eq!(
+            boundary[0],
+            clone.recent_commits(1).unwrap().iter().next().unwrap().hash
+        );
+
+        clone.deepen("origin", 1).unwrap();
+        assert!(clone.is_shallow().unwrap());
+        assert_eq!(clone.recent_commits(10).unwrap().len(), 2);
+
+        clone.unshallow("origin").unwrap();
+        assert!(!clone.is_shallow().unwrap());
+        assert!(clone.shallow_commits().unwrap().is_empty());
+        assert_eq!(clone.recent_commits(10).unwrap().len(), 4);
+
+        // Unshallowing a complete repository is a no-op
+        clone.unshallow("origin").unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&source_path).unwrap();
+        fs::remove_dir_all(&clone_path).unwrap();
+    }
+
+    #[test]
+    fn test_clone_options_args() {
+        assert!(CloneOptions::new().args().unwrap().is_empty());
+
+        let options = CloneOptions::new()
+            .with_depth(1)
+            .with_single_branch()
+            .with_branch("release")
+            .with_bare()
+            .with_mirror()
+            .with_recurse_submodules()
+            .with_filter("blob:none");
+        assert_eq!(
+            options.args().unwrap(),
+            [
+                "--depth=1",
+                "--single-branch",
+                "--branch=release",
+                "--mirror",
+                "--recurse-submodules",
+                "--filter=blob:none",
+            ]
+        );
+
+        assert!(CloneOptions::new().with_branch("--bare").args().is_err());
+        assert!(CloneOptions::new().with_filter("").args().is_err());
+    }
+
+    #[test]
+    fn test_clone_with_options() {
+        let source = TempRepo::new("clone_options_source").unwrap();
+        source.commit_many(3).unwrap();
+        source.create_branch("release", None).unwrap();
+        source
+            .config()
+            .set("uploadpack.allowFilter", "true")
+            .unwrap();
+        let url = format!("file://{}", source.path().display());
+
+        let shallow_path = env::temp_dir().join("test_clone_options_shallow");
+        let bare_path = env::temp_dir().join("test_clone_options_bare");
+        for path in [&shallow_path, &bare_path] {
+            if path.exists() {
+                fs::remove_dir_all(path).unwrap();
+            }
+        }
+
+        let shallow = Repository::clone_with_options(
+            &url,
+            &shallow_path,
+            CloneOptions::new()
+                .with_depth(1)
+                .with_branch("release")
+                .with_filter("blob:none"),
+        )
+        .unwrap();
+        assert!(shallow.is_shallow().unwrap());
+        assert_eq!(shallow.recent_commits(10).unwrap().len(), 1);
+        assert_eq!(shallow.current_branch().unwrap().unwrap().name, "release");
+        // --depth implies a single branch
+        let remote_branches = shallow.branches().unwrap();
+        assert_eq!(remote_branches.remote().count(), 1);
+        assert_eq!(
+            shallow
+                .config()
+                .get("remote.origin.partialclonefilter")
+                .unwrap(),
+            "blob:none"
+        );
+
+        let bare =
+            Repository::clone_with_options(&url, &bare_path, CloneOptions::new().with_bare())
+                .unwrap();
+        assert!(bare_path.join("HEAD").exists());
+        assert!(!bare_path.join(".git").exists());
+        assert_eq!(bare.recent_commits(10).unwrap().len(), 3);
+
+        fs::remove_dir_all(&shallow_path).unwrap();
+        fs::remove_dir_all(&bare_path).unwrap();
+    }
+
+    #[test]
+    fn test_progress_callbacks() {
+        use crate::ProgressStage;
+        use std::sync::{Arc, Mutex};
+
+        fn recorder() -> (Arc<Mutex<Vec<Progress>>>, impl Fn(&Progress) + Send + Sync) {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let sink = events.clone();
+            (events, move |progress: &Progress| {
+                sink.lock().unwrap().push(progress.clone())
+            })
+        }
+        fn has_stage(events: &[Progress], wanted: ProgressStage) -> bool {
+            events.iter().any(|event| {
+                matches!(event, Progress::Objects { stage, done: true, .. } if *stage == wanted)
+            })
+        }
+
+        let source = TempRepo::new("progress_source").unwrap();
+        source.commit_many(3).unwrap();
+        let url = format!("file://{}", source.path().display());
+        let clone_path = env::temp_dir().join("test_progress_clone");
+        if clone_path.exists() {
+            fs::remove_dir_all(&clone_path).unwrap();
+        }
+
+        let (events, callback) = recorder();
+        let clone = Repository::clone_with_options(
+            &url,
+            &clone_path,
+            CloneOptions::new().with_progress(callback),
+        )
+        .unwrap();
+        assert!(has_stage(&events.lock().unwrap(), ProgressStage::Receiving));
+
+        source
+            .commit_file("new.txt", "new\n", "New commit")
+            .unwrap();
+        let (events, callback) = recorder();
+        clone
+            .fetch_with_options("origin", FetchOptions::new().with_progress(callback))
+            .unwrap();
+        let events = events.lock().unwrap();
+        // Small fetches are unpacked without a "Receiving objects" stage
+        assert!(has_stage(&events, ProgressStage::Counting));
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, Progress::Objects { remote: true, .. }))
+        );
+        assert!(events.iter().any(|event| matches!(
+            event,
+            Progress::RefUpdate { flag: ' ', to, .. } if to == "origin/master"
+        )));
+        drop(events);
+
+        let bare = TempRepo::new_bare("progress_bare").unwrap();
+        clone
+            .add_remote("backup", &bare.path().display().to_string())
+            .unwrap();
+        let (events, callback) = recorder();
+        clone
+            .push_with_options(
+                "backup",
+                "master",
+                PushOptions::new().with_progress(callback),
+            )
+            .unwrap();
+        let events = events.lock().unwrap();
+        assert!(has_stage(&events, ProgressStage::Writing));
+        assert!(events.contains(&Progress::RefUpdate {
+            flag: '*',
+            summary: "[new branch]".to_string(),
+            from: "refs/heads/master".to_string(),
+            to: "refs/heads/master".to_string(),
+        }));
+
+        fs::remove_dir_all(&clone_path).unwrap();
+    }
+
+    #[test]
+    fn test_clone_with_reference() {
+        let source_path = env::temp_dir().join("test_clone_reference_source");
+        let shared_path = env::temp_dir().join("test_clone_reference_shared");
+        let dissociated_path = env::temp_dir().join("test_clone_reference_dissociated");
+        for path in [&shared_path, &dissociated_path] {
+            if path.exists() {
+                fs::remove_dir_all(path).unwrap();
+            }
+        }
+
+        let source = create_test_repo(&source_path);
+        source
+            .config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(source_path.join("file.txt"), "content").unwrap();
+        source.add(&["file.txt"]).unwrap();
+        source.commit("Initial commit").unwrap();
+        assert!(source.alternates().unwrap().is_empty());
+
+        let url = format!("file://{}", source_path.display());
+        let shared = Repository::clone_with_options(
+            &url,
+            &shared_path,
+            CloneOptions::new().with_reference(&source_path),
+        )
+        .unwrap();
+        let alternates = shared.alternates().unwrap();
+        assert_eq!(alternates.len(), 1);
+        assert_eq!(
+            alternates[0].canonicalize().unwrap(),
+            source_path.join(".git/objects").canonicalize().unwrap()
+        );
+
+        let dissociated = Repository::clone_with_options(
+            &url,
+            &dissociated_path,
+            CloneOptions::new()
+                .with_reference(&source_path)
+                .with_dissociate(),
+        )
+        .unwrap();
+        assert!(dissociated.alternates().unwrap().is_empty());
+        assert!(dissociated_path.join("file.txt").exists());
+
+        // Clean up
+        fs::remove_dir_all(&source_path).unwrap();
+        fs::remove_dir_all(&shared_path).unwrap();
+        fs::remove_dir_all(&dissociated_path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_remote_verbose() {
+        let output = "\
+multi\ta (fetch)
+multi\ta (push)
+multi\tb (push)
+nourl\t
+origin\thttps://github.com/me/x (fetch)
+origin\tssh://git@github.com/me/x (push)
+spaces\t/tmp/a b (fetch)
+spaces\t/tmp/a b (push)
+partial\tfile:///srv/x (fetch) [blob:none]
+partial\tfile:///srv/x (push)
+";
+        assert_eq!(
+            parse_remote_verbose(output),
+            vec![
+                Remote::new("multi".to_string(), "a".to_string(), None),
+                Remote::new(
+                    "origin".to_string(),
+                    "https://github.com/me/x".to_string(),
+                    Some("ssh://git@github.com/me/x".to_string())
+                ),
+                Remote::new("spaces".to_string(), "/tmp/a b".to_string(), None),
+                Remote::new("partial".to_string(), "file:///srv/x".to_string(), None),
+            ]
+        );
+        assert!(parse_remote_verbose("").is_empty());
+    }
+
+    #[test]
+    fn test_list_remotes_blobless_clone() {
+        let source_path = env::temp_dir().join("test_list_remotes_blobless_source");
+        let clone_path = env::temp_dir().join("test_list_remotes_blobless_clone");
+        if clone_path.exists() {
+            fs::remove_dir_all(&clone_path).unwrap();
+        }
+
+        let source = create_test_repo(&source_path);
+        source
+            .config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        source
+            .config()
+            .set("uploadpack.allowFilter", "true")
+            .unwrap();
+        fs::write(source_path.join("file.txt"), "content").unwrap();
+        source.add(&["file.txt"]).unwrap();
+        source.commit("Initial commit").unwrap();
+
+        let url = format!("file://{}", source_path.display());
+        let clone = Repository::clone_with_options(
+            &url,
+            &clone_path,
+            CloneOptions::new().with_filter("blob:none"),
+        )
+        .unwrap();
+
+        let remotes = clone.list_remotes().unwrap();
+        assert_eq!(remotes.len(), 1);
+        let origin = remotes.find("origin").unwrap();
+        assert_eq!(origin.fetch_url, url);
+        assert_eq!(origin.push_url, None);
+
+        // Clean up
+        fs::remove_dir_all(&source_path).unwrap();
+        fs::remove_dir_all(&clone_path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_push_rejection() {
+        let stdout = "To /srv/repo.git\n\
+                      !\trefs/heads/main:refs/heads/main\t[remote rejected] (pre-receive hook declined)\n\
+                      !\trefs/heads/dev:refs/heads/dev\t[rejected] (non-fast-forward)\n\
+                      =\trefs/heads/docs:refs/heads/docs\t[up to date]\n\
+                      Done\n";
+        let stderr = "remote: Protected branch        \n\
+                      remote:        \n\
+                      error: failed to push some refs to '/srv/repo.git'\n";
+
+        let rejection = parse_push_rejection(stdout, stderr).unwrap();
+        assert_eq!(rejection.refs.len(), 2);
+        assert_eq!(rejection.refs[0].destination, "refs/heads/main");
+        assert_eq!(rejection.refs[0].summary, "remote rejected");
+        assert!(rejection.refs[0].is_remote_rejected());
+        assert_eq!(
+            rejection.refs[0].reason.as_deref(),
+            Some("pre-receive hook declined")
+        );
+        assert!(rejection.refs[1].is_non_fast_forward());
+        assert_eq!(rejection.remote_messages, vec!["Protected branch"]);
+
+        assert!(parse_push_rejection("Done\n", "fatal: unable to access\n").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_push_rejected_by_hook() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let remote_path = env::temp_dir().join("test_push_rejected_remote.git");
+        let local_path = env::temp_dir().join("test_push_rejected_local");
+        if remote_path.exists() {
+            fs::remove_dir_all(&remote_path).unwrap();
+        }
+
+        Repository::init(&remote_path, true).unwrap();
+        let hook = remote_path.join("hooks/pre-receive");
+        fs::write(
+            &hook,
+            "#!/bin/sh\necho 'Pushes to this branch require review'\nexit 1\n",
+        )
+        .unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let repo = create_test_repo(&local_path);
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(local_path.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+        repo.add_remote("origin", &remote_path.to_string_lossy())
+            .unwrap();
+
+        let branch = repo.current_branch().unwrap().unwrap().name;
+        match repo.push("origin", &branch) {
+            Err(GitError::PushRejected(rejection)) => {
+                assert_eq!(rejection.refs.len(), 1);
+                assert!(rejection.refs[0].is_remote_rejected());
+                assert_eq!(
+                    rejection.remote_messages,
+                    vec!["Pushes to this branch require review"]
+                );
+            }
+            other => panic!("Expected PushRejected, got {:?}", other),
+        }
+
+        // Without the hook the same push succeeds
+        fs::remove_file(&hook).unwrap();
+        repo.push("origin", &branch).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&remote_path).unwrap();
+        fs::remove_dir_all(&local_path).unwrap();
+    }
+
     #[test]
     fn test_rename_remote() {
         let test_path = env::temp_dir().join("test_rename_remote");
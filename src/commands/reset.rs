@@ -1,4 +1,4 @@
-use crate::utils::git;
+use crate::utils::{CommandContext, git, validate_arg};
 use crate::{Repository, Result};
 use std::path::Path;
 
@@ -7,6 +7,11 @@ pub enum ResetMode {
     Soft,
     Mixed,
     Hard,
+    /// Reset the index and update files that differ between the commit and HEAD,
+    /// keeping local changes to other files; used to back out of a conflicted merge
+    Merge,
+    /// Like Hard, but aborts instead of overwriting files with local changes
+    Keep,
 }
 
 impl ResetMode {
@@ -15,13 +20,17 @@ impl ResetMode {
             ResetMode::Soft => "--soft",
             ResetMode::Mixed => "--mixed",
             ResetMode::Hard => "--hard",
+            ResetMode::Merge => "--merge",
+            ResetMode::Keep => "--keep",
         }
     }
 }
 
-pub fn reset<P: AsRef<Path>>(repo_path: P, mode: ResetMode, commit: &str) -> Result<()> {
-    let args = vec!["reset", mode.as_str(), commit];
-    git(&args, Some(repo_path.as_ref()))?;
+pub fn reset<C: CommandContext + ?Sized>(repo: &C, mode: ResetMode, commit: &str) -> Result<()> {
+    validate_arg("revision", commit)?;
+
+    let args = vec!["reset", mode.as_str(), commit, "--"];
+    git(&args, Some(repo))?;
     Ok(())
 }
 
@@ -39,8 +48,8 @@ impl Repository {
     ///
     /// A `Result` indicating success or a `GitError` if the operation fails.
     pub fn reset_soft(&self, commit: &str) -> Result<()> {
-        Self::ensure_git()?;
-        reset(self.repo_path(), ResetMode::Soft, commit)?;
+        self.check_git()?;
+        reset(self, ResetMode::Soft, commit)?;
         Ok(())
     }
 
@@ -57,8 +66,8 @@ impl Repository {
     ///
     /// A `Result` indicating success or a `GitError` if the operation fails.
     pub fn reset_mixed(&self, commit: &str) -> Result<()> {
-        Self::ensure_git()?;
-        reset(self.repo_path(), ResetMode::Mixed, commit)?;
+        self.check_git()?;
+        reset(self, ResetMode::Mixed, commit)?;
         Ok(())
     }
 
@@ -75,8 +84,8 @@ impl Repository {
     ///
     /// A `Result` indicating success or a `GitError` if the operation fails.
     pub fn reset_hard(&self, commit: &str) -> Result<()> {
-        Self::ensure_git()?;
-        reset(self.repo_path(), ResetMode::Hard, commit)?;
+        self.check_git()?;
+        reset(self, ResetMode::Hard, commit)?;
         Ok(())
     }
 
@@ -93,8 +102,42 @@ impl Repository {
     ///
     /// A `Result` indicating success or a `GitError` if the operation fails.
     pub fn reset_with_mode(&self, commit: &str, mode: ResetMode) -> Result<()> {
-        Self::ensure_git()?;
-        reset(self.repo_path(), mode, commit)?;
+        self.check_git()?;
+        reset(self, mode, commit)?;
+        Ok(())
+    }
+
+    /// Reset index entries for specific paths to their state in the given commit.
+    ///
+    /// Equivalent to `git reset <rev> -- <paths>`. HEAD and the working directory are
+    /// left untouched; only the index entries for `paths` change. Paths that do not
+    /// exist in `rev` are removed from the index.
+    ///
+    /// # Arguments
+    ///
+    /// * `rev` - The commit hash, reference, or "HEAD~N" to take the entries from
+    /// * `paths` - The paths to reset
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `GitError` if the operation fails.
+    pub fn reset_paths_to<P: AsRef<Path>>(&self, rev: &str, paths: &[P]) -> Result<()> {
+        self.check_git()?;
+
+        validate_arg("revision", rev)?;
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let path_strings: Vec<String> = paths
+            .iter()
+            .map(|path| path.as_ref().to_string_lossy().to_string())
+            .collect();
+
+        let mut args = vec!["reset", "-q", rev, "--"];
+        args.extend(path_strings.iter().map(|path| path.as_str()));
+
+        git(&args, Some(self))?;
         Ok(())
     }
 }
@@ -142,6 +185,8 @@ mod tests {
         assert_eq!(ResetMode::Soft.as_str(), "--soft");
         assert_eq!(ResetMode::Mixed.as_str(), "--mixed");
         assert_eq!(ResetMode::Hard.as_str(), "--hard");
+        assert_eq!(ResetMode::Merge.as_str(), "--merge");
+        assert_eq!(ResetMode::Keep.as_str(), "--keep");
     }
 
     #[test]
@@ -247,7 +292,7 @@ mod tests {
         repo.add(&["file1.txt"]).unwrap();
 
         // Reset to HEAD (should unstage changes)
-        reset(temp_dir, ResetMode::Mixed, "HEAD").unwrap();
+        reset(&temp_dir, ResetMode::Mixed, "HEAD").unwrap();
 
         // Verify file is no longer staged but working directory is modified
         let status = repo.status().unwrap();
@@ -356,4 +401,101 @@ mod tests {
         // Clean up
         fs::remove_dir_all(&temp_dir).unwrap();
     }
+
+    #[test]
+    fn test_reset_keep() {
+        let (temp_dir, repo) = create_test_repo("reset_keep");
+
+        let first_commit =
+            create_file_and_commit(&repo, &temp_dir, "file1.txt", "content1", "First commit");
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "content2", "Second commit");
+
+        // A local change to a file that differs between the commits blocks --keep
+        fs::write(temp_dir.join("file1.txt"), "local").unwrap();
+        assert!(
+            repo.reset_with_mode(&first_commit, ResetMode::Keep)
+                .is_err()
+        );
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("file1.txt")).unwrap(),
+            "local"
+        );
+
+        // Without conflicting local changes it behaves like --hard
+        fs::write(temp_dir.join("file1.txt"), "content2").unwrap();
+        fs::write(temp_dir.join("untouched.txt"), "keep me").unwrap();
+        // Refresh stale stat data in the index so git sees file1.txt as unmodified
+        repo.status().unwrap();
+        repo.reset_with_mode(&first_commit, ResetMode::Keep)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("file1.txt")).unwrap(),
+            "content1"
+        );
+        assert!(temp_dir.join("untouched.txt").exists());
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_reset_merge_aborts_conflicted_merge() {
+        let (temp_dir, repo) = create_test_repo("reset_merge");
+
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "base", "Base");
+        repo.checkout_new("feature", None).unwrap();
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "feature", "Feature");
+        let branches = repo.branches().unwrap();
+        repo.checkout(branches.find("master").unwrap()).unwrap();
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "master", "Master");
+
+        assert!(matches!(
+            repo.merge("feature").unwrap(),
+            crate::MergeStatus::Conflicts(_)
+        ));
+
+        repo.reset_with_mode("HEAD", ResetMode::Merge).unwrap();
+        assert!(repo.status().unwrap().is_clean());
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("file1.txt")).unwrap(),
+            "master"
+        );
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_reset_paths_to() {
+        let (temp_dir, repo) = create_test_repo("reset_paths_to");
+
+        let first_commit =
+            create_file_and_commit(&repo, &temp_dir, "file1.txt", "v1", "First commit");
+        create_file_and_commit(&repo, &temp_dir, "file1.txt", "v2", "Second commit");
+        create_file_and_commit(&repo, &temp_dir, "file2.txt", "new", "Third commit");
+
+        repo.reset_paths_to(&first_commit, &["file1.txt", "file2.txt"])
+            .unwrap();
+
+        // HEAD and the working tree are unchanged
+        let head = git(&["rev-parse", "HEAD"], Some(&temp_dir)).unwrap();
+        assert_ne!(head.trim(), first_commit);
+        assert_eq!(
+            fs::read_to_string(temp_dir.join("file1.txt")).unwrap(),
+            "v2"
+        );
+
+        // The index now holds file1.txt from the first commit and no file2.txt
+        let staged = git(&["diff", "--cached", "--name-status"], Some(&temp_dir)).unwrap();
+        assert!(staged.contains("M\tfile1.txt"));
+        assert!(staged.contains("D\tfile2.txt"));
+        let index_content = git(&["show", ":file1.txt"], Some(&temp_dir)).unwrap();
+        assert_eq!(index_content, "v1");
+
+        let empty: [&str; 0] = [];
+        repo.reset_paths_to("HEAD", &empty).unwrap();
+
+        // Clean up
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
 }
@@ -0,0 +1,270 @@
+//! Git revert operations
+//!
+//! This module undoes existing commits by recording new commits that reverse their
+//! changes (`git revert`), so published history can be rolled back without rewriting it.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{Hash, Repository, RevertOptions, RevertStatus};
+//!
+//! let repo = Repository::open(".")?;
+//! let bad = Hash::from("a1b2c3d");
+//!
+//! match repo.revert(&bad)? {
+//!     RevertStatus::Success(hash) => println!("Reverted in {}", hash.short()),
+//!     RevertStatus::Conflicts(files) => {
+//!         println!("Conflicts in {:?}", files);
+//!         repo.revert_abort()?;
+//!     }
+//! }
+//!
+//! // Undo several commits in one working-tree change, to commit later
+//! let commits = [Hash::from("d4e5f6a"), Hash::from("b7c8d9e")];
+//! repo.revert_with_options(&commits, RevertOptions::new().with_no_commit())?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::merge::extract_conflicted_files;
+use crate::error::{GitError, Result};
+use crate::repository::{Repository, resolve_git_dir};
+use crate::types::Hash;
+use crate::utils::{git, git_raw_with_env, validate_arg};
+use std::path::PathBuf;
+
+/// The result of a revert operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum RevertStatus {
+    /// All commits were reverted; HEAD points at the last revert commit (or is
+    /// unchanged with [`RevertOptions::with_no_commit`], leaving the changes staged)
+    Success(Hash),
+    /// Reverting a commit conflicted with later changes; the revert is left in progress
+    Conflicts(Vec<PathBuf>),
+}
+
+/// Options for revert operations
+#[derive(Debug, Clone, Default)]
+pub struct RevertOptions {
+    no_commit: bool,
+    mainline: Option<u32>,
+}
+
+impl RevertOptions {
+    /// Create new RevertOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the reversal to the index and working tree without committing (`--no-commit`)
+    pub fn with_no_commit(mut self) -> Self {
+        self.no_commit = true;
+        self
+    }
+
+    /// Revert a merge commit relative to its `parent`-th parent (1-based, `-m <parent>`)
+    ///
+    /// Parent 1 is usually the branch that was merged into, so `with_mainline(1)`
+    /// undoes everything the merge brought in.
+    pub fn with_mainline(mut self, parent: u32) -> Self {
+        self.mainline = Some(parent);
+        self
+    }
+}
+
+impl Repository {
+    /// Record a new commit that reverses the changes of `hash`.
+    ///
+    /// The revert commit gets git's default message (`Revert "<subject>"`).
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The commit to revert
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `RevertStatus`.
+    pub fn revert(&self, hash: &Hash) -> Result<RevertStatus> {
+        self.revert_with_options(std::slice::from_ref(hash), RevertOptions::new())
+    }
+
+    /// Revert one or more commits with custom options.
+    ///
+    /// Commits are reverted in the order given, so list newer commits first.
+    ///
+    /// # Arguments
+    ///
+    /// * `hashes` - The commits to revert
+    /// * `options` - Revert options (no-commit, mainline parent)
+    pub fn revert_with_options(
+        &self,
+        hashes: &[Hash],
+        options: RevertOptions,
+    ) -> Result<RevertStatus> {
+        self.check_git()?;
+        if hashes.is_empty() {
+            return Err(GitError::CommandFailed(
+                "No commits specified to revert".to_string(),
+            ));
+        }
+
+        let mainline;
+        let mut args = vec!["revert", "--no-edit"];
+        if options.no_commit {
+            args.push("--no-commit");
+        }
+        if let Some(parent) = options.mainline {
+            mainline = parent.to_string();
+            args.push("-m");
+            args.push(&mainline);
+        }
+        for hash in hashes {
+            validate_arg("revision", hash.as_str())?;
+            args.push(hash.as_str());
+        }
+
+        self.revert_step(&args)
+    }
+
+    /// Check if a revert is waiting to be continued or aborted.
+    pub fn revert_in_progress(&self) -> Result<bool> {
+        self.check_git()?;
+
+        let git_dir = resolve_git_dir(self)?;
+        Ok(git_dir.join("REVERT_HEAD").exists())
+    }
+
+    /// Continue a revert after its conflicts have been resolved and staged.
+    pub fn revert_continue(&self) -> Result<RevertStatus> {
+        self.check_git()?;
+        self.revert_step(&["revert", "--continue"])
+    }
+
+    /// Abort an in-progress revert and restore the branch to where it was.
+    pub fn revert_abort(&self) -> Result<()> {
+        self.check_git()?;
+
+        git(&["revert", "--abort"], Some(self))?;
+        Ok(())
+    }
+
+    /// Run a revert step and map its outcome to a [`RevertStatus`]
+    fn revert_step(&self, args: &[&str]) -> Result<RevertStatus> {
+        // `--continue` opens an editor for the commit message; keep the default one
+        let output = git_raw_with_env(args, Some(self), &[("GIT_EDITOR", "true")])?;
+        if output.status.success() {
+            let head = git(&["rev-parse", "HEAD"], Some(self))?;
+            return Ok(RevertStatus::Success(Hash::from(head.trim())));
+        }
+
+        let conflicts = extract_conflicted_files(self)?;
+        if !conflicts.is_empty() {
+            return Ok(RevertStatus::Conflicts(conflicts));
+        }
+
+        Err(GitError::CommandFailed(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use std::fs;
+
+    #[test]
+    fn test_revert_single_and_multiple() {
+        let repo = TempRepo::new("revert_multiple").unwrap();
+        repo.commit_file("file.txt", "base\n", "Base").unwrap();
+        let add_a = repo.commit_file("a.txt", "a\n", "Add a").unwrap();
+        let add_b = repo.commit_file("b.txt", "b\n", "Add b").unwrap();
+        let add_c = repo.commit_file("c.txt", "c\n", "Add c").unwrap();
+
+        let status = repo.revert(&add_b).unwrap();
+        let RevertStatus::Success(head) = status else {
+            panic!("expected success, got {:?}", status);
+        };
+        assert_eq!(head, repo.head_hash().unwrap());
+        assert!(!repo.path().join("b.txt").exists());
+        let log = repo.recent_commits(1).unwrap();
+        assert_eq!(
+            log.iter().next().unwrap().message.subject,
+            "Revert \"Add b\""
+        );
+
+        // --no-commit leaves HEAD alone and stages the reversal of every commit
+        let before = repo.head_hash().unwrap();
+        let status = repo
+            .revert_with_options(&[add_c, add_a], RevertOptions::new().with_no_commit())
+            .unwrap();
+        assert_eq!(status, RevertStatus::Success(before));
+        assert!(!repo.path().join("a.txt").exists());
+        assert!(!repo.path().join("c.txt").exists());
+        assert!(repo.status().unwrap().has_changes());
+        repo.commit("Revert a").unwrap();
+
+        assert!(repo.revert_with_options(&[], RevertOptions::new()).is_err());
+        assert!(repo.revert(&Hash::from("--quit")).is_err());
+    }
+
+    #[test]
+    fn test_revert_merge_mainline() {
+        let repo = TempRepo::new("revert_mainline").unwrap();
+        repo.commit_file("file.txt", "base\n", "Base").unwrap();
+        let main = repo.current_branch().unwrap().unwrap();
+        repo.checkout_new("feature", None).unwrap();
+        repo.commit_file("feature.txt", "feature\n", "Feature")
+            .unwrap();
+        repo.checkout(&main).unwrap();
+        repo.commit_file("main.txt", "main\n", "Main").unwrap();
+        repo.merge("feature").unwrap();
+        let merge = repo.head_hash().unwrap();
+
+        // A merge commit needs a mainline parent
+        assert!(repo.revert(&merge).is_err());
+        let status = repo
+            .revert_with_options(&[merge], RevertOptions::new().with_mainline(1))
+            .unwrap();
+        assert!(matches!(status, RevertStatus::Success(_)));
+        assert!(!repo.path().join("feature.txt").exists());
+        assert!(repo.path().join("main.txt").exists());
+    }
+
+    #[test]
+    fn test_revert_conflicts() {
+        let repo = TempRepo::new("revert_conflicts").unwrap();
+        repo.commit_file("file.txt", "one\n", "One").unwrap();
+        let two = repo.commit_file("file.txt", "two\n", "Two").unwrap();
+        repo.commit_file("file.txt", "three\n", "Three").unwrap();
+        let before = repo.head_hash().unwrap();
+
+        let status = repo.revert(&two).unwrap();
+        assert_eq!(
+            status,
+            RevertStatus::Conflicts(vec![PathBuf::from("file.txt")])
+        );
+        assert!(repo.revert_in_progress().unwrap());
+        repo.revert_abort().unwrap();
+        assert!(!repo.revert_in_progress().unwrap());
+        assert_eq!(repo.head_hash().unwrap(), before);
+
+        assert!(matches!(
+            repo.revert(&two).unwrap(),
+            RevertStatus::Conflicts(_)
+        ));
+        repo.write_file("file.txt", "one\nthree\n").unwrap();
+        repo.mark_resolved(&["file.txt"]).unwrap();
+        assert!(matches!(
+            repo.revert_continue().unwrap(),
+            RevertStatus::Success(_)
+        ));
+        assert_eq!(
+            fs::read_to_string(repo.path().join("file.txt")).unwrap(),
+            "one\nthree\n"
+        );
+        assert!(!repo.revert_in_progress().unwrap());
+    }
+}
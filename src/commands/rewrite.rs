@@ -0,0 +1,728 @@
+//! History rewriting
+//!
+//! This module removes files from every commit reachable from any ref, for remediating
+//! leaked secrets or accidentally committed binaries. History is streamed through
+//! `git fast-export --no-data`, matching file entries are dropped, and the result is
+//! loaded back with `git fast-import --force`, so blob contents are never re-read.
+//! If `fast-export` fails, or [`PurgeOptions::with_filter_branch`] asks for it, the
+//! rewrite falls back to `git filter-branch --index-filter`, which is much slower.
+//!
+//! Rewriting is destructive: every commit from the first affected one onward gets a
+//! new hash, and collaborators must re-clone or rebase. Run with
+//! [`PurgeOptions::with_dry_run`] first to see what would change.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{PurgeOptions, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//! let options = PurgeOptions::new().with_path("config/secrets.env");
+//!
+//! // Preview the rewrite
+//! let report = repo.purge_from_history(options.clone().with_dry_run())?;
+//! println!("{} commits touch the file", report.touching_commits.len());
+//!
+//! // Rewrite and drop the old objects
+//! let report = repo.purge_from_history(options.with_prune())?;
+//! println!("Rewrote {} refs", report.refs_rewritten.len());
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, git_raw, git_raw_with_stdin, git_with_env, unquote_path};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Options selecting what to remove from history
+#[derive(Debug, Clone, Default)]
+pub struct PurgeOptions {
+    /// Paths to remove; a directory removes everything beneath it
+    pub paths: Vec<PathBuf>,
+    /// Blobs to remove wherever they appear, by hash or any revision naming a blob
+    pub blobs: Vec<String>,
+    /// Report what would change without rewriting anything
+    pub dry_run: bool,
+    /// Rewrite even if the repository does not look like a fresh clone
+    pub force: bool,
+    /// Rewrite with `git filter-branch` instead of `fast-export`/`fast-import`
+    pub filter_branch: bool,
+    /// Expire reflogs and prune unreachable objects after rewriting
+    pub prune: bool,
+}
+
+impl PurgeOptions {
+    /// Create new default purge options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove a path (file or directory) from every commit
+    pub fn with_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Remove a blob from every commit, whatever path it appears at
+    pub fn with_blob(mut self, blob: impl Into<String>) -> Self {
+        self.blobs.push(blob.into());
+        self
+    }
+
+    /// Only report what would be removed
+    pub fn with_dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
+    /// Skip the fresh clone check; a dirty working tree is still refused
+    pub fn with_force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// Use the slower `git filter-branch` backend
+    pub fn with_filter_branch(mut self) -> Self {
+        self.filter_branch = true;
+        self
+    }
+
+    /// Expire all reflogs and prune unreachable objects so the removed data is gone
+    pub fn with_prune(mut self) -> Self {
+        self.prune = true;
+        self
+    }
+}
+
+/// The outcome of a history purge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurgeReport {
+    /// Whether this was a dry run and nothing was rewritten
+    pub dry_run: bool,
+    /// Commits that add, modify, or delete a purged file, newest first.
+    /// Their descendants are rewritten too.
+    pub touching_commits: Vec<Hash>,
+    /// Every path removed from at least one commit, sorted
+    pub paths_removed: Vec<PathBuf>,
+    /// The full hashes of the blobs selected for removal
+    pub blobs_removed: Vec<Hash>,
+    /// Refs whose target changed, sorted by name (empty for a dry run)
+    pub refs_rewritten: Vec<String>,
+}
+
+impl PurgeReport {
+    /// Check whether the purge matched anything
+    pub fn is_empty(&self) -> bool {
+        self.touching_commits.is_empty()
+    }
+}
+
+impl Repository {
+    /// Remove paths or blobs from all history.
+    ///
+    /// Refuses to run if nothing is selected, if a merge is in progress, or if the
+    /// working tree is dirty. Unless [`PurgeOptions::with_force`] is set, it also refuses
+    /// repositories that do not look freshly cloned (a stash, or a reflog with more than
+    /// one entry), since local-only work is hard to recover after the rewrite. Afterwards
+    /// the current branch is hard reset to its new tip, which deletes purged files from
+    /// the working tree. Commits left empty by the purge are kept.
+    ///
+    /// Old objects stay reachable through reflogs until they expire; use
+    /// [`PurgeOptions::with_prune`] to remove them immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - What to remove and how
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `PurgeReport` describing the affected commits, paths, and refs.
+    pub fn purge_from_history(&self, options: PurgeOptions) -> Result<PurgeReport> {
+        self.check_git()?;
+
+        if options.paths.is_empty() && options.blobs.is_empty() {
+            return Err(GitError::CommandFailed(
+                "No paths or blobs selected for removal".to_string(),
+            ));
+        }
+
+        let blobs = self.resolve_blobs(&options.blobs)?;
+        let touching_commits = self.touching_commits(&options.paths, &blobs)?;
+        let paths_removed = self.matching_paths(&options.paths, &blobs)?;
+
+        let mut report = PurgeReport {
+            dry_run: options.dry_run,
+            touching_commits,
+            paths_removed,
+            blobs_removed: blobs.iter().map(|blob| Hash::from(blob.as_str())).collect(),
+            refs_rewritten: Vec::new(),
+        };
+        if options.dry_run || report.is_empty() {
+            return Ok(report);
+        }
+
+        if self.merge_in_progress()? {
+            return Err(GitError::CommandFailed(
+                "Cannot rewrite history while a merge is in progress".to_string(),
+            ));
+        }
+        if self.is_dirty()? {
+            return Err(GitError::CommandFailed(
+                "Working tree has uncommitted changes; commit or stash them first".to_string(),
+            ));
+        }
+        if !options.force && !self.looks_freshly_cloned()? {
+            return Err(GitError::CommandFailed(
+                "Repository has stashes or reflog history that the rewrite could strand; \
+                 purge a fresh clone, or use with_force()"
+                    .to_string(),
+            ));
+        }
+
+        let refs_before = self.ref_targets()?;
+
+        if options.filter_branch || !self.purge_with_fast_import(&options.paths, &blobs)? {
+            self.purge_with_filter_branch(&options.paths, &blobs)?;
+        }
+
+        let refs_after = self.ref_targets()?;
+        report.refs_rewritten = refs_after
+            .iter()
+            .filter(|(name, target)| refs_before.get(*name) != Some(target))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        if options.prune {
+            git(&["reflog", "expire", "--expire=now", "--all"], Some(self))?;
+            git(&["gc", "--prune=now", "--quiet"], Some(self))?;
+        }
+
+        Ok(report)
+    }
+
+    /// A fresh clone has no stash and a single reflog entry per ref
+    fn looks_freshly_cloned(&self) -> Result<bool> {
+        let selectors = git(
+            &["log", "--walk-reflogs", "--all", "--format=%gd"],
+            Some(self),
+        )?;
+        Ok(!selectors
+            .lines()
+            .any(|selector| selector.starts_with("refs/stash@") || selector.ends_with("@{1}")))
+    }
+
+    /// Rewrite through `fast-export`/`fast-import`
+    ///
+    /// Returns `false` without touching anything if `fast-export` fails, so the caller
+    /// can fall back to `filter-branch`.
+    fn purge_with_fast_import(&self, paths: &[PathBuf], blobs: &HashSet<String>) -> Result<bool> {
+        let export = git_raw(
+            &[
+                "fast-export",
+                "--all",
+                "--no-data",
+                "--signed-tags=strip",
+                "--tag-of-filtered-object=rewrite",
+            ],
+            Some(self),
+        )?;
+        if !export.status.success() {
+            return Ok(false);
+        }
+
+        let filtered = filter_fast_export(&export.stdout, paths, blobs);
+        let import = git_raw_with_stdin(
+            &["fast-import", "--force", "--quiet"],
+            Some(self),
+            &filtered,
+        )?;
+        if !import.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git fast-import failed: {}",
+                String::from_utf8_lossy(&import.stderr)
+            )));
+        }
+
+        // fast-import only moves refs; bring the index and working tree along. The tree
+        // was checked to be clean, so this only drops the purged files.
+        git(&["reset", "--hard", "-q", "HEAD"], Some(self))?;
+        Ok(true)
+    }
+
+    /// Rewrite through `filter-branch --index-filter`, then drop its `refs/original/` backups
+    fn purge_with_filter_branch(&self, paths: &[PathBuf], blobs: &HashSet<String>) -> Result<()> {
+        let mut commands = Vec::new();
+        if !paths.is_empty() {
+            let quoted: Vec<String> = paths
+                .iter()
+                .map(|path| shell_quote(&path.to_string_lossy()))
+                .collect();
+            commands.push(format!(
+                "git --literal-pathspecs rm -r -q --cached --ignore-unmatch -- {}",
+                quoted.join(" ")
+            ));
+        }
+        if !blobs.is_empty() {
+            // update-index unquotes the C-quoted paths ls-files prints
+            let hashes: Vec<&str> = blobs.iter().map(String::as_str).collect();
+            commands.push(format!(
+                "git ls-files -s | grep -E '^[0-7]+ ({}) ' | cut -f2- | \
+                 git update-index --force-remove --stdin",
+                hashes.join("|")
+            ));
+        }
+
+        let index_filter = commands.join(" && ");
+        git_with_env(
+            &[
+                "filter-branch",
+                "--force",
+                "--index-filter",
+                &index_filter,
+                "--tag-name-filter",
+                "cat",
+                "--",
+                "--all",
+            ],
+            Some(self),
+            &[("FILTER_BRANCH_SQUELCH_WARNING", "1")],
+        )?;
+
+        let backups = git(
+            &[
+                "for-each-ref",
+                "--format=delete %(refname)",
+                "refs/original/",
+            ],
+            Some(self),
+        )?;
+        let output =
+            git_raw_with_stdin(&["update-ref", "--stdin"], Some(self), backups.as_bytes())?;
+        if !output.status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git update-ref failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Resolve blob selectors to full hashes, rejecting anything that is not a blob
+    fn resolve_blobs(&self, blobs: &[String]) -> Result<HashSet<String>> {
+        // `<rev>:<path>^{blob}` would be read as a path, so check the type separately
+        blobs
+            .iter()
+            .map(|blob| {
+                let hash = git(&["rev-parse", "--verify", "-q", blob], Some(self))
+                    .map_err(|_| GitError::CommandFailed(format!("Unknown object: {}", blob)))?;
+                let hash = hash.trim().to_string();
+                let object_type = git(&["cat-file", "-t", &hash], Some(self))?;
+                if object_type.trim() != "blob" {
+                    return Err(GitError::CommandFailed(format!("Not a blob: {}", blob)));
+                }
+                Ok(hash)
+            })
+            .collect()
+    }
+
+    /// Commits that change a purged path or add or remove a purged blob, newest first
+    fn touching_commits(&self, paths: &[PathBuf], blobs: &HashSet<String>) -> Result<Vec<Hash>> {
+        let mut commits = Vec::new();
+        let mut seen = HashSet::new();
+        let mut collect = |output: String| {
+            for hash in output.lines().filter(|line| !line.is_empty()) {
+                if seen.insert(hash.to_string()) {
+                    commits.push(hash.to_string());
+                }
+            }
+        };
+
+        if !paths.is_empty() {
+            let mut args = vec!["log", "--all", "--format=%H", "--"];
+            let path_args: Vec<String> = paths
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect();
+            args.extend(path_args.iter().map(String::as_str));
+            collect(git(&args, Some(self))?);
+        }
+
+        for blob in blobs {
+            let find_object = format!("--find-object={}", blob);
+            collect(git(
+                &["log", "--all", "--format=%H", &find_object],
+                Some(self),
+            )?);
+        }
+
+        // Restore a single newest-first order across the separate queries. There can be
+        // a hash per commit in history, so they go through stdin rather than arguments.
+        if !commits.is_empty() {
+            let input: String = commits.iter().map(|hash| format!("{}\n", hash)).collect();
+            let output = git_raw_with_stdin(
+                &["rev-list", "--no-walk=sorted", "--stdin"],
+                Some(self),
+                input.as_bytes(),
+            )?;
+            if !output.status.success() {
+                return Err(GitError::CommandFailed(format!(
+                    "git rev-list failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+            let sorted = String::from_utf8_lossy(&output.stdout);
+            return Ok(sorted.lines().map(Hash::from).collect());
+        }
+        Ok(Vec::new())
+    }
+
+    /// Every path in history that the purge removes
+    fn matching_paths(&self, paths: &[PathBuf], blobs: &HashSet<String>) -> Result<Vec<PathBuf>> {
+        let mut matched = HashSet::new();
+
+        if !paths.is_empty() {
+            let mut args = vec!["log", "--all", "--format=", "--name-only", "-z", "--"];
+            let path_args: Vec<String> = paths
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect();
+            args.extend(path_args.iter().map(String::as_str));
+            let output = git(&args, Some(self))?;
+            matched.extend(
+                output
+                    .split('\0')
+                    .map(|path| path.trim_start_matches('\n'))
+                    .filter(|path| !path.is_empty())
+                    .map(PathBuf::from),
+            );
+        }
+
+        if !blobs.is_empty() {
+            let objects = git(&["rev-list", "--objects", "--all"], Some(self))?;
+            for line in objects.lines() {
+                if let Some((hash, path)) = line.split_once(' ')
+                    && blobs.contains(hash)
+                {
+                    matched.insert(PathBuf::from(path));
+                }
+            }
+        }
+
+        let mut matched: Vec<PathBuf> = matched.into_iter().collect();
+        matched.sort();
+        Ok(matched)
+    }
+
+    /// Map every ref to the object it points at
+    fn ref_targets(&self) -> Result<BTreeMap<String, String>> {
+        let output = git(
+            &["for-each-ref", "--format=%(refname) %(objectname)"],
+            Some(self),
+        )?;
+        Ok(output
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(name, target)| (name.to_string(), target.to_string()))
+            .collect())
+    }
+}
+
+/// Quote a string for a POSIX shell command line
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Drop file entries for purged paths or blobs from a `git fast-export --no-data` stream
+///
+/// `data <n>` payloads (commit and tag messages) are copied verbatim so message text is
+/// never mistaken for commands.
+fn filter_fast_export(stream: &[u8], paths: &[PathBuf], blobs: &HashSet<String>) -> Vec<u8> {
+    let mut output = Vec::with_capacity(stream.len());
+    let mut pos = 0;
+
+    while pos < stream.len() {
+        let end = stream[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(stream.len(), |i| pos + i + 1);
+        let line = &stream[pos..end];
+        pos = end;
+
+        if let Some(count) = line
+            .strip_prefix(b"data ")
+            .and_then(|rest| std::str::from_utf8(rest).ok())
+            .and_then(|rest| rest.trim_end().parse::<usize>().ok())
+        {
+            output.extend_from_slice(line);
+            let data_end = (pos + count).min(stream.len());
+            output.extend_from_slice(&stream[pos..data_end]);
+            pos = data_end;
+            continue;
+        }
+
+        if !is_purged_file_command(line, paths, blobs) {
+            output.extend_from_slice(line);
+        }
+    }
+
+    output
+}
+
+/// Check whether a `M <mode> <dataref> <path>` or `D <path>` command touches a purged file
+fn is_purged_file_command(line: &[u8], paths: &[PathBuf], blobs: &HashSet<String>) -> bool {
+    let Ok(line) = std::str::from_utf8(line) else {
+        return false;
+    };
+    let line = line.trim_end_matches('\n');
+
+    let (dataref, path) = if let Some(rest) = line.strip_prefix("M ") {
+        let mut fields = rest.splitn(3, ' ');
+        let (Some(_mode), Some(dataref), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return false;
+        };
+        (Some(dataref), path)
+    } else if let Some(path) = line.strip_prefix("D ") {
+        (None, path)
+    } else {
+        return false;
+    };
+
+    if dataref.is_some_and(|dataref| blobs.contains(dataref)) {
+        return true;
+    }
+
+    let path = unquote_path(path);
+    let path = Path::new(&path);
+    paths.iter().any(|purged| path.starts_with(purged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
+        let temp_dir = env::temp_dir().join(format!("rustic_git_rewrite_test_{}", test_name));
+
+        // Clean up if exists
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+
+        // Configure git user for testing
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_filter_fast_export() {
+        let secret_blob = "a".repeat(40);
+        let blobs = HashSet::from([secret_blob.clone()]);
+        let paths = vec![PathBuf::from("secrets")];
+        let stream = format!(
+            "commit refs/heads/main\n\
+             mark :1\n\
+             data 20\n\
+             M 100644 deadbeef x\n\
+             \n\
+             M 100644 {} leaked.txt\n\
+             M 100644 1111 secrets/key.pem\n\
+             M 100644 2222 \"secrets/my key.pem\"\n\
+             M 100644 3333 secretsfile.txt\n\
+             D secrets/old.pem\n\
+             M 100644 4444 README.md\n\n",
+            secret_blob
+        );
+
+        let filtered = filter_fast_export(stream.as_bytes(), &paths, &blobs);
+        assert_eq!(
+            String::from_utf8(filtered).unwrap(),
+            "commit refs/heads/main\n\
+             mark :1\n\
+             data 20\n\
+             M 100644 deadbeef x\n\
+             \n\
+             M 100644 3333 secretsfile.txt\n\
+             M 100644 4444 README.md\n\n"
+        );
+    }
+
+    #[test]
+    fn test_purge_requires_targets_and_clean_tree() {
+        let (test_path, repo) = create_test_repo("guards");
+        fs::write(test_path.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+
+        assert!(repo.purge_from_history(PurgeOptions::new()).is_err());
+        assert!(
+            repo.purge_from_history(PurgeOptions::new().with_blob("HEAD"))
+                .is_err()
+        );
+
+        // Local reflog history needs force
+        fs::write(test_path.join("other.txt"), "other").unwrap();
+        repo.add(&["other.txt"]).unwrap();
+        repo.commit("Second commit").unwrap();
+        let error = repo
+            .purge_from_history(PurgeOptions::new().with_path("file.txt"))
+            .unwrap_err();
+        assert!(error.to_string().contains("with_force"));
+
+        // A dirty tree is refused even when forced, and left untouched
+        fs::write(test_path.join("file.txt"), "modified").unwrap();
+        assert!(
+            repo.purge_from_history(PurgeOptions::new().with_path("file.txt").with_force())
+                .is_err()
+        );
+        assert_eq!(
+            fs::read_to_string(test_path.join("file.txt")).unwrap(),
+            "modified"
+        );
+
+        // Dry runs are always allowed
+        let report = repo
+            .purge_from_history(PurgeOptions::new().with_path("file.txt").with_dry_run())
+            .unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.touching_commits.len(), 1);
+
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_purge_path_and_blob_from_history() {
+        let (test_path, repo) = create_test_repo("purge");
+
+        fs::write(test_path.join("README.md"), "readme").unwrap();
+        fs::write(test_path.join("secret.env"), "TOKEN=abc").unwrap();
+        repo.add(&["README.md", "secret.env"]).unwrap();
+        repo.commit("Initial commit").unwrap();
+
+        fs::create_dir(test_path.join("keys")).unwrap();
+        fs::write(test_path.join("keys/id_rsa"), "private").unwrap();
+        fs::write(test_path.join("big.bin"), "binary data").unwrap();
+        repo.add(&["keys/id_rsa", "big.bin"]).unwrap();
+        repo.commit("Add keys and binary").unwrap();
+
+        repo.rm(&["secret.env"]).unwrap();
+        repo.commit("Remove secret").unwrap();
+
+        let big_blob = git(&["rev-parse", "HEAD:big.bin"], Some(&test_path))
+            .unwrap()
+            .trim()
+            .to_string();
+        let options = PurgeOptions::new()
+            .with_path("secret.env")
+            .with_path("keys")
+            .with_blob("HEAD:big.bin");
+
+        let dry_run = repo
+            .purge_from_history(options.clone().with_dry_run())
+            .unwrap();
+        assert_eq!(dry_run.touching_commits.len(), 3);
+        assert_eq!(
+            dry_run.paths_removed,
+            vec![
+                PathBuf::from("big.bin"),
+                PathBuf::from("keys/id_rsa"),
+                PathBuf::from("secret.env"),
+            ]
+        );
+        assert_eq!(dry_run.blobs_removed, vec![Hash::from(big_blob.as_str())]);
+        assert!(dry_run.refs_rewritten.is_empty());
+        assert_eq!(repo.recent_commits(10).unwrap().len(), 3);
+
+        let report = repo
+            .purge_from_history(options.with_force().with_prune())
+            .unwrap();
+        assert!(!report.dry_run);
+        assert_eq!(report.refs_rewritten.len(), 1);
+        assert!(report.refs_rewritten[0].starts_with("refs/heads/"));
+
+        // Commits are kept, but no longer contain the purged files
+        assert_eq!(repo.recent_commits(10).unwrap().len(), 3);
+        let all_paths = git(
+            &["log", "--all", "--format=", "--name-only"],
+            Some(&test_path),
+        )
+        .unwrap();
+        assert_eq!(all_paths.trim(), "README.md");
+        assert!(!repo.object_exists(&big_blob).unwrap());
+        assert!(!test_path.join("keys/id_rsa").exists());
+        assert!(test_path.join("README.md").exists());
+
+        // Nothing left to purge
+        let report = repo
+            .purge_from_history(PurgeOptions::new().with_path("secret.env"))
+            .unwrap();
+        assert!(report.is_empty());
+
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+    #[test]
+    fn test_purge_fresh_clone_with_filter_branch() {
+        let (source_path, source) = create_test_repo("filter_branch_source");
+        fs::write(source_path.join("README.md"), "readme").unwrap();
+        fs::write(source_path.join("it's secret.env"), "TOKEN=abc").unwrap();
+        fs::write(source_path.join("big.bin"), "binary data").unwrap();
+        source.add_all().unwrap();
+        source.commit("Initial commit").unwrap();
+        fs::write(source_path.join("README.md"), "readme v2").unwrap();
+        source.add(&["README.md"]).unwrap();
+        source.commit("Update readme").unwrap();
+
+        let clone_path = env::temp_dir().join("rustic_git_rewrite_test_filter_branch_clone");
+        if clone_path.exists() {
+            fs::remove_dir_all(&clone_path).unwrap();
+        }
+        let repo = Repository::clone(source_path.to_str().unwrap(), &clone_path).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        // A fresh clone needs no force
+        let report = repo
+            .purge_from_history(
+                PurgeOptions::new()
+                    .with_path("it's secret.env")
+                    .with_blob("HEAD:big.bin")
+                    .with_filter_branch(),
+            )
+            .unwrap();
+        assert_eq!(report.touching_commits.len(), 1);
+        assert!(
+            report
+                .refs_rewritten
+                .iter()
+                .any(|r| r.starts_with("refs/heads/"))
+        );
+
+        let all_paths = git(
+            &["log", "--all", "--format=", "--name-only"],
+            Some(&clone_path),
+        )
+        .unwrap();
+        let mut all_paths: Vec<&str> = all_paths.lines().filter(|l| !l.is_empty()).collect();
+        all_paths.dedup();
+        assert_eq!(all_paths, vec!["README.md"]);
+        assert_eq!(repo.recent_commits(10).unwrap().len(), 2);
+        assert!(!clone_path.join("big.bin").exists());
+        assert!(clone_path.join("README.md").exists());
+        let backups = git(&["for-each-ref", "refs/original/"], Some(&clone_path)).unwrap();
+        assert!(backups.is_empty());
+
+        fs::remove_dir_all(&source_path).unwrap();
+        fs::remove_dir_all(&clone_path).unwrap();
+    }
+}
@@ -0,0 +1,219 @@
+//! Sparse checkout and the sparse index
+//!
+//! In a large monorepo most developers only need a few directories. A cone-mode sparse
+//! checkout limits the working tree to them, and the sparse index additionally collapses
+//! everything outside those directories into single index entries, so status, add,
+//! diff and commit scale with the checked-out part instead of the whole repository.
+//!
+//! Staging through [`Repository::add`], [`Repository::add_all`] and
+//! [`Repository::add_update`] passes `--sparse`, so changes to files outside the
+//! sparse-checkout definition are staged instead of being skipped or rejected.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::Repository;
+//!
+//! let repo = Repository::open(".")?;
+//! repo.sparse_checkout_set(&["services/billing", "libs/common"])?;
+//! repo.enable_sparse_index()?;
+//! assert!(repo.is_sparse_index()?);
+//!
+//! let status = repo.status()?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::GitError;
+use crate::utils::{git, git_raw};
+use crate::{Repository, Result};
+
+impl Repository {
+    /// Restrict the working tree to the given directories (cone mode)
+    ///
+    /// Files directly in the repository root are always checked out. Calling this again
+    /// replaces the previous set of directories.
+    ///
+    /// # Arguments
+    /// * `directories` - Directories to check out, relative to the repository root
+    pub fn sparse_checkout_set(&self, directories: &[&str]) -> Result<()> {
+        self.check_git()?;
+
+        let mut args = vec!["sparse-checkout", "set", "--cone", "--"];
+        args.extend_from_slice(directories);
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// List the directories of the current sparse checkout
+    pub fn sparse_checkout_list(&self) -> Result<Vec<String>> {
+        self.check_git()?;
+
+        if !self.is_sparse_checkout()? {
+            return Ok(Vec::new());
+        }
+        let output = git(&["sparse-checkout", "list"], Some(self))?;
+        Ok(output
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Check out the full working tree again and turn off sparse checkout
+    ///
+    /// This also turns off the sparse index.
+    pub fn sparse_checkout_disable(&self) -> Result<()> {
+        self.check_git()?;
+
+        git(&["sparse-checkout", "disable"], Some(self))?;
+        Ok(())
+    }
+
+    /// Check whether a sparse checkout is active
+    pub fn is_sparse_checkout(&self) -> Result<bool> {
+        self.check_git()?;
+        self.config_flag("core.sparseCheckout")
+    }
+
+    /// Switch the index to the sparse format (`index.sparse=true`)
+    ///
+    /// Requires an active cone-mode sparse checkout, e.g. from
+    /// [`sparse_checkout_set`](Self::sparse_checkout_set).
+    pub fn enable_sparse_index(&self) -> Result<()> {
+        self.check_git()?;
+
+        if !self.is_sparse_checkout()? || !self.config_flag("core.sparseCheckoutCone")? {
+            return Err(GitError::CommandFailed(
+                "the sparse index requires a cone-mode sparse checkout".to_string(),
+            ));
+        }
+        git(
+            &["sparse-checkout", "reapply", "--sparse-index"],
+            Some(self),
+        )?;
+        Ok(())
+    }
+
+    /// Convert the index back to the full format, keeping the sparse checkout
+    pub fn disable_sparse_index(&self) -> Result<()> {
+        self.check_git()?;
+
+        if !self.is_sparse_checkout()? {
+            return Ok(());
+        }
+        git(
+            &["sparse-checkout", "reapply", "--no-sparse-index"],
+            Some(self),
+        )?;
+        Ok(())
+    }
+
+    /// Check whether the sparse index is in use
+    ///
+    /// Git only honors `index.sparse` together with a cone-mode sparse checkout, so all
+    /// three settings must be enabled.
+    pub fn is_sparse_index(&self) -> Result<bool> {
+        self.check_git()?;
+
+        Ok(self.config_flag("index.sparse")?
+            && self.config_flag("core.sparseCheckout")?
+            && self.config_flag("core.sparseCheckoutCone")?)
+    }
+
+    /// Read a boolean config value, treating unset as false
+    fn config_flag(&self, key: &str) -> Result<bool> {
+        let output = git_raw(&["config", "--type=bool", "--get", key], Some(self))?;
+        Ok(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Repository;
+    use std::path::{Path, PathBuf};
+    use std::{env, fs};
+
+    fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
+        let temp_dir = env::temp_dir().join(format!("rustic_git_sparse_test_{}", test_name));
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        for dir in ["app", "lib"] {
+            fs::create_dir_all(temp_dir.join(dir)).unwrap();
+            fs::write(temp_dir.join(dir).join("file.txt"), dir).unwrap();
+        }
+        fs::write(temp_dir.join("README.md"), "readme").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("Initial commit").unwrap();
+
+        (temp_dir, repo)
+    }
+
+    #[test]
+    fn test_sparse_index_enable_and_detect() {
+        let (temp_dir, repo) = create_test_repo("enable");
+        assert!(!repo.is_sparse_checkout().unwrap());
+        assert!(!repo.is_sparse_index().unwrap());
+        assert!(repo.sparse_checkout_list().unwrap().is_empty());
+        assert!(repo.enable_sparse_index().is_err());
+
+        repo.sparse_checkout_set(&["app"]).unwrap();
+        assert!(repo.is_sparse_checkout().unwrap());
+        assert_eq!(repo.sparse_checkout_list().unwrap(), vec!["app"]);
+        assert!(!temp_dir.join("lib").exists());
+
+        repo.enable_sparse_index().unwrap();
+        assert!(repo.is_sparse_index().unwrap());
+
+        repo.disable_sparse_index().unwrap();
+        assert!(!repo.is_sparse_index().unwrap());
+        assert!(repo.is_sparse_checkout().unwrap());
+
+        repo.sparse_checkout_disable().unwrap();
+        assert!(!repo.is_sparse_checkout().unwrap());
+        assert!(temp_dir.join("lib/file.txt").exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_status_add_diff_with_sparse_index() {
+        let (temp_dir, repo) = create_test_repo("workflow");
+        repo.sparse_checkout_set(&["app"]).unwrap();
+        repo.enable_sparse_index().unwrap();
+        assert!(repo.status().unwrap().is_clean());
+
+        fs::write(temp_dir.join("app/file.txt"), "changed").unwrap();
+        let status = repo.status().unwrap();
+        assert_eq!(status.unstaged_files().count(), 1);
+        assert_eq!(
+            repo.diff().unwrap().files[0].path,
+            Path::new("app/file.txt")
+        );
+
+        // A file outside the sparse-checkout definition is staged, not skipped
+        fs::create_dir_all(temp_dir.join("lib")).unwrap();
+        fs::write(temp_dir.join("lib/file.txt"), "outside").unwrap();
+        repo.add_all().unwrap();
+        let status = repo.status().unwrap();
+        assert_eq!(status.staged_files().count(), 2);
+        assert_eq!(status.unstaged_files().count(), 0);
+
+        // Explicit paths outside the cone are accepted too
+        fs::write(temp_dir.join("lib/file.txt"), "outside again").unwrap();
+        repo.add(&["lib/file.txt"]).unwrap();
+        assert_eq!(repo.status().unwrap().unstaged_files().count(), 0);
+
+        repo.commit("Change both").unwrap();
+        assert!(repo.status().unwrap().is_clean());
+        assert!(repo.is_sparse_index().unwrap());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -30,8 +30,8 @@
 
 use crate::error::{GitError, Result};
 use crate::repository::Repository;
-use crate::types::Hash;
-use crate::utils::{git, parse_unix_timestamp};
+use crate::types::{Hash, Identity};
+use crate::utils::{git, git_raw, git_with_env, parse_unix_timestamp};
 use chrono::{DateTime, Utc};
 use std::fmt;
 use std::path::PathBuf;
@@ -129,6 +129,8 @@ pub struct StashOptions {
     pub staged_only: bool,
     /// Paths to specifically stash
     pub paths: Vec<PathBuf>,
+    /// Identity recorded on the stash commits, instead of the configured user
+    pub identity: Option<Identity>,
 }
 
 impl StashOptions {
@@ -173,6 +175,14 @@ impl StashOptions {
         self.paths = paths;
         self
     }
+
+    /// Create the stash as `identity` without changing the config
+    ///
+    /// Useful where no user is configured, since stashing creates commits.
+    pub fn with_identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
 }
 
 /// Options for applying stashes
@@ -223,12 +233,9 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn stash_list(&self) -> Result<StashList> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
-        let output = git(
-            &["stash", "list", "--format=%gd %H %ct %gs"],
-            Some(self.repo_path()),
-        )?;
+        let output = git(&["stash", "list", "--format=%gd %H %ct %gs"], Some(self))?;
 
         if output.trim().is_empty() {
             return Ok(StashList::new(vec![]));
@@ -295,7 +302,7 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn stash_push(&self, message: &str, options: StashOptions) -> Result<Stash> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         let mut args = vec!["stash", "push"];
 
@@ -329,7 +336,9 @@ impl Repository {
             }
         }
 
-        git(&args, Some(self.repo_path()))?;
+        let env = options.identity.as_ref().map(Identity::env);
+        let env: &[(&str, &str)] = env.as_ref().map_or(&[], |env| env.as_slice());
+        git_with_env(&args, Some(self), env)?;
 
         // Get the newly created stash (it will be at index 0)
         let stashes = self.stash_list()?;
@@ -358,7 +367,7 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn stash_apply(&self, index: usize, options: StashApplyOptions) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         let mut args = vec!["stash", "apply"];
 
@@ -373,7 +382,7 @@ impl Repository {
         let stash_ref = format!("stash@{{{}}}", index);
         args.push(&stash_ref);
 
-        git(&args, Some(self.repo_path()))?;
+        git(&args, Some(self))?;
         Ok(())
     }
 
@@ -394,7 +403,7 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn stash_pop(&self, index: usize, options: StashApplyOptions) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         let mut args = vec!["stash", "pop"];
 
@@ -409,7 +418,7 @@ impl Repository {
         let stash_ref = format!("stash@{{{}}}", index);
         args.push(&stash_ref);
 
-        git(&args, Some(self.repo_path()))?;
+        git(&args, Some(self))?;
         Ok(())
     }
 
@@ -430,16 +439,74 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn stash_show(&self, index: usize) -> Result<String> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         let output = git(
             &["stash", "show", &format!("stash@{{{}}}", index)],
-            Some(self.repo_path()),
+            Some(self),
         )?;
 
         Ok(output)
     }
 
+    /// List the files touched by a stash
+    ///
+    /// Includes tracked files changed in the working tree or index as well as
+    /// untracked files that were stashed with `--include-untracked` (stored in
+    /// the stash commit's third parent).
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The stash index to inspect (0 is most recent)
+    ///
+    /// # Returns
+    ///
+    /// A sorted, de-duplicated list of repository-relative paths
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// for path in repo.stash_files(0)? {
+    ///     println!("{}", path.display());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn stash_files(&self, index: usize) -> Result<Vec<PathBuf>> {
+        self.check_git()?;
+
+        let stash_ref = format!("stash@{{{}}}", index);
+        let base_ref = format!("{}^1", stash_ref);
+        let index_ref = format!("{}^2", stash_ref);
+
+        // Working tree changes against the base commit, plus anything staged
+        let mut files = parse_nul_paths(&git(
+            &["diff", "--name-only", "-z", &base_ref, &stash_ref],
+            Some(self),
+        )?);
+        files.extend(parse_nul_paths(&git(
+            &["diff", "--name-only", "-z", &base_ref, &index_ref],
+            Some(self),
+        )?));
+
+        let untracked_ref = format!("{}^3", stash_ref);
+        let has_untracked = git_raw(&["rev-parse", "--verify", "-q", &untracked_ref], Some(self))?
+            .status
+            .success();
+        if has_untracked {
+            files.extend(parse_nul_paths(&git(
+                &["ls-tree", "-r", "--name-only", "-z", &untracked_ref],
+                Some(self),
+            )?));
+        }
+
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
     /// Delete a specific stash
     ///
     /// # Arguments
@@ -456,11 +523,11 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn stash_drop(&self, index: usize) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         git(
             &["stash", "drop", &format!("stash@{{{}}}", index)],
-            Some(self.repo_path()),
+            Some(self),
         )?;
 
         Ok(())
@@ -478,14 +545,23 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn stash_clear(&self) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
-        git(&["stash", "clear"], Some(self.repo_path()))?;
+        git(&["stash", "clear"], Some(self))?;
         Ok(())
     }
 }
 
 /// Parse a stash list line into a Stash struct
+/// Parse NUL-separated path output into a list of paths
+fn parse_nul_paths(output: &str) -> Vec<PathBuf> {
+    output
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
 fn parse_stash_line(index: usize, line: &str) -> Result<Stash> {
     // Format: "stash@{0} hash timestamp On branch: message"
     let parts: Vec<&str> = line.splitn(4, ' ').collect();
@@ -741,6 +817,42 @@ mod tests {
         fs::remove_dir_all(&test_path).unwrap();
     }
 
+    #[test]
+    fn test_stash_files_includes_untracked() {
+        let (repo, test_path) = create_test_repo();
+
+        create_test_commit(&repo, &test_path, "tracked.txt", "initial");
+        create_test_commit(&repo, &test_path, "staged.txt", "initial");
+
+        fs::write(test_path.join("tracked.txt"), "modified").unwrap();
+        fs::write(test_path.join("staged.txt"), "staged change").unwrap();
+        repo.add(&["staged.txt"]).unwrap();
+        fs::write(test_path.join("new.txt"), "untracked").unwrap();
+
+        repo.stash_push("With untracked", StashOptions::new().with_untracked())
+            .unwrap();
+
+        let files = repo.stash_files(0).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("new.txt"),
+                PathBuf::from("staged.txt"),
+                PathBuf::from("tracked.txt"),
+            ]
+        );
+
+        // Without untracked files only tracked changes are listed
+        fs::write(test_path.join("tracked.txt"), "again").unwrap();
+        repo.stash_save("Tracked only").unwrap();
+        let files = repo.stash_files(0).unwrap();
+        assert_eq!(files, vec![PathBuf::from("tracked.txt")]);
+
+        assert!(repo.stash_files(5).is_err());
+
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
     #[test]
     fn test_stash_list_filtering() {
         let (repo, test_path) = create_test_repo();
@@ -776,6 +888,29 @@ mod tests {
         fs::remove_dir_all(&test_path).unwrap();
     }
 
+    #[test]
+    fn test_stash_push_with_identity() {
+        let (repo, test_path) = create_test_repo();
+        create_test_commit(&repo, &test_path, "initial.txt", "initial content");
+
+        fs::write(test_path.join("initial.txt"), "modified").unwrap();
+        repo.stash_push(
+            "bot stash",
+            StashOptions::new().with_identity(Identity::new("Stash Bot", "stash@example.com")),
+        )
+        .unwrap();
+
+        let author = git(
+            &["log", "-1", "--format=%an <%ae>", "stash@{0}"],
+            Some(&repo),
+        )
+        .unwrap();
+        assert_eq!(author.trim(), "Stash Bot <stash@example.com>");
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
     #[test]
     fn test_stash_options_builder() {
         let options = StashOptions::new()
@@ -0,0 +1,433 @@
+//! Commit statistics
+//!
+//! This module aggregates commit and line-change counts per author and per day from a
+//! single `git log --numstat` pass, returning structured data for project dashboards.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{LogOptions, Repository};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! // Statistics for the whole history of HEAD
+//! let stats = repo.commit_stats(None)?;
+//! for author in stats.authors.iter() {
+//!     println!(
+//!         "{}: {} commits, +{} -{}",
+//!         author.name, author.commits, author.insertions, author.deletions
+//!     );
+//! }
+//!
+//! // Statistics for a range
+//! let release = repo.commit_stats(Some("v1.0.0..HEAD"))?;
+//! println!("{} commits since v1.0.0", release.total_commits);
+//!
+//! // Contributors since the last release, with .mailmap applied
+//! for contributor in repo.shortlog(&LogOptions::new().range("v1.0.0", "HEAD"))? {
+//!     println!("{} ({})", contributor.name, contributor.commits);
+//! }
+//!
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::log::{LogOptions, build_log_args};
+use crate::error::Result;
+use crate::repository::Repository;
+use crate::utils::{git, parse_unix_timestamp, validate_arg};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Record separator placed before each commit header in the log output
+const RECORD_SEPARATOR: char = '\x00';
+
+/// Field separator used inside a commit header
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// Log format: record separator, then hash, author name, author email, author timestamp
+const STATS_LOG_FORMAT: &str = "--format=%x00%H%x1f%an%x1f%ae%x1f%at";
+
+/// Shortlog format: like `STATS_LOG_FORMAT`, with names and emails mapped through
+/// `.mailmap` (`%aN`, `%aE`) as `git shortlog` does
+const SHORTLOG_FORMAT: &str = "--pretty=format:%x00%H%x1f%aN%x1f%aE%x1f%at";
+
+/// Aggregated statistics for a single author
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorStats {
+    /// The author name
+    pub name: String,
+    /// The author email
+    pub email: String,
+    /// Number of commits authored
+    pub commits: usize,
+    /// Total lines added
+    pub insertions: usize,
+    /// Total lines removed
+    pub deletions: usize,
+    /// Author date of the author's oldest commit in the range
+    pub first_commit: DateTime<Utc>,
+    /// Author date of the author's newest commit in the range
+    pub last_commit: DateTime<Utc>,
+}
+
+impl AuthorStats {
+    /// Get total changes (insertions + deletions)
+    pub fn total_changes(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+impl fmt::Display for AuthorStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>6}  {} <{}> (+{} -{})",
+            self.commits, self.name, self.email, self.insertions, self.deletions
+        )
+    }
+}
+
+/// Per-contributor statistics returned by [`Repository::shortlog`]
+///
+/// The same type as [`AuthorStats`], so shortlog results and the authors of
+/// [`CommitStats`] can be handled by the same code.
+pub type ContributorStats = AuthorStats;
+
+/// Aggregated statistics for a single day (UTC, by author date)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayStats {
+    /// The calendar day
+    pub date: NaiveDate,
+    /// Number of commits authored on this day
+    pub commits: usize,
+    /// Total lines added
+    pub insertions: usize,
+    /// Total lines removed
+    pub deletions: usize,
+}
+
+/// Commit statistics over a range of history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitStats {
+    /// Per-author statistics, sorted by commit count (descending) then name
+    pub authors: Box<[AuthorStats]>,
+    /// Per-day statistics, sorted chronologically
+    pub days: Box<[DayStats]>,
+    /// Total number of commits in the range
+    pub total_commits: usize,
+    /// Total lines added in the range
+    pub insertions: usize,
+    /// Total lines removed in the range
+    pub deletions: usize,
+}
+
+impl CommitStats {
+    /// Find statistics for an author by name or email
+    pub fn author(&self, name_or_email: &str) -> Option<&AuthorStats> {
+        self.authors
+            .iter()
+            .find(|a| a.name == name_or_email || a.email == name_or_email)
+    }
+
+    /// Find statistics for a specific day
+    pub fn day(&self, date: NaiveDate) -> Option<&DayStats> {
+        self.days.iter().find(|d| d.date == date)
+    }
+
+    /// Get the most active day by commit count
+    pub fn busiest_day(&self) -> Option<&DayStats> {
+        self.days.iter().max_by_key(|d| d.commits)
+    }
+
+    /// Check if the range contained no commits
+    pub fn is_empty(&self) -> bool {
+        self.total_commits == 0
+    }
+}
+
+impl fmt::Display for CommitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} commits, {} insertions(+), {} deletions(-)",
+            self.total_commits, self.insertions, self.deletions
+        )?;
+        for author in self.authors.iter() {
+            writeln!(
+                f,
+                "  {} <{}>: {} commits, +{} -{}",
+                author.name, author.email, author.commits, author.insertions, author.deletions
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Repository {
+    /// Compute per-author and per-day commit statistics.
+    ///
+    /// Runs a single `git log --numstat` over the given range and aggregates commit,
+    /// insertion, and deletion counts. Binary files do not contribute line counts.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - Optional revision range (e.g. `"v1.0..HEAD"`); defaults to `HEAD`
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the aggregated `CommitStats` or a `GitError`.
+    pub fn commit_stats(&self, range: Option<&str>) -> Result<CommitStats> {
+        self.check_git()?;
+
+        let range = range.unwrap_or("HEAD");
+        validate_arg("revision", range)?;
+
+        let args = [
+            "log",
+            STATS_LOG_FORMAT,
+            "--numstat",
+            "--no-renames",
+            range,
+            "--",
+        ];
+
+        let output = git(&args, Some(self))?;
+        parse_stats_output(&output)
+    }
+
+    /// Summarize contributors, like `git shortlog -sne` with line counts
+    ///
+    /// Commits are selected with the usual [`LogOptions`] (range, dates, paths, ...)
+    /// and grouped by author name and email after `.mailmap`, giving
+    /// [`ContributorStats`], the same [`AuthorStats`] as
+    /// [`commit_stats`](Self::commit_stats). Binary files and merge
+    /// commits do not contribute line counts, but merges are counted as commits.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The log options selecting the commits
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the contributors, sorted by commit count (descending) then
+    /// name, or a `GitError`.
+    pub fn shortlog(&self, options: &LogOptions) -> Result<Vec<ContributorStats>> {
+        self.check_git()?;
+
+        // Signature fields would be prepended to the format
+        let options = LogOptions {
+            show_signatures: false,
+            ..options.clone()
+        };
+        let args_vec = build_log_args(SHORTLOG_FORMAT, &["--numstat", "--no-renames"], &options)?;
+        let all_args: Vec<&str> = args_vec.iter().map(|s| s.as_str()).collect();
+
+        let output = git(&all_args, Some(self))?;
+        Ok(parse_stats_output(&output)?.authors.into_vec())
+    }
+}
+
+/// Parse `git log --numstat` output produced with `STATS_LOG_FORMAT` or `SHORTLOG_FORMAT`
+fn parse_stats_output(output: &str) -> Result<CommitStats> {
+    let mut authors: BTreeMap<(String, String), AuthorStats> = BTreeMap::new();
+    let mut days: BTreeMap<NaiveDate, DayStats> = BTreeMap::new();
+    let mut total_commits = 0;
+    let mut total_insertions = 0;
+    let mut total_deletions = 0;
+
+    for record in output.split(RECORD_SEPARATOR) {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+
+        let fields: Vec<&str> = header.split(FIELD_SEPARATOR).collect();
+        if fields.len() < 4 {
+            continue; // Skip malformed records
+        }
+
+        let name = fields[1].to_string();
+        let email = fields[2].to_string();
+        let timestamp = parse_unix_timestamp(fields[3])?;
+        let date = timestamp.date_naive();
+
+        let (insertions, deletions) = lines.fold((0, 0), |(ins, del), line| {
+            let (added, removed) = parse_numstat_counts(line);
+            (ins + added, del + removed)
+        });
+
+        let author = authors
+            .entry((name.clone(), email.clone()))
+            .or_insert_with(|| AuthorStats {
+                name,
+                email,
+                commits: 0,
+                insertions: 0,
+                deletions: 0,
+                first_commit: timestamp,
+                last_commit: timestamp,
+            });
+        author.commits += 1;
+        author.insertions += insertions;
+        author.deletions += deletions;
+        author.first_commit = author.first_commit.min(timestamp);
+        author.last_commit = author.last_commit.max(timestamp);
+
+        let day = days.entry(date).or_insert_with(|| DayStats {
+            date,
+            commits: 0,
+            insertions: 0,
+            deletions: 0,
+        });
+        day.commits += 1;
+        day.insertions += insertions;
+        day.deletions += deletions;
+
+        total_commits += 1;
+        total_insertions += insertions;
+        total_deletions += deletions;
+    }
+
+    let mut authors: Vec<AuthorStats> = authors.into_values().collect();
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(CommitStats {
+        authors: authors.into_boxed_slice(),
+        days: days.into_values().collect::<Vec<_>>().into_boxed_slice(),
+        total_commits,
+        insertions: total_insertions,
+        deletions: total_deletions,
+    })
+}
+
+/// Parse a numstat line like "10\t2\tpath" into (insertions, deletions)
+///
+/// Binary files are reported as "-\t-\tpath" and count as zero lines.
+fn parse_numstat_counts(line: &str) -> (usize, usize) {
+    let mut parts = line.split('\t');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(added), Some(removed), Some(_)) => {
+            (added.parse().unwrap_or(0), removed.parse().unwrap_or(0))
+        }
+        _ => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    #[test]
+    fn test_parse_numstat_counts() {
+        assert_eq!(parse_numstat_counts("10\t2\tsrc/lib.rs"), (10, 2));
+        assert_eq!(parse_numstat_counts("-\t-\timage.png"), (0, 0));
+        assert_eq!(parse_numstat_counts(""), (0, 0));
+    }
+
+    #[test]
+    fn test_parse_stats_output() {
+        let output = "\x00aaa\x1fAlice\x1falice@example.com\x1f1700000000\n\n5\t1\ta.txt\n2\t0\tb.txt\n\
+                      \x00bbb\x1fBob\x1fbob@example.com\x1f1700000100\n\n-\t-\tlogo.png\n\
+                      \x00ccc\x1fAlice\x1falice@example.com\x1f1700100000\n\n0\t3\ta.txt\n";
+        let stats = parse_stats_output(output).unwrap();
+
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(stats.insertions, 7);
+        assert_eq!(stats.deletions, 4);
+
+        assert_eq!(stats.authors.len(), 2);
+        let alice = &stats.authors[0];
+        assert_eq!(alice.name, "Alice");
+        assert_eq!(alice.commits, 2);
+        assert_eq!(alice.insertions, 7);
+        assert_eq!(alice.deletions, 4);
+        assert_eq!(alice.first_commit.timestamp(), 1700000000);
+        assert_eq!(alice.last_commit.timestamp(), 1700100000);
+        let bob = stats.author("bob@example.com").unwrap();
+        assert_eq!(bob.commits, 1);
+        assert_eq!(bob.total_changes(), 0);
+        assert_eq!(bob.first_commit, bob.last_commit);
+
+        assert_eq!(stats.days.len(), 2);
+        assert!(stats.days[0].date < stats.days[1].date);
+        assert_eq!(stats.busiest_day().unwrap().commits, 2);
+    }
+
+    #[test]
+    fn test_repository_shortlog() {
+        let repo = crate::testing::TempRepo::new("shortlog").unwrap();
+        repo.commit_file("a.txt", "one\ntwo\n", "First").unwrap();
+        repo.write_file("b.txt", "b\n").unwrap();
+        repo.add(&["b.txt"]).unwrap();
+        repo.commit_with_author("Second", "Ann <ann@old.example.com>")
+            .unwrap();
+        repo.write_file("c.txt", "c\nc\n").unwrap();
+        repo.add(&["c.txt"]).unwrap();
+        repo.commit_with_author("Third", "Ann Lee <ann@example.com>")
+            .unwrap();
+
+        let contributors: Vec<ContributorStats> = repo.shortlog(&LogOptions::new()).unwrap();
+        assert_eq!(contributors.len(), 3);
+
+        // .mailmap merges Ann's identities
+        repo.write_file(
+            ".mailmap",
+            "Ann Lee <ann@example.com> Ann <ann@old.example.com>\n",
+        )
+        .unwrap();
+        let contributors = repo.shortlog(&LogOptions::new()).unwrap();
+        assert_eq!(contributors.len(), 2);
+        let ann = &contributors[0];
+        assert_eq!(ann.name, "Ann Lee");
+        assert_eq!(ann.email, "ann@example.com");
+        assert_eq!(ann.commits, 2);
+        assert_eq!((ann.insertions, ann.deletions), (3, 0));
+        assert!(ann.first_commit <= ann.last_commit);
+        assert_eq!(contributors[1].commits, 1);
+        assert_eq!(contributors[1].insertions, 2);
+
+        let filtered = repo
+            .shortlog(&LogOptions::new().paths(vec!["a.txt".into()]))
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].commits, 1);
+    }
+
+    #[test]
+    fn test_repository_commit_stats() {
+        let test_path = env::temp_dir().join("rustic_git_stats_test");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        fs::write(test_path.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        repo.add(&["a.txt"]).unwrap();
+        let first = repo.commit("First").unwrap();
+
+        fs::write(test_path.join("a.txt"), "one\nthree\nfour\n").unwrap();
+        repo.add(&["a.txt"]).unwrap();
+        repo.commit("Second").unwrap();
+
+        let stats = repo.commit_stats(None).unwrap();
+        assert_eq!(stats.total_commits, 2);
+        assert_eq!(stats.insertions, 4);
+        assert_eq!(stats.deletions, 1);
+        assert_eq!(stats.author("Test User").unwrap().commits, 2);
+
+        let range = format!("{}..HEAD", first);
+        let stats = repo.commit_stats(Some(&range)).unwrap();
+        assert_eq!(stats.total_commits, 1);
+        assert_eq!(stats.insertions, 1);
+        assert_eq!(stats.deletions, 1);
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
-use crate::utils::git;
+use crate::error::GitError;
+use crate::utils::{git, git_raw};
 use crate::{Repository, Result};
 use std::fmt;
 use std::path::PathBuf;
@@ -11,6 +12,8 @@ pub enum IndexStatus {
     Deleted,
     Renamed,
     Copied,
+    /// Path has an unresolved merge conflict
+    Unmerged,
 }
 
 impl IndexStatus {
@@ -22,6 +25,7 @@ impl IndexStatus {
             'D' => Self::Deleted,
             'R' => Self::Renamed,
             'C' => Self::Copied,
+            'U' => Self::Unmerged,
             _ => Self::Clean,
         }
     }
@@ -35,6 +39,7 @@ impl IndexStatus {
             Self::Deleted => 'D',
             Self::Renamed => 'R',
             Self::Copied => 'C',
+            Self::Unmerged => 'U',
         }
     }
 }
@@ -84,11 +89,165 @@ impl fmt::Display for WorktreeStatus {
     }
 }
 
+/// Change indicators for a submodule entry, from the porcelain v2 `S<c><m><u>` field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SubmoduleState {
+    /// The submodule's checked-out commit differs from the recorded one
+    pub commit_changed: bool,
+    /// The submodule has modifications to tracked files
+    pub tracked_changes: bool,
+    /// The submodule has untracked files
+    pub untracked_changes: bool,
+}
+
+impl SubmoduleState {
+    /// Check if the submodule working tree has local modifications
+    pub const fn is_dirty(&self) -> bool {
+        self.tracked_changes || self.untracked_changes
+    }
+}
+
+/// The kind of object a status entry refers to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EntryKind {
+    /// Regular or executable file
+    #[default]
+    File,
+    /// Symbolic link
+    Symlink,
+    /// Submodule, with its change indicators
+    Submodule(SubmoduleState),
+}
+
+impl EntryKind {
+    /// Check if the entry is a symbolic link
+    pub const fn is_symlink(&self) -> bool {
+        matches!(self, Self::Symlink)
+    }
+
+    /// Check if the entry is a submodule
+    pub const fn is_submodule(&self) -> bool {
+        matches!(self, Self::Submodule(_))
+    }
+
+    /// Get the submodule state if the entry is a submodule
+    pub const fn submodule(&self) -> Option<&SubmoduleState> {
+        match self {
+            Self::Submodule(state) => Some(state),
+            _ => None,
+        }
+    }
+}
+
+/// Which submodule changes git should report (`--ignore-submodules=<when>`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IgnoreSubmodules {
+    /// Report all submodule changes, including untracked files inside them
+    None,
+    /// Ignore untracked files inside submodules
+    Untracked,
+    /// Ignore all changes to submodule working trees; only commit changes are shown
+    Dirty,
+    /// Ignore submodules entirely
+    All,
+}
+
+impl IgnoreSubmodules {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Untracked => "untracked",
+            Self::Dirty => "dirty",
+            Self::All => "all",
+        }
+    }
+}
+
+/// Options for status queries
+#[derive(Debug, Clone, Default)]
+pub struct StatusOptions {
+    pub ignore_submodules: Option<IgnoreSubmodules>,
+    pub recurse_submodules: bool,
+}
+
+impl StatusOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override which submodule changes are reported
+    pub fn with_ignore_submodules(mut self, mode: IgnoreSubmodules) -> Self {
+        self.ignore_submodules = Some(mode);
+        self
+    }
+
+    /// Include the changed files inside dirty submodules, with paths prefixed by
+    /// the submodule path (e.g. `vendor/lib/src/main.rs`)
+    pub fn with_recurse_submodules(mut self) -> Self {
+        self.recurse_submodules = true;
+        self
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FileEntry {
     pub path: PathBuf,
     pub index_status: IndexStatus,
     pub worktree_status: WorktreeStatus,
+    pub kind: EntryKind,
+}
+
+/// Per-state entry counts, as returned by [`GitStatus::summary`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StatusSummary {
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub ignored: usize,
+    pub conflicted: usize,
+}
+
+impl StatusSummary {
+    /// Check if there is nothing to report
+    pub const fn is_clean(&self) -> bool {
+        self.staged == 0
+            && self.unstaged == 0
+            && self.untracked == 0
+            && self.ignored == 0
+            && self.conflicted == 0
+    }
+}
+
+impl fmt::Display for StatusSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "clean");
+        }
+
+        let parts = [
+            (self.conflicted, "conflicted"),
+            (self.staged, "staged"),
+            (self.unstaged, "unstaged"),
+            (self.untracked, "untracked"),
+            (self.ignored, "ignored"),
+        ];
+        let parts: Vec<String> = parts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!("{} {}", count, label))
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// Status entries split into buckets, as returned by [`GitStatus::grouped`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusGroups<'a> {
+    pub staged: Vec<&'a FileEntry>,
+    pub unstaged: Vec<&'a FileEntry>,
+    pub untracked: Vec<&'a FileEntry>,
+    pub ignored: Vec<&'a FileEntry>,
+    pub conflicted: Vec<&'a FileEntry>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -108,9 +267,12 @@ impl GitStatus {
     // New API methods for staged/unstaged files
     /// Get all files that have changes in the index (staged)
     pub fn staged_files(&self) -> impl Iterator<Item = &FileEntry> + '_ {
-        self.entries
-            .iter()
-            .filter(|entry| !matches!(entry.index_status, IndexStatus::Clean))
+        self.entries.iter().filter(|entry| {
+            !matches!(
+                entry.index_status,
+                IndexStatus::Clean | IndexStatus::Unmerged
+            )
+        })
     }
 
     /// Get all files that have changes in the working tree (unstaged)
@@ -154,40 +316,160 @@ impl GitStatus {
             .filter(move |entry| entry.worktree_status == status)
     }
 
+    /// Get all submodule entries
+    pub fn submodules(&self) -> impl Iterator<Item = &FileEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind.is_submodule())
+    }
+
+    /// Get all files with unresolved merge conflicts
+    pub fn conflicted_files(&self) -> impl Iterator<Item = &FileEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.index_status, IndexStatus::Unmerged))
+    }
+
     /// Get all file entries
     pub fn entries(&self) -> &[FileEntry] {
         &self.entries
     }
 
-    fn parse_porcelain_output(output: &str) -> Self {
-        let mut entries = Vec::new();
+    /// Count entries per state
+    ///
+    /// Conflicted entries are only counted as conflicted. An entry with both staged
+    /// and unstaged changes is counted in both buckets, matching `git status`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let summary = repo.status()?.summary();
+    /// println!("{}", summary); // e.g. "2 staged, 1 unstaged, 3 untracked"
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn summary(&self) -> StatusSummary {
+        let groups = self.grouped();
+        StatusSummary {
+            staged: groups.staged.len(),
+            unstaged: groups.unstaged.len(),
+            untracked: groups.untracked.len(),
+            ignored: groups.ignored.len(),
+            conflicted: groups.conflicted.len(),
+        }
+    }
 
-        for line in output.lines() {
-            if line.len() < 3 {
-                continue;
+    /// Split entries into staged, unstaged, untracked, ignored and conflicted buckets
+    ///
+    /// Each bucket is sorted by path. Bucket membership follows the same rules as
+    /// [`GitStatus::summary`].
+    pub fn grouped(&self) -> StatusGroups<'_> {
+        let mut groups = StatusGroups::default();
+
+        for entry in self.sorted_entries() {
+            match (&entry.index_status, &entry.worktree_status) {
+                (IndexStatus::Unmerged, _) => groups.conflicted.push(entry),
+                (_, WorktreeStatus::Untracked) => groups.untracked.push(entry),
+                (_, WorktreeStatus::Ignored) => groups.ignored.push(entry),
+                (index_status, worktree_status) => {
+                    if *index_status != IndexStatus::Clean {
+                        groups.staged.push(entry);
+                    }
+                    if *worktree_status != WorktreeStatus::Clean {
+                        groups.unstaged.push(entry);
+                    }
+                }
             }
+        }
+
+        groups
+    }
+
+    /// Get all entries sorted by path
+    ///
+    /// The sort is stable, so the output is deterministic regardless of the order
+    /// git reported entries in.
+    pub fn sorted_entries(&self) -> Vec<&FileEntry> {
+        let mut entries: Vec<&FileEntry> = self.entries.iter().collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries
+    }
+
+    /// Sort the entries in place by path
+    pub fn sort_by_path(&mut self) {
+        self.entries.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+
+    /// Render the entries in the format of `git status --porcelain` (v1)
+    ///
+    /// One `XY path` line per entry, in git's order (tracked changes, then untracked,
+    /// then ignored files, each by byte-wise path), with paths quoted the way git
+    /// quotes them. Renamed and copied entries show only the new path, and conflicted
+    /// entries are reported as `UU` since the parsed status keeps no per-side detail.
+    pub fn to_porcelain(&self) -> String {
+        let mut entries: Vec<(String, &FileEntry)> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.path.to_string_lossy().into_owned(), entry))
+            .collect();
+        entries.sort_by_cached_key(|(path, entry)| {
+            let group = match entry.worktree_status {
+                WorktreeStatus::Untracked => 1,
+                WorktreeStatus::Ignored => 2,
+                _ => 0,
+            };
+            (group, path.clone())
+        });
+
+        let mut out = String::new();
+        for (path, entry) in entries {
+            let (x, y) = short_status(entry);
+            out.push(x);
+            out.push(y);
+            out.push(' ');
+            out.push_str(&quote_path(&path));
+            out.push('\n');
+        }
+        out
+    }
 
-            let index_char = line.chars().nth(0).unwrap_or(' ');
-            let worktree_char = line.chars().nth(1).unwrap_or(' ');
-            let filename = line[3..].to_string();
-            let path = PathBuf::from(&filename);
+    /// Parse `git status --porcelain=v2 -z` output
+    fn parse_porcelain_output(output: &str) -> Self {
+        let mut entries = Vec::new();
+        let mut records = output.split('\0');
+
+        while let Some(record) = records.next() {
+            let entry = match record.as_bytes().first() {
+                // Ordinary change: 1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>
+                Some(b'1') => parse_changed_record(record, 9),
+                // Rename or copy: 2 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <Xscore> <path>\0<orig>
+                Some(b'2') => {
+                    records.next(); // Skip the original path
+                    parse_changed_record(record, 10)
+                }
+                // Unmerged: u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>
+                Some(b'u') => parse_changed_record(record, 11).map(|entry| FileEntry {
+                    index_status: IndexStatus::Unmerged,
+                    ..entry
+                }),
+                Some(b'?') => parse_untracked_record(record, WorktreeStatus::Untracked),
+                Some(b'!') => parse_untracked_record(record, WorktreeStatus::Ignored),
+                _ => None,
+            };
 
-            let index_status = IndexStatus::from_char(index_char);
-            let worktree_status = WorktreeStatus::from_char(worktree_char);
+            let Some(entry) = entry else {
+                continue;
+            };
 
             // Skip entries that are completely clean
-            if matches!(index_status, IndexStatus::Clean)
-                && matches!(worktree_status, WorktreeStatus::Clean)
+            if matches!(entry.index_status, IndexStatus::Clean)
+                && matches!(entry.worktree_status, WorktreeStatus::Clean)
             {
                 continue;
             }
 
-            let entry = FileEntry {
-                path,
-                index_status,
-                worktree_status,
-            };
-
             entries.push(entry);
         }
 
@@ -197,17 +479,332 @@ impl GitStatus {
     }
 }
 
+/// Groups entries like `git status`, each line carrying its `--short` code, and ends
+/// with the [`StatusSummary`] counts
+///
+/// ```text
+/// Changes to be committed:
+///   M  src/lib.rs
+/// Changes not staged for commit:
+///    M README.md
+/// Untracked files:
+///   ?? notes.txt
+///
+/// 1 staged, 1 unstaged, 1 untracked
+/// ```
+impl fmt::Display for GitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return writeln!(f, "nothing to commit, working tree clean");
+        }
+
+        let groups = self.grouped();
+        let sections = [
+            ("Unmerged paths", &groups.conflicted),
+            ("Changes to be committed", &groups.staged),
+            ("Changes not staged for commit", &groups.unstaged),
+            ("Untracked files", &groups.untracked),
+            ("Ignored files", &groups.ignored),
+        ];
+        for (title, entries) in sections {
+            if entries.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", title)?;
+            for entry in entries {
+                let (x, y) = short_status(entry);
+                writeln!(f, "  {}{} {}", x, y, entry.path.display())?;
+            }
+        }
+        writeln!(f)?;
+        writeln!(f, "{}", self.summary())
+    }
+}
+
+/// The two-letter `XY` code of an entry in `git status --short` output
+fn short_status(entry: &FileEntry) -> (char, char) {
+    match (&entry.index_status, &entry.worktree_status) {
+        (IndexStatus::Unmerged, _) => ('U', 'U'),
+        (_, WorktreeStatus::Untracked) => ('?', '?'),
+        (_, WorktreeStatus::Ignored) => ('!', '!'),
+        (index_status, worktree_status) => (index_status.to_char(), worktree_status.to_char()),
+    }
+}
+
+/// Quote a path like git's porcelain output: paths containing spaces, quotes,
+/// backslashes, control characters or non-ASCII bytes become C-style string literals
+pub(crate) fn quote_path(path: &str) -> String {
+    let needs_quoting = path
+        .bytes()
+        .any(|b| b == b' ' || b == b'"' || b == b'\\' || !(0x20..0x7f).contains(&b));
+    if !needs_quoting {
+        return path.to_string();
+    }
+
+    let mut quoted = String::with_capacity(path.len() + 2);
+    quoted.push('"');
+    for b in path.bytes() {
+        match b {
+            b'"' => quoted.push_str("\\\""),
+            b'\\' => quoted.push_str("\\\\"),
+            b'\t' => quoted.push_str("\\t"),
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            b' '..=b'~' => quoted.push(b as char),
+            _ => quoted.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Parse a porcelain v2 tracked-entry record with `field_count` space-separated fields
+fn parse_changed_record(record: &str, field_count: usize) -> Option<FileEntry> {
+    let fields: Vec<&str> = record.splitn(field_count, ' ').collect();
+    if fields.len() != field_count {
+        return None;
+    }
+
+    let mut xy = fields[1].chars();
+    let index_status = IndexStatus::from_char(xy.next()?);
+    let worktree_status = WorktreeStatus::from_char(xy.next()?);
+
+    // Mode fields follow the submodule field (three for unmerged entries' stages plus
+    // the worktree); the worktree mode comes last and is 000000 when deleted
+    let modes = if field_count == 11 {
+        &fields[3..7]
+    } else {
+        &fields[3..6]
+    };
+    let mode = modes
+        .iter()
+        .rev()
+        .copied()
+        .find(|mode| *mode != "000000")
+        .unwrap_or("000000");
+
+    Some(FileEntry {
+        path: PathBuf::from(fields[field_count - 1]),
+        index_status,
+        worktree_status,
+        kind: parse_entry_kind(fields[2], mode),
+    })
+}
+
+/// Parse a porcelain v2 untracked (`?`) or ignored (`!`) record
+fn parse_untracked_record(record: &str, worktree_status: WorktreeStatus) -> Option<FileEntry> {
+    let path = record.get(2..).filter(|path| !path.is_empty())?;
+
+    Some(FileEntry {
+        path: PathBuf::from(path),
+        index_status: IndexStatus::Clean,
+        worktree_status,
+        kind: EntryKind::File,
+    })
+}
+
+/// Determine the entry kind from the submodule field and the most recent file mode
+fn parse_entry_kind(submodule: &str, mode: &str) -> EntryKind {
+    let mut chars = submodule.chars();
+    if chars.next() == Some('S') {
+        return EntryKind::Submodule(SubmoduleState {
+            commit_changed: chars.next() == Some('C'),
+            tracked_changes: chars.next() == Some('M'),
+            untracked_changes: chars.next() == Some('U'),
+        });
+    }
+
+    if mode == "120000" {
+        EntryKind::Symlink
+    } else {
+        EntryKind::File
+    }
+}
+
 impl Repository {
     /// Get the status of the repository.
     ///
+    /// Each entry records whether it is a file, symlink, or submodule; submodule
+    /// entries carry their commit and dirty-worktree indicators.
+    ///
     /// # Returns
     ///
     /// A `Result` containing the `GitStatus` or a `GitError`.
     pub fn status(&self) -> Result<GitStatus> {
-        Self::ensure_git()?;
+        self.status_with_options(&StatusOptions::new())
+    }
+
+    /// Get the status of the repository with custom options.
+    ///
+    /// With `with_recurse_submodules()`, each dirty submodule's own changes are
+    /// included after the submodule entry, recursively through nested submodules.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The status options to use
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `GitStatus` or a `GitError`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{IgnoreSubmodules, Repository, StatusOptions};
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let options = StatusOptions::new()
+    ///     .with_ignore_submodules(IgnoreSubmodules::None)
+    ///     .with_recurse_submodules();
+    ///
+    /// for entry in repo.status_with_options(&options)?.entries() {
+    ///     println!("{}", entry.path.display());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn status_with_options(&self, options: &StatusOptions) -> Result<GitStatus> {
+        self.check_git()?;
+
+        let args = self.status_args(options);
+        let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let stdout = git(&args_str, Some(self))?;
+        self.status_from_output(&stdout, options)
+    }
+
+    /// Build the `git status` arguments for `options`
+    pub(crate) fn status_args(&self, options: &StatusOptions) -> Vec<String> {
+        let mut args = vec![
+            "status".to_string(),
+            "--porcelain=v2".to_string(),
+            "-z".to_string(),
+        ];
+        if let Some(mode) = options.ignore_submodules {
+            args.push(format!("--ignore-submodules={}", mode.as_str()));
+        }
+        // Porcelain paths are always repository-relative, so scope to the subdirectory
+        // here and strip its prefix below
+        if self.subdir().is_some() {
+            args.push("--".to_string());
+            args.push(".".to_string());
+        }
+        args
+    }
+
+    /// Turn `git status` output into a [`GitStatus`], applying symlink detection,
+    /// submodule recursion and path styling
+    pub(crate) fn status_from_output(
+        &self,
+        stdout: &str,
+        options: &StatusOptions,
+    ) -> Result<GitStatus> {
+        let status = GitStatus::parse_porcelain_output(stdout);
+
+        let mut entries = Vec::with_capacity(status.entries.len());
+        for mut entry in status.entries.into_vec() {
+            // Porcelain v2 reports no mode for untracked paths, so check the filesystem
+            if matches!(entry.worktree_status, WorktreeStatus::Untracked)
+                && self
+                    .repo_path()
+                    .join(&entry.path)
+                    .symlink_metadata()
+                    .is_ok_and(|metadata| metadata.file_type().is_symlink())
+            {
+                entry.kind = EntryKind::Symlink;
+            }
+
+            let nested = match entry.kind.submodule() {
+                Some(state) if options.recurse_submodules && state.is_dirty() => {
+                    let submodule = self.submodule_handle(&entry.path);
+                    Some(submodule.status_with_options(options)?)
+                }
+                _ => None,
+            };
+
+            let prefix = entry.path.clone();
+            entries.push(entry);
+
+            if let Some(nested) = nested {
+                entries.extend(nested.entries.into_vec().into_iter().map(|mut nested| {
+                    nested.path = prefix.join(&nested.path);
+                    nested
+                }));
+            }
+        }
+
+        let command_dir = self.command_dir();
+        for entry in &mut entries {
+            if let Some(subdir) = self.subdir()
+                && let Ok(relative) = entry.path.strip_prefix(subdir)
+            {
+                entry.path = relative.to_path_buf();
+            }
+            entry.path = self.styled_path(&command_dir, std::mem::take(&mut entry.path));
+        }
+
+        Ok(GitStatus {
+            entries: entries.into_boxed_slice(),
+        })
+    }
+
+    /// Check whether the repository has any staged, unstaged, or untracked changes.
+    ///
+    /// This is much cheaper than building a full `GitStatus` when only a boolean is
+    /// needed (e.g. for shell prompts): it runs `git diff --quiet`,
+    /// `git diff --cached --quiet`, and an untracked file probe, stopping at the
+    /// first one that reports changes. Ignored files do not make the repository dirty.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if there are changes, or a `GitError`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// if repo.is_dirty()? {
+    ///     println!("*");
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn is_dirty(&self) -> Result<bool> {
+        self.check_git()?;
+
+        if self.diff_has_changes(&["diff", "--quiet"])? {
+            return Ok(true);
+        }
 
-        let stdout = git(&["status", "--porcelain"], Some(self.repo_path()))?;
-        Ok(GitStatus::parse_porcelain_output(&stdout))
+        if self.diff_has_changes(&["diff", "--cached", "--quiet"])? {
+            return Ok(true);
+        }
+
+        let untracked = git(
+            &[
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "--directory",
+                "--no-empty-directory",
+            ],
+            Some(self),
+        )?;
+        Ok(!untracked.trim().is_empty())
+    }
+
+    /// Run a `git diff --quiet` variant and map its exit code to "has changes"
+    fn diff_has_changes(&self, args: &[&str]) -> Result<bool> {
+        let output = git_raw(args, Some(self))?;
+
+        // Exit code 0 means no changes, 1 means changes, anything else is an error
+        match output.status.code() {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            _ => Err(GitError::CommandFailed(format!(
+                "git diff failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+        }
     }
 }
 
@@ -217,10 +814,31 @@ mod tests {
     use std::env;
     use std::fs;
 
+    /// Build porcelain v2 records from short-format "XY path" lines to keep fixtures readable
+    fn porcelain_v2(short: &str) -> String {
+        let mut output = String::new();
+        for line in short.lines().filter(|line| line.len() >= 3) {
+            let (xy, path) = line.split_at(2);
+            let path = &path[1..];
+            match xy {
+                "??" => output.push_str(&format!("? {}\0", path)),
+                "!!" => output.push_str(&format!("! {}\0", path)),
+                _ => output.push_str(&format!(
+                    "1 {} N... 100644 100644 100644 {} {} {}\0",
+                    xy.replace(' ', "."),
+                    "0".repeat(40),
+                    "0".repeat(40),
+                    path
+                )),
+            }
+        }
+        output
+    }
+
     #[test]
     fn test_parse_porcelain_output() {
         let output = "M  modified.txt\nA  added.txt\nD  deleted.txt\n?? untracked.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         assert_eq!(status.entries.len(), 4);
 
@@ -272,7 +890,7 @@ mod tests {
     #[test]
     fn test_clean_repository_status() {
         let output = "";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         assert!(status.is_clean());
         assert!(!status.has_changes());
@@ -301,11 +919,260 @@ mod tests {
         fs::remove_dir_all(&test_path).unwrap();
     }
 
+    #[test]
+    fn test_parse_porcelain_v2_entry_kinds() {
+        let hash = "0".repeat(40);
+        let output = format!(
+            "1 .M N... 120000 120000 120000 {h} {h} link\0\
+             1 .M S.MU 160000 160000 160000 {h} {h} vendor/lib\0\
+             1 M. SC.. 160000 160000 160000 {h} {h} vendor/other\0\
+             2 R. N... 100644 100644 100644 {h} {h} R100 new name.txt\0old name.txt\0\
+             1 .D N... 120000 120000 000000 {h} {h} gone-link\0\
+             ? untracked.txt\0",
+            h = hash
+        );
+        let status = GitStatus::parse_porcelain_output(&output);
+
+        assert_eq!(status.entries.len(), 6);
+
+        assert_eq!(status.entries[0].kind, EntryKind::Symlink);
+        assert_eq!(status.entries[0].worktree_status, WorktreeStatus::Modified);
+
+        let dirty = status.entries[1].kind.submodule().unwrap();
+        assert!(!dirty.commit_changed);
+        assert!(dirty.tracked_changes);
+        assert!(dirty.untracked_changes);
+        assert!(dirty.is_dirty());
+
+        let moved = status.entries[2].kind.submodule().unwrap();
+        assert!(moved.commit_changed);
+        assert!(!moved.is_dirty());
+        assert_eq!(status.submodules().count(), 2);
+
+        assert_eq!(status.entries[3].path, PathBuf::from("new name.txt"));
+        assert_eq!(status.entries[3].index_status, IndexStatus::Renamed);
+        assert_eq!(status.entries[3].kind, EntryKind::File);
+
+        // Deleted worktree entries fall back to the index mode
+        assert_eq!(status.entries[4].kind, EntryKind::Symlink);
+        assert_eq!(status.entries[4].worktree_status, WorktreeStatus::Deleted);
+
+        assert_eq!(status.entries[5].path, PathBuf::from("untracked.txt"));
+        assert_eq!(status.entries[5].kind, EntryKind::File);
+    }
+
+    /// Create `<base>/sub` and a superproject `<base>/super` with `sub` added at `vendor`
+    fn create_superproject(name: &str) -> (PathBuf, PathBuf, Repository) {
+        let base = env::temp_dir().join(name);
+        if base.exists() {
+            fs::remove_dir_all(&base).unwrap();
+        }
+        let sub_path = base.join("sub");
+        let super_path = base.join("super");
+
+        let sub = Repository::init(&sub_path, false).unwrap();
+        sub.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(sub_path.join("lib.txt"), "lib").unwrap();
+        sub.add(&["lib.txt"]).unwrap();
+        sub.commit("Sub initial").unwrap();
+
+        let repo = Repository::init(&super_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        git(
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                sub_path.to_str().unwrap(),
+                "vendor",
+            ],
+            Some(&super_path),
+        )
+        .unwrap();
+
+        (base, super_path, repo)
+    }
+
+    #[test]
+    fn test_repository_status_recurse_submodules() {
+        let (base, super_path, repo) = create_superproject("test_status_recurse");
+        repo.commit("Add submodule").unwrap();
+
+        fs::write(super_path.join("vendor/lib.txt"), "changed").unwrap();
+        fs::write(super_path.join("vendor/new.txt"), "new").unwrap();
+
+        // Without recursion only the submodule entry is reported
+        let status = repo.status().unwrap();
+        assert_eq!(status.entries.len(), 1);
+        assert!(status.entries[0].kind.is_submodule());
+
+        // Ignoring dirty submodules hides it entirely
+        let options = StatusOptions::new().with_ignore_submodules(IgnoreSubmodules::Dirty);
+        assert!(repo.status_with_options(&options).unwrap().is_clean());
+
+        let options = StatusOptions::new()
+            .with_ignore_submodules(IgnoreSubmodules::None)
+            .with_recurse_submodules();
+        let status = repo.status_with_options(&options).unwrap();
+        let paths: Vec<_> = status.entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("vendor"),
+                PathBuf::from("vendor/lib.txt"),
+                PathBuf::from("vendor/new.txt"),
+            ]
+        );
+        assert_eq!(status.entries[1].worktree_status, WorktreeStatus::Modified);
+        assert_eq!(status.entries[2].worktree_status, WorktreeStatus::Untracked);
+
+        // Clean up
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_recurse_submodules_keeps_handle_settings() {
+        use crate::testing::{MockBackend, MockResponse};
+        use std::time::Duration;
+
+        let hash = "0".repeat(40);
+        let backend = MockBackend::new();
+        backend
+            .expect(
+                &["status", "--porcelain=v2", "-z"],
+                MockResponse::success(&format!(
+                    "1 .M S.M. 160000 160000 160000 {h} {h} vendor\0",
+                    h = hash
+                )),
+            )
+            .expect(
+                &["status", "--porcelain=v2", "-z"],
+                MockResponse::success(&format!(
+                    "1 .M N... 100644 100644 100644 {h} {h} lib.txt\0",
+                    h = hash
+                )),
+            );
+        let repo = Repository::from_backend("/nonexistent/superproject", backend.clone())
+            .hermetic()
+            .with_timeout(Duration::from_secs(5));
+
+        // The nested status runs through the same backend
+        let status = repo
+            .status_with_options(&StatusOptions::new().with_recurse_submodules())
+            .unwrap();
+        let paths: Vec<_> = status.entries.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("vendor"), PathBuf::from("vendor/lib.txt")]
+        );
+        backend.verify().unwrap();
+
+        let submodule = repo.submodule_handle("vendor");
+        assert_eq!(
+            submodule.repo_path(),
+            std::path::Path::new("/nonexistent/superproject/vendor")
+        );
+        assert!(submodule.hermetic_options().is_some());
+        assert_eq!(submodule.timeout(), Some(Duration::from_secs(5)));
+        assert!(submodule.has_backend());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_repository_status_symlinks_and_submodules() {
+        let (base, super_path, repo) = create_superproject("test_status_kinds");
+        fs::write(super_path.join("target.txt"), "target").unwrap();
+        std::os::unix::fs::symlink("target.txt", super_path.join("tracked-link")).unwrap();
+        repo.add_all().unwrap();
+        repo.commit("Add submodule and link").unwrap();
+
+        // Dirty the submodule and add an untracked symlink
+        fs::write(super_path.join("vendor/lib.txt"), "changed").unwrap();
+        fs::write(super_path.join("vendor/new.txt"), "new").unwrap();
+        std::os::unix::fs::symlink("target.txt", super_path.join("new-link")).unwrap();
+
+        let status = repo.status().unwrap();
+
+        let vendor = status
+            .entries
+            .iter()
+            .find(|e| e.path.to_str() == Some("vendor"))
+            .unwrap();
+        let state = vendor.kind.submodule().unwrap();
+        assert!(state.tracked_changes);
+        assert!(state.untracked_changes);
+        assert!(!state.commit_changed);
+
+        let new_link = status
+            .entries
+            .iter()
+            .find(|e| e.path.to_str() == Some("new-link"))
+            .unwrap();
+        assert_eq!(new_link.worktree_status, WorktreeStatus::Untracked);
+        assert_eq!(new_link.kind, EntryKind::Symlink);
+
+        // Clean up
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_repository_is_dirty() {
+        let test_path = env::temp_dir().join("test_is_dirty_repo");
+
+        // Clean up if exists
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        // Empty repository is clean
+        assert!(!repo.is_dirty().unwrap());
+
+        // Untracked file
+        fs::write(test_path.join("file.txt"), "content").unwrap();
+        assert!(repo.is_dirty().unwrap());
+
+        // Staged file on an unborn branch
+        repo.add(&["file.txt"]).unwrap();
+        assert!(repo.is_dirty().unwrap());
+
+        repo.commit("Initial commit").unwrap();
+        assert!(!repo.is_dirty().unwrap());
+
+        // Unstaged modification
+        fs::write(test_path.join("file.txt"), "changed").unwrap();
+        assert!(repo.is_dirty().unwrap());
+
+        // Staged modification
+        repo.add(&["file.txt"]).unwrap();
+        assert!(repo.is_dirty().unwrap());
+        repo.commit("Second commit").unwrap();
+
+        // Ignored files do not count
+        fs::write(test_path.join(".gitignore"), "*.log\n").unwrap();
+        repo.add(&[".gitignore"]).unwrap();
+        repo.commit("Add gitignore").unwrap();
+        fs::write(test_path.join("debug.log"), "noise").unwrap();
+        assert!(!repo.is_dirty().unwrap());
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
     #[test]
     fn test_parse_porcelain_output_edge_cases() {
         // Test empty lines and malformed lines
         let output = "\n\nM  valid.txt\nXX\n  \nA  another.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         assert_eq!(status.entries.len(), 2);
 
@@ -327,7 +1194,7 @@ mod tests {
     #[test]
     fn test_parse_porcelain_all_status_types() {
         let output = "M  modified.txt\nA  added.txt\nD  deleted.txt\nR  renamed.txt\nC  copied.txt\n?? untracked.txt\n!! ignored.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         assert_eq!(status.entries.len(), 7);
 
@@ -384,7 +1251,7 @@ mod tests {
     #[test]
     fn test_parse_porcelain_worktree_modifications() {
         let output = " M worktree_modified.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         assert_eq!(status.entries.len(), 1);
         let entry = &status.entries[0];
@@ -396,7 +1263,7 @@ mod tests {
     #[test]
     fn test_parse_porcelain_unknown_status() {
         let output = "XY unknown.txt\nZ  another_unknown.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         // Unknown statuses should be treated as clean/clean and ignored
         assert_eq!(status.entries.len(), 0);
@@ -424,6 +1291,7 @@ mod tests {
         assert_eq!(IndexStatus::from_char('D'), IndexStatus::Deleted);
         assert_eq!(IndexStatus::from_char('R'), IndexStatus::Renamed);
         assert_eq!(IndexStatus::from_char('C'), IndexStatus::Copied);
+        assert_eq!(IndexStatus::from_char('U'), IndexStatus::Unmerged);
         assert_eq!(IndexStatus::from_char(' '), IndexStatus::Clean);
         assert_eq!(IndexStatus::from_char('X'), IndexStatus::Clean); // unknown char
 
@@ -433,6 +1301,7 @@ mod tests {
         assert_eq!(IndexStatus::Deleted.to_char(), 'D');
         assert_eq!(IndexStatus::Renamed.to_char(), 'R');
         assert_eq!(IndexStatus::Copied.to_char(), 'C');
+        assert_eq!(IndexStatus::Unmerged.to_char(), 'U');
         assert_eq!(IndexStatus::Clean.to_char(), ' ');
     }
 
@@ -464,6 +1333,7 @@ mod tests {
             IndexStatus::Deleted,
             IndexStatus::Renamed,
             IndexStatus::Copied,
+            IndexStatus::Unmerged,
         ] {
             assert_eq!(IndexStatus::from_char(status.to_char()), status);
         }
@@ -499,16 +1369,19 @@ mod tests {
             path: PathBuf::from("test.txt"),
             index_status: IndexStatus::Modified,
             worktree_status: WorktreeStatus::Clean,
+            kind: EntryKind::File,
         };
         let entry2 = FileEntry {
             path: PathBuf::from("test.txt"),
             index_status: IndexStatus::Modified,
             worktree_status: WorktreeStatus::Clean,
+            kind: EntryKind::File,
         };
         let entry3 = FileEntry {
             path: PathBuf::from("other.txt"),
             index_status: IndexStatus::Modified,
             worktree_status: WorktreeStatus::Clean,
+            kind: EntryKind::File,
         };
 
         assert_eq!(entry1, entry2);
@@ -522,11 +1395,13 @@ mod tests {
                 path: PathBuf::from("file1.txt"),
                 index_status: IndexStatus::Modified,
                 worktree_status: WorktreeStatus::Clean,
+                kind: EntryKind::File,
             },
             FileEntry {
                 path: PathBuf::from("file2.txt"),
                 index_status: IndexStatus::Added,
                 worktree_status: WorktreeStatus::Clean,
+                kind: EntryKind::File,
             },
         ];
         let entries2 = entries1.clone();
@@ -534,6 +1409,7 @@ mod tests {
             path: PathBuf::from("different.txt"),
             index_status: IndexStatus::Modified,
             worktree_status: WorktreeStatus::Clean,
+            kind: EntryKind::File,
         }];
 
         let status1 = GitStatus {
@@ -556,6 +1432,7 @@ mod tests {
             path: PathBuf::from("file1.txt"),
             index_status: IndexStatus::Modified,
             worktree_status: WorktreeStatus::Clean,
+            kind: EntryKind::File,
         }];
         let status1 = GitStatus {
             entries: entries.into_boxed_slice(),
@@ -571,6 +1448,7 @@ mod tests {
             path: PathBuf::from("file1.txt"),
             index_status: IndexStatus::Modified,
             worktree_status: WorktreeStatus::Clean,
+            kind: EntryKind::File,
         }];
         let status = GitStatus {
             entries: entries.into_boxed_slice(),
@@ -585,7 +1463,7 @@ mod tests {
     #[test]
     fn test_new_api_methods() {
         let output = "M  file1.txt\nMM file2.txt\nA  file3.txt\n D file4.txt\n?? file5.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         // Test staged files (index changes)
         let staged: Vec<_> = status.staged_files().collect();
@@ -616,7 +1494,7 @@ mod tests {
     #[test]
     fn test_parse_porcelain_filenames_with_spaces() {
         let output = "M  file with spaces.txt\nA  another file.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         assert_eq!(status.entries.len(), 2);
 
@@ -638,7 +1516,7 @@ mod tests {
     #[test]
     fn test_parse_porcelain_unicode_filenames() {
         let output = "M  测试文件.txt\nA  🚀rocket.txt\n";
-        let status = GitStatus::parse_porcelain_output(output);
+        let status = GitStatus::parse_porcelain_output(&porcelain_v2(output));
 
         assert_eq!(status.entries.len(), 2);
 
@@ -656,4 +1534,213 @@ mod tests {
             .unwrap();
         assert_eq!(rocket_entry.index_status, IndexStatus::Added);
     }
+
+    #[test]
+    fn test_parse_porcelain_unmerged_entries() {
+        let hash = "0".repeat(40);
+        let output = format!(
+            "u UU N... 100644 100644 100644 100644 {h} {h} {h} both.txt\0\
+             u AA N... 000000 100644 100644 100644 {h} {h} {h} added.txt\0\
+             u UD N... 100644 100644 000000 000000 {h} {h} {h} deleted.txt\0",
+            h = hash
+        );
+        let status = GitStatus::parse_porcelain_output(&output);
+
+        assert_eq!(status.entries.len(), 3);
+        assert!(
+            status
+                .entries
+                .iter()
+                .all(|entry| entry.index_status == IndexStatus::Unmerged)
+        );
+        assert_eq!(status.conflicted_files().count(), 3);
+        assert_eq!(status.staged_files().count(), 0);
+    }
+
+    #[test]
+    fn test_status_summary_and_grouped() {
+        let hash = "0".repeat(40);
+        let mut output = porcelain_v2(
+            "?? z_untracked.txt\nMM both.txt\nM  b_staged.txt\n M a_unstaged.txt\nA  a_added.txt\n!! ignored.log\n",
+        );
+        output.push_str(&format!(
+            "u UU N... 100644 100644 100644 100644 {h} {h} {h} conflict.txt\0",
+            h = hash
+        ));
+        let status = GitStatus::parse_porcelain_output(&output);
+
+        let summary = status.summary();
+        assert_eq!(
+            summary,
+            StatusSummary {
+                staged: 3,
+                unstaged: 2,
+                untracked: 1,
+                ignored: 1,
+                conflicted: 1,
+            }
+        );
+        assert!(!summary.is_clean());
+        assert_eq!(
+            summary.to_string(),
+            "1 conflicted, 3 staged, 2 unstaged, 1 untracked, 1 ignored"
+        );
+
+        let groups = status.grouped();
+        let paths = |entries: &[&FileEntry]| -> Vec<String> {
+            entries
+                .iter()
+                .map(|entry| entry.path.to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(
+            paths(&groups.staged),
+            vec!["a_added.txt", "b_staged.txt", "both.txt"]
+        );
+        assert_eq!(paths(&groups.unstaged), vec!["a_unstaged.txt", "both.txt"]);
+        assert_eq!(paths(&groups.untracked), vec!["z_untracked.txt"]);
+        assert_eq!(paths(&groups.ignored), vec!["ignored.log"]);
+        assert_eq!(paths(&groups.conflicted), vec!["conflict.txt"]);
+    }
+
+    #[test]
+    fn test_status_summary_clean() {
+        let status = GitStatus::parse_porcelain_output("");
+        let summary = status.summary();
+
+        assert!(summary.is_clean());
+        assert_eq!(summary, StatusSummary::default());
+        assert_eq!(summary.to_string(), "clean");
+        assert_eq!(status.grouped(), StatusGroups::default());
+    }
+
+    #[test]
+    fn test_status_display_and_porcelain() {
+        let hash = "0".repeat(40);
+        let mut output = porcelain_v2("?? new file.txt\nMM both.txt\nA  b.txt\n M b/nested.txt\n");
+        output.push_str(&format!(
+            "u UU N... 100644 100644 100644 100644 {h} {h} {h} conflict.txt\0",
+            h = hash
+        ));
+        let status = GitStatus::parse_porcelain_output(&output);
+
+        assert_eq!(
+            status.to_string(),
+            "\
+Unmerged paths:
+  UU conflict.txt
+Changes to be committed:
+  A  b.txt
+  MM both.txt
+Changes not staged for commit:
+   M b/nested.txt
+  MM both.txt
+Untracked files:
+  ?? new file.txt
+
+1 conflicted, 2 staged, 2 unstaged, 1 untracked
+"
+        );
+        assert_eq!(
+            status.to_porcelain(),
+            "A  b.txt\n M b/nested.txt\nMM both.txt\nUU conflict.txt\n?? \"new file.txt\"\n"
+        );
+
+        let clean = GitStatus::parse_porcelain_output("");
+        assert_eq!(clean.to_string(), "nothing to commit, working tree clean\n");
+        assert_eq!(clean.to_porcelain(), "");
+    }
+
+    #[test]
+    fn test_to_porcelain_matches_git() {
+        let temp = crate::testing::TempRepo::new("status_porcelain").unwrap();
+        temp.commit_files(
+            &[("a.txt", "a"), ("b.txt", "b"), ("gone.txt", "x")],
+            "Initial",
+        )
+        .unwrap();
+        temp.write_file("a.txt", "changed").unwrap();
+        temp.write_file("b.txt", "staged").unwrap();
+        temp.add(&["b.txt"]).unwrap();
+        temp.write_file("b.txt", "staged then changed").unwrap();
+        fs::remove_file(temp.path().join("gone.txt")).unwrap();
+        temp.write_file("b/nested.txt", "new").unwrap();
+        temp.write_file("with space.txt", "new").unwrap();
+        temp.write_file("t\u{e4}st.txt", "new").unwrap();
+
+        let expected = crate::utils::git(&["status", "--porcelain"], Some(temp.repo())).unwrap();
+        assert_eq!(temp.status().unwrap().to_porcelain(), expected);
+    }
+
+    #[test]
+    fn test_status_sorting() {
+        let output = porcelain_v2("?? c.txt\nM  a.txt\n M b/nested.txt\nA  b.txt\n");
+        let mut status = GitStatus::parse_porcelain_output(&output);
+
+        let sorted: Vec<_> = status
+            .sorted_entries()
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect();
+        assert_eq!(
+            sorted,
+            vec![
+                PathBuf::from("a.txt"),
+                PathBuf::from("b/nested.txt"),
+                PathBuf::from("b.txt"),
+                PathBuf::from("c.txt"),
+            ]
+        );
+
+        status.sort_by_path();
+        let in_place: Vec<_> = status
+            .entries
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect();
+        assert_eq!(in_place, sorted);
+    }
+
+    #[test]
+    fn test_repository_status_merge_conflict() {
+        let test_path = env::temp_dir().join("test_status_merge_conflict");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        fs::write(test_path.join("file.txt"), "base\n").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Base").unwrap();
+        let base_branch = repo.current_branch().unwrap().unwrap();
+
+        repo.checkout_new("feature", None).unwrap();
+        fs::write(test_path.join("file.txt"), "feature\n").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Feature change").unwrap();
+
+        repo.checkout(&base_branch).unwrap();
+        fs::write(test_path.join("file.txt"), "main\n").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit("Main change").unwrap();
+
+        let _ = repo.merge("feature");
+        fs::write(test_path.join("new.txt"), "new\n").unwrap();
+
+        let status = repo.status().unwrap();
+        let summary = status.summary();
+        assert_eq!(summary.conflicted, 1);
+        assert_eq!(summary.untracked, 1);
+        assert_eq!(summary.staged, 0);
+
+        let groups = status.grouped();
+        assert_eq!(groups.conflicted[0].path, PathBuf::from("file.txt"));
+        assert_eq!(groups.conflicted[0].index_status, IndexStatus::Unmerged);
+
+        fs::remove_dir_all(&test_path).unwrap();
+    }
 }
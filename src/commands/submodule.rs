@@ -0,0 +1,624 @@
+//! Git submodule operations
+//!
+//! This module wraps `git submodule add`, `init`, `update`, `status`, `foreach` and
+//! `deinit`, and reads the submodule declarations from `.gitmodules`.
+//!
+//! Git refuses to clone submodules over the local `file` transport unless
+//! `protocol.file.allow` permits it; use `with_allow_file_protocol` on the add and
+//! update options for submodules referenced by a local path.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{Repository, SubmoduleUpdateOptions};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! let vendor = repo.submodule_add("https://github.com/example/lib.git", "vendor/lib")?;
+//! println!("Added {} from {:?}", vendor.name, vendor.url);
+//!
+//! repo.submodule_update_with_options(SubmoduleUpdateOptions::new().with_init().with_recursive())?;
+//!
+//! for status in repo.submodule_status()? {
+//!     println!("{} {} at {}", status.state, status.path.display(), status.hash.short());
+//! }
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, validate_arg, validate_ref_name};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A submodule declared in `.gitmodules`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submodule {
+    /// The submodule name (the `[submodule "<name>"]` section)
+    pub name: String,
+    /// Path of the submodule, relative to the repository root
+    pub path: PathBuf,
+    /// The URL it is cloned from
+    pub url: Option<String>,
+    /// The branch followed by `git submodule update --remote`
+    pub branch: Option<String>,
+}
+
+/// How a submodule's checkout relates to the commit recorded in the superproject
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmoduleCheckout {
+    /// Not initialized (`-`)
+    Uninitialized,
+    /// The recorded commit is checked out (` `)
+    Current,
+    /// A different commit is checked out (`+`)
+    Changed,
+    /// The submodule has merge conflicts (`U`)
+    Conflicted,
+}
+
+impl SubmoduleCheckout {
+    /// Convert from the status character of `git submodule status`
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '-' => Some(Self::Uninitialized),
+            ' ' => Some(Self::Current),
+            '+' => Some(Self::Changed),
+            'U' => Some(Self::Conflicted),
+            _ => None,
+        }
+    }
+
+    /// Convert to the status character of `git submodule status`
+    pub const fn to_char(&self) -> char {
+        match self {
+            Self::Uninitialized => '-',
+            Self::Current => ' ',
+            Self::Changed => '+',
+            Self::Conflicted => 'U',
+        }
+    }
+}
+
+impl fmt::Display for SubmoduleCheckout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uninitialized => write!(f, "uninitialized"),
+            Self::Current => write!(f, "current"),
+            Self::Changed => write!(f, "changed"),
+            Self::Conflicted => write!(f, "conflicted"),
+        }
+    }
+}
+
+/// One line of `git submodule status`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmoduleStatus {
+    /// Path of the submodule
+    pub path: PathBuf,
+    /// The checked-out commit, or the recorded one when uninitialized
+    pub hash: Hash,
+    /// How the checkout relates to the recorded commit
+    pub state: SubmoduleCheckout,
+    /// `git describe` output for the commit, such as `heads/main` or `v1.2-3-gabc1234`
+    pub describe: Option<String>,
+}
+
+impl SubmoduleStatus {
+    /// Check if the submodule has been initialized
+    pub fn is_initialized(&self) -> bool {
+        self.state != SubmoduleCheckout::Uninitialized
+    }
+}
+
+/// Options for adding a submodule
+#[derive(Debug, Clone, Default)]
+pub struct SubmoduleAddOptions {
+    /// Branch to check out and record for `update --remote` (`-b`)
+    pub branch: Option<String>,
+    /// Submodule name, defaulting to its path (`--name`)
+    pub name: Option<String>,
+    /// Shallow clone with this many commits (`--depth`)
+    pub depth: Option<u32>,
+    /// Add the submodule even if its path is ignored (`--force`)
+    pub force: bool,
+    /// Allow cloning over the local `file` transport (`-c protocol.file.allow=always`)
+    pub allow_file_protocol: bool,
+}
+
+impl SubmoduleAddOptions {
+    /// Create new default submodule add options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track `branch` instead of the remote's default branch
+    pub fn with_branch(mut self, branch: &str) -> Self {
+        self.branch = Some(branch.to_string());
+        self
+    }
+
+    /// Name the submodule instead of using its path
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// Clone only the last `depth` commits
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Add the submodule even if its path is ignored
+    pub fn with_force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// Allow a submodule URL that is a local path
+    pub fn with_allow_file_protocol(mut self) -> Self {
+        self.allow_file_protocol = true;
+        self
+    }
+}
+
+/// Options for updating submodules
+#[derive(Debug, Clone, Default)]
+pub struct SubmoduleUpdateOptions {
+    /// Initialize uninitialized submodules first (`--init`)
+    pub init: bool,
+    /// Update nested submodules too (`--recursive`)
+    pub recursive: bool,
+    /// Check out the tip of the tracked remote branch instead of the recorded commit (`--remote`)
+    pub remote: bool,
+    /// Discard local changes in the submodules (`--force`)
+    pub force: bool,
+    /// Shallow clone with this many commits (`--depth`)
+    pub depth: Option<u32>,
+    /// Allow cloning over the local `file` transport (`-c protocol.file.allow=always`)
+    pub allow_file_protocol: bool,
+    /// Limit the update to these submodule paths (all when empty)
+    pub paths: Vec<PathBuf>,
+}
+
+impl SubmoduleUpdateOptions {
+    /// Create new default submodule update options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initialize submodules that are not initialized yet
+    pub fn with_init(mut self) -> Self {
+        self.init = true;
+        self
+    }
+
+    /// Update nested submodules too
+    pub fn with_recursive(mut self) -> Self {
+        self.recursive = true;
+        self
+    }
+
+    /// Move submodules to the tip of their tracked branch
+    pub fn with_remote(mut self) -> Self {
+        self.remote = true;
+        self
+    }
+
+    /// Discard local changes in the submodules
+    pub fn with_force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// Clone only the last `depth` commits
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Allow submodule URLs that are local paths
+    pub fn with_allow_file_protocol(mut self) -> Self {
+        self.allow_file_protocol = true;
+        self
+    }
+
+    /// Update only the submodule at `path`
+    pub fn with_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+}
+
+impl Repository {
+    /// Add a submodule cloned from `url` at `path` and stage it with `.gitmodules`
+    ///
+    /// # Arguments
+    /// * `url` - URL of the repository to add, or a path relative to the superproject's remote
+    /// * `path` - Where to check it out, relative to the working directory
+    ///
+    /// # Returns
+    /// The submodule as declared in `.gitmodules`
+    pub fn submodule_add<P: AsRef<Path>>(&self, url: &str, path: P) -> Result<Submodule> {
+        self.submodule_add_with_options(url, path, SubmoduleAddOptions::new())
+    }
+
+    /// Add a submodule with custom options
+    pub fn submodule_add_with_options<P: AsRef<Path>>(
+        &self,
+        url: &str,
+        path: P,
+        options: SubmoduleAddOptions,
+    ) -> Result<Submodule> {
+        self.check_git()?;
+        validate_arg("submodule URL", url)?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let depth;
+        let mut args = Vec::new();
+        if options.allow_file_protocol {
+            args.extend(["-c", "protocol.file.allow=always"]);
+        }
+        args.extend(["submodule", "--quiet", "add"]);
+        if let Some(ref branch) = options.branch {
+            validate_ref_name("branch", branch)?;
+            args.push("-b");
+            args.push(branch);
+        }
+        if let Some(ref name) = options.name {
+            validate_arg("submodule name", name)?;
+            args.push("--name");
+            args.push(name);
+        }
+        if let Some(value) = options.depth {
+            depth = value.to_string();
+            args.push("--depth");
+            args.push(&depth);
+        }
+        if options.force {
+            args.push("--force");
+        }
+        args.push("--");
+        args.push(url);
+        args.push(&path_str);
+        git(&args, Some(self))?;
+
+        let name = match options.name {
+            Some(name) => name,
+            None => {
+                // Git names the submodule after its path relative to the root
+                let output = git(&["ls-files", "--full-name", "--", &path_str], Some(self))?;
+                output.lines().next().unwrap_or(&path_str).to_string()
+            }
+        };
+        self.submodules()?
+            .into_iter()
+            .find(|submodule| submodule.name == name)
+            .ok_or_else(|| {
+                GitError::CommandFailed(format!(
+                    "git submodule add did not declare {} in .gitmodules",
+                    name
+                ))
+            })
+    }
+
+    /// List the submodules declared in `.gitmodules`
+    ///
+    /// Returns an empty list when the repository has no `.gitmodules`.
+    pub fn submodules(&self) -> Result<Vec<Submodule>> {
+        self.check_git()?;
+
+        let gitmodules = self.repo_path().join(".gitmodules");
+        if !gitmodules.exists() {
+            return Ok(Vec::new());
+        }
+        let gitmodules = gitmodules.to_string_lossy();
+        let output = git(
+            &["config", "--file", &gitmodules, "-z", "--list"],
+            Some(self),
+        )?;
+        Ok(parse_gitmodules(&output))
+    }
+
+    /// Copy the URLs of submodules into `.git/config` so they can be updated
+    ///
+    /// # Arguments
+    /// * `paths` - Submodules to initialize; all when empty
+    pub fn submodule_init<P: AsRef<Path>>(&self, paths: &[P]) -> Result<()> {
+        self.check_git()?;
+
+        let path_strs: Vec<_> = paths
+            .iter()
+            .map(|path| path.as_ref().to_string_lossy())
+            .collect();
+        let mut args = vec!["submodule", "--quiet", "init", "--"];
+        args.extend(path_strs.iter().map(|path| path.as_ref()));
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// Check out the recorded commit in every initialized submodule
+    pub fn submodule_update(&self) -> Result<()> {
+        self.submodule_update_with_options(SubmoduleUpdateOptions::new())
+    }
+
+    /// Update submodules with custom options
+    pub fn submodule_update_with_options(&self, options: SubmoduleUpdateOptions) -> Result<()> {
+        self.check_git()?;
+
+        let depth;
+        let path_strs: Vec<_> = options
+            .paths
+            .iter()
+            .map(|path| path.to_string_lossy())
+            .collect();
+        let mut args = Vec::new();
+        if options.allow_file_protocol {
+            args.extend(["-c", "protocol.file.allow=always"]);
+        }
+        args.extend(["submodule", "--quiet", "update"]);
+        if options.init {
+            args.push("--init");
+        }
+        if options.recursive {
+            args.push("--recursive");
+        }
+        if options.remote {
+            args.push("--remote");
+        }
+        if options.force {
+            args.push("--force");
+        }
+        if let Some(value) = options.depth {
+            depth = value.to_string();
+            args.push("--depth");
+            args.push(&depth);
+        }
+        args.push("--");
+        args.extend(path_strs.iter().map(|path| path.as_ref()));
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// Get the checkout state of every submodule
+    pub fn submodule_status(&self) -> Result<Vec<SubmoduleStatus>> {
+        self.check_git()?;
+
+        let output = git(&["submodule", "status"], Some(self))?;
+        Ok(parse_submodule_status(&output))
+    }
+
+    /// Run a shell command in every initialized submodule
+    ///
+    /// The command sees `$name`, `$sm_path`, `$sha1` and `$toplevel`. Git stops at the
+    /// first submodule where it fails.
+    ///
+    /// # Arguments
+    /// * `command` - Shell command to run
+    /// * `recursive` - Also run it in nested submodules
+    ///
+    /// # Returns
+    /// The combined standard output, with an `Entering '<path>'` line per submodule
+    pub fn submodule_foreach(&self, command: &str, recursive: bool) -> Result<String> {
+        self.check_git()?;
+
+        let mut args = vec!["submodule", "foreach"];
+        if recursive {
+            args.push("--recursive");
+        }
+        args.push(command);
+        git(&args, Some(self))
+    }
+
+    /// Unregister a submodule and empty its working directory
+    ///
+    /// The submodule stays declared in `.gitmodules` and can be initialized again.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the submodule
+    /// * `force` - Remove the working tree even with local modifications
+    pub fn submodule_deinit<P: AsRef<Path>>(&self, path: P, force: bool) -> Result<()> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let mut args = vec!["submodule", "--quiet", "deinit"];
+        if force {
+            args.push("--force");
+        }
+        args.push("--");
+        args.push(&path_str);
+        git(&args, Some(self))?;
+        Ok(())
+    }
+}
+
+/// Parse `git config --file .gitmodules -z --list`: NUL-terminated
+/// `submodule.<name>.<key>\n<value>` entries, where `<name>` may contain dots
+fn parse_gitmodules(output: &str) -> Vec<Submodule> {
+    let mut submodules: Vec<Submodule> = Vec::new();
+
+    for entry in output.split('\0') {
+        let Some((key, value)) = entry.split_once('\n') else {
+            continue;
+        };
+        let Some((name, key)) = key
+            .strip_prefix("submodule.")
+            .and_then(|rest| rest.rsplit_once('.'))
+        else {
+            continue;
+        };
+
+        let index = match submodules.iter().position(|s| s.name == name) {
+            Some(index) => index,
+            None => {
+                submodules.push(Submodule {
+                    name: name.to_string(),
+                    path: PathBuf::new(),
+                    url: None,
+                    branch: None,
+                });
+                submodules.len() - 1
+            }
+        };
+        let submodule = &mut submodules[index];
+        match key {
+            "path" => submodule.path = PathBuf::from(value),
+            "url" => submodule.url = Some(value.to_string()),
+            "branch" => submodule.branch = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    // A section without a path is not a usable submodule
+    submodules.retain(|submodule| !submodule.path.as_os_str().is_empty());
+    submodules
+}
+
+/// Parse `git submodule status`: `<state><hash> <path>[ (<describe>)]` per line
+fn parse_submodule_status(output: &str) -> Vec<SubmoduleStatus> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let state = SubmoduleCheckout::from_char(line.chars().next()?)?;
+            let (hash, rest) = line[1..].split_once(' ')?;
+            let (path, describe) = match rest.strip_suffix(')') {
+                Some(rest) => match rest.rsplit_once(" (") {
+                    Some((path, describe)) => (path, Some(describe.to_string())),
+                    None => (rest, None),
+                },
+                None => (rest, None),
+            };
+            Some(SubmoduleStatus {
+                path: PathBuf::from(path),
+                hash: Hash::from(hash),
+                state,
+                describe,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+
+    #[test]
+    fn test_parse_gitmodules() {
+        let output = "submodule.vendor/lib.path\nvendor/lib\0\
+                      submodule.vendor/lib.url\nhttps://example.com/lib.git\0\
+                      submodule.v1.2.path\ndeps/v1\0\
+                      submodule.v1.2.url\n../v1\0\
+                      submodule.v1.2.branch\nstable\0\
+                      submodule.orphan.url\n../orphan\0";
+        assert_eq!(
+            parse_gitmodules(output),
+            vec![
+                Submodule {
+                    name: "vendor/lib".to_string(),
+                    path: PathBuf::from("vendor/lib"),
+                    url: Some("https://example.com/lib.git".to_string()),
+                    branch: None,
+                },
+                Submodule {
+                    name: "v1.2".to_string(),
+                    path: PathBuf::from("deps/v1"),
+                    url: Some("../v1".to_string()),
+                    branch: Some("stable".to_string()),
+                },
+            ]
+        );
+        assert!(parse_gitmodules("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_submodule_status() {
+        let output = " e02d64cc0c6b03357598e4ab9605eaa0b26b7f2f vendor/lib (heads/master)\n\
+                      -e02d64cc0c6b03357598e4ab9605eaa0b26b7f2f deps/ven dor\n\
+                      +1234567890123456789012345678901234567890 tools (v1.0-1-g1234567)\n\
+                      U0000000000000000000000000000000000000000 conflicted\n";
+        let statuses = parse_submodule_status(output);
+        assert_eq!(statuses.len(), 4);
+
+        assert_eq!(statuses[0].state, SubmoduleCheckout::Current);
+        assert_eq!(statuses[0].path, PathBuf::from("vendor/lib"));
+        assert_eq!(statuses[0].describe.as_deref(), Some("heads/master"));
+        assert_eq!(
+            statuses[0].hash,
+            Hash::from("e02d64cc0c6b03357598e4ab9605eaa0b26b7f2f")
+        );
+
+        assert_eq!(statuses[1].state, SubmoduleCheckout::Uninitialized);
+        assert_eq!(statuses[1].path, PathBuf::from("deps/ven dor"));
+        assert_eq!(statuses[1].describe, None);
+        assert!(!statuses[1].is_initialized());
+
+        assert_eq!(statuses[2].state, SubmoduleCheckout::Changed);
+        assert_eq!(statuses[2].describe.as_deref(), Some("v1.0-1-g1234567"));
+        assert_eq!(statuses[3].state, SubmoduleCheckout::Conflicted);
+    }
+
+    #[test]
+    fn test_submodule_checkout_char_conversion() {
+        for state in [
+            SubmoduleCheckout::Uninitialized,
+            SubmoduleCheckout::Current,
+            SubmoduleCheckout::Changed,
+            SubmoduleCheckout::Conflicted,
+        ] {
+            assert_eq!(SubmoduleCheckout::from_char(state.to_char()), Some(state));
+        }
+        assert_eq!(SubmoduleCheckout::from_char('x'), None);
+    }
+
+    #[test]
+    fn test_submodule_lifecycle() {
+        let lib = TempRepo::new("submodule_lib").unwrap();
+        let lib_head = lib.commit_file("lib.txt", "lib\n", "Lib commit").unwrap();
+        let repo = TempRepo::new("submodule_super").unwrap();
+        let url = lib.path().to_string_lossy().to_string();
+
+        assert!(repo.submodules().unwrap().is_empty());
+        assert!(repo.submodule_add("--upload-pack=evil", "vendor").is_err());
+
+        let submodule = repo
+            .submodule_add_with_options(
+                &url,
+                "vendor/lib",
+                SubmoduleAddOptions::new()
+                    .with_name("lib")
+                    .with_allow_file_protocol(),
+            )
+            .unwrap();
+        assert_eq!(submodule.name, "lib");
+        assert_eq!(submodule.path, PathBuf::from("vendor/lib"));
+        assert_eq!(submodule.url.as_deref(), Some(url.as_str()));
+        repo.commit("Add submodule").unwrap();
+
+        let statuses = repo.submodule_status().unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].state, SubmoduleCheckout::Current);
+        assert_eq!(statuses[0].hash, lib_head);
+
+        let output = repo.submodule_foreach("echo $name", false).unwrap();
+        assert!(output.contains("Entering 'vendor/lib'"));
+        assert!(output.contains("lib"));
+
+        repo.submodule_deinit("vendor/lib", false).unwrap();
+        assert_eq!(
+            repo.submodule_status().unwrap()[0].state,
+            SubmoduleCheckout::Uninitialized
+        );
+        assert!(!repo.path().join("vendor/lib/lib.txt").exists());
+
+        repo.submodule_init(&["vendor/lib"]).unwrap();
+        repo.submodule_update_with_options(
+            SubmoduleUpdateOptions::new().with_allow_file_protocol(),
+        )
+        .unwrap();
+        assert!(repo.path().join("vendor/lib/lib.txt").exists());
+        assert!(repo.submodule_status().unwrap()[0].is_initialized());
+    }
+}
@@ -0,0 +1,462 @@
+//! High-level "Sync" workflow
+//!
+//! [`Repository::sync`] does what most "Sync" buttons do: fetch the current branch's
+//! upstream, bring the branch up to date by fast-forwarding, merging or rebasing, and
+//! optionally push the result. The [`SyncReport`] says exactly what happened, so
+//! callers can show it without re-deriving it from refs.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{Repository, SyncOptions, SyncOutcome, SyncStrategy};
+//!
+//! let repo = Repository::open(".")?;
+//! let report = repo.sync(
+//!     SyncOptions::new()
+//!         .with_strategy(SyncStrategy::Rebase)
+//!         .with_push(),
+//! )?;
+//!
+//! match &report.outcome {
+//!     SyncOutcome::UpToDate => println!("{} is up to date", report.branch),
+//!     SyncOutcome::FastForwarded { commits } => println!("Pulled {} commits", commits),
+//!     SyncOutcome::Rebased { commits } => println!("Rebased {} local commits", commits),
+//!     SyncOutcome::Merged(hash) => println!("Merge commit {}", hash.short()),
+//!     SyncOutcome::Conflicts(files) => println!("Resolve conflicts in {:?}", files),
+//! }
+//! for pushed in &report.pushed {
+//!     println!("Pushed {}", pushed);
+//! }
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::merge::{extract_conflicted_files, merge};
+use crate::commands::{MergeOptions, MergeStatus};
+use crate::error::GitError;
+use crate::types::Hash;
+use crate::utils::{git, git_raw};
+use crate::{Repository, Result};
+use std::path::PathBuf;
+
+/// How local commits are combined with upstream changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SyncStrategy {
+    /// Merge the upstream branch, fast-forwarding when possible (default)
+    #[default]
+    Merge,
+    /// Rebase local commits onto the upstream branch
+    Rebase,
+    /// Only fast-forward; fail if local and upstream have diverged
+    FastForwardOnly,
+}
+
+/// Options for [`Repository::sync`]
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    strategy: SyncStrategy,
+    push: bool,
+    autostash: bool,
+    prune: bool,
+}
+
+impl SyncOptions {
+    /// Create new SyncOptions with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how upstream changes are integrated
+    pub fn with_strategy(mut self, strategy: SyncStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Push the branch to its upstream after integrating
+    pub fn with_push(mut self) -> Self {
+        self.push = true;
+        self
+    }
+
+    /// Stash local modifications around the merge or rebase (`--autostash`)
+    pub fn with_autostash(mut self) -> Self {
+        self.autostash = true;
+        self
+    }
+
+    /// Remove remote-tracking refs that no longer exist on the remote while fetching
+    pub fn with_prune(mut self) -> Self {
+        self.prune = true;
+        self
+    }
+}
+
+/// How the local branch was brought up to date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncOutcome {
+    /// Upstream had nothing new
+    UpToDate,
+    /// The branch was fast-forwarded by the given number of commits
+    FastForwarded { commits: usize },
+    /// The given number of local commits were replayed onto upstream
+    Rebased { commits: usize },
+    /// A merge commit was created
+    Merged(Hash),
+    /// The merge or rebase stopped on conflicts; it is left in progress
+    Conflicts(Vec<PathBuf>),
+}
+
+/// The result of [`Repository::sync`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncReport {
+    /// The local branch that was synced
+    pub branch: String,
+    /// The remote the upstream lives on
+    pub remote: String,
+    /// The upstream branch as a full ref on the remote (e.g. `refs/heads/main`)
+    pub upstream: String,
+    /// Local commits not on upstream before integrating
+    pub ahead: usize,
+    /// Upstream commits not on the local branch before integrating
+    pub behind: usize,
+    /// How upstream changes were integrated
+    pub outcome: SyncOutcome,
+    /// Refs updated on the remote by the push step
+    pub pushed: Vec<String>,
+}
+
+impl SyncReport {
+    /// Check if the sync stopped on conflicts
+    pub fn has_conflicts(&self) -> bool {
+        matches!(self.outcome, SyncOutcome::Conflicts(_))
+    }
+}
+
+impl Repository {
+    /// Fetch, integrate upstream changes into the current branch, and optionally push
+    ///
+    /// The current branch must have an upstream (`branch.<name>.remote` and
+    /// `branch.<name>.merge`). A branch that has only local commits is not changed;
+    /// a branch that is only behind is fast-forwarded regardless of strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Strategy, push and fetch behavior
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `SyncReport`. Conflicts are reported in the outcome
+    /// rather than as an error, and nothing is pushed when they occur.
+    pub fn sync(&self, options: SyncOptions) -> Result<SyncReport> {
+        self.check_git()?;
+        let _guard = self.lock();
+
+        let branch = git(&["symbolic-ref", "--short", "-q", "HEAD"], Some(self))
+            .map(|output| output.trim().to_string())
+            .map_err(|_| GitError::CommandFailed("Cannot sync a detached HEAD".to_string()))?;
+
+        let remote = self.branch_config(&branch, "remote")?;
+        let upstream = self.branch_config(&branch, "merge")?;
+        let (Some(remote), Some(upstream)) = (remote, upstream) else {
+            return Err(GitError::CommandFailed(format!(
+                "Branch '{}' has no upstream configured",
+                branch
+            )));
+        };
+
+        let mut fetch_args = vec!["fetch"];
+        if options.prune {
+            fetch_args.push("--prune");
+        }
+        fetch_args.push(&remote);
+        git(&fetch_args, Some(self))?;
+
+        let tracking = "@{upstream}";
+        let (ahead, behind) = self.ahead_behind("HEAD", tracking)?;
+
+        let outcome = if behind == 0 {
+            SyncOutcome::UpToDate
+        } else if ahead == 0 || options.strategy == SyncStrategy::FastForwardOnly {
+            self.sync_fast_forward(tracking, behind, &options)?
+        } else {
+            match options.strategy {
+                SyncStrategy::Rebase => self.sync_rebase(tracking, &options)?,
+                _ => self.sync_merge(tracking, behind, &options)?,
+            }
+        };
+
+        let mut pushed = Vec::new();
+        if options.push && !matches!(outcome, SyncOutcome::Conflicts(_)) {
+            let (ahead_after, _) = self.ahead_behind("HEAD", tracking)?;
+            if ahead_after > 0 {
+                let refspec = format!("{}:{}", branch, upstream);
+                self.push(&remote, &refspec)?;
+                pushed.push(upstream.clone());
+            }
+        }
+
+        Ok(SyncReport {
+            branch,
+            remote,
+            upstream,
+            ahead,
+            behind,
+            outcome,
+            pushed,
+        })
+    }
+
+    /// Read `branch.<name>.<key>`, or `None` if it is not set
+    fn branch_config(&self, branch: &str, key: &str) -> Result<Option<String>> {
+        let key = format!("branch.{}.{}", branch, key);
+        let output = git_raw(&["config", "--get", &key], Some(self))?;
+        if output.status.success() {
+            let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            Ok(Some(value).filter(|value| !value.is_empty()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn sync_fast_forward(
+        &self,
+        upstream: &str,
+        behind: usize,
+        options: &SyncOptions,
+    ) -> Result<SyncOutcome> {
+        let mut args = vec!["merge", "--ff-only"];
+        if options.autostash {
+            args.push("--autostash");
+        }
+        args.push(upstream);
+        git(&args, Some(self))?;
+        Ok(SyncOutcome::FastForwarded { commits: behind })
+    }
+
+    fn sync_merge(
+        &self,
+        upstream: &str,
+        behind: usize,
+        options: &SyncOptions,
+    ) -> Result<SyncOutcome> {
+        if options.autostash {
+            // MergeOptions has no autostash switch, so fall back to the raw command
+            let output = git_raw(&["merge", "--autostash", "--no-edit", upstream], Some(self))?;
+            if !output.status.success() {
+                return self.conflicts_or_error("merge", &output);
+            }
+            let head = git(&["rev-parse", "HEAD"], Some(self))?;
+            return Ok(SyncOutcome::Merged(Hash::from(head.trim())));
+        }
+
+        Ok(match merge(self, upstream, &MergeOptions::new())? {
+            MergeStatus::Success(hash) => SyncOutcome::Merged(hash),
+            MergeStatus::FastForward(_) => SyncOutcome::FastForwarded { commits: behind },
+            MergeStatus::UpToDate => SyncOutcome::UpToDate,
+            MergeStatus::Conflicts(files) => SyncOutcome::Conflicts(files),
+        })
+    }
+
+    fn sync_rebase(&self, upstream: &str, options: &SyncOptions) -> Result<SyncOutcome> {
+        let mut args = vec!["rebase"];
+        if options.autostash {
+            args.push("--autostash");
+        }
+        args.push(upstream);
+
+        let output = git_raw(&args, Some(self))?;
+        if !output.status.success() {
+            return self.conflicts_or_error("rebase", &output);
+        }
+
+        let (commits, _) = self.ahead_behind("HEAD", upstream)?;
+        Ok(SyncOutcome::Rebased { commits })
+    }
+
+    /// Report conflicts if the failed command left any, otherwise surface the failure
+    fn conflicts_or_error(
+        &self,
+        command: &str,
+        output: &std::process::Output,
+    ) -> Result<SyncOutcome> {
+        let conflicts = extract_conflicted_files(self)?;
+        if !conflicts.is_empty() {
+            return Ok(SyncOutcome::Conflicts(conflicts));
+        }
+
+        Err(GitError::CommandFailed(format!(
+            "git {} failed: stdout='{}' stderr='{}'",
+            command,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PushOptions;
+    use std::path::Path;
+    use std::{env, fs};
+
+    /// A bare "origin" plus two clones that both track its default branch
+    fn setup(test_name: &str) -> (PathBuf, Repository, Repository) {
+        let base = env::temp_dir().join(format!("rustic_git_sync_test_{}", test_name));
+        if base.exists() {
+            fs::remove_dir_all(&base).unwrap();
+        }
+        fs::create_dir_all(&base).unwrap();
+
+        let origin = base.join("origin.git");
+        Repository::init(&origin, true).unwrap();
+        let origin_url = origin.to_string_lossy().to_string();
+
+        let seed = Repository::clone(&origin_url, base.join("local")).unwrap();
+        seed.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        commit_file(&seed, &base.join("local"), "shared.txt", "base\n");
+        let branch = seed.current_branch().unwrap().unwrap().name;
+        seed.push_with_options("origin", &branch, PushOptions::new().with_set_upstream())
+            .unwrap();
+
+        let other = Repository::clone(&origin_url, base.join("other")).unwrap();
+        other
+            .config()
+            .set_user("Other User", "other@example.com")
+            .unwrap();
+
+        (base, seed, other)
+    }
+
+    fn commit_file(repo: &Repository, dir: &Path, name: &str, content: &str) -> Hash {
+        fs::write(dir.join(name), content).unwrap();
+        repo.add(&[name]).unwrap();
+        repo.commit(&format!("Update {}", name)).unwrap()
+    }
+
+    fn push_from_other(base: &Path, other: &Repository, name: &str, content: &str) {
+        commit_file(other, &base.join("other"), name, content);
+        let branch = other.current_branch().unwrap().unwrap().name;
+        other.push("origin", &branch).unwrap();
+    }
+
+    #[test]
+    fn test_sync_up_to_date() {
+        let (base, local, _other) = setup("up_to_date");
+
+        let report = local.sync(SyncOptions::new().with_push()).unwrap();
+        assert_eq!(report.outcome, SyncOutcome::UpToDate);
+        assert_eq!(report.remote, "origin");
+        assert!(report.upstream.starts_with("refs/heads/"));
+        assert_eq!((report.ahead, report.behind), (0, 0));
+        assert!(report.pushed.is_empty());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_sync_fast_forward() {
+        let (base, local, other) = setup("fast_forward");
+        push_from_other(&base, &other, "remote.txt", "remote\n");
+
+        let report = local.sync(SyncOptions::new()).unwrap();
+        assert_eq!(report.outcome, SyncOutcome::FastForwarded { commits: 1 });
+        assert_eq!((report.ahead, report.behind), (0, 1));
+        assert!(base.join("local").join("remote.txt").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_sync_rebase_and_push() {
+        let (base, local, other) = setup("rebase_push");
+        push_from_other(&base, &other, "remote.txt", "remote\n");
+        commit_file(&local, &base.join("local"), "local.txt", "local\n");
+
+        let report = local
+            .sync(
+                SyncOptions::new()
+                    .with_strategy(SyncStrategy::Rebase)
+                    .with_push(),
+            )
+            .unwrap();
+        assert_eq!(report.outcome, SyncOutcome::Rebased { commits: 1 });
+        assert_eq!((report.ahead, report.behind), (1, 1));
+        assert_eq!(report.pushed, vec![report.upstream.clone()]);
+
+        // Linear history: the local commit sits directly on top of the remote one
+        let parents = git(&["rev-list", "--parents", "-n", "1", "HEAD"], Some(&local)).unwrap();
+        assert_eq!(parents.split_whitespace().count(), 2);
+
+        let report = other.sync(SyncOptions::new()).unwrap();
+        assert_eq!(report.outcome, SyncOutcome::FastForwarded { commits: 1 });
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_sync_merge() {
+        let (base, local, other) = setup("merge");
+        push_from_other(&base, &other, "remote.txt", "remote\n");
+        commit_file(&local, &base.join("local"), "local.txt", "local\n");
+
+        let report = local.sync(SyncOptions::new()).unwrap();
+        assert!(matches!(report.outcome, SyncOutcome::Merged(_)));
+        assert!(report.pushed.is_empty());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_sync_fast_forward_only_diverged() {
+        let (base, local, other) = setup("ff_only");
+        push_from_other(&base, &other, "remote.txt", "remote\n");
+        commit_file(&local, &base.join("local"), "local.txt", "local\n");
+
+        let result = local.sync(SyncOptions::new().with_strategy(SyncStrategy::FastForwardOnly));
+        assert!(matches!(result, Err(GitError::CommandFailed(_))));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_sync_rebase_conflicts() {
+        let (base, local, other) = setup("rebase_conflicts");
+        push_from_other(&base, &other, "shared.txt", "remote\n");
+        commit_file(&local, &base.join("local"), "shared.txt", "local\n");
+
+        let report = local
+            .sync(
+                SyncOptions::new()
+                    .with_strategy(SyncStrategy::Rebase)
+                    .with_push(),
+            )
+            .unwrap();
+        assert!(report.has_conflicts());
+        assert_eq!(
+            report.outcome,
+            SyncOutcome::Conflicts(vec![PathBuf::from("shared.txt")])
+        );
+        assert!(report.pushed.is_empty());
+        assert_eq!(
+            local.conflict_operation().unwrap(),
+            Some(crate::ConflictOperation::Rebase)
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_sync_without_upstream() {
+        let (base, local, _other) = setup("no_upstream");
+        local.checkout_new("topic", None).unwrap();
+
+        match local.sync(SyncOptions::new()) {
+            Err(GitError::CommandFailed(msg)) => assert!(msg.contains("no upstream")),
+            other => panic!("Expected missing upstream error, got: {:?}", other),
+        }
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
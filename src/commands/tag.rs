@@ -31,10 +31,11 @@
 use crate::commands::log::Author;
 use crate::error::{GitError, Result};
 use crate::repository::Repository;
-use crate::types::Hash;
-use crate::utils::{git, parse_unix_timestamp};
+use crate::types::{Hash, Identity};
+use crate::utils::{git, git_with_env, parse_unix_timestamp, validate_ref_name};
 use chrono::{DateTime, Utc};
 use std::fmt;
+use std::path::PathBuf;
 
 /// Represents a Git tag
 #[derive(Debug, Clone, PartialEq)]
@@ -155,6 +156,14 @@ pub struct TagOptions {
     pub message: Option<String>,
     /// Sign the tag with GPG (requires annotated)
     pub sign: bool,
+    /// Read the tag message from a file (`-F <file>`)
+    pub message_file: Option<PathBuf>,
+    /// Override the tagger name (sets `GIT_COMMITTER_NAME`)
+    pub tagger_name: Option<String>,
+    /// Override the tagger email (sets `GIT_COMMITTER_EMAIL`)
+    pub tagger_email: Option<String>,
+    /// Override the tagger date (sets `GIT_COMMITTER_DATE`)
+    pub tagger_date: Option<DateTime<Utc>>,
 }
 
 impl TagOptions {
@@ -188,6 +197,54 @@ impl TagOptions {
         self.annotated = true; // Signing implies annotated tag
         self
     }
+
+    /// Read the tag message from a file (implies annotated)
+    ///
+    /// Useful for long release notes. Takes precedence over `with_message`.
+    pub fn with_message_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.message_file = Some(path.into());
+        self.annotated = true; // Message file implies annotated tag
+        self
+    }
+
+    /// Override the tagger identity recorded in the tag object
+    pub fn with_tagger(mut self, name: &str, email: &str) -> Self {
+        self.tagger_name = Some(name.to_string());
+        self.tagger_email = Some(email.to_string());
+        self
+    }
+
+    /// Record `identity` as the tagger, without changing the config
+    ///
+    /// Same as [`with_tagger`](Self::with_tagger) with the identity's name and email.
+    pub fn with_identity(self, identity: Identity) -> Self {
+        self.with_tagger(&identity.name, &identity.email)
+    }
+
+    /// Override the tagger date recorded in the tag object
+    pub fn with_tagger_date(mut self, date: DateTime<Utc>) -> Self {
+        self.tagger_date = Some(date);
+        self
+    }
+
+    /// Build the environment variables git reads the tagger identity from
+    fn tagger_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+
+        if let Some(ref name) = self.tagger_name {
+            env.push(("GIT_COMMITTER_NAME", name.clone()));
+        }
+
+        if let Some(ref email) = self.tagger_email {
+            env.push(("GIT_COMMITTER_EMAIL", email.clone()));
+        }
+
+        if let Some(date) = self.tagger_date {
+            env.push(("GIT_COMMITTER_DATE", format!("@{} +0000", date.timestamp())));
+        }
+
+        env
+    }
 }
 
 impl Repository {
@@ -210,7 +267,7 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn tags(&self) -> Result<TagList> {
-        Self::ensure_git()?;
+        self.check_git()?;
 
         // Use git for-each-ref to get all tag information in a single call
         // Format: refname:short objecttype objectname *objectname taggername taggeremail taggerdate:unix subject body
@@ -220,7 +277,7 @@ impl Repository {
                 "--format=%(refname:short)|%(objecttype)|%(objectname)|%(*objectname)|%(taggername)|%(taggeremail)|%(taggerdate:unix)|%(subject)|%(body)",
                 "refs/tags/",
             ],
-            Some(self.repo_path()),
+            Some(self),
         )?;
 
         if output.trim().is_empty() {
@@ -303,11 +360,12 @@ impl Repository {
         target: Option<&Hash>,
         options: TagOptions,
     ) -> Result<Tag> {
-        Self::ensure_git()?;
+        self.check_git()?;
+        validate_ref_name("tag", name)?;
 
         let mut args = vec!["tag"];
 
-        if options.annotated || options.message.is_some() {
+        if options.annotated || options.message.is_some() || options.message_file.is_some() {
             args.push("-a");
         }
 
@@ -319,7 +377,15 @@ impl Repository {
             args.push("-s");
         }
 
-        if let Some(ref message) = options.message {
+        let message_file = options
+            .message_file
+            .as_ref()
+            .map(|path| path.to_string_lossy().to_string());
+
+        if let Some(ref file) = message_file {
+            args.push("-F");
+            args.push(file);
+        } else if let Some(ref message) = options.message {
             args.push("-m");
             args.push(message);
         }
@@ -330,10 +396,16 @@ impl Repository {
             args.push(target_hash.as_str());
         }
 
-        git(&args, Some(self.repo_path()))?;
+        let tagger_env = options.tagger_env();
+        let env: Vec<(&str, &str)> = tagger_env
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+
+        git_with_env(&args, Some(self), &env)?;
 
         // Get the created tag information
-        let show_output = git(&["show", "--format=fuller", name], Some(self.repo_path()))?;
+        let show_output = git(&["show", "--format=fuller", name, "--"], Some(self))?;
         parse_tag_info(name, &show_output)
     }
 
@@ -353,9 +425,10 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn delete_tag(&self, name: &str) -> Result<()> {
-        Self::ensure_git()?;
+        self.check_git()?;
+        validate_ref_name("tag", name)?;
 
-        git(&["tag", "-d", name], Some(self.repo_path()))?;
+        git(&["tag", "-d", name], Some(self))?;
         Ok(())
     }
 
@@ -381,9 +454,10 @@ impl Repository {
     /// # Ok::<(), rustic_git::GitError>(())
     /// ```
     pub fn show_tag(&self, name: &str) -> Result<Tag> {
-        Self::ensure_git()?;
+        self.check_git()?;
+        validate_ref_name("tag", name)?;
 
-        let show_output = git(&["show", "--format=fuller", name], Some(self.repo_path()))?;
+        let show_output = git(&["show", "--format=fuller", name, "--"], Some(self))?;
         parse_tag_info(name, &show_output)
     }
 }
@@ -728,6 +802,58 @@ mod tests {
         assert_eq!(options.message, Some("Test message".to_string()));
     }
 
+    #[test]
+    fn test_tag_options_message_file_and_tagger() {
+        let date = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let options = TagOptions::new()
+            .with_message_file("notes.md")
+            .with_tagger("Release Bot", "bot@example.com")
+            .with_tagger_date(date);
+
+        assert!(options.annotated);
+        assert_eq!(options.message_file, Some(PathBuf::from("notes.md")));
+
+        let env = options.tagger_env();
+        assert!(env.contains(&("GIT_COMMITTER_NAME", "Release Bot".to_string())));
+        assert!(env.contains(&("GIT_COMMITTER_EMAIL", "bot@example.com".to_string())));
+        assert!(env.contains(&("GIT_COMMITTER_DATE", "@1700000000 +0000".to_string())));
+    }
+
+    #[test]
+    fn test_create_tag_with_message_file_and_tagger_override() {
+        let (repo, test_path) = create_test_repo();
+        create_test_commit(&repo, &test_path);
+
+        let notes_path = test_path.join("RELEASE_NOTES.md");
+        fs::write(&notes_path, "Release 2.0.0\n\n- Feature A\n- Feature B\n").unwrap();
+
+        let date = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let options = TagOptions::new()
+            .with_message_file(&notes_path)
+            .with_tagger("Release Bot", "bot@example.com")
+            .with_tagger_date(date);
+        repo.create_tag_with_options("v2.0.0", None, options)
+            .unwrap();
+
+        let tags = repo.tags().unwrap();
+        let tag = tags.find("v2.0.0").unwrap();
+        assert_eq!(tag.tag_type, TagType::Annotated);
+
+        assert!(tag.message.as_ref().unwrap().starts_with("Release 2.0.0"));
+
+        // The full multi-line message is stored in the tag object
+        let raw = git(&["cat-file", "-p", "v2.0.0"], Some(&test_path)).unwrap();
+        assert!(raw.contains("- Feature A\n- Feature B"));
+
+        let tagger = tag.tagger.as_ref().unwrap();
+        assert_eq!(tagger.name, "Release Bot");
+        assert_eq!(tagger.email, "<bot@example.com>");
+        assert_eq!(tagger.timestamp, date);
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
     #[test]
     fn test_show_tag() {
         let (repo, test_path) = create_test_repo();
@@ -0,0 +1,386 @@
+//! All-or-nothing multi-step workflows
+//!
+//! Release automation typically chains several operations (commit, tag, push), and a
+//! failure halfway through leaves the repository in a state nobody planned for.
+//! [`Repository::transaction`] runs the steps through a [`Transaction`], which records
+//! every local side effect and undoes them in reverse order if a later step fails.
+//!
+//! A push is a step like any other: if it fails, the earlier local steps are rolled
+//! back. A push that succeeded cannot be undone, though, so it belongs at the end of
+//! the sequence, and a transaction should push at most once.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{Repository, TagOptions};
+//!
+//! let repo = Repository::open(".")?;
+//! repo.add(&["Cargo.toml", "CHANGELOG.md"])?;
+//!
+//! // If the push fails, the tag is deleted and the commit is reset (keeping it staged)
+//! repo.transaction(|tx| {
+//!     tx.commit("Release 1.2.0")?;
+//!     tx.create_tag_with_options(
+//!         "v1.2.0",
+//!         None,
+//!         TagOptions::new().with_message("Release 1.2.0".to_string()),
+//!     )?;
+//!     tx.push("origin", "main")
+//! })?;
+//!
+//! // The tag is published once the release commit is on the remote
+//! repo.push("origin", "v1.2.0")?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::commands::{Branch, Tag, TagOptions};
+use crate::error::GitError;
+use crate::types::Hash;
+use crate::utils::{git, git_raw};
+use crate::{Repository, Result};
+
+/// A local side effect that can be reverted
+#[derive(Debug, Clone)]
+enum Undo {
+    /// Move HEAD back to the given commit, or make it unborn again, keeping the index
+    Head(Option<Hash>),
+    /// Restore a ref to its previous value, or delete it if it did not exist
+    Ref {
+        name: String,
+        previous: Option<Hash>,
+    },
+}
+
+/// A sequence of operations that is rolled back as a whole on failure
+///
+/// Created by [`Repository::transaction`]. Operations performed through the
+/// transaction are recorded; anything done directly on [`Transaction::repo`] is not.
+pub struct Transaction<'a> {
+    repo: &'a Repository,
+    undo: Vec<Undo>,
+}
+
+impl<'a> Transaction<'a> {
+    fn new(repo: &'a Repository) -> Self {
+        Self {
+            repo,
+            undo: Vec::new(),
+        }
+    }
+
+    /// The repository the transaction operates on
+    ///
+    /// Useful for read-only queries between steps. Changes made through it are not
+    /// rolled back.
+    pub fn repo(&self) -> &'a Repository {
+        self.repo
+    }
+
+    /// Commit staged changes; rollback resets HEAD and leaves the changes staged
+    pub fn commit(&mut self, message: &str) -> Result<Hash> {
+        let previous = resolve_ref(self.repo, "HEAD")?;
+        let hash = self.repo.commit(message)?;
+        self.undo.push(Undo::Head(previous));
+        Ok(hash)
+    }
+
+    /// Create a tag; rollback deletes it, or restores the tag it overwrote
+    pub fn create_tag(&mut self, name: &str, target: Option<&Hash>) -> Result<Tag> {
+        self.create_tag_with_options(name, target, TagOptions::new())
+    }
+
+    /// Create a tag with custom options; rollback deletes it, or restores the tag it overwrote
+    pub fn create_tag_with_options(
+        &mut self,
+        name: &str,
+        target: Option<&Hash>,
+        options: TagOptions,
+    ) -> Result<Tag> {
+        let ref_name = format!("refs/tags/{}", name);
+        let previous = resolve_ref(self.repo, &ref_name)?;
+        let tag = self.repo.create_tag_with_options(name, target, options)?;
+        self.undo.push(Undo::Ref {
+            name: ref_name,
+            previous,
+        });
+        Ok(tag)
+    }
+
+    /// Create a branch; rollback deletes it
+    pub fn create_branch(&mut self, name: &str, start_point: Option<&str>) -> Result<Branch> {
+        let branch = self.repo.create_branch(name, start_point)?;
+        self.undo.push(Undo::Ref {
+            name: format!("refs/heads/{}", name),
+            previous: None,
+        });
+        Ok(branch)
+    }
+
+    /// Push a branch or tag to a remote
+    ///
+    /// If the push fails, the transaction rolls back the earlier steps. A push that
+    /// succeeded is not recorded and cannot be undone, so it should be the last step;
+    /// a later failure leaves the pushed refs on the remote.
+    pub fn push(&mut self, remote: &str, refspec: &str) -> Result<()> {
+        self.repo.push(remote, refspec)
+    }
+
+    /// Undo recorded side effects, most recent first
+    fn rollback(&mut self) -> Result<()> {
+        while let Some(undo) = self.undo.pop() {
+            match undo {
+                Undo::Head(Some(hash)) => {
+                    git(&["reset", "--soft", hash.as_str()], Some(self.repo))?;
+                }
+                Undo::Head(None) => {
+                    git(&["update-ref", "-d", "HEAD"], Some(self.repo))?;
+                }
+                Undo::Ref {
+                    name,
+                    previous: Some(hash),
+                } => {
+                    git(&["update-ref", &name, hash.as_str()], Some(self.repo))?;
+                }
+                Undo::Ref {
+                    name,
+                    previous: None,
+                } => {
+                    git(&["update-ref", "-d", &name], Some(self.repo))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolve a ref to the object it points at, or `None` if it does not exist
+fn resolve_ref(repo: &Repository, name: &str) -> Result<Option<Hash>> {
+    let output = git_raw(&["rev-parse", "-q", "--verify", name], Some(repo))?;
+    if output.status.success() {
+        let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(Hash::from(hash)))
+    } else {
+        Ok(None)
+    }
+}
+
+impl Repository {
+    /// Run a sequence of operations, rolling back local side effects if any step fails
+    ///
+    /// Commits are undone with a soft reset (the changes stay staged), created
+    /// branches and tags are deleted, and overwritten tags are restored. A failed
+    /// [`Transaction::push`] rolls these back too, but a successful one stays on the
+    /// remote.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - Closure performing the operations through the given `Transaction`
+    ///
+    /// # Returns
+    ///
+    /// The closure's result. On failure the closure's error is returned after the
+    /// rollback; if the rollback fails too, a `GitError::CommandFailed` describes both.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let hash = repo.transaction(|tx| {
+    ///     let hash = tx.commit("Bump version")?;
+    ///     tx.create_tag("v2.0.0", Some(&hash))?;
+    ///     tx.push("origin", "main")?;
+    ///     Ok(hash)
+    /// })?;
+    /// println!("Released {}", hash.short());
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn transaction<T, F>(&self, steps: F) -> Result<T>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T>,
+    {
+        self.check_git()?;
+        let _guard = self.lock();
+
+        let mut transaction = Transaction::new(self);
+        match steps(&mut transaction) {
+            Ok(value) => Ok(value),
+            Err(error) => match transaction.rollback() {
+                Ok(()) => Err(error),
+                Err(rollback_error) => Err(GitError::CommandFailed(format!(
+                    "{}; rollback failed: {}",
+                    error, rollback_error
+                ))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::{env, fs};
+
+    fn create_test_repo(test_name: &str) -> (PathBuf, Repository) {
+        let temp_dir = env::temp_dir().join(format!("rustic_git_transaction_test_{}", test_name));
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+
+        let repo = Repository::init(&temp_dir, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        (temp_dir, repo)
+    }
+
+    fn commit_file(repo: &Repository, temp_dir: &std::path::Path, content: &str) -> Hash {
+        fs::write(temp_dir.join("file.txt"), content).unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        repo.commit(content).unwrap()
+    }
+
+    #[test]
+    fn test_transaction_success_keeps_changes() {
+        let (temp_dir, repo) = create_test_repo("success");
+        commit_file(&repo, &temp_dir, "initial");
+
+        fs::write(temp_dir.join("file.txt"), "release").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+
+        let hash = repo
+            .transaction(|tx| {
+                let hash = tx.commit("Release")?;
+                tx.create_tag("v1.0.0", Some(&hash))?;
+                tx.create_branch("release", None)?;
+                Ok(hash)
+            })
+            .unwrap();
+
+        assert_eq!(resolve_ref(&repo, "HEAD").unwrap(), Some(hash.clone()));
+        assert_eq!(
+            resolve_ref(&repo, "refs/tags/v1.0.0").unwrap(),
+            Some(hash.clone())
+        );
+        assert_eq!(
+            resolve_ref(&repo, "refs/heads/release").unwrap(),
+            Some(hash)
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_on_failed_push() {
+        let (temp_dir, repo) = create_test_repo("rollback");
+        let initial = commit_file(&repo, &temp_dir, "initial");
+
+        fs::write(temp_dir.join("file.txt"), "release").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+
+        let result = repo.transaction(|tx| {
+            let hash = tx.commit("Release")?;
+            tx.create_tag("v1.0.0", Some(&hash))?;
+            tx.create_branch("release", None)?;
+            tx.push("no-such-remote", "v1.0.0")
+        });
+
+        assert!(matches!(result, Err(GitError::CommandFailed(_))));
+        assert_eq!(resolve_ref(&repo, "HEAD").unwrap(), Some(initial));
+        assert_eq!(resolve_ref(&repo, "refs/tags/v1.0.0").unwrap(), None);
+        assert_eq!(resolve_ref(&repo, "refs/heads/release").unwrap(), None);
+
+        // The committed change is staged again
+        let status = repo.status().unwrap();
+        assert_eq!(status.staged_files().count(), 1);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_transaction_rolls_back_on_rejected_push() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (temp_dir, repo) = create_test_repo("rejected_push");
+        let remote_path = env::temp_dir().join("rustic_git_transaction_test_rejected_push.git");
+        if remote_path.exists() {
+            fs::remove_dir_all(&remote_path).unwrap();
+        }
+        Repository::init(&remote_path, true).unwrap();
+        let hook = remote_path.join("hooks/pre-receive");
+        fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        repo.add_remote("origin", &remote_path.to_string_lossy())
+            .unwrap();
+
+        let initial = commit_file(&repo, &temp_dir, "initial");
+        repo.create_tag("latest", None).unwrap();
+        fs::write(temp_dir.join("file.txt"), "release").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+        let branch = repo.current_branch().unwrap().unwrap().name;
+
+        let result = repo.transaction(|tx| {
+            tx.commit("Release")?;
+            tx.create_tag_with_options("latest", None, TagOptions::new().with_force())?;
+            tx.push("origin", &branch)
+        });
+
+        assert!(matches!(result, Err(GitError::PushRejected(_))));
+        assert_eq!(resolve_ref(&repo, "HEAD").unwrap(), Some(initial.clone()));
+        assert_eq!(
+            resolve_ref(&repo, "refs/tags/latest").unwrap(),
+            Some(initial)
+        );
+
+        fs::remove_dir_all(&remote_path).unwrap();
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_transaction_restores_overwritten_tag() {
+        let (temp_dir, repo) = create_test_repo("overwritten_tag");
+        let initial = commit_file(&repo, &temp_dir, "initial");
+        repo.create_tag("latest", None).unwrap();
+        commit_file(&repo, &temp_dir, "second");
+
+        let result: Result<()> = repo.transaction(|tx| {
+            tx.create_tag_with_options("latest", None, TagOptions::new().with_force())?;
+            Err(GitError::CommandFailed("later step failed".to_string()))
+        });
+
+        match result {
+            Err(GitError::CommandFailed(msg)) => assert_eq!(msg, "later step failed"),
+            other => panic!("Expected the step error, got: {:?}", other),
+        }
+        assert_eq!(
+            resolve_ref(&repo, "refs/tags/latest").unwrap(),
+            Some(initial)
+        );
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+
+    #[test]
+    fn test_transaction_rolls_back_first_commit() {
+        let (temp_dir, repo) = create_test_repo("unborn");
+        fs::write(temp_dir.join("file.txt"), "content").unwrap();
+        repo.add(&["file.txt"]).unwrap();
+
+        let result = repo.transaction(|tx| {
+            tx.commit("Initial")?;
+            tx.create_tag("v0.1.0", None)?;
+            tx.create_tag("v0.1.0", None)
+        });
+
+        assert!(result.is_err());
+        assert_eq!(resolve_ref(&repo, "HEAD").unwrap(), None);
+        assert_eq!(resolve_ref(&repo, "refs/tags/v0.1.0").unwrap(), None);
+        assert_eq!(repo.status().unwrap().staged_files().count(), 1);
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
@@ -0,0 +1,485 @@
+//! Git worktree operations
+//!
+//! A repository can have several working trees checked out at once: the main one and
+//! any number of linked worktrees created with `git worktree add`. Build tooling uses
+//! them to work on several branches side by side without cloning again. This module
+//! wraps `git worktree add`, `list`, `remove`, `prune`, `lock` and `unlock`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use rustic_git::{Repository, WorktreeAddOptions};
+//!
+//! let repo = Repository::open(".")?;
+//!
+//! // Check out a new branch in a sibling directory
+//! let options = WorktreeAddOptions::new().with_new_branch("hotfix");
+//! let worktree = repo.worktree_add_with_options("../hotfix", options)?;
+//! println!("{} is on {:?}", worktree.path.display(), worktree.branch);
+//!
+//! for worktree in repo.worktree_list()?.iter() {
+//!     println!("{}", worktree.path.display());
+//! }
+//!
+//! repo.worktree_remove("../hotfix", false)?;
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::error::{GitError, Result};
+use crate::repository::Repository;
+use crate::types::Hash;
+use crate::utils::{git, validate_arg, validate_ref_name};
+use std::path::{Path, PathBuf};
+
+/// A working tree attached to the repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worktree {
+    /// Absolute path of the working tree
+    pub path: PathBuf,
+    /// The commit checked out, `None` for a bare repository
+    pub head: Option<Hash>,
+    /// The branch checked out (short name), `None` when detached or bare
+    pub branch: Option<String>,
+    /// Whether this entry is a bare repository
+    pub bare: bool,
+    /// Whether HEAD is detached
+    pub detached: bool,
+    /// Whether the worktree is locked against pruning and removal
+    pub locked: bool,
+    /// The reason given when locking, if any
+    pub lock_reason: Option<String>,
+    /// Why `git worktree prune` would remove this worktree, if it would
+    pub prunable: Option<String>,
+}
+
+impl Worktree {
+    /// Check whether `git worktree prune` would remove this worktree
+    pub fn is_prunable(&self) -> bool {
+        self.prunable.is_some()
+    }
+}
+
+/// The worktrees of a repository, main working tree first
+#[derive(Debug, Clone)]
+pub struct WorktreeList {
+    worktrees: Box<[Worktree]>,
+}
+
+impl WorktreeList {
+    /// Create a new WorktreeList, with the main working tree first
+    pub fn new(worktrees: Vec<Worktree>) -> Self {
+        Self {
+            worktrees: worktrees.into_boxed_slice(),
+        }
+    }
+
+    /// Get an iterator over all worktrees
+    pub fn iter(&self) -> impl Iterator<Item = &Worktree> + '_ {
+        self.worktrees.iter()
+    }
+
+    /// Get the main working tree (or the bare repository)
+    pub fn main(&self) -> Option<&Worktree> {
+        self.worktrees.first()
+    }
+
+    /// Get an iterator over linked worktrees only
+    pub fn linked(&self) -> impl Iterator<Item = &Worktree> + '_ {
+        self.worktrees.iter().skip(1)
+    }
+
+    /// Find the worktree at `path`
+    pub fn find<P: AsRef<Path>>(&self, path: P) -> Option<&Worktree> {
+        let path = path.as_ref();
+        let canonical = path.canonicalize().ok();
+        self.worktrees.iter().find(|worktree| {
+            worktree.path == path
+                || canonical.is_some() && worktree.path.canonicalize().ok() == canonical
+        })
+    }
+
+    /// Find the worktree that has `branch` checked out
+    pub fn find_by_branch(&self, branch: &str) -> Option<&Worktree> {
+        self.worktrees
+            .iter()
+            .find(|worktree| worktree.branch.as_deref() == Some(branch))
+    }
+
+    /// Get the total number of worktrees, including the main one
+    pub fn len(&self) -> usize {
+        self.worktrees.len()
+    }
+
+    /// Check if the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.worktrees.is_empty()
+    }
+}
+
+/// Options for adding a worktree
+#[derive(Debug, Clone, Default)]
+pub struct WorktreeAddOptions {
+    /// Create a new branch for the worktree (`-b <branch>`)
+    pub new_branch: Option<String>,
+    /// The commit or branch to check out (defaults to HEAD, or a branch named after the
+    /// directory)
+    pub commitish: Option<String>,
+    /// Detach HEAD instead of checking out a branch (`--detach`)
+    pub detach: bool,
+    /// Check out a branch already checked out elsewhere, or reuse a registered path (`--force`)
+    pub force: bool,
+    /// Lock the worktree right after creating it (`--lock`)
+    pub lock: bool,
+}
+
+impl WorktreeAddOptions {
+    /// Create new default worktree options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create `branch` and check it out in the new worktree
+    pub fn with_new_branch(mut self, branch: &str) -> Self {
+        self.new_branch = Some(branch.to_string());
+        self
+    }
+
+    /// Check out `commitish` (a branch, tag or commit)
+    pub fn with_commitish(mut self, commitish: &str) -> Self {
+        self.commitish = Some(commitish.to_string());
+        self
+    }
+
+    /// Detach HEAD in the new worktree
+    pub fn with_detach(mut self) -> Self {
+        self.detach = true;
+        self
+    }
+
+    /// Override git's safety checks
+    pub fn with_force(mut self) -> Self {
+        self.force = true;
+        self
+    }
+
+    /// Lock the new worktree
+    pub fn with_lock(mut self) -> Self {
+        self.lock = true;
+        self
+    }
+}
+
+impl Repository {
+    /// Add a linked worktree at `path`
+    ///
+    /// # Arguments
+    /// * `path` - Directory to create the worktree in
+    /// * `commitish` - Branch, tag or commit to check out; `None` lets git create a
+    ///   branch named after the directory
+    ///
+    /// # Returns
+    /// The new worktree
+    pub fn worktree_add<P: AsRef<Path>>(
+        &self,
+        path: P,
+        commitish: Option<&str>,
+    ) -> Result<Worktree> {
+        let mut options = WorktreeAddOptions::new();
+        options.commitish = commitish.map(str::to_string);
+        self.worktree_add_with_options(path, options)
+    }
+
+    /// Add a linked worktree at `path` with custom options
+    pub fn worktree_add_with_options<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: WorktreeAddOptions,
+    ) -> Result<Worktree> {
+        self.check_git()?;
+
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy();
+        let mut args = vec!["worktree", "add", "--quiet"];
+        if let Some(ref branch) = options.new_branch {
+            validate_ref_name("branch", branch)?;
+            args.push("-b");
+            args.push(branch);
+        }
+        if options.detach {
+            args.push("--detach");
+        }
+        if options.force {
+            args.push("--force");
+        }
+        if options.lock {
+            args.push("--lock");
+        }
+        args.push("--");
+        args.push(&path_str);
+        if let Some(ref commitish) = options.commitish {
+            validate_arg("commit", commitish)?;
+            args.push(commitish);
+        }
+        git(&args, Some(self))?;
+
+        // Git resolves relative paths against the directory it runs in
+        let resolved = if path.is_relative() {
+            self.command_dir().join(path)
+        } else {
+            path.to_path_buf()
+        };
+        self.worktree_list()?
+            .find(&resolved)
+            .cloned()
+            .ok_or_else(|| {
+                GitError::CommandFailed(format!(
+                    "git worktree add did not register {}",
+                    path.display()
+                ))
+            })
+    }
+
+    /// List the main working tree and all linked worktrees
+    pub fn worktree_list(&self) -> Result<WorktreeList> {
+        self.check_git()?;
+
+        let output = git(&["worktree", "list", "--porcelain"], Some(self))?;
+        Ok(WorktreeList::new(parse_worktree_list(&output)))
+    }
+
+    /// Remove a linked worktree and its directory
+    ///
+    /// # Arguments
+    /// * `path` - Path of the worktree to remove
+    /// * `force` - Remove it even with uncommitted changes, or while locked
+    pub fn worktree_remove<P: AsRef<Path>>(&self, path: P, force: bool) -> Result<()> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let mut args = vec!["worktree", "remove"];
+        if force {
+            // Given twice, `--force` also removes locked worktrees
+            args.extend(["--force", "--force"]);
+        }
+        args.push("--");
+        args.push(&path_str);
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// Drop the administrative files of worktrees whose directories are gone
+    pub fn worktree_prune(&self) -> Result<()> {
+        self.check_git()?;
+
+        git(&["worktree", "prune"], Some(self))?;
+        Ok(())
+    }
+
+    /// Lock a worktree so it is neither pruned nor removed
+    ///
+    /// Useful for worktrees on removable media or network shares.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the worktree to lock
+    /// * `reason` - Optional explanation, reported by [`worktree_list`](Self::worktree_list)
+    pub fn worktree_lock<P: AsRef<Path>>(&self, path: P, reason: Option<&str>) -> Result<()> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        let mut args = vec!["worktree", "lock"];
+        if let Some(reason) = reason {
+            args.push("--reason");
+            args.push(reason);
+        }
+        args.push("--");
+        args.push(&path_str);
+        git(&args, Some(self))?;
+        Ok(())
+    }
+
+    /// Unlock a worktree locked with [`worktree_lock`](Self::worktree_lock)
+    pub fn worktree_unlock<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.check_git()?;
+
+        let path_str = path.as_ref().to_string_lossy();
+        git(&["worktree", "unlock", "--", &path_str], Some(self))?;
+        Ok(())
+    }
+}
+
+/// Parse `git worktree list --porcelain`: blank-line separated records of
+/// `worktree <path>`, `HEAD <hash>`, `branch <ref>`, and `bare`, `detached`,
+/// `locked [<reason>]`, `prunable <reason>` markers
+fn parse_worktree_list(output: &str) -> Vec<Worktree> {
+    let mut worktrees = Vec::new();
+    let mut current: Option<Worktree> = None;
+
+    for line in output.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            worktrees.extend(current.take());
+            current = Some(Worktree {
+                path: PathBuf::from(path),
+                head: None,
+                branch: None,
+                bare: false,
+                detached: false,
+                locked: false,
+                lock_reason: None,
+                prunable: None,
+            });
+            continue;
+        }
+        let Some(worktree) = current.as_mut() else {
+            continue;
+        };
+
+        let (key, value) = match line.split_once(' ') {
+            Some((key, value)) => (key, Some(value)),
+            None => (line, None),
+        };
+        match (key, value) {
+            ("HEAD", Some(hash)) => worktree.head = Some(Hash::from(hash)),
+            ("branch", Some(branch)) => {
+                let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+                worktree.branch = Some(branch.to_string());
+            }
+            ("bare", _) => worktree.bare = true,
+            ("detached", _) => worktree.detached = true,
+            ("locked", reason) => {
+                worktree.locked = true;
+                worktree.lock_reason = reason.map(str::to_string);
+            }
+            ("prunable", reason) => {
+                worktree.prunable = Some(reason.unwrap_or_default().to_string());
+            }
+            _ => {}
+        }
+    }
+    worktrees.extend(current);
+
+    worktrees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempRepo;
+    use std::fs;
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let output = "\
+worktree /src/main
+HEAD 43204cee92020c56341758ca011cfea08b2939a6
+branch refs/heads/master
+
+worktree /src/feature
+HEAD 43204cee92020c56341758ca011cfea08b2939a6
+branch refs/heads/feature/x
+prunable gitdir file points to non-existent location
+
+worktree /mnt/usb/detached
+HEAD 5678abc5678abc5678abc5678abc5678abc5678c
+detached
+locked usb drive
+
+worktree /mnt/plain-lock
+HEAD 5678abc5678abc5678abc5678abc5678abc5678c
+detached
+locked
+";
+        let list = WorktreeList::new(parse_worktree_list(output));
+        assert_eq!(list.len(), 4);
+
+        let main = list.main().unwrap();
+        assert_eq!(main.path, PathBuf::from("/src/main"));
+        assert_eq!(main.branch.as_deref(), Some("master"));
+        assert_eq!(
+            main.head,
+            Some(Hash::from("43204cee92020c56341758ca011cfea08b2939a6"))
+        );
+        assert!(!main.locked && !main.detached && !main.is_prunable());
+
+        let feature = list.find_by_branch("feature/x").unwrap();
+        assert_eq!(
+            feature.prunable.as_deref(),
+            Some("gitdir file points to non-existent location")
+        );
+
+        let detached = list.find("/mnt/usb/detached").unwrap();
+        assert!(detached.detached && detached.locked);
+        assert_eq!(detached.branch, None);
+        assert_eq!(detached.lock_reason.as_deref(), Some("usb drive"));
+
+        let plain = list.find("/mnt/plain-lock").unwrap();
+        assert!(plain.locked);
+        assert_eq!(plain.lock_reason, None);
+
+        assert_eq!(list.linked().count(), 3);
+    }
+
+    #[test]
+    fn test_parse_worktree_list_bare() {
+        let worktrees = parse_worktree_list("worktree /srv/repo.git\nbare\n");
+        assert_eq!(worktrees.len(), 1);
+        assert!(worktrees[0].bare);
+        assert_eq!(worktrees[0].head, None);
+        assert!(parse_worktree_list("").is_empty());
+    }
+
+    #[test]
+    fn test_worktree_lifecycle() {
+        let repo = TempRepo::new("worktree_lifecycle").unwrap();
+        repo.commit_file("file.txt", "content\n", "Initial commit")
+            .unwrap();
+        let base = repo.path().with_extension("worktrees");
+        let feature_path = base.join("feature");
+        let detached_path = base.join("detached");
+
+        let feature = repo
+            .worktree_add_with_options(
+                &feature_path,
+                WorktreeAddOptions::new().with_new_branch("feature"),
+            )
+            .unwrap();
+        assert_eq!(feature.branch.as_deref(), Some("feature"));
+        assert_eq!(feature.head, Some(repo.head_hash().unwrap()));
+        assert!(feature_path.join("file.txt").exists());
+
+        let detached = repo.worktree_add(&detached_path, Some("HEAD")).unwrap();
+        assert!(detached.detached);
+
+        let list = repo.worktree_list().unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.linked().count(), 2);
+        assert!(list.find(repo.path()).is_some());
+
+        // A locked worktree needs force to remove
+        repo.worktree_lock(&detached_path, Some("in use")).unwrap();
+        let locked = repo.worktree_list().unwrap();
+        let locked = locked.find(&detached_path).unwrap();
+        assert_eq!(locked.lock_reason.as_deref(), Some("in use"));
+        assert!(repo.worktree_remove(&detached_path, false).is_err());
+        repo.worktree_unlock(&detached_path).unwrap();
+        repo.worktree_remove(&detached_path, false).unwrap();
+        assert!(!detached_path.exists());
+
+        // A deleted directory leaves a prunable entry behind
+        fs::remove_dir_all(&feature_path).unwrap();
+        assert!(
+            repo.worktree_list()
+                .unwrap()
+                .find_by_branch("feature")
+                .unwrap()
+                .is_prunable()
+        );
+        repo.worktree_prune().unwrap();
+        assert_eq!(repo.worktree_list().unwrap().len(), 1);
+
+        assert!(
+            repo.worktree_add(base.join("bad"), Some("--orphan"))
+                .is_err()
+        );
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
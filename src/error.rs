@@ -1,5 +1,7 @@
+use crate::types::Hash;
 use std::fmt;
 use std::io;
+use std::time::Duration;
 
 pub type Result<T> = std::result::Result<T, GitError>;
 
@@ -7,6 +9,22 @@ pub type Result<T> = std::result::Result<T, GitError>;
 pub enum GitError {
     IoError(String),
     CommandFailed(String),
+    /// The remote refused one or more refs during a push
+    PushRejected(PushRejection),
+    /// An abbreviated object name matches more than one object
+    Ambiguous {
+        /// The abbreviation that was looked up
+        prefix: String,
+        /// Every object whose id starts with `prefix`
+        candidates: Vec<Hash>,
+    },
+    /// A git process was killed after exceeding the repository's timeout
+    TimedOut {
+        /// The git subcommand that was running
+        command: String,
+        /// The limit that was exceeded
+        timeout: Duration,
+    },
 }
 
 impl fmt::Display for GitError {
@@ -14,10 +32,85 @@ impl fmt::Display for GitError {
         match self {
             GitError::IoError(msg) => write!(f, "IO error: {}", msg),
             GitError::CommandFailed(msg) => write!(f, "Git command failed: {}", msg),
+            GitError::PushRejected(rejection) => write!(f, "Push rejected: {}", rejection),
+            GitError::Ambiguous { prefix, candidates } => {
+                let candidates: Vec<&str> = candidates.iter().map(Hash::as_str).collect();
+                write!(
+                    f,
+                    "Ambiguous object name {}: candidates are {}",
+                    prefix,
+                    candidates.join(", ")
+                )
+            }
+            GitError::TimedOut { command, timeout } => {
+                write!(f, "git {} timed out after {:?}", command, timeout)
+            }
         }
     }
 }
 
+/// A ref the remote refused to update during a push
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedRef {
+    /// The local ref being pushed (empty for deletions)
+    pub source: String,
+    /// The remote ref that was not updated
+    pub destination: String,
+    /// Git's summary of the failure, e.g. `rejected` or `remote rejected`
+    pub summary: String,
+    /// The reason given, e.g. `non-fast-forward` or `pre-receive hook declined`
+    pub reason: Option<String>,
+}
+
+impl RejectedRef {
+    /// Check whether the local branch is behind the remote and needs to fetch first
+    pub fn is_non_fast_forward(&self) -> bool {
+        matches!(
+            self.reason.as_deref(),
+            Some("non-fast-forward") | Some("fetch first")
+        )
+    }
+
+    /// Check whether a server-side hook or branch protection rule refused the update
+    pub fn is_remote_rejected(&self) -> bool {
+        self.summary == "remote rejected"
+    }
+}
+
+impl fmt::Display for RejectedRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {} [{}]",
+            self.source, self.destination, self.summary
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Details of a rejected push, including messages printed by the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushRejection {
+    /// The refs the remote refused
+    pub refs: Vec<RejectedRef>,
+    /// Lines the server sent with a `remote:` prefix (hook output, protection rules)
+    pub remote_messages: Vec<String>,
+}
+
+impl fmt::Display for PushRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let refs: Vec<String> = self.refs.iter().map(ToString::to_string).collect();
+        write!(f, "{}", refs.join(", "))?;
+        for message in &self.remote_messages {
+            write!(f, "\nremote: {}", message)?;
+        }
+        Ok(())
+    }
+}
+
 impl std::error::Error for GitError {}
 
 impl From<io::Error> for GitError {
@@ -49,6 +142,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_git_error_ambiguous_display() {
+        let error = GitError::Ambiguous {
+            prefix: "6bb2".to_string(),
+            candidates: vec![Hash::from("6bb2aaa"), Hash::from("6bb2bbb")],
+        };
+        assert_eq!(
+            error.to_string(),
+            "Ambiguous object name 6bb2: candidates are 6bb2aaa, 6bb2bbb"
+        );
+    }
+
+    #[test]
+    fn test_git_error_timed_out_display() {
+        let error = GitError::TimedOut {
+            command: "fetch".to_string(),
+            timeout: Duration::from_millis(1500),
+        };
+        assert_eq!(error.to_string(), "git fetch timed out after 1.5s");
+    }
+
     #[test]
     fn test_git_error_clone() {
         let error1 = GitError::IoError("test error".to_string());
@@ -96,6 +210,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_push_rejected_display() {
+        let error = GitError::PushRejected(PushRejection {
+            refs: vec![RejectedRef {
+                source: "refs/heads/main".to_string(),
+                destination: "refs/heads/main".to_string(),
+                summary: "remote rejected".to_string(),
+                reason: Some("protected branch hook declined".to_string()),
+            }],
+            remote_messages: vec!["Pushes to main require a pull request".to_string()],
+        });
+
+        assert_eq!(
+            error.to_string(),
+            "Push rejected: refs/heads/main -> refs/heads/main [remote rejected] \
+             (protected branch hook declined)\nremote: Pushes to main require a pull request"
+        );
+    }
+
     #[test]
     fn test_result_type_alias() {
         fn test_function() -> Result<String> {
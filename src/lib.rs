@@ -1,17 +1,40 @@
+#[cfg(feature = "tokio")]
+mod async_repository;
+mod backend;
 mod commands;
 mod error;
 mod repository;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 mod types;
 mod utils;
 
+#[cfg(feature = "tokio")]
+pub use async_repository::AsyncRepository;
+pub use backend::{CliBackend, GitBackend, GitInvocation};
 pub use commands::{
-    Author, Branch, BranchList, BranchType, Commit, CommitDetails, CommitLog, CommitMessage,
-    DiffChunk, DiffLine, DiffLineType, DiffOptions, DiffOutput, DiffStats, DiffStatus,
-    FastForwardMode, FetchOptions, FileDiff, FileEntry, GitStatus, IndexStatus, LogOptions,
-    MergeOptions, MergeStatus, MergeStrategy, MoveOptions, PushOptions, Remote, RemoteList,
-    RemoveOptions, RepoConfig, ResetMode, RestoreOptions, Stash, StashApplyOptions, StashList,
-    StashOptions, Tag, TagList, TagOptions, TagType, WorktreeStatus,
+    AmOptions, AmStatus, ApplyOptions, ArchiveFormat, ArchiveOptions, Author, AuthorStats,
+    AutoCrlf, BlameHunk, BlameLine, BlameOptions, BlameOutput, Branch, BranchComparison,
+    BranchList, BranchPruneOptions, BranchPruneReport, BranchType, BundleInfo, BundleRef,
+    CherryPickOptions, CherryPickStatus, CloneOptions, Commit, CommitDetails, CommitGraphOptions,
+    CommitIndex, CommitLog, CommitMessage, CommitOptions, CommitStats, CommitWithStats,
+    ComparedCommit, ConflictOperation, ContributorStats, DayStats, DiffChunk, DiffLine,
+    DiffLineType, DiffOptions, DiffOutput, DiffStats, DiffStatus, EffectiveRemoteUrls, EntryKind,
+    EolSetting, FastForwardMode, FetchOptions, FileDiff, FileEntry, FileLineEndings, FileMode,
+    FileRevision, FileStat, FilterDriver, GitStatus, GraphEdge, GraphNode, IgnoreSubmodules,
+    IgnoreTemplate, IndexEntry, IndexStatus, LargeBlob, LfsLock, LfsUnlockOptions, LineEnding,
+    LineEndingConfig, LogDate, LogGraph, LogOptions, MaintenanceScheduler, MergeDriver,
+    MergeOptions, MergeStatus, MergeStrategy, MoveOptions, Note, Notes, NotesRef, ObjectType, Page,
+    Progress, ProgressCallback, ProgressStage, PurgeOptions, PurgeReport, PushOptions,
+    RebaseOptions, RebaseStatus, RefSelector, ReflogEntry, ReflogExpireOptions, Remote, RemoteList,
+    RemoveOptions, RepoConfig, ResetMode, ResolveOptions, ResolveStatus, RestoreOptions,
+    RevertOptions, RevertStatus, Revspec, SignatureStatus, SignatureValidity, SigningConfig,
+    SigningFormat, Stash, StashApplyOptions, StashList, StashOptions, StatusGroups, StatusOptions,
+    StatusSummary, Submodule, SubmoduleAddOptions, SubmoduleCheckout, SubmoduleFormat,
+    SubmoduleState, SubmoduleStatus, SubmoduleUpdateOptions, SyncOptions, SyncOutcome, SyncReport,
+    SyncStrategy, Tag, TagList, TagOptions, TagType, Transaction, TreeEntry, UrlRewrite, Worktree,
+    WorktreeAddOptions, WorktreeList, WorktreeStatus,
 };
-pub use error::{GitError, Result};
-pub use repository::Repository;
-pub use types::Hash;
+pub use error::{GitError, PushRejection, RejectedRef, Result};
+pub use repository::{HermeticOptions, PathStyle, Repository, RepositoryLock};
+pub use types::{GitUrl, GitVersion, GitWarning, Hash, Identity, UrlScheme, WarningKind};
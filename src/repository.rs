@@ -1,15 +1,228 @@
+use std::env;
+use std::marker::PhantomData;
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::process::Command;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+
+use crate::backend::{BackendHandle, GitBackend};
 use crate::error::{GitError, Result};
-use crate::utils::{git, git_raw};
+use crate::types::{GitVersion, GitWarning, Hash};
+use crate::utils::{CommandContext, git, git_raw};
+
+static GIT_CHECKED: OnceLock<Result<GitVersion>> = OnceLock::new();
+
+#[cfg(windows)]
+const NULL_DEVICE: &str = "NUL";
+#[cfg(not(windows))]
+const NULL_DEVICE: &str = "/dev/null";
+
+/// Settings for running every command of a [`Repository`] in isolation from the machine.
+///
+/// In hermetic mode the user's global and system gitconfig are ignored, inherited `GIT_*`
+/// environment variables are dropped, `HOME` points at a fixed directory, and all
+/// commits use a fixed author and committer. Library behavior
+/// (and, with [`with_date`](Self::with_date), even commit hashes) is then reproducible
+/// across developer machines and CI runners.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HermeticOptions {
+    /// Author and committer name
+    pub name: String,
+    /// Author and committer email
+    pub email: String,
+    /// Author and committer date; the current time is used when `None`
+    pub date: Option<DateTime<Utc>>,
+    /// Value of `HOME` for every command; the directory does not need to exist
+    pub home: PathBuf,
+}
+
+impl Default for HermeticOptions {
+    fn default() -> Self {
+        Self {
+            name: "Rustic Git".to_string(),
+            email: "rustic-git@localhost".to_string(),
+            date: None,
+            home: env::temp_dir().join("rustic-git-hermetic-home"),
+        }
+    }
+}
+
+impl HermeticOptions {
+    /// Create new default hermetic options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fixed author and committer identity
+    pub fn with_identity(mut self, name: &str, email: &str) -> Self {
+        self.name = name.to_string();
+        self.email = email.to_string();
+        self
+    }
+
+    /// Set a fixed author and committer date
+    pub fn with_date(mut self, date: DateTime<Utc>) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// Set the directory used as `HOME`
+    pub fn with_home<P: AsRef<Path>>(mut self, home: P) -> Self {
+        self.home = home.as_ref().to_path_buf();
+        self
+    }
+
+    fn apply(&self, cmd: &mut Command) {
+        for (key, _) in env::vars_os() {
+            if key.to_string_lossy().starts_with("GIT_") {
+                cmd.env_remove(key);
+            }
+        }
+
+        cmd.env("GIT_CONFIG_GLOBAL", NULL_DEVICE)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_ATTR_NOSYSTEM", "1")
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("HOME", &self.home)
+            .env_remove("XDG_CONFIG_HOME")
+            .env("GIT_AUTHOR_NAME", &self.name)
+            .env("GIT_AUTHOR_EMAIL", &self.email)
+            .env("GIT_COMMITTER_NAME", &self.name)
+            .env("GIT_COMMITTER_EMAIL", &self.email);
+
+        if let Some(date) = self.date {
+            let raw_date = format!("{} +0000", date.timestamp());
+            cmd.env("GIT_AUTHOR_DATE", &raw_date)
+                .env("GIT_COMMITTER_DATE", &raw_date);
+        }
+    }
+}
+
+/// How paths in returned status, diff, and log results are expressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStyle {
+    /// Relative to the repository root (or to the handle's directory for
+    /// [`Repository::at`] handles), exactly as git reports them
+    #[default]
+    RepoRelative,
+    /// Absolute filesystem paths, ready to open without joining
+    Absolute,
+}
+
+/// Owner and recursion depth of an [`OperationLock`]
+#[derive(Debug, Default)]
+struct LockState {
+    owner: Option<ThreadId>,
+    depth: usize,
+}
 
-static GIT_CHECKED: OnceLock<Result<()>> = OnceLock::new();
+/// Reentrant lock shared by all handles derived from a serialized [`Repository`]
+#[derive(Debug, Default)]
+struct OperationLock {
+    state: Mutex<LockState>,
+    released: Condvar,
+}
 
+impl OperationLock {
+    /// Take the lock for the current thread, waiting for other threads if `block` is set
+    fn acquire(self: &Arc<Self>, block: bool) -> Option<RepositoryLock> {
+        let current = thread::current().id();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            match state.owner {
+                Some(owner) if owner != current => {
+                    if !block {
+                        return None;
+                    }
+                    state = self.released.wait(state).unwrap_or_else(|e| e.into_inner());
+                }
+                _ => {
+                    state.owner = Some(current);
+                    state.depth += 1;
+                    return Some(RepositoryLock::new(Some(Arc::clone(self))));
+                }
+            }
+        }
+    }
+}
+
+/// Guard returned by [`Repository::lock`] and [`Repository::try_lock`]
+///
+/// While it is alive, other threads' commands on the same repository wait. The owning
+/// thread can keep using the repository (the lock is reentrant). Dropping the guard
+/// releases the lock. For repositories without serialization the guard does nothing.
+///
+/// The lock belongs to the thread that took it, so the guard cannot be moved to (and
+/// dropped on) another thread:
+///
+/// ```compile_fail
+/// use rustic_git::Repository;
+///
+/// let repo = Repository::open(".").unwrap().with_serialization();
+/// let guard = repo.lock();
+/// std::thread::spawn(move || drop(guard));
+/// ```
 #[derive(Debug)]
+#[must_use = "the repository is unlocked as soon as the guard is dropped"]
+pub struct RepositoryLock {
+    lock: Option<Arc<OperationLock>>,
+    /// Keeps the guard `!Send`; releasing it must happen on the owning thread
+    _not_send: PhantomData<*const ()>,
+}
+
+impl RepositoryLock {
+    fn new(lock: Option<Arc<OperationLock>>) -> Self {
+        Self {
+            lock,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for RepositoryLock {
+    fn drop(&mut self) {
+        if let Some(lock) = &self.lock {
+            let mut state = lock.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.depth -= 1;
+            if state.depth == 0 {
+                state.owner = None;
+                lock.released.notify_one();
+            }
+        }
+    }
+}
+
+/// Callback registered with [`Repository::with_warning_handler`]
+#[derive(Clone)]
+struct WarningHandler(Arc<dyn Fn(&GitWarning) + Send + Sync>);
+
+impl std::fmt::Debug for WarningHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WarningHandler")
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Repository {
     repo_path: PathBuf,
+    /// Explicit git directory passed as `--git-dir` (with `repo_path` as `--work-tree`)
+    git_dir: Option<PathBuf>,
+    hermetic: Option<HermeticOptions>,
+    /// Directory below `repo_path` that commands run in, set by [`Repository::at`]
+    subdir: Option<PathBuf>,
+    path_style: PathStyle,
+    /// Limit applied to every git process run for this handle
+    timeout: Option<Duration>,
+    /// Lock shared with clones and derived handles when serialization is enabled
+    operation_lock: Option<Arc<OperationLock>>,
+    /// Receives hints and warnings from the stderr of every command
+    warning_handler: Option<WarningHandler>,
+    /// Runs commands instead of the `git` executable when set
+    backend: Option<BackendHandle>,
 }
 
 impl Repository {
@@ -23,17 +236,67 @@ impl Repository {
     ///
     /// A `Result` containing either `Ok(())` if Git is available or a `GitError`.
     pub fn ensure_git() -> Result<()> {
+        Self::git_version().map(|_| ())
+    }
+
+    /// Get the version of the git executable.
+    ///
+    /// `git --version` runs once per process; the result (or the failure) is cached
+    /// together with the availability check done by [`ensure_git`](Self::ensure_git).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the detected `GitVersion` or a `GitError` if git is not
+    /// available.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rustic_git::Repository;
+    ///
+    /// let version = Repository::git_version()?;
+    /// if version.at_least(2, 38, 0) {
+    ///     println!("git {} supports merge-tree --write-tree", version);
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn git_version() -> Result<GitVersion> {
         GIT_CHECKED
             .get_or_init(|| {
-                git_raw(&["--version"], None)
-                    .map_err(|_| GitError::CommandFailed("Git not found in PATH".to_string()))
-                    .map(|_| ())
+                let output = git_raw(&["--version"], None::<&Path>)
+                    .map_err(|_| GitError::CommandFailed("Git not found in PATH".to_string()))?;
+                if !output.status.success() {
+                    return Err(GitError::CommandFailed("Git not found in PATH".to_string()));
+                }
+                GitVersion::parse(&String::from_utf8_lossy(&output.stdout))
             })
             .clone()
     }
 
+    /// Check that this handle can run commands.
+    ///
+    /// Same as [`ensure_git`](Self::ensure_git), except that a handle with a
+    /// [`GitBackend`] does not need the git executable.
+    pub(crate) fn check_git(&self) -> Result<()> {
+        match &self.backend {
+            Some(_) => Ok(()),
+            None => Self::ensure_git(),
+        }
+    }
+
+    /// The git version that command-line flags are chosen for
+    pub(crate) fn command_git_version(&self) -> Result<GitVersion> {
+        match &self.backend {
+            Some(BackendHandle(backend)) => backend.version(),
+            None => Self::git_version(),
+        }
+    }
+
     /// Open an existing Git repository at the specified path.
     ///
+    /// The path may be a main working tree or a linked worktree created with
+    /// `git worktree add`, where `.git` is a file pointing at the real git directory.
+    ///
     /// # Arguments
     ///
     /// * `path` - The path to an existing Git repository.
@@ -54,13 +317,90 @@ impl Repository {
             )));
         }
 
-        // Check if it's a valid git repository by running git status
-        let _stdout = git(&["status", "--porcelain"], Some(path_ref)).map_err(|_| {
-            GitError::CommandFailed(format!("Not a git repository: {}", path_ref.display()))
+        // Let git resolve the repository (including `.git` files in linked worktrees)
+        // instead of inspecting the `.git` entry ourselves
+        let inside_work_tree = git(&["rev-parse", "--is-inside-work-tree"], Some(path_ref))
+            .map_err(|_| {
+                GitError::CommandFailed(format!("Not a git repository: {}", path_ref.display()))
+            })?;
+        if inside_work_tree.trim() != "true" {
+            return Err(GitError::CommandFailed(format!(
+                "Not a git repository: {}",
+                path_ref.display()
+            )));
+        }
+
+        Ok(Self::unchecked(path_ref))
+    }
+
+    /// A handle for `path` with default settings, without checking that it is a repository
+    pub(crate) fn unchecked(path: &Path) -> Self {
+        Self {
+            repo_path: path.to_path_buf(),
+            git_dir: None,
+            hermetic: None,
+            subdir: None,
+            path_style: PathStyle::default(),
+            timeout: None,
+            operation_lock: None,
+            warning_handler: None,
+            backend: None,
+        }
+    }
+
+    /// Open a repository whose git directory and work tree live in separate places.
+    ///
+    /// Every command run through the returned `Repository` receives `--git-dir` and
+    /// `--work-tree`, as needed for deployment setups that check files out into a
+    /// directory outside the repository (or keep a bare repository elsewhere).
+    /// [`repo_path`](Self::repo_path) returns the work tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `git_dir` - The git directory (e.g. a bare repository or a `.git` directory)
+    /// * `work_tree` - The directory holding the checked-out files
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the opened `Repository` or a `GitError` if either path
+    /// does not exist or `git_dir` is not a git directory.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open_with("/srv/site.git", "/var/www/site")?;
+    /// let status = repo.status()?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn open_with<G: AsRef<Path>, W: AsRef<Path>>(git_dir: G, work_tree: W) -> Result<Self> {
+        Self::ensure_git()?;
+
+        // Commands run inside the work tree, so relative paths must be made absolute
+        let absolute = |path: &Path| {
+            path.canonicalize().map_err(|_| {
+                GitError::CommandFailed(format!("Path does not exist: {}", path.display()))
+            })
+        };
+        let git_dir = absolute(git_dir.as_ref())?;
+        let work_tree = absolute(work_tree.as_ref())?;
+
+        let git_dir_arg = format!("--git-dir={}", git_dir.display());
+        git(&[&git_dir_arg, "rev-parse", "--git-dir"], None::<&Path>).map_err(|_| {
+            GitError::CommandFailed(format!("Not a git repository: {}", git_dir.display()))
         })?;
 
         Ok(Self {
-            repo_path: path_ref.to_path_buf(),
+            repo_path: work_tree,
+            git_dir: Some(git_dir),
+            hermetic: None,
+            subdir: None,
+            path_style: PathStyle::default(),
+            timeout: None,
+            operation_lock: None,
+            warning_handler: None,
+            backend: None,
         })
     }
 
@@ -83,17 +423,114 @@ impl Repository {
         }
         args.push(path.as_ref().to_str().unwrap_or(""));
 
-        let _stdout = git(&args, None)?;
+        let _stdout = git(&args, None::<&Path>)?;
 
-        Ok(Self {
-            repo_path: path.as_ref().to_path_buf(),
-        })
+        Ok(Self::unchecked(path.as_ref()))
+    }
+
+    /// Create a handle whose commands are all run by `backend`.
+    ///
+    /// Nothing is checked or created on disk and the git executable is not needed, so
+    /// this is the entry point for unit tests with a
+    /// [`MockBackend`](crate::testing::MockBackend). Methods that read files in the
+    /// repository directly (for example the conflict and merge state helpers) still
+    /// look at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path reported by [`repo_path`](Self::repo_path)
+    /// * `backend` - Executes every git command of the handle
+    pub fn from_backend<P: AsRef<Path>, B: GitBackend + 'static>(path: P, backend: B) -> Self {
+        Self::unchecked(path.as_ref()).with_backend(backend)
     }
 
     pub fn repo_path(&self) -> &Path {
         &self.repo_path
     }
 
+    /// Get the git directory for this working tree.
+    ///
+    /// For the main working tree this is `<repo>/.git`. For a linked worktree it is
+    /// the per-worktree directory (e.g. `<main>/.git/worktrees/<name>`), which holds
+    /// state such as `HEAD`, `index`, and `MERGE_HEAD`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the absolute path to the git directory or a `GitError`.
+    pub fn git_dir(&self) -> Result<PathBuf> {
+        self.check_git()?;
+
+        resolve_git_dir(self)
+    }
+
+    /// Get the common git directory shared by all worktrees of this repository.
+    ///
+    /// Objects, refs, and configuration live here. For the main working tree this is
+    /// the same as [`git_dir`](Self::git_dir).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the absolute path to the common directory or a `GitError`.
+    pub fn common_dir(&self) -> Result<PathBuf> {
+        self.check_git()?;
+
+        let output = git(&["rev-parse", "--git-common-dir"], Some(self))?;
+        let common_dir = PathBuf::from(output.trim());
+
+        // The main working tree reports the common dir relative to the working directory
+        if common_dir.is_absolute() {
+            Ok(common_dir)
+        } else {
+            Ok(self.command_dir().join(common_dir).canonicalize()?)
+        }
+    }
+
+    /// Check whether this is a linked worktree rather than the main working tree.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if the git directory differs from the common directory.
+    pub fn is_linked_worktree(&self) -> Result<bool> {
+        Ok(self.git_dir()? != self.common_dir()?)
+    }
+
+    /// Check whether this is a shallow clone with truncated history.
+    ///
+    /// Operations such as `describe` and `merge-base` may give incomplete results in
+    /// shallow repositories; see [`deepen`](Self::deepen) and [`unshallow`](Self::unshallow).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if the repository is shallow.
+    pub fn is_shallow(&self) -> Result<bool> {
+        self.check_git()?;
+
+        let output = git(&["rev-parse", "--is-shallow-repository"], Some(self))?;
+        Ok(output.trim() == "true")
+    }
+
+    /// Get the commits at the shallow boundary, whose parents are missing locally.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the boundary commit hashes, empty for a complete repository.
+    pub fn shallow_commits(&self) -> Result<Vec<Hash>> {
+        self.check_git()?;
+
+        let output = git(&["rev-parse", "--git-path", "shallow"], Some(self))?;
+        let shallow_file = self.command_dir().join(output.trim());
+        if !shallow_file.exists() {
+            return Ok(Vec::new());
+        }
+
+        Ok(std::fs::read_to_string(shallow_file)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(Hash::from)
+            .collect())
+    }
+
     /// Get a configuration manager for this repository
     ///
     /// Returns a `RepoConfig` instance that can be used to get and set
@@ -117,11 +554,436 @@ impl Repository {
     pub fn config(&self) -> crate::commands::RepoConfig<'_> {
         crate::commands::RepoConfig::new(self)
     }
+
+    /// Run all further commands in hermetic mode with default [`HermeticOptions`].
+    ///
+    /// Only commands issued through the returned `Repository` are isolated; the
+    /// `open`/`init` call that created it already ran with the ambient environment.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?.hermetic();
+    /// // Commits as "Rustic Git <rustic-git@localhost>" regardless of ~/.gitconfig
+    /// repo.commit("Reproducible commit")?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn hermetic(self) -> Self {
+        self.with_hermetic(HermeticOptions::default())
+    }
+
+    /// Run all further commands in hermetic mode with custom options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Identity, date, and home directory used for every command
+    pub fn with_hermetic(mut self, options: HermeticOptions) -> Self {
+        self.hermetic = Some(options);
+        self
+    }
+
+    /// Get the hermetic options in effect, if hermetic mode is enabled.
+    pub fn hermetic_options(&self) -> Option<&HermeticOptions> {
+        self.hermetic.as_ref()
+    }
+
+    /// Get a handle that runs commands from a subdirectory of the work tree.
+    ///
+    /// This matches running git from inside that directory: pathspecs passed to
+    /// methods like [`add`](Self::add) are relative to it, and [`status`](Self::status)
+    /// and diffs only report paths below it, relative to it. Other output keeps git's
+    /// own convention (log and object listings stay repository-relative).
+    /// [`repo_path`](Self::repo_path) still returns the work tree root.
+    ///
+    /// # Arguments
+    ///
+    /// * `subdir` - A directory relative to the current handle's directory
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new handle or a `GitError` if the directory does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?;
+    /// let package = repo.at("packages/core")?;
+    /// package.add(&["Cargo.toml"])?;
+    /// for entry in package.status()?.entries.iter() {
+    ///     println!("packages/core/{}", entry.path.display());
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn at<P: AsRef<Path>>(&self, subdir: P) -> Result<Self> {
+        let subdir = match &self.subdir {
+            Some(current) => current.join(subdir.as_ref()),
+            None => subdir.as_ref().to_path_buf(),
+        };
+        if subdir.is_absolute() || !self.repo_path.join(&subdir).is_dir() {
+            return Err(GitError::CommandFailed(format!(
+                "Not a directory in the work tree: {}",
+                subdir.display()
+            )));
+        }
+
+        Ok(Self {
+            repo_path: self.repo_path.clone(),
+            git_dir: self.git_dir.clone(),
+            hermetic: self.hermetic.clone(),
+            subdir: Some(subdir),
+            path_style: self.path_style,
+            timeout: self.timeout,
+            operation_lock: self.operation_lock.clone(),
+            warning_handler: self.warning_handler.clone(),
+            backend: self.backend.clone(),
+        })
+    }
+
+    /// A handle for a submodule checked out at `path`, relative to the work tree
+    ///
+    /// The handle keeps this handle's hermetic mode, path style, timeout, warning
+    /// handler and backend, so commands run in the submodule behave like commands run
+    /// here. The serialization lock is not shared, since the submodule is a separate
+    /// repository. The path is not checked; callers pass paths git reported.
+    pub(crate) fn submodule_handle<P: AsRef<Path>>(&self, path: P) -> Self {
+        Self {
+            repo_path: self.repo_path.join(path.as_ref()),
+            git_dir: None,
+            hermetic: self.hermetic.clone(),
+            subdir: None,
+            path_style: self.path_style,
+            timeout: self.timeout,
+            operation_lock: None,
+            warning_handler: self.warning_handler.clone(),
+            backend: self.backend.clone(),
+        }
+    }
+
+    /// Get the subdirectory this handle runs commands from, if created with [`at`](Self::at)
+    pub fn subdir(&self) -> Option<&Path> {
+        self.subdir.as_deref()
+    }
+
+    /// The directory git commands run in, against which cwd-relative output is resolved
+    pub(crate) fn command_dir(&self) -> PathBuf {
+        match &self.subdir {
+            Some(subdir) => self.repo_path.join(subdir),
+            None => self.repo_path.clone(),
+        }
+    }
+
+    /// Choose how paths in status, diff, and log results are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - Repository-relative (the default) or absolute paths
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::{PathStyle, Repository};
+    ///
+    /// let repo = Repository::open(".")?.with_path_style(PathStyle::Absolute);
+    /// for entry in repo.status()?.entries.iter() {
+    ///     let contents = std::fs::read(&entry.path)?;
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_path_style(mut self, style: PathStyle) -> Self {
+        self.path_style = style;
+        self
+    }
+
+    /// Kill any git process run for this repository that takes longer than `timeout`.
+    ///
+    /// Applies to every command run through this handle (and handles derived from it
+    /// with [`at`](Self::at)); a command that exceeds it fails with
+    /// [`GitError::TimedOut`]. Streaming APIs such as
+    /// [`blame_incremental`](Self::blame_incremental) are not limited, since their
+    /// callback already controls how long the process lives.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    /// use std::time::Duration;
+    ///
+    /// let repo = Repository::open(".")?.with_timeout(Duration::from_secs(10));
+    /// let status = repo.status()?;
+    ///
+    /// // Give a slow network operation more time, just for this call
+    /// repo.with_timeout_override(Some(Duration::from_secs(300)))
+    ///     .fetch("origin")?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Get a handle to the same repository with a different timeout.
+    ///
+    /// Intended for a single call (or a few) that needs a longer limit, or none at
+    /// all with `None`, without changing the default on `self`.
+    pub fn with_timeout_override(&self, timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            ..self.clone()
+        }
+    }
+
+    /// Get the timeout applied to git processes, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Serialize commands on this repository across threads.
+    ///
+    /// Each git process run through this handle, its clones, and handles derived from it
+    /// (with [`at`](Self::at) or [`with_timeout_override`](Self::with_timeout_override))
+    /// first takes a shared lock, so concurrent calls no longer race on `index.lock`.
+    /// Multi-step operations such as [`transaction`](Self::transaction) and
+    /// [`sync`](Self::sync) hold it for their whole duration; callers can do the same
+    /// with [`lock`](Self::lock). Streaming APIs such as
+    /// [`blame_incremental`](Self::blame_incremental) are not serialized.
+    ///
+    /// Only handles sharing the lock are coordinated; separately opened handles (or
+    /// other processes) are not.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    /// use std::thread;
+    ///
+    /// let repo = Repository::open(".")?.with_serialization();
+    ///
+    /// let worker = repo.clone();
+    /// let handle = thread::spawn(move || worker.add(&["a.txt"]));
+    /// repo.add(&["b.txt"])?;
+    /// handle.join().unwrap()?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn with_serialization(mut self) -> Self {
+        if self.operation_lock.is_none() {
+            self.operation_lock = Some(Arc::new(OperationLock::default()));
+        }
+        self
+    }
+
+    /// Check whether commands on this handle are serialized.
+    pub fn is_serialized(&self) -> bool {
+        self.operation_lock.is_some()
+    }
+
+    /// Hold the repository lock, waiting until other threads release it.
+    ///
+    /// The returned guard keeps other threads' commands waiting until it is dropped,
+    /// making a sequence of calls atomic with respect to them. The current thread can
+    /// keep calling methods on the repository. Without
+    /// [`with_serialization`](Self::with_serialization) the guard does nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    ///
+    /// let repo = Repository::open(".")?.with_serialization();
+    /// let _guard = repo.lock();
+    /// repo.add_all()?;
+    /// repo.commit("Snapshot")?;
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn lock(&self) -> RepositoryLock {
+        match &self.operation_lock {
+            Some(lock) => lock
+                .acquire(true)
+                .expect("blocking acquire always returns a guard"),
+            None => RepositoryLock::new(None),
+        }
+    }
+
+    /// Hold the repository lock if no other thread does.
+    ///
+    /// # Returns
+    ///
+    /// `None` if another thread holds the lock. Always succeeds without
+    /// [`with_serialization`](Self::with_serialization) or when the current thread
+    /// already holds it.
+    pub fn try_lock(&self) -> Option<RepositoryLock> {
+        match &self.operation_lock {
+            Some(lock) => lock.acquire(false),
+            None => Some(RepositoryLock::new(None)),
+        }
+    }
+
+    /// Receive the hints and warnings git prints on stderr.
+    ///
+    /// Git reports useful advice (`hint: ...`) and non-fatal problems
+    /// (`warning: ...`) even when a command succeeds, and the library would otherwise
+    /// discard them. The handler is called for each [`GitWarning`] of every command
+    /// run through this handle and handles derived from it, successful or not,
+    /// on the thread that ran the command. Streaming APIs such as
+    /// [`blame_incremental`](Self::blame_incremental) do not report warnings.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rustic_git::Repository;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let repo = Repository::open(".")?
+    ///     .with_warning_handler(move |warning| sink.lock().unwrap().push(warning.clone()));
+    ///
+    /// repo.add(&["notes.txt"])?;
+    /// for warning in warnings.lock().unwrap().iter() {
+    ///     eprintln!("{}", warning);
+    /// }
+    /// # Ok::<(), rustic_git::GitError>(())
+    /// ```
+    pub fn with_warning_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&GitWarning) + Send + Sync + 'static,
+    {
+        self.warning_handler = Some(WarningHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Run all further commands through `backend` instead of the git executable.
+    ///
+    /// The backend receives each command's arguments, per-command environment, and
+    /// stdin. Hermetic settings, `--git-dir`/`--work-tree`, and the timeout only apply
+    /// to real git processes; serialization and the warning handler still apply.
+    pub fn with_backend<B: GitBackend + 'static>(mut self, backend: B) -> Self {
+        self.backend = Some(BackendHandle(Arc::new(backend)));
+        self
+    }
+
+    /// Check whether commands run through a [`GitBackend`]
+    pub fn has_backend(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    /// Get the path style used for returned paths
+    pub fn path_style(&self) -> PathStyle {
+        self.path_style
+    }
+
+    /// Resolve a repository-relative path to an absolute path in the work tree.
+    ///
+    /// Absolute paths are returned unchanged. The path does not need to exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A path relative to the repository root
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the absolute path or a `GitError` if the current
+    /// directory cannot be determined.
+    pub fn to_absolute<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf> {
+        Ok(std::path::absolute(self.repo_path.join(path))?)
+    }
+
+    /// Express a path as relative to the repository root.
+    ///
+    /// Relative paths are assumed to already be repository-relative and are returned
+    /// unchanged. Absolute paths are compared against the work tree, resolving
+    /// symlinks when the path exists, so `/tmp` vs `/private/tmp` style aliases match.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - An absolute path inside the work tree, or a repository-relative path
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the repository-relative path or a `GitError` if the path
+    /// lies outside the work tree.
+    pub fn to_repo_relative<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf> {
+        let path = path.as_ref();
+        if path.is_relative() {
+            return Ok(path.to_path_buf());
+        }
+
+        let root = std::path::absolute(&self.repo_path)?;
+        if let Ok(relative) = path.strip_prefix(&root) {
+            return Ok(relative.to_path_buf());
+        }
+        if let (Ok(canonical_root), Ok(canonical_path)) = (root.canonicalize(), path.canonicalize())
+            && let Ok(relative) = canonical_path.strip_prefix(&canonical_root)
+        {
+            return Ok(relative.to_path_buf());
+        }
+
+        Err(GitError::CommandFailed(format!(
+            "Path is outside the work tree: {}",
+            path.display()
+        )))
+    }
+
+    /// Apply the configured [`PathStyle`] to a path git reported relative to `base`
+    pub(crate) fn styled_path(&self, base: &Path, path: PathBuf) -> PathBuf {
+        match self.path_style {
+            PathStyle::RepoRelative => path,
+            PathStyle::Absolute => {
+                let joined = base.join(&path);
+                std::path::absolute(&joined).unwrap_or(joined)
+            }
+        }
+    }
+}
+
+impl CommandContext for Repository {
+    fn configure(&self, cmd: &mut Command) {
+        cmd.current_dir(self.command_dir());
+        if let Some(hermetic) = &self.hermetic {
+            hermetic.apply(cmd);
+        }
+        if let Some(git_dir) = &self.git_dir {
+            cmd.arg("--git-dir").arg(git_dir);
+            cmd.arg("--work-tree").arg(&self.repo_path);
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn serialize(&self) -> Option<RepositoryLock> {
+        self.operation_lock.is_some().then(|| self.lock())
+    }
+
+    fn backend(&self) -> Option<&dyn GitBackend> {
+        self.backend
+            .as_ref()
+            .map(|BackendHandle(backend)| backend.as_ref())
+    }
+
+    fn report_stderr(&self, stderr: &[u8]) {
+        if let Some(WarningHandler(handler)) = &self.warning_handler {
+            for warning in GitWarning::parse_all(&String::from_utf8_lossy(stderr)) {
+                handler(&warning);
+            }
+        }
+    }
+}
+
+/// Resolve the absolute git directory for a working tree path or repository
+pub(crate) fn resolve_git_dir<C: CommandContext + ?Sized>(context: &C) -> Result<PathBuf> {
+    let output = git(&["rev-parse", "--absolute-git-dir"], Some(context))?;
+    Ok(PathBuf::from(output.trim()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::WarningKind;
     use std::env;
     use std::fs;
 
@@ -282,6 +1144,14 @@ mod tests {
         assert!(result3.is_ok());
     }
 
+    #[test]
+    fn test_git_version_cached() {
+        let version = Repository::git_version().unwrap();
+        assert!(version.major >= 2);
+        assert_eq!(Repository::git_version().unwrap(), version);
+        assert!(version.raw.starts_with("git version"));
+    }
+
     #[test]
     fn test_init_with_empty_string_path() {
         let result = Repository::init("", false);
@@ -404,4 +1274,411 @@ mod tests {
             fs::remove_dir_all(&test_path).unwrap();
         }
     }
+
+    #[test]
+    fn test_open_linked_worktree() {
+        let base = env::temp_dir().join("test_linked_worktree");
+        if base.exists() {
+            fs::remove_dir_all(&base).unwrap();
+        }
+        let main_path = base.join("main");
+        let worktree_path = base.join("linked");
+
+        let repo = Repository::init(&main_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(main_path.join("file.txt"), "content").unwrap();
+        git(&["add", "file.txt"], Some(&main_path)).unwrap();
+        git(&["commit", "-m", "Initial commit"], Some(&main_path)).unwrap();
+        git(
+            &[
+                "worktree",
+                "add",
+                "-b",
+                "linked",
+                worktree_path.to_str().unwrap(),
+            ],
+            Some(&main_path),
+        )
+        .unwrap();
+
+        // `.git` is a file in a linked worktree
+        assert!(worktree_path.join(".git").is_file());
+
+        let linked = Repository::open(&worktree_path).unwrap();
+        let main_git_dir = main_path.join(".git").canonicalize().unwrap();
+
+        assert_eq!(repo.git_dir().unwrap(), main_git_dir);
+        assert_eq!(repo.common_dir().unwrap(), main_git_dir);
+        assert!(!repo.is_linked_worktree().unwrap());
+
+        assert_eq!(
+            linked.git_dir().unwrap(),
+            main_git_dir.join("worktrees").join("linked")
+        );
+        assert_eq!(linked.common_dir().unwrap(), main_git_dir);
+        assert!(linked.is_linked_worktree().unwrap());
+
+        // Clean up
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_open_with_separate_git_dir() {
+        let base = env::temp_dir().join("test_open_with_separate_git_dir");
+        if base.exists() {
+            fs::remove_dir_all(&base).unwrap();
+        }
+        let git_dir = base.join("site.git");
+        let work_tree = base.join("www");
+        fs::create_dir_all(&work_tree).unwrap();
+        Repository::init(&git_dir, true).unwrap();
+
+        let repo = Repository::open_with(&git_dir, &work_tree).unwrap();
+        assert_eq!(repo.repo_path(), work_tree.canonicalize().unwrap());
+        assert_eq!(repo.git_dir().unwrap(), git_dir.canonicalize().unwrap());
+
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::write(work_tree.join("index.html"), "<h1>hi</h1>").unwrap();
+        repo.add(&["index.html"]).unwrap();
+        let hash = repo.commit("Deploy site").unwrap();
+
+        // The commit landed in the separate git directory, not in the work tree
+        assert!(!work_tree.join(".git").exists());
+        assert!(repo.status().unwrap().is_clean());
+        assert_eq!(
+            repo.recent_commits(1).unwrap().iter().next().unwrap().hash,
+            hash
+        );
+        let head = git(&["rev-parse", "HEAD"], Some(&git_dir)).unwrap();
+        assert_eq!(head.trim(), hash.as_str());
+
+        // Both paths must exist and the git directory must be a repository
+        assert!(Repository::open_with(base.join("missing.git"), &work_tree).is_err());
+        assert!(Repository::open_with(&work_tree, &work_tree).is_err());
+
+        // Clean up
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_hermetic_mode() {
+        let base = env::temp_dir().join("test_hermetic_mode");
+        if base.exists() {
+            fs::remove_dir_all(&base).unwrap();
+        }
+
+        let date = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let options = HermeticOptions::new()
+            .with_identity("CI Bot", "ci@example.com")
+            .with_date(date)
+            .with_home(base.join("home"));
+
+        // Identical content, identity, and date yield identical commit hashes
+        let mut hashes = Vec::new();
+        for name in ["one", "two"] {
+            let path = base.join(name);
+            let repo = Repository::init(&path, false)
+                .unwrap()
+                .with_hermetic(options.clone());
+            assert_eq!(repo.hermetic_options(), Some(&options));
+
+            // No user.name/user.email configuration is needed
+            fs::write(path.join("file.txt"), "content").unwrap();
+            repo.add(&["file.txt"]).unwrap();
+            hashes.push(repo.commit("Initial commit").unwrap());
+
+            let commit = repo
+                .recent_commits(1)
+                .unwrap()
+                .iter()
+                .next()
+                .unwrap()
+                .clone();
+            assert_eq!(commit.author.name, "CI Bot");
+            assert_eq!(commit.committer.email, "ci@example.com");
+            assert_eq!(commit.timestamp, date);
+
+            // The global config is the null device, so nothing global is visible
+            let global = git_raw(&["config", "--global", "--list"], Some(&repo)).unwrap();
+            assert!(String::from_utf8_lossy(&global.stdout).trim().is_empty());
+        }
+        assert_eq!(hashes[0], hashes[1]);
+
+        let repo = Repository::open(base.join("one")).unwrap();
+        assert!(repo.hermetic_options().is_none());
+        let repo = repo.hermetic();
+        assert_eq!(repo.hermetic_options(), Some(&HermeticOptions::default()));
+
+        // Clean up
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_at_subdirectory() {
+        let test_path = env::temp_dir().join("test_at_subdirectory");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        for package in ["a", "b"] {
+            let dir = test_path.join("packages").join(package);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("lib.txt"), package).unwrap();
+        }
+        repo.add(&["."]).unwrap();
+        repo.commit("Add packages").unwrap();
+
+        let package = repo.at("packages").unwrap().at("a").unwrap();
+        assert_eq!(package.subdir(), Some(Path::new("packages/a")));
+        assert_eq!(package.repo_path(), repo.repo_path());
+        assert!(repo.subdir().is_none());
+
+        fs::write(test_path.join("packages/a/lib.txt"), "changed a").unwrap();
+        fs::write(test_path.join("packages/a/new.txt"), "new").unwrap();
+        fs::write(test_path.join("packages/b/lib.txt"), "changed b").unwrap();
+
+        // Status and diff only see the package, with package-relative paths
+        let status = package.status().unwrap();
+        let mut paths: Vec<&Path> = status.entries.iter().map(|e| e.path.as_path()).collect();
+        paths.sort();
+        assert_eq!(paths, [Path::new("lib.txt"), Path::new("new.txt")]);
+        let diff = package.diff().unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff.files[0].path, Path::new("lib.txt"));
+
+        // Pathspecs are relative to the package
+        package.add(&["new.txt"]).unwrap();
+        let staged: Vec<PathBuf> = repo
+            .status()
+            .unwrap()
+            .staged_files()
+            .map(|e| e.path.clone())
+            .collect();
+        assert_eq!(staged, [PathBuf::from("packages/a/new.txt")]);
+
+        // The full repository still sees everything
+        assert_eq!(repo.status().unwrap().entries.len(), 3);
+        assert!(package.git_dir().unwrap().ends_with(".git"));
+
+        assert!(repo.at("packages/missing").is_err());
+        assert!(repo.at("packages/a/lib.txt").is_err());
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_path_style_and_conversions() {
+        let test_path = env::temp_dir().join("test_path_style");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        fs::create_dir_all(test_path.join("src")).unwrap();
+        fs::write(test_path.join("src/lib.rs"), "one").unwrap();
+        repo.add(&["src/lib.rs"]).unwrap();
+        let hash = repo.commit("Initial commit").unwrap();
+        fs::write(test_path.join("src/lib.rs"), "two").unwrap();
+
+        let absolute = repo.to_absolute("src/lib.rs").unwrap();
+        assert!(absolute.is_absolute());
+        assert_eq!(
+            absolute,
+            std::path::absolute(test_path.join("src/lib.rs")).unwrap()
+        );
+        assert_eq!(repo.to_absolute(&absolute).unwrap(), absolute);
+        assert_eq!(
+            repo.to_repo_relative(&absolute).unwrap(),
+            PathBuf::from("src/lib.rs")
+        );
+        assert_eq!(
+            repo.to_repo_relative(absolute.canonicalize().unwrap())
+                .unwrap(),
+            PathBuf::from("src/lib.rs")
+        );
+        assert_eq!(
+            repo.to_repo_relative("src/lib.rs").unwrap(),
+            PathBuf::from("src/lib.rs")
+        );
+        assert!(repo.to_repo_relative(env::temp_dir()).is_err());
+
+        assert_eq!(repo.path_style(), PathStyle::RepoRelative);
+        assert_eq!(
+            repo.status().unwrap().entries[0].path,
+            Path::new("src/lib.rs")
+        );
+
+        let repo = repo.with_path_style(PathStyle::Absolute);
+        assert_eq!(repo.status().unwrap().entries[0].path, absolute);
+        assert_eq!(repo.diff().unwrap().files[0].path, absolute);
+        assert_eq!(
+            repo.show_commit(&hash).unwrap().files_changed,
+            vec![absolute.clone()]
+        );
+        assert_eq!(repo.file_history("src/lib.rs").unwrap()[0].path(), absolute);
+
+        // Subdirectory handles resolve their relative output against the subdirectory
+        let src = repo.at("src").unwrap();
+        assert_eq!(src.path_style(), PathStyle::Absolute);
+        assert_eq!(src.status().unwrap().entries[0].path, absolute);
+        assert_eq!(src.diff().unwrap().files[0].path, absolute);
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_command_timeout() {
+        let test_path = env::temp_dir().join("test_command_timeout");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        repo.config().set("alias.hang", "!sleep 5").unwrap();
+        assert_eq!(repo.timeout(), None);
+
+        let repo = repo.with_timeout(Duration::from_millis(200));
+        assert_eq!(repo.timeout(), Some(Duration::from_millis(200)));
+
+        // Quick commands are unaffected and still capture their output
+        assert!(repo.status().unwrap().is_clean());
+        assert_eq!(
+            repo.config().get("alias.hang").unwrap(),
+            "!sleep 5".to_string()
+        );
+
+        let started = std::time::Instant::now();
+        match crate::utils::git(&["hang"], Some(&repo)) {
+            Err(GitError::TimedOut { command, timeout }) => {
+                assert_eq!(command, "hang");
+                assert_eq!(timeout, Duration::from_millis(200));
+            }
+            other => panic!("Expected TimedOut, got: {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(3));
+
+        // Derived handles keep the timeout; overrides only affect the returned handle
+        assert_eq!(repo.at(".").unwrap().timeout(), repo.timeout());
+        let relaxed = repo.with_timeout_override(Some(Duration::from_secs(30)));
+        assert_eq!(relaxed.timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(repo.with_timeout_override(None).timeout(), None);
+        assert_eq!(repo.timeout(), Some(Duration::from_millis(200)));
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_operation_serialization() {
+        let test_path = env::temp_dir().join("test_operation_serialization");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let repo = Repository::init(&test_path, false).unwrap();
+        assert!(!repo.is_serialized());
+        assert!(repo.try_lock().is_some());
+
+        let repo = repo.with_serialization();
+        assert!(repo.is_serialized());
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+
+        // The lock is reentrant for the holding thread and blocks other threads
+        {
+            let _guard = repo.lock();
+            assert!(repo.try_lock().is_some());
+            assert!(repo.status().unwrap().is_clean());
+
+            let other = repo.at(".").unwrap();
+            assert!(other.is_serialized());
+            assert!(
+                thread::spawn(move || other.try_lock().is_none())
+                    .join()
+                    .unwrap()
+            );
+        }
+        let other = repo.clone();
+        assert!(
+            thread::spawn(move || other.try_lock().is_some())
+                .join()
+                .unwrap()
+        );
+
+        // Concurrent staging from many threads no longer races on index.lock
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let repo = repo.clone();
+                let name = format!("file{}.txt", i);
+                fs::write(test_path.join(&name), "content").unwrap();
+                thread::spawn(move || {
+                    for _ in 0..5 {
+                        repo.add(&[name.as_str()])?;
+                    }
+                    Ok::<(), GitError>(())
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap().unwrap();
+        }
+        assert_eq!(repo.status().unwrap().staged_files().count(), 8);
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
+
+    #[test]
+    fn test_warning_handler() {
+        let test_path = env::temp_dir().join("test_warning_handler");
+        if test_path.exists() {
+            fs::remove_dir_all(&test_path).unwrap();
+        }
+
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&warnings);
+        let repo = Repository::init(&test_path, false)
+            .unwrap()
+            .with_warning_handler(move |warning| sink.lock().unwrap().push(warning.clone()));
+        repo.config()
+            .set_user("Test User", "test@example.com")
+            .unwrap();
+        repo.config().set("core.autocrlf", "true").unwrap();
+
+        // Successful command that warns about line endings
+        fs::write(test_path.join("unix.txt"), "line\n").unwrap();
+        repo.add(&["unix.txt"]).unwrap();
+
+        let collected = warnings.lock().unwrap().clone();
+        assert!(
+            collected
+                .iter()
+                .any(|w| w.kind == WarningKind::Warning && w.message.contains("unix.txt")),
+            "Expected a line ending warning, got: {:?}",
+            collected
+        );
+
+        // Derived handles report to the same handler
+        warnings.lock().unwrap().clear();
+        fs::write(test_path.join("other.txt"), "line\n").unwrap();
+        repo.at(".").unwrap().add(&["other.txt"]).unwrap();
+        assert!(!warnings.lock().unwrap().is_empty());
+
+        // Clean up
+        fs::remove_dir_all(&test_path).unwrap();
+    }
 }
@@ -0,0 +1,548 @@
+//! Fixtures for testing code built on rustic-git
+//!
+//! Enabled with the `testing` feature, usually as a dev-dependency:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! rustic-git = { version = "0.6", features = ["testing"] }
+//! ```
+//!
+//! [`TempRepo`] creates an isolated repository in the system temp directory, runs every
+//! command in [hermetic mode](crate::Repository::hermetic) with a fixed test identity,
+//! and deletes the directory when dropped. It dereferences to [`Repository`], so the
+//! whole API is available on it directly.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use rustic_git::testing::TempRepo;
+//!
+//! let repo = TempRepo::new("release_notes")?;
+//! let first = repo.commit_file("CHANGELOG.md", "## 1.0.0\n", "Release 1.0.0")?;
+//! repo.checkout_new("feature", None)?;
+//! repo.commit_file("src/lib.rs", "pub fn feature() {}\n", "Add feature")?;
+//!
+//! assert_eq!(repo.recent_commits(10)?.len(), 2);
+//! assert!(repo.is_ancestor(first.as_str(), "HEAD")?);
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+//!
+//! [`MockBackend`] goes further and needs neither git nor a repository on disk: it
+//! answers each command with a scripted [`MockResponse`] and records what was run.
+//!
+//! ```rust
+//! use rustic_git::Repository;
+//! use rustic_git::testing::{MockBackend, MockResponse};
+//!
+//! let mock = MockBackend::new();
+//! mock.expect(&["add", "--sparse", "--", "notes.txt"], MockResponse::success(""))
+//!     .expect(
+//!         &["push", "--porcelain", "origin", "main"],
+//!         MockResponse::failure(1, "fatal: unable to access remote\n"),
+//!     );
+//!
+//! let repo = Repository::from_backend("/not/on/disk", mock.clone());
+//! repo.add(&["notes.txt"])?;
+//! assert!(repo.push("origin", "main").is_err());
+//!
+//! mock.verify()?;
+//! assert_eq!(mock.calls().len(), 2);
+//! # Ok::<(), rustic_git::GitError>(())
+//! ```
+
+use crate::backend::{GitBackend, GitInvocation};
+use crate::error::{GitError, Result};
+use crate::repository::{HermeticOptions, Repository};
+use crate::types::{GitVersion, Hash};
+use std::collections::VecDeque;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Author and committer name used by [`TempRepo`]
+pub const TEST_USER_NAME: &str = "Test User";
+/// Author and committer email used by [`TempRepo`]
+pub const TEST_USER_EMAIL: &str = "test@example.com";
+
+/// Distinguishes fixtures created with the same name in one process
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A repository in a fresh temp directory that is removed on drop
+#[derive(Debug)]
+pub struct TempRepo {
+    repo: Repository,
+    path: PathBuf,
+    keep: bool,
+}
+
+impl TempRepo {
+    /// Create an empty repository with a working tree
+    ///
+    /// # Arguments
+    /// * `name` - Label included in the directory name, to find leftovers from failed runs
+    pub fn new(name: &str) -> Result<Self> {
+        Self::create(name, false)
+    }
+
+    /// Create an empty bare repository, e.g. to push to as a remote
+    pub fn new_bare(name: &str) -> Result<Self> {
+        Self::create(name, true)
+    }
+
+    fn create(name: &str, bare: bool) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "rustic_git_fixture_{}_{}_{}",
+            name,
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        if path.exists() {
+            fs::remove_dir_all(&path)?;
+        }
+
+        let repo = Repository::init(&path, bare)?
+            .with_hermetic(HermeticOptions::new().with_identity(TEST_USER_NAME, TEST_USER_EMAIL));
+        // Also in config, so plain `git` run in the directory commits as the same user
+        repo.config().set_user(TEST_USER_NAME, TEST_USER_EMAIL)?;
+
+        Ok(Self {
+            repo,
+            path,
+            keep: false,
+        })
+    }
+
+    /// The repository handle
+    pub fn repo(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// The repository directory (the work tree, or the git directory if bare)
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keep the directory after drop, for inspecting a failing test
+    pub fn keep(mut self) -> Self {
+        self.keep = true;
+        self
+    }
+
+    /// Write a file relative to the repository root, creating parent directories
+    ///
+    /// # Returns
+    /// The absolute path of the written file
+    pub fn write_file<P: AsRef<Path>>(&self, path: P, contents: &str) -> Result<PathBuf> {
+        let full_path = self.path.join(path);
+        if let Some(parent) = full_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&full_path, contents)?;
+        Ok(full_path)
+    }
+
+    /// Write a file, stage it, and commit it
+    pub fn commit_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        contents: &str,
+        message: &str,
+    ) -> Result<Hash> {
+        self.commit_files(&[(path.as_ref(), contents)], message)
+    }
+
+    /// Write several files, stage them, and commit them together
+    pub fn commit_files<P: AsRef<Path>>(&self, files: &[(P, &str)], message: &str) -> Result<Hash> {
+        for (path, contents) in files {
+            self.write_file(path, contents)?;
+        }
+        let paths: Vec<&Path> = files.iter().map(|(path, _)| path.as_ref()).collect();
+        self.repo.add(&paths)?;
+        self.repo.commit(message)
+    }
+
+    /// Create `count` commits, each changing `file.txt`, and return their hashes in order
+    pub fn commit_many(&self, count: usize) -> Result<Vec<Hash>> {
+        (0..count)
+            .map(|i| {
+                self.commit_file(
+                    "file.txt",
+                    &format!("revision {}\n", i),
+                    &format!("Commit {}", i),
+                )
+            })
+            .collect()
+    }
+
+    /// The commit HEAD points at, failing if HEAD is unborn
+    ///
+    /// Unlike [`Repository::head`], which this would otherwise shadow through `Deref`,
+    /// an unborn HEAD is an error rather than `None`.
+    pub fn head_hash(&self) -> Result<Hash> {
+        self.repo
+            .head()?
+            .ok_or_else(|| GitError::CommandFailed("HEAD is unborn".to_string()))
+    }
+
+    /// Add another fixture as a remote, by path
+    pub fn add_remote_fixture(&self, name: &str, remote: &TempRepo) -> Result<()> {
+        let url = remote.path.to_str().ok_or_else(|| {
+            GitError::IoError(format!(
+                "Fixture path is not valid UTF-8: {}",
+                remote.path.display()
+            ))
+        })?;
+        self.repo.add_remote(name, url)
+    }
+}
+
+impl Deref for TempRepo {
+    type Target = Repository;
+
+    fn deref(&self) -> &Repository {
+        &self.repo
+    }
+}
+
+impl Drop for TempRepo {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// Output a [`MockBackend`] returns for a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    /// Process exit code; anything but 0 makes the library treat the command as failed
+    pub exit_code: i32,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl MockResponse {
+    /// A successful command printing `stdout`
+    pub fn success(stdout: &str) -> Self {
+        Self {
+            exit_code: 0,
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// A failed command printing `stderr`
+    pub fn failure(exit_code: i32, stderr: &str) -> Self {
+        Self {
+            exit_code,
+            stdout: Vec::new(),
+            stderr: stderr.as_bytes().to_vec(),
+        }
+    }
+
+    /// Also print `stderr`, e.g. a `hint:` line for a warning handler
+    pub fn with_stderr(mut self, stderr: &str) -> Self {
+        self.stderr = stderr.as_bytes().to_vec();
+        self
+    }
+
+    fn to_output(&self) -> Output {
+        Output {
+            status: exit_status(self.exit_code),
+            stdout: self.stdout.clone(),
+            stderr: self.stderr.clone(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    // Raw wait status: the exit code lives in the second byte
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+#[derive(Debug)]
+struct MockState {
+    expected: VecDeque<(Vec<String>, MockResponse)>,
+    stubs: Vec<(Vec<String>, MockResponse)>,
+    calls: Vec<GitInvocation>,
+    version: GitVersion,
+}
+
+/// A [`GitBackend`] that answers commands from a script instead of running git
+///
+/// Expectations registered with [`expect`](Self::expect) must be called exactly once, in
+/// order; stubs registered with [`stub`](Self::stub) answer any number of times. Any
+/// other command fails with [`GitError::CommandFailed`] naming it. Every command is
+/// recorded, and clones share the script and the record, so keep a clone to inspect it
+/// after handing one to [`Repository::from_backend`].
+#[derive(Debug, Clone)]
+pub struct MockBackend {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState {
+                expected: VecDeque::new(),
+                stubs: Vec::new(),
+                calls: Vec::new(),
+                version: GitVersion {
+                    major: 2,
+                    minor: 45,
+                    patch: 0,
+                    raw: "git version 2.45.0".to_string(),
+                },
+            })),
+        }
+    }
+}
+
+impl MockBackend {
+    /// Create a backend with an empty script, reporting git 2.45.0
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report a different git version, to test version-dependent flags
+    pub fn with_version(self, major: u32, minor: u32, patch: u32) -> Self {
+        self.state().version = GitVersion {
+            major,
+            minor,
+            patch,
+            raw: format!("git version {}.{}.{}", major, minor, patch),
+        };
+        self
+    }
+
+    /// Expect `git <args>` as the next scripted command and answer it with `response`
+    pub fn expect(&self, args: &[&str], response: MockResponse) -> &Self {
+        self.state()
+            .expected
+            .push_back((args.iter().map(|arg| arg.to_string()).collect(), response));
+        self
+    }
+
+    /// Answer `git <args>` with `response` whenever it runs, outside the expected order
+    pub fn stub(&self, args: &[&str], response: MockResponse) -> &Self {
+        self.state()
+            .stubs
+            .push((args.iter().map(|arg| arg.to_string()).collect(), response));
+        self
+    }
+
+    /// All commands run so far, including unexpected ones
+    pub fn calls(&self) -> Vec<GitInvocation> {
+        self.state().calls.clone()
+    }
+
+    /// Check that every expected command has run
+    ///
+    /// # Returns
+    ///
+    /// A `GitError::CommandFailed` listing the commands still expected.
+    pub fn verify(&self) -> Result<()> {
+        let state = self.state();
+        if state.expected.is_empty() {
+            return Ok(());
+        }
+        let pending: Vec<String> = state
+            .expected
+            .iter()
+            .map(|(args, _)| format!("git {}", args.join(" ")))
+            .collect();
+        Err(GitError::CommandFailed(format!(
+            "expected git commands did not run: {}",
+            pending.join(", ")
+        )))
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl GitBackend for MockBackend {
+    fn run(&self, invocation: &GitInvocation) -> Result<Output> {
+        let mut state = self.state();
+        state.calls.push(invocation.clone());
+
+        if state
+            .expected
+            .front()
+            .is_some_and(|(args, _)| *args == invocation.args)
+        {
+            let (_, response) = state.expected.pop_front().expect("front was checked");
+            return Ok(response.to_output());
+        }
+        if let Some((_, response)) = state
+            .stubs
+            .iter()
+            .find(|(args, _)| *args == invocation.args)
+        {
+            return Ok(response.to_output());
+        }
+
+        Err(GitError::CommandFailed(match state.expected.front() {
+            Some((args, _)) => {
+                format!("unexpected {}, expected git {}", invocation, args.join(" "))
+            }
+            None => format!("unexpected {}", invocation),
+        }))
+    }
+
+    fn version(&self) -> Result<GitVersion> {
+        Ok(self.state().version.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_repo_lifecycle() {
+        let path;
+        {
+            let repo = TempRepo::new("lifecycle").unwrap();
+            path = repo.path().to_path_buf();
+            assert!(path.join(".git").exists());
+
+            let hash = repo
+                .commit_file("nested/dir/file.txt", "content\n", "Add file")
+                .unwrap();
+            assert_eq!(repo.head_hash().unwrap(), hash);
+
+            let log = repo.recent_commits(1).unwrap();
+            let commit = log.iter().next().unwrap();
+            assert_eq!(commit.author.name, TEST_USER_NAME);
+            assert_eq!(commit.author.email, TEST_USER_EMAIL);
+            assert!(repo.status().unwrap().is_clean());
+        }
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_temp_repo_names_are_unique() {
+        let first = TempRepo::new("unique").unwrap();
+        let second = TempRepo::new("unique").unwrap();
+        assert_ne!(first.path(), second.path());
+    }
+
+    #[test]
+    fn test_temp_repo_commits_branches_and_remotes() {
+        let repo = TempRepo::new("helpers").unwrap();
+        let hashes = repo.commit_many(3).unwrap();
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(repo.head_hash().unwrap(), hashes[2]);
+
+        repo.checkout_new("feature", None).unwrap();
+        repo.commit_files(&[("a.txt", "a\n"), ("b.txt", "b\n")], "Two files")
+            .unwrap();
+        assert_eq!(repo.recent_commits(10).unwrap().len(), 4);
+
+        let remote = TempRepo::new_bare("helpers_remote").unwrap();
+        repo.add_remote_fixture("origin", &remote).unwrap();
+        repo.push("origin", "feature").unwrap();
+        assert!(remote.path().join("refs/heads/feature").exists());
+    }
+
+    #[test]
+    fn test_mock_backend_scripted_commands() {
+        let mock = MockBackend::new();
+        mock.stub(
+            &["add", "--sparse", "--", "a.txt"],
+            MockResponse::success(""),
+        )
+        .expect(
+            &[
+                "check-ignore",
+                "--stdin",
+                "-z",
+                "--verbose",
+                "--non-matching",
+            ],
+            MockResponse::success("\0\0\0a.txt\0.gitignore\x001\0*.log\0b.log\0"),
+        );
+        let repo = Repository::from_backend("/nonexistent/mock", mock.clone());
+        assert!(repo.has_backend());
+
+        repo.add(&["a.txt"]).unwrap();
+        let ignored = repo.ignore_check_batch(&["a.txt", "b.log"]).unwrap();
+        assert_eq!(
+            ignored,
+            vec![
+                (PathBuf::from("a.txt"), false),
+                (PathBuf::from("b.log"), true)
+            ]
+        );
+        repo.add(&["a.txt"]).unwrap();
+        mock.verify().unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 3);
+        assert!(calls[0].matches(&["add", "--sparse", "--", "a.txt"]));
+        assert_eq!(calls[1].stdin.as_deref(), Some(&b"a.txt\0b.log\0"[..]));
+    }
+
+    #[test]
+    fn test_mock_backend_failures_and_unexpected_commands() {
+        let mock = MockBackend::new().with_version(2, 30, 0);
+        mock.expect(
+            &["add", "--", "a.txt"],
+            MockResponse::failure(128, "fatal: bad path\n"),
+        )
+        .expect(&["add", "--", "b.txt"], MockResponse::success(""));
+        let repo = Repository::from_backend("/nonexistent/mock", mock.clone());
+
+        // Exit codes reach the library's error handling like a real process would
+        match repo.add(&["a.txt"]).unwrap_err() {
+            GitError::CommandFailed(msg) => assert!(msg.contains("fatal: bad path")),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        match repo.add(&["c.txt"]).unwrap_err() {
+            GitError::CommandFailed(msg) => {
+                assert_eq!(
+                    msg,
+                    "unexpected git add -- c.txt, expected git add -- b.txt"
+                )
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert!(mock.verify().is_err());
+        assert_eq!(mock.calls().len(), 2);
+
+        assert!(
+            repo.blame_incremental("a.txt", |_| std::ops::ControlFlow::Continue(()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_mock_backend_reports_warnings() {
+        let mock = MockBackend::new();
+        mock.expect(
+            &["add", "--sparse", "--", "a.txt"],
+            MockResponse::success("").with_stderr("warning: in the working copy of 'a.txt', LF will be replaced by CRLF the next time Git touches it\n"),
+        );
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&warnings);
+        let repo = Repository::from_backend("/nonexistent/mock", mock)
+            .with_warning_handler(move |warning| sink.lock().unwrap().push(warning.clone()));
+
+        repo.add(&["a.txt"]).unwrap();
+        assert_eq!(warnings.lock().unwrap().len(), 1);
+    }
+}
@@ -1,21 +1,66 @@
-use std::path::Path;
-use std::process::Command;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Output, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
+use crate::backend::{GitBackend, GitInvocation};
 use crate::error::{GitError, Result};
+use crate::repository::RepositoryLock;
 use chrono::{DateTime, Utc};
 
+/// Where a git command runs.
+///
+/// A plain [`Path`] only sets the working directory. A [`Repository`](crate::Repository)
+/// also passes any explicit `--git-dir`/`--work-tree` it was opened with, so every
+/// command targets the same repository regardless of the directory layout.
+pub trait CommandContext {
+    /// Apply the context to `cmd` before any subcommand arguments are added
+    fn configure(&self, cmd: &mut Command);
+
+    /// Maximum time a git process may run before it is killed
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Lock to hold while a git process runs, if commands must be serialized
+    fn serialize(&self) -> Option<RepositoryLock> {
+        None
+    }
+
+    /// Backend that runs commands instead of the git executable
+    fn backend(&self) -> Option<&dyn GitBackend> {
+        None
+    }
+
+    /// Inspect the stderr of a finished git process, e.g. to forward its warnings
+    fn report_stderr(&self, _stderr: &[u8]) {}
+}
+
+impl CommandContext for Path {
+    fn configure(&self, cmd: &mut Command) {
+        cmd.current_dir(self);
+    }
+}
+
+impl CommandContext for PathBuf {
+    fn configure(&self, cmd: &mut Command) {
+        self.as_path().configure(cmd);
+    }
+}
+
 /// Executes a git command and returns the stdout as a String.
 /// Automatically handles error checking and provides descriptive error messages.
 ///
 /// # Arguments
 ///
 /// * `args` - The arguments to pass to the git command.
-/// * `working_dir` - The working directory to use for the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
 ///
 /// # Returns
 ///
 /// A `Result` containing the stdout as String or a `GitError` if the command fails.
-pub fn git(args: &[&str], working_dir: Option<&Path>) -> Result<String> {
+pub fn git<C: CommandContext + ?Sized>(args: &[&str], working_dir: Option<&C>) -> Result<String> {
     let output = git_raw(args, working_dir)?;
 
     if !output.status.success() {
@@ -35,20 +80,562 @@ pub fn git(args: &[&str], working_dir: Option<&Path>) -> Result<String> {
 /// # Arguments
 ///
 /// * `args` - The arguments to pass to the git command.
-/// * `working_dir` - The working directory to use for the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
 ///
 /// # Returns
 ///
 /// A `Result` containing the raw command output or a `GitError` if the command fails to execute.
-pub fn git_raw(args: &[&str], working_dir: Option<&Path>) -> Result<std::process::Output> {
+pub fn git_raw<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+) -> Result<std::process::Output> {
+    git_raw_with_env(args, working_dir, &[])
+}
+
+/// Executes a git command with additional environment variables and returns the stdout.
+///
+/// Behaves like [`git`], but sets the given environment variables on the spawned process.
+/// Useful for values git only reads from the environment, such as `GIT_COMMITTER_DATE`.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+/// * `env` - Environment variables to set for the git command.
+///
+/// # Returns
+///
+/// A `Result` containing the stdout as String or a `GitError` if the command fails.
+pub fn git_with_env<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+    env: &[(&str, &str)],
+) -> Result<String> {
+    let output = git_raw_with_env(args, working_dir, env)?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&"<unknown>"),
+            error_msg
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Creates the base `git` command shared by all runners.
+///
+/// Messages are forced to the untranslated C locale because several parsers match
+/// git's human-readable output ("Already up to date", "On branch", hint and warning
+/// prefixes). `LANGUAGE` is removed as well, since gettext would otherwise prefer it.
+/// A context's environment applied afterwards (or the per-call `env` of
+/// `git_raw_with_env`) can still override these.
+fn git_command() -> Command {
     let mut cmd = Command::new("git");
+    cmd.env("LC_ALL", "C")
+        .env("LANG", "C")
+        .env_remove("LANGUAGE");
+    cmd
+}
+
+/// Executes a git command with additional environment variables and returns the raw Output.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+/// * `env` - Environment variables to set for the git command.
+///
+/// # Returns
+///
+/// A `Result` containing the raw command output or a `GitError` if the command fails to execute.
+pub fn git_raw_with_env<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+    env: &[(&str, &str)],
+) -> Result<std::process::Output> {
+    if let Some(context) = working_dir
+        && let Some(backend) = context.backend()
+    {
+        return run_backend(backend, context, GitInvocation::new(args, env, None));
+    }
+
+    let mut cmd = git_command();
+    if let Some(context) = working_dir {
+        context.configure(&mut cmd);
+    }
     cmd.args(args);
 
-    if let Some(dir) = working_dir {
-        cmd.current_dir(dir);
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+
+    let _guard = working_dir.and_then(|context| context.serialize());
+    let output = match working_dir.and_then(|context| context.timeout()) {
+        Some(timeout) => {
+            cmd.stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            wait_with_timeout(cmd.spawn()?, args, timeout)?
+        }
+        None => cmd.output()?,
+    };
+
+    if let Some(context) = working_dir {
+        context.report_stderr(&output.stderr);
+    }
+    Ok(output)
+}
+
+/// Executes a git command, feeds `input` to its stdin, and returns the raw Output.
+///
+/// Input is written from a separate thread so large inputs cannot deadlock against
+/// a full stdout pipe.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+/// * `input` - The bytes to write to the command's stdin.
+///
+/// # Returns
+///
+/// A `Result` containing the raw command output or a `GitError` if the command fails to execute.
+pub fn git_raw_with_stdin<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+    input: &[u8],
+) -> Result<std::process::Output> {
+    git_raw_with_env_and_stdin(args, working_dir, &[], input)
+}
+
+/// Executes a git command with extra environment variables, feeds `input` to its
+/// stdin, and returns the raw Output.
+pub(crate) fn git_raw_with_env_and_stdin<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+    env: &[(&str, &str)],
+    input: &[u8],
+) -> Result<std::process::Output> {
+    if let Some(context) = working_dir
+        && let Some(backend) = context.backend()
+    {
+        return run_backend(backend, context, GitInvocation::new(args, env, Some(input)));
+    }
+
+    let mut cmd = git_command();
+    if let Some(context) = working_dir {
+        context.configure(&mut cmd);
+    }
+    cmd.args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+
+    let _guard = working_dir.and_then(|context| context.serialize());
+    let mut child = cmd.spawn()?;
+    let mut stdin = child.stdin.take().ok_or_else(|| {
+        GitError::CommandFailed("Failed to open stdin for git command".to_string())
+    })?;
+
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = match working_dir.and_then(|context| context.timeout()) {
+        Some(timeout) => wait_with_timeout(child, args, timeout)?,
+        None => child.wait_with_output()?,
+    };
+    if let Some(context) = working_dir {
+        context.report_stderr(&output.stderr);
+    }
+
+    // A broken pipe means git exited before reading all input; its status and
+    // stderr in `output` describe why, so leave that for the caller to inspect
+    match writer.join() {
+        Ok(Err(error)) if error.kind() != std::io::ErrorKind::BrokenPipe => {
+            return Err(error.into());
+        }
+        Err(_) => {
+            return Err(GitError::IoError(
+                "stdin writer thread panicked".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    Ok(output)
+}
+
+/// Run a command through the context's backend instead of spawning git
+fn run_backend<C: CommandContext + ?Sized>(
+    backend: &dyn GitBackend,
+    context: &C,
+    invocation: GitInvocation,
+) -> Result<Output> {
+    let _guard = context.serialize();
+    let output = backend.run(&invocation)?;
+    context.report_stderr(&output.stderr);
+    Ok(output)
+}
+
+/// Wait for `child` to exit, killing it once `timeout` has elapsed.
+///
+/// stdout and stderr are drained on separate threads so a chatty process cannot block
+/// on a full pipe. On timeout the readers are detached rather than joined, since
+/// grandchildren (hooks, filters, ssh) may keep the pipes open after git is killed.
+fn wait_with_timeout(mut child: Child, args: &[&str], timeout: Duration) -> Result<Output> {
+    fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buffer);
+            }
+            buffer
+        })
+    }
+
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let mut interval = Duration::from_millis(1);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(GitError::TimedOut {
+                command: args.first().unwrap_or(&"<unknown>").to_string(),
+                timeout,
+            });
+        }
+        thread::sleep(interval.min(deadline - now));
+        interval = (interval * 2).min(Duration::from_millis(25));
+    };
+
+    let join = |handle: thread::JoinHandle<Vec<u8>>| {
+        handle
+            .join()
+            .map_err(|_| GitError::IoError("output reader thread panicked".to_string()))
+    };
+
+    Ok(Output {
+        status,
+        stdout: join(stdout)?,
+        stderr: join(stderr)?,
+    })
+}
+
+/// Executes a git command, hands each stderr status line to `on_line` as it is
+/// written, and returns the raw Output with the complete stderr.
+///
+/// Lines are split on `\r` as well as `\n`, since git redraws progress lines in place.
+/// `on_line` runs on a reader thread. With a [`GitBackend`] the command runs to
+/// completion first and its stderr is replayed.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+/// * `on_line` - Called with every non-empty stderr line.
+///
+/// # Returns
+///
+/// A `Result` containing the raw command output or a `GitError` if the command fails to execute.
+pub(crate) fn git_raw_with_stderr_lines<C, F>(
+    args: &[&str],
+    working_dir: Option<&C>,
+    on_line: F,
+) -> Result<Output>
+where
+    C: CommandContext + ?Sized,
+    F: Fn(&str) + Send + 'static,
+{
+    if let Some(context) = working_dir
+        && let Some(backend) = context.backend()
+    {
+        let output = run_backend(backend, context, GitInvocation::new(args, &[], None))?;
+        String::from_utf8_lossy(&output.stderr)
+            .split(['\r', '\n'])
+            .filter(|line| !line.is_empty())
+            .for_each(&on_line);
+        return Ok(output);
+    }
+
+    let mut cmd = git_command();
+    if let Some(context) = working_dir {
+        context.configure(&mut cmd);
+    }
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let _guard = working_dir.and_then(|context| context.serialize());
+    let mut child = cmd.spawn()?;
+    let stderr = child.stderr.take();
+    let reader = thread::spawn(move || {
+        let mut captured = Vec::new();
+        let Some(mut stderr) = stderr else {
+            return captured;
+        };
+        let mut line = Vec::new();
+        let mut buffer = [0u8; 4096];
+        while let Ok(read) = stderr.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            for &byte in &buffer[..read] {
+                if byte == b'\r' || byte == b'\n' {
+                    if !line.is_empty() {
+                        on_line(&String::from_utf8_lossy(&line));
+                        line.clear();
+                    }
+                } else {
+                    line.push(byte);
+                }
+            }
+            captured.extend_from_slice(&buffer[..read]);
+        }
+        if !line.is_empty() {
+            on_line(&String::from_utf8_lossy(&line));
+        }
+        captured
+    });
+
+    let mut output = match working_dir.and_then(|context| context.timeout()) {
+        Some(timeout) => wait_with_timeout(child, args, timeout)?,
+        None => child.wait_with_output()?,
+    };
+    output.stderr = reader
+        .join()
+        .map_err(|_| GitError::IoError("stderr reader thread panicked".to_string()))?;
+
+    if let Some(context) = working_dir {
+        context.report_stderr(&output.stderr);
+    }
+    Ok(output)
+}
+
+/// Spawns a git command with piped stdout and stderr for incremental reading.
+///
+/// The caller is responsible for reading the output and waiting on the child.
+/// The context's timeout does not apply, since the caller controls the child's lifetime.
+/// Contexts with a [`GitBackend`] are rejected, as a backend cannot produce a process.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+///
+/// # Returns
+///
+/// A `Result` containing the spawned `Child` or a `GitError` if the command fails to start.
+pub fn git_spawn<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+) -> Result<Child> {
+    if working_dir.is_some_and(|context| context.backend().is_some()) {
+        return Err(GitError::CommandFailed(format!(
+            "git {} streams output from a process and cannot run through a backend",
+            args.first().unwrap_or(&"<unknown>")
+        )));
+    }
+
+    let mut cmd = git_command();
+    if let Some(context) = working_dir {
+        context.configure(&mut cmd);
+    }
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    cmd.spawn().map_err(GitError::from)
+}
+
+/// A streaming git process whose stderr is collected on a background thread
+///
+/// Draining stderr concurrently keeps git from blocking on a full stderr pipe while
+/// the caller reads stdout. Dropping the process before [`finish`](Self::finish) kills
+/// and reaps it, so an early return never leaves git running or a zombie behind.
+pub(crate) struct GitProcess {
+    command: String,
+    child: Option<Child>,
+    stderr: Option<JoinHandle<Vec<u8>>>,
+}
+
+impl GitProcess {
+    /// Wait for git to exit after its stdout was read, reporting a failure with its stderr
+    pub(crate) fn finish(mut self) -> Result<()> {
+        let Some(mut child) = self.child.take() else {
+            return Ok(());
+        };
+        let status = child.wait()?;
+        let stderr = self
+            .stderr
+            .take()
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default();
+
+        if !status.success() {
+            return Err(GitError::CommandFailed(format!(
+                "git {} failed: {}",
+                self.command,
+                String::from_utf8_lossy(&stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for GitProcess {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
     }
+}
+
+/// Spawns a git command like [`git_spawn`], returning its stdout for incremental reading
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+///
+/// # Returns
+///
+/// A `Result` containing the running `GitProcess` and its stdout, or a `GitError` if the
+/// command fails to start.
+pub(crate) fn git_spawn_stdout<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+) -> Result<(GitProcess, ChildStdout)> {
+    let mut child = git_spawn(args, working_dir)?;
+    let stderr = child.stderr.take().map(|mut pipe| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        })
+    });
+    let stdout = child.stdout.take();
+    let command = args.first().unwrap_or(&"<unknown>").to_string();
+    let process = GitProcess {
+        command,
+        child: Some(child),
+        stderr,
+    };
+
+    let stdout = stdout.ok_or_else(|| {
+        GitError::CommandFailed(format!("Failed to capture git {} output", process.command))
+    })?;
+    Ok((process, stdout))
+}
+
+/// Executes a git command on the tokio runtime and returns the stdout as a String.
+///
+/// The async counterpart of [`git_with_env`]: the process is spawned with
+/// `tokio::process::Command`, so awaiting it never blocks a runtime thread.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+/// * `env` - Environment variables to set for the git command.
+///
+/// # Returns
+///
+/// A `Result` containing the stdout as String or a `GitError` if the command fails.
+#[cfg(feature = "tokio")]
+pub(crate) async fn git_async<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+    env: &[(&str, &str)],
+) -> Result<String> {
+    let output = git_raw_async(args, working_dir, env).await?;
+
+    if !output.status.success() {
+        let error_msg = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&"<unknown>"),
+            error_msg
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Executes a git command on the tokio runtime and returns the raw Output.
+///
+/// The async counterpart of [`git_raw_with_env`]. Backends, the context's timeout
+/// and stderr reporting apply as they do there; the timeout uses tokio's timer and the
+/// process is killed when it expires (or when the future is dropped). The thread-based
+/// serialization lock is not taken, since it cannot be held across an await point.
+///
+/// # Arguments
+///
+/// * `args` - The arguments to pass to the git command.
+/// * `working_dir` - The working directory or repository to run the git command in.
+/// * `env` - Environment variables to set for the git command.
+///
+/// # Returns
+///
+/// A `Result` containing the raw command output or a `GitError` if the command fails to execute.
+#[cfg(feature = "tokio")]
+pub(crate) async fn git_raw_async<C: CommandContext + ?Sized>(
+    args: &[&str],
+    working_dir: Option<&C>,
+    env: &[(&str, &str)],
+) -> Result<Output> {
+    if let Some(context) = working_dir
+        && let Some(backend) = context.backend()
+    {
+        return run_backend(backend, context, GitInvocation::new(args, env, None));
+    }
+
+    let mut cmd = git_command();
+    if let Some(context) = working_dir {
+        context.configure(&mut cmd);
+    }
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+
+    let mut cmd = tokio::process::Command::from(cmd);
+    cmd.kill_on_drop(true);
+    let output = cmd.output();
+    let output = match working_dir.and_then(|context| context.timeout()) {
+        Some(timeout) => {
+            tokio::time::timeout(timeout, output)
+                .await
+                .map_err(|_| GitError::TimedOut {
+                    command: args.first().unwrap_or(&"<unknown>").to_string(),
+                    timeout,
+                })??
+        }
+        None => output.await?,
+    };
 
-    cmd.output().map_err(GitError::from)
+    if let Some(context) = working_dir {
+        context.report_stderr(&output.stderr);
+    }
+    Ok(output)
 }
 
 /// Parse Unix timestamp to DateTime<Utc>
@@ -77,15 +664,262 @@ pub fn parse_unix_timestamp(timestamp_str: &str) -> Result<DateTime<Utc>> {
         .ok_or_else(|| GitError::CommandFailed(format!("Invalid timestamp value: {}", timestamp)))
 }
 
+/// Undo the C-style quoting git applies to paths with spaces or special characters
+///
+/// Git quotes such paths in `fast-export` streams and in the headers of `git diff`
+/// patches; an unquoted path is returned as is.
+pub(crate) fn unquote_path(path: &str) -> String {
+    let Some(inner) = path
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    else {
+        return path.to_string();
+    };
+
+    let mut bytes = Vec::with_capacity(inner.len());
+    let mut chars = inner.bytes().peekable();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match chars.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b'a') => bytes.push(0x07),
+            Some(b'b') => bytes.push(0x08),
+            Some(b'f') => bytes.push(0x0c),
+            Some(b'v') => bytes.push(0x0b),
+            Some(digit @ b'0'..=b'7') => {
+                let mut value = u32::from(digit - b'0');
+                for _ in 0..2 {
+                    if let Some(next @ b'0'..=b'7') = chars.peek().copied() {
+                        value = value * 8 + u32::from(next - b'0');
+                        chars.next();
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Check a user-supplied branch, tag or remote name before passing it to git
+///
+/// Applies the rules of `git check-ref-format --allow-onelevel` in-process, so a name
+/// that git would refuse is rejected before any command runs. Checking in-process
+/// avoids spawning git for every name and keeps working with a custom
+/// [`GitBackend`](crate::GitBackend). Two rules are stricter than git's: names starting
+/// with `-` are refused because git would parse them as an option (such as `--force`),
+/// and `HEAD` is refused because it is never a valid branch, tag or remote name. The
+/// tests compare the rules against git's own output.
+///
+/// # Arguments
+///
+/// * `kind` - What the name is, for the error message (e.g. `"branch"`)
+/// * `name` - The name to check
+pub(crate) fn validate_ref_name(kind: &str, name: &str) -> Result<()> {
+    if !is_valid_ref_name(name) {
+        return Err(GitError::CommandFailed(format!(
+            "Invalid {} name: {:?}",
+            kind, name
+        )));
+    }
+    Ok(())
+}
+
+/// Check a user-supplied positional argument, such as a revision (`HEAD~2`,
+/// `main..topic`), a refspec or a remote URL, before passing it to git
+///
+/// These allow far more syntax than ref names, so only empty values and values git
+/// would parse as an option are rejected.
+///
+/// # Arguments
+///
+/// * `kind` - What the value is, for the error message (e.g. `"revision"`)
+/// * `value` - The value to check
+pub(crate) fn validate_arg(kind: &str, value: &str) -> Result<()> {
+    if value.is_empty() || value.starts_with('-') {
+        return Err(GitError::CommandFailed(format!(
+            "Invalid {}: {:?}",
+            kind, value
+        )));
+    }
+    Ok(())
+}
+
+fn is_valid_ref_name(name: &str) -> bool {
+    if name.is_empty()
+        || name == "@"
+        || name == "HEAD"
+        || name.starts_with('-')
+        || name.starts_with('/')
+        || name.ends_with('/')
+        || name.ends_with('.')
+        || name.contains("..")
+        || name.contains("//")
+        || name.contains("@{")
+    {
+        return false;
+    }
+    if name.chars().any(|c| {
+        c.is_ascii_control() || matches!(c, ' ' | '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+    }) {
+        return false;
+    }
+    name.split('/')
+        .all(|component| !component.starts_with('.') && !component.ends_with(".lock"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::env;
+    use std::ffi::OsStr;
     use std::path::Path;
 
+    #[test]
+    fn test_git_command_forces_c_locale() {
+        let cmd = git_command();
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new("LC_ALL"), Some(OsStr::new("C")))));
+        assert!(envs.contains(&(OsStr::new("LANG"), Some(OsStr::new("C")))));
+        assert!(envs.contains(&(OsStr::new("LANGUAGE"), None)));
+    }
+
+    #[test]
+    fn test_unquote_path() {
+        assert_eq!(unquote_path("plain.txt"), "plain.txt");
+        assert_eq!(unquote_path("\"with space.txt\""), "with space.txt");
+        assert_eq!(unquote_path("\"a\\\"b\\\\c\\n\""), "a\"b\\c\n");
+        assert_eq!(unquote_path("\"caf\\303\\251\""), "café");
+    }
+
+    #[test]
+    fn test_validate_ref_name() {
+        for name in [
+            "main",
+            "feature/login",
+            "v1.0.0",
+            "release-2024",
+            "a.b",
+            "héllo",
+        ] {
+            assert!(validate_ref_name("branch", name).is_ok(), "{}", name);
+        }
+        for name in [
+            "",
+            "--force",
+            "-b",
+            "@",
+            "HEAD",
+            "a..b",
+            "a b",
+            "a~1",
+            "a^",
+            "a:b",
+            "a?",
+            "a*",
+            "a[b",
+            "a\\b",
+            "/a",
+            "a/",
+            "a//b",
+            "a.",
+            ".a",
+            "a/.b",
+            "a.lock",
+            "a/b.lock/c",
+            "a@{1}",
+            "a\tb",
+        ] {
+            assert!(validate_ref_name("branch", name).is_err(), "{:?}", name);
+        }
+
+        match validate_ref_name("tag", "--delete").unwrap_err() {
+            GitError::CommandFailed(msg) => assert_eq!(msg, "Invalid tag name: \"--delete\""),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ref_name_rules_match_git() {
+        let names = [
+            "main",
+            "feature/login",
+            "v1.0.0",
+            "héllo",
+            "a@b",
+            "x@",
+            "refs/heads/x",
+            "@",
+            "@{",
+            "a@{1}",
+            "a..b",
+            "..",
+            "a b",
+            "a\tb",
+            "a\u{7f}b",
+            "a~1",
+            "a^",
+            "a:b",
+            "a?",
+            "a*",
+            "a[b",
+            "a\\b",
+            "/a",
+            "a/",
+            "a//b",
+            "a.",
+            ".a",
+            "a/.b",
+            "a/./b",
+            "a.lock",
+            "a.lock/b",
+            "a/b.lock/c",
+        ];
+        for name in names {
+            let output = git_command()
+                .args(["check-ref-format", "--allow-onelevel", name])
+                .output()
+                .unwrap();
+            assert_eq!(
+                is_valid_ref_name(name),
+                output.status.success(),
+                "{:?}",
+                name
+            );
+        }
+
+        // Stricter than git on purpose
+        for name in ["HEAD", "-b", "--force"] {
+            assert!(!is_valid_ref_name(name), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_validate_arg() {
+        for value in [
+            "HEAD~2",
+            "main..topic",
+            "HEAD:src/lib.rs",
+            "+main:main",
+            "stash@{0}",
+        ] {
+            assert!(validate_arg("revision", value).is_ok(), "{}", value);
+        }
+        assert!(validate_arg("revision", "").is_err());
+        assert!(validate_arg("revision", "--output=/tmp/x").is_err());
+        assert!(validate_arg("URL", "-uhttps://example.com").is_err());
+    }
+
     #[test]
     fn test_git_raw_version_command() {
-        let result = git_raw(&["--version"], None);
+        let result = git_raw(&["--version"], None::<&Path>);
         assert!(result.is_ok());
 
         let output = result.unwrap();
@@ -98,7 +932,7 @@ mod tests {
 
     #[test]
     fn test_git_version_command() {
-        let result = git(&["--version"], None);
+        let result = git(&["--version"], None::<&Path>);
         assert!(result.is_ok());
 
         let output = result.unwrap();
@@ -107,7 +941,7 @@ mod tests {
 
     #[test]
     fn test_git_raw_invalid_command() {
-        let result = git_raw(&["invalid-command-that-does-not-exist"], None);
+        let result = git_raw(&["invalid-command-that-does-not-exist"], None::<&Path>);
         assert!(result.is_ok()); // Command executes but fails
 
         let output = result.unwrap();
@@ -117,7 +951,7 @@ mod tests {
 
     #[test]
     fn test_git_invalid_command_returns_error() {
-        let result = git(&["invalid-command-that-does-not-exist"], None);
+        let result = git(&["invalid-command-that-does-not-exist"], None::<&Path>);
         assert!(result.is_err());
 
         match result.unwrap_err() {
@@ -174,7 +1008,7 @@ mod tests {
 
     #[test]
     fn test_git_empty_args() {
-        let result = git(&[], None);
+        let result = git(&[], None::<&Path>);
         assert!(result.is_err());
 
         match result.unwrap_err() {
@@ -187,7 +1021,7 @@ mod tests {
 
     #[test]
     fn test_git_raw_empty_args() {
-        let result = git_raw(&[], None);
+        let result = git_raw(&[], None::<&Path>);
         assert!(result.is_ok());
 
         let output = result.unwrap();
@@ -196,13 +1030,40 @@ mod tests {
 
     #[test]
     fn test_git_help_command() {
-        let result = git(&["--help"], None);
+        let result = git(&["--help"], None::<&Path>);
         assert!(result.is_ok());
 
         let output = result.unwrap();
         assert!(output.contains("usage:") || output.contains("Git") || output.contains("git"));
     }
 
+    #[test]
+    fn test_git_with_env_sets_variables() {
+        let result = git_with_env(
+            &["var", "GIT_COMMITTER_IDENT"],
+            None::<&Path>,
+            &[
+                ("GIT_COMMITTER_NAME", "Env User"),
+                ("GIT_COMMITTER_EMAIL", "env@example.com"),
+            ],
+        );
+        assert!(result.is_ok());
+
+        let output = result.unwrap();
+        assert!(output.contains("Env User <env@example.com>"));
+    }
+
+    #[test]
+    fn test_git_raw_with_stdin() {
+        let output =
+            git_raw_with_stdin(&["hash-object", "--stdin"], None::<&Path>, b"hello\n").unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
+
     #[test]
     fn test_parse_unix_timestamp() {
         // Test valid timestamp